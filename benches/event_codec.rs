@@ -0,0 +1,146 @@
+//! イベントの保存形式ごとのリプレイ性能の比較
+//!
+//! `cargo bench --bench event_codec`
+//!
+//! 貸出・延長・返却の3イベントからなる貸出を、保存された形式（JSON / MessagePack）から
+//! デコードしてリプレイするまでのスループットを測定する。
+//! 会員IDは保存時と同じく暗号化エンベロープに置き換えてある。
+
+use chrono::{Duration, Utc};
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+use rusty_library_ddd::adapters::postgres::EventCodec;
+use rusty_library_ddd::domain::events::{BookLoaned, BookReturned, DomainEvent, LoanExtended};
+use rusty_library_ddd::domain::loan::replay_events;
+use rusty_library_ddd::domain::value_objects::{BookId, LoanId, MemberId, StaffId};
+use serde_json::{Value, json};
+use std::hint::black_box;
+
+/// 測定する貸出の件数
+const LOANS: usize = 1_000;
+
+/// 1件の貸出のイベント（保存時と同じく会員IDを封緘した形）
+fn sealed_loan() -> Vec<Value> {
+    let now = Utc::now();
+    let loan_id = LoanId::new();
+    let book_id = BookId::new();
+    let member_id = MemberId::new();
+
+    let events = [
+        DomainEvent::BookLoaned(BookLoaned {
+            loan_id,
+            book_id,
+            member_id,
+            loaned_at: now,
+            due_date: now + Duration::days(14),
+            loaned_by: StaffId::new(),
+            override_token: None,
+        }),
+        DomainEvent::LoanExtended(LoanExtended {
+            loan_id,
+            old_due_date: now + Duration::days(14),
+            new_due_date: now + Duration::days(28),
+            extended_at: now + Duration::days(10),
+            extension_count: 1,
+        }),
+        DomainEvent::BookReturned(BookReturned {
+            loan_id,
+            book_id,
+            member_id,
+            returned_at: now + Duration::days(20),
+            was_overdue: false,
+        }),
+    ];
+
+    events
+        .iter()
+        .map(|event| {
+            let mut value = serde_json::to_value(event).unwrap();
+            if let Some(field) = value
+                .as_object_mut()
+                .and_then(|o| o.values_mut().next())
+                .and_then(|v| v.get_mut("member_id"))
+            {
+                // 実データと同じ大きさの封緘済みエンベロープ（AES-256-GCMの暗号文）
+                *field = json!({
+                    "key_id": uuid::Uuid::new_v4(),
+                    "ciphertext": "q83vEjRWeJCrze8SNFZ4kKvN7xI0VniQq83vEjRWeJCrze8SNFZ4kA==",
+                });
+            }
+            value
+        })
+        .collect()
+}
+
+/// 封緘を解いた後のイベントとしてデコードする（会員IDは匿名化センチネルで代用）
+fn open(mut value: Value) -> DomainEvent {
+    if let Some(field) = value
+        .as_object_mut()
+        .and_then(|o| o.values_mut().next())
+        .and_then(|v| v.get_mut("member_id"))
+    {
+        *field = json!(MemberId::ANONYMISED);
+    }
+    serde_json::from_value(value).unwrap()
+}
+
+fn replay(c: &mut Criterion) {
+    let loans: Vec<Vec<Value>> = (0..LOANS).map(|_| sealed_loan()).collect();
+    let json: Vec<Vec<Vec<u8>>> = loans
+        .iter()
+        .map(|events| {
+            events
+                .iter()
+                .map(|v| serde_json::to_vec(v).unwrap())
+                .collect()
+        })
+        .collect();
+    let msgpack: Vec<Vec<Vec<u8>>> = loans
+        .iter()
+        .map(|events| {
+            events
+                .iter()
+                .map(|v| EventCodec::to_msgpack(v).unwrap())
+                .collect()
+        })
+        .collect();
+
+    let size = |rows: &[Vec<Vec<u8>>]| rows.iter().flatten().map(Vec::len).sum::<usize>();
+    println!(
+        "stored size for {} events: json {} bytes, msgpack {} bytes",
+        LOANS * 3,
+        size(&json),
+        size(&msgpack)
+    );
+
+    let mut group = c.benchmark_group("replay");
+    group.throughput(Throughput::Elements((LOANS * 3) as u64));
+
+    group.bench_function(BenchmarkId::new("decode", EventCodec::Json), |b| {
+        b.iter(|| {
+            for rows in &json {
+                let events: Vec<DomainEvent> = rows
+                    .iter()
+                    .map(|bytes| open(serde_json::from_slice(bytes).unwrap()))
+                    .collect();
+                black_box(replay_events(&events));
+            }
+        })
+    });
+
+    group.bench_function(BenchmarkId::new("decode", EventCodec::MessagePack), |b| {
+        b.iter(|| {
+            for rows in &msgpack {
+                let events: Vec<DomainEvent> = rows
+                    .iter()
+                    .map(|bytes| open(EventCodec::from_msgpack(bytes).unwrap()))
+                    .collect();
+                black_box(replay_events(&events));
+            }
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, replay);
+criterion_main!(benches);
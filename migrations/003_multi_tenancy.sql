@@ -0,0 +1,49 @@
+-- 複数の図書館（テナント）で1つのデプロイメントを共有するためのマルチテナント対応
+
+-- テナント（図書館）と館ごとの貸出ポリシー
+CREATE TABLE tenants (
+    tenant_id UUID PRIMARY KEY,
+    name VARCHAR(100) NOT NULL,
+    subdomain VARCHAR(63) NOT NULL UNIQUE,
+    max_active_loans INTEGER NOT NULL DEFAULT 5,
+    loan_period_days INTEGER NOT NULL DEFAULT 14,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT max_active_loans_check CHECK (max_active_loans > 0),
+    CONSTRAINT loan_period_days_check CHECK (loan_period_days > 0)
+);
+
+-- 既定テナント（単一館運用および既存データの所属先）
+INSERT INTO tenants (tenant_id, name, subdomain)
+VALUES ('00000000-0000-0000-0000-000000000000', 'default', 'default');
+
+-- 既存データは既定テナントに所属させる
+ALTER TABLE events
+    ADD COLUMN tenant_id UUID NOT NULL
+        DEFAULT '00000000-0000-0000-0000-000000000000'
+        REFERENCES tenants(tenant_id);
+
+ALTER TABLE loans_view
+    ADD COLUMN tenant_id UUID NOT NULL
+        DEFAULT '00000000-0000-0000-0000-000000000000'
+        REFERENCES tenants(tenant_id);
+
+-- テナント単位の検索用インデックス
+CREATE INDEX idx_events_tenant_aggregate ON events(tenant_id, aggregate_id, aggregate_version);
+CREATE INDEX idx_loans_view_tenant_member ON loans_view(tenant_id, member_id);
+
+-- 行レベルセキュリティ
+--
+-- アダプターはトランザクションごとに `app.tenant_id` を設定する。
+-- 未設定の場合は NULL となり、どの行にもマッチしない（読み書きとも拒否）。
+-- FORCE によりテーブル所有者にもポリシーを適用する（スーパーユーザーは除く）。
+ALTER TABLE events ENABLE ROW LEVEL SECURITY;
+ALTER TABLE events FORCE ROW LEVEL SECURITY;
+CREATE POLICY tenant_isolation ON events
+    USING (tenant_id = NULLIF(current_setting('app.tenant_id', true), '')::uuid)
+    WITH CHECK (tenant_id = NULLIF(current_setting('app.tenant_id', true), '')::uuid);
+
+ALTER TABLE loans_view ENABLE ROW LEVEL SECURITY;
+ALTER TABLE loans_view FORCE ROW LEVEL SECURITY;
+CREATE POLICY tenant_isolation ON loans_view
+    USING (tenant_id = NULLIF(current_setting('app.tenant_id', true), '')::uuid)
+    WITH CHECK (tenant_id = NULLIF(current_setting('app.tenant_id', true), '')::uuid);
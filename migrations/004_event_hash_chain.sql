@@ -0,0 +1,21 @@
+-- 監査のための改ざん検知ハッシュチェーン
+--
+-- 各イベントは直前のイベント（テナント全体・集約内の両方）のハッシュを含めてハッシュ化される。
+-- ハッシュはアプリケーション（EventStore::append）で計算する。
+-- 導入前のイベントはハッシュを持たず、チェーンは最初にハッシュ化されたイベントから始まる。
+
+ALTER TABLE events
+    ADD COLUMN event_hash BYTEA,
+    ADD COLUMN prev_hash BYTEA,
+    ADD COLUMN aggregate_prev_hash BYTEA,
+    ADD CONSTRAINT event_hash_length_check CHECK (
+        (event_hash IS NULL AND prev_hash IS NULL AND aggregate_prev_hash IS NULL)
+        OR (
+            octet_length(event_hash) = 32
+            AND octet_length(prev_hash) = 32
+            AND octet_length(aggregate_prev_hash) = 32
+        )
+    );
+
+-- チェーンの先頭（テナントの最新のハッシュ化済みイベント）の取得用
+CREATE INDEX idx_events_tenant_sequence ON events(tenant_id, sequence_number);
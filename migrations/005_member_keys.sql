@@ -0,0 +1,22 @@
+-- 会員識別子の暗号シュレッディング（削除請求への対応）
+--
+-- イベントの member_id は会員ごとの鍵で暗号化して保存する。
+-- イベントには key_id のみを記録するため、鍵の行を削除すると
+-- その会員のイベントから会員を特定できなくなる（貸出統計は残る）。
+
+CREATE TABLE member_keys (
+    key_id UUID PRIMARY KEY,
+    tenant_id UUID NOT NULL REFERENCES tenants(tenant_id),
+    member_id UUID NOT NULL,
+    key BYTEA NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (tenant_id, member_id),
+    CONSTRAINT key_length_check CHECK (octet_length(key) = 32)
+);
+
+ALTER TABLE member_keys ENABLE ROW LEVEL SECURITY;
+ALTER TABLE member_keys FORCE ROW LEVEL SECURITY;
+
+CREATE POLICY tenant_isolation ON member_keys
+    USING (tenant_id = NULLIF(current_setting('app.tenant_id', true), '')::uuid)
+    WITH CHECK (tenant_id = NULLIF(current_setting('app.tenant_id', true), '')::uuid);
@@ -0,0 +1,29 @@
+-- 読書履歴の保持期間と、履歴の保持を選択した会員
+--
+-- 返却済みの貸出は、館ごとの保持期間が経過すると loans_view から
+-- 会員との紐付けが消される（member_id を匿名化センチネルに置き換える）。
+-- 履歴の保持を選択した会員の貸出は対象外とする。
+
+-- 館ごとの保持期間（日数、NULLは無期限）
+ALTER TABLE tenants
+    ADD COLUMN history_retention_days INTEGER,
+    ADD CONSTRAINT history_retention_days_check CHECK (history_retention_days >= 0);
+
+-- 読書履歴の保持を選択した会員（ReadingHistoryPreferenceChanged の投影）
+CREATE TABLE reading_history_opt_ins (
+    tenant_id UUID NOT NULL REFERENCES tenants(tenant_id),
+    member_id UUID NOT NULL,
+    opted_in_at TIMESTAMPTZ NOT NULL,
+    PRIMARY KEY (tenant_id, member_id)
+);
+
+-- 保持期間を過ぎた返却済み貸出の検索用
+CREATE INDEX idx_loans_view_tenant_returned ON loans_view(tenant_id, returned_at)
+    WHERE status = 'returned';
+
+ALTER TABLE reading_history_opt_ins ENABLE ROW LEVEL SECURITY;
+ALTER TABLE reading_history_opt_ins FORCE ROW LEVEL SECURITY;
+
+CREATE POLICY tenant_isolation ON reading_history_opt_ins
+    USING (tenant_id = NULLIF(current_setting('app.tenant_id', true), '')::uuid)
+    WITH CHECK (tenant_id = NULLIF(current_setting('app.tenant_id', true), '')::uuid);
@@ -0,0 +1,193 @@
+-- イベントテーブルを発生日時（occurred_at）で年単位にレンジパーティション化し、
+-- 古い年のパーティションを退避するアーカイブスキーマを用意する
+--
+-- パーティションは events_y<年>（UTCの1月1日から翌年1月1日まで）。
+-- 範囲外のイベントは events_default に入り、create_event_partition で年のパーティションへ移される。
+-- アーカイブ済みのパーティションは event_archive スキーマの events に付け替えられる。
+-- 現役のイベントとアーカイブ済みのイベントの両方を読む場合は event_log ビューを使用する。
+--
+-- パーティションキーを含まない一意制約は作成できないため、
+-- (aggregate_id, aggregate_version) の一意制約は廃止する。
+-- 集約のバージョンはテナント単位のチェーンロック（hash_chain）の下で採番されるため重複しない。
+
+-- 1. 既存のテーブルを退避する
+--
+-- 全テナントの行を移すため、退避したテーブルの行レベルセキュリティを外す。
+-- sequence_number のシーケンスは新しいテーブルに引き継ぐ。
+ALTER TABLE events RENAME TO events_unpartitioned;
+ALTER TABLE events_unpartitioned NO FORCE ROW LEVEL SECURITY;
+ALTER TABLE events_unpartitioned DISABLE ROW LEVEL SECURITY;
+ALTER SEQUENCE events_sequence_number_seq OWNED BY NONE;
+
+-- 2. パーティション化したテーブル
+CREATE TABLE events (
+    event_id UUID NOT NULL DEFAULT gen_random_uuid(),
+    aggregate_id UUID NOT NULL,
+    aggregate_version INTEGER NOT NULL,
+    aggregate_type VARCHAR(50) NOT NULL,
+    event_type VARCHAR(100) NOT NULL,
+    event_data JSONB NOT NULL,
+    occurred_at TIMESTAMPTZ NOT NULL,
+    sequence_number BIGINT NOT NULL DEFAULT nextval('events_sequence_number_seq'),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    tenant_id UUID NOT NULL
+        DEFAULT '00000000-0000-0000-0000-000000000000'
+        REFERENCES tenants(tenant_id),
+    event_hash BYTEA,
+    prev_hash BYTEA,
+    aggregate_prev_hash BYTEA,
+    CONSTRAINT event_hash_length_check CHECK (
+        (event_hash IS NULL AND prev_hash IS NULL AND aggregate_prev_hash IS NULL)
+        OR (
+            octet_length(event_hash) = 32
+            AND octet_length(prev_hash) = 32
+            AND octet_length(aggregate_prev_hash) = 32
+        )
+    )
+) PARTITION BY RANGE (occurred_at);
+
+ALTER SEQUENCE events_sequence_number_seq OWNED BY events.sequence_number;
+
+CREATE TABLE events_default PARTITION OF events DEFAULT;
+
+-- 3. アーカイブスキーマ
+CREATE SCHEMA event_archive;
+
+CREATE TABLE event_archive.events (LIKE events INCLUDING CONSTRAINTS)
+    PARTITION BY RANGE (occurred_at);
+
+-- 4. 年のパーティションを作成する
+--
+-- 既定パーティションにその年のイベントがあれば、新しいパーティションへ移す。
+-- 既に存在する年、アーカイブ済みの年には何もせず FALSE を返す
+-- （アーカイブ済みの年に後から追加されたイベントは既定パーティションに残る）。
+CREATE FUNCTION create_event_partition(p_year INTEGER) RETURNS BOOLEAN
+LANGUAGE plpgsql AS $$
+DECLARE
+    partition_name TEXT := format('events_y%s', p_year);
+    range_from TIMESTAMPTZ := make_timestamptz(p_year, 1, 1, 0, 0, 0, 'UTC');
+    range_to TIMESTAMPTZ := make_timestamptz(p_year + 1, 1, 1, 0, 0, 0, 'UTC');
+BEGIN
+    IF to_regclass(partition_name) IS NOT NULL
+        OR to_regclass(format('event_archive.%I', partition_name)) IS NOT NULL THEN
+        RETURN FALSE;
+    END IF;
+
+    IF EXISTS (
+        SELECT 1 FROM events_default
+        WHERE occurred_at >= range_from AND occurred_at < range_to
+    ) THEN
+        ALTER TABLE events DETACH PARTITION events_default;
+        EXECUTE format(
+            'CREATE TABLE %I PARTITION OF events FOR VALUES FROM (%L) TO (%L)',
+            partition_name, range_from, range_to
+        );
+        EXECUTE format(
+            'INSERT INTO %I SELECT * FROM events_default WHERE occurred_at >= %L AND occurred_at < %L',
+            partition_name, range_from, range_to
+        );
+        DELETE FROM events_default WHERE occurred_at >= range_from AND occurred_at < range_to;
+        ALTER TABLE events ATTACH PARTITION events_default DEFAULT;
+    ELSE
+        EXECUTE format(
+            'CREATE TABLE %I PARTITION OF events FOR VALUES FROM (%L) TO (%L)',
+            partition_name, range_from, range_to
+        );
+    END IF;
+
+    RETURN TRUE;
+END;
+$$;
+
+-- 5. 既存のイベントの最初の年から翌年までのパーティションを作成し、行を移す
+DO $$
+DECLARE
+    current_year INTEGER := EXTRACT(YEAR FROM NOW() AT TIME ZONE 'UTC')::INTEGER;
+    first_year INTEGER;
+BEGIN
+    SELECT COALESCE(MIN(EXTRACT(YEAR FROM occurred_at AT TIME ZONE 'UTC'))::INTEGER, current_year)
+    INTO first_year
+    FROM events_unpartitioned;
+
+    FOR partition_year IN first_year .. current_year + 1 LOOP
+        PERFORM create_event_partition(partition_year);
+    END LOOP;
+END;
+$$;
+
+INSERT INTO events (
+    event_id,
+    aggregate_id,
+    aggregate_version,
+    aggregate_type,
+    event_type,
+    event_data,
+    occurred_at,
+    sequence_number,
+    created_at,
+    tenant_id,
+    event_hash,
+    prev_hash,
+    aggregate_prev_hash
+)
+SELECT
+    event_id,
+    aggregate_id,
+    aggregate_version,
+    aggregate_type,
+    event_type,
+    event_data,
+    occurred_at,
+    sequence_number,
+    created_at,
+    tenant_id,
+    event_hash,
+    prev_hash,
+    aggregate_prev_hash
+FROM events_unpartitioned;
+
+DO $$
+BEGIN
+    IF (SELECT COUNT(*) FROM events) <> (SELECT COUNT(*) FROM events_unpartitioned) THEN
+        RAISE EXCEPTION 'events were lost while partitioning the events table';
+    END IF;
+END;
+$$;
+
+DROP TABLE events_unpartitioned;
+
+-- 6. インデックス（各パーティションに作成される）
+ALTER TABLE events ADD PRIMARY KEY (event_id, occurred_at);
+CREATE INDEX idx_events_sequence_number ON events(sequence_number);
+CREATE INDEX idx_events_event_type ON events(event_type);
+CREATE INDEX idx_events_occurred_at ON events(occurred_at);
+CREATE INDEX idx_events_tenant_aggregate ON events(tenant_id, aggregate_id, aggregate_version);
+CREATE INDEX idx_events_tenant_sequence ON events(tenant_id, sequence_number);
+
+-- アーカイブには集約の読み込みとチェーン検証に必要なものだけを作成する
+ALTER TABLE event_archive.events ADD PRIMARY KEY (event_id, occurred_at);
+CREATE INDEX idx_archived_events_tenant_aggregate
+    ON event_archive.events(tenant_id, aggregate_id, aggregate_version);
+CREATE INDEX idx_archived_events_tenant_sequence
+    ON event_archive.events(tenant_id, sequence_number);
+
+-- 7. 行レベルセキュリティ（パーティションには親テーブル経由でのみアクセスする）
+ALTER TABLE events ENABLE ROW LEVEL SECURITY;
+ALTER TABLE events FORCE ROW LEVEL SECURITY;
+CREATE POLICY tenant_isolation ON events
+    USING (tenant_id = NULLIF(current_setting('app.tenant_id', true), '')::uuid)
+    WITH CHECK (tenant_id = NULLIF(current_setting('app.tenant_id', true), '')::uuid);
+
+ALTER TABLE event_archive.events ENABLE ROW LEVEL SECURITY;
+ALTER TABLE event_archive.events FORCE ROW LEVEL SECURITY;
+CREATE POLICY tenant_isolation ON event_archive.events
+    USING (tenant_id = NULLIF(current_setting('app.tenant_id', true), '')::uuid)
+    WITH CHECK (tenant_id = NULLIF(current_setting('app.tenant_id', true), '')::uuid);
+
+-- 8. 現役とアーカイブ済みのイベントを合わせたイベントログ
+--
+-- security_invoker により、参照元の行レベルセキュリティが呼び出し元の権限で適用される。
+CREATE VIEW event_log WITH (security_invoker = true) AS
+SELECT * FROM events
+UNION ALL
+SELECT * FROM event_archive.events;
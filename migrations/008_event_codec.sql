@@ -0,0 +1,31 @@
+-- イベントデータのバイナリ形式（コーデック）
+--
+-- event_codec に行ごとのコーデックを記録する。
+--   json    : event_data（JSONB）に保存（既定、既存の行）
+--   msgpack : event_payload（MessagePack）に保存し、event_data は NULL
+-- 形式の異なる行が混在していても読み込める。既存の行は convert-events で変換する。
+
+ALTER TABLE events
+    ADD COLUMN event_codec VARCHAR(20) NOT NULL DEFAULT 'json',
+    ADD COLUMN event_payload BYTEA,
+    ALTER COLUMN event_data DROP NOT NULL,
+    ADD CONSTRAINT event_codec_data_check CHECK (
+        (event_codec = 'json' AND event_data IS NOT NULL AND event_payload IS NULL)
+        OR (event_codec <> 'json' AND event_data IS NULL AND event_payload IS NOT NULL)
+    );
+
+-- アーカイブにも同じ列と制約を追加する（パーティションを付け替えられるように）
+ALTER TABLE event_archive.events
+    ADD COLUMN event_codec VARCHAR(20) NOT NULL DEFAULT 'json',
+    ADD COLUMN event_payload BYTEA,
+    ALTER COLUMN event_data DROP NOT NULL,
+    ADD CONSTRAINT event_codec_data_check CHECK (
+        (event_codec = 'json' AND event_data IS NOT NULL AND event_payload IS NULL)
+        OR (event_codec <> 'json' AND event_data IS NULL AND event_payload IS NOT NULL)
+    );
+
+-- ビューの列は作成時に固定されるため、追加した列を含めて作り直す
+CREATE OR REPLACE VIEW event_log WITH (security_invoker = true) AS
+SELECT * FROM events
+UNION ALL
+SELECT * FROM event_archive.events;
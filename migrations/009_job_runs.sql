@@ -0,0 +1,19 @@
+-- バックグラウンドジョブの実行履歴
+--
+-- 複数のインスタンスが同じスケジュールで起動するため、ジョブと実行時刻の組を一意にして
+-- 同じ回を二度実行しないようにする（実行中の排他はアドバイザリロックで行う）。
+-- ジョブはテナントをまたいで実行されるため、tenants と同じく行レベルセキュリティは設定しない。
+CREATE TABLE job_runs (
+    run_id UUID PRIMARY KEY,
+    job_name VARCHAR(100) NOT NULL,
+    scheduled_for TIMESTAMPTZ NOT NULL,
+    instance_id VARCHAR(255) NOT NULL,
+    started_at TIMESTAMPTZ NOT NULL,
+    finished_at TIMESTAMPTZ,
+    status VARCHAR(20) NOT NULL,
+    summary TEXT,
+    CONSTRAINT job_runs_slot_unique UNIQUE (job_name, scheduled_for),
+    CONSTRAINT job_runs_status_check CHECK (status IN ('running', 'succeeded', 'failed'))
+);
+
+CREATE INDEX idx_job_runs_job_started ON job_runs(job_name, started_at DESC);
@@ -0,0 +1,22 @@
+-- 会員への通知の配信記録
+--
+-- 通知はイベントから少なくとも1回配信されるため、配信した通知を重複排除キーで記録し、
+-- イベントを再処理しても同じ通知を二度送らないようにする。
+-- 重複排除キーは通知の種類・集約ID・集約のバージョンから作るため、会員IDなどの個人情報は持たない。
+CREATE TABLE notification_deliveries (
+    tenant_id UUID NOT NULL REFERENCES tenants(tenant_id),
+    dedup_key VARCHAR(255) NOT NULL,
+    kind VARCHAR(50) NOT NULL,
+    event_id UUID NOT NULL,
+    delivered_at TIMESTAMPTZ NOT NULL,
+    PRIMARY KEY (tenant_id, dedup_key),
+    CONSTRAINT notification_deliveries_kind_check
+        CHECK (kind IN ('overdue', 'extension_confirmation', 'return_confirmation'))
+);
+
+ALTER TABLE notification_deliveries ENABLE ROW LEVEL SECURITY;
+ALTER TABLE notification_deliveries FORCE ROW LEVEL SECURITY;
+
+CREATE POLICY tenant_isolation ON notification_deliveries
+    USING (tenant_id = NULLIF(current_setting('app.tenant_id', true), '')::uuid)
+    WITH CHECK (tenant_id = NULLIF(current_setting('app.tenant_id', true), '')::uuid);
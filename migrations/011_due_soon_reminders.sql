@@ -0,0 +1,15 @@
+-- 返却期限のリマインダー
+--
+-- 返却期限が近い貸出中の貸出を館ごとに返却期限の範囲で検索するためのインデックスと、
+-- リマインダーの配信記録の種類を追加する。
+-- リマインダーを送ったかどうかは貸出のイベント（LoanDueSoonReminded）で管理する。
+
+-- 返却期限が近い貸出の検索用
+CREATE INDEX idx_loans_view_tenant_active_due ON loans_view(tenant_id, due_date)
+    WHERE status = 'active';
+
+ALTER TABLE notification_deliveries
+    DROP CONSTRAINT notification_deliveries_kind_check,
+    ADD CONSTRAINT notification_deliveries_kind_check
+        CHECK (kind IN ('overdue', 'extension_confirmation', 'return_confirmation',
+                        'due_soon_reminder'));
@@ -0,0 +1,20 @@
+-- 延滞の段階的な督促と紛失
+--
+-- 延滞した貸出には返却期限からの経過日数に応じて督促を送り（1日・14日・30日）、
+-- 最終督促の後も返却されない貸出は延滞60日で紛失（弁償の対象）とする。
+-- 督促の段階は貸出のイベント（OverdueNoticeSent）で管理する。
+
+-- 紛失の状態を追加する
+ALTER TABLE loans_view
+    DROP CONSTRAINT status_check,
+    ADD CONSTRAINT status_check CHECK (status IN ('active', 'overdue', 'returned', 'lost'));
+
+-- 督促を進める延滞中の貸出の検索用
+CREATE INDEX idx_loans_view_tenant_overdue_due ON loans_view(tenant_id, due_date)
+    WHERE status = 'overdue';
+
+ALTER TABLE notification_deliveries
+    DROP CONSTRAINT notification_deliveries_kind_check,
+    ADD CONSTRAINT notification_deliveries_kind_check
+        CHECK (kind IN ('overdue', 'extension_confirmation', 'return_confirmation',
+                        'due_soon_reminder', 'overdue_notice', 'lost_declaration'));
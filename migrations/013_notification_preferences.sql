@@ -0,0 +1,54 @@
+-- 会員の通知設定と保留中の通知
+--
+-- 会員は通知の受け取り方（手段・言語・まとめ方・通知を送らない時間帯）を選べる。
+-- 日次ダイジェストにまとめる通知と、通知を送らない時間帯に発生した通知は、
+-- 送る日時まで deferred_notices に保留する。
+-- 通知を保留するのは設定のある会員だけのため、設定を削除（削除請求）すると保留中の通知も削除される。
+CREATE TABLE notification_preferences (
+    tenant_id UUID NOT NULL REFERENCES tenants(tenant_id),
+    member_id UUID NOT NULL,
+    channel VARCHAR(20) NOT NULL,
+    language VARCHAR(10) NOT NULL,
+    digest VARCHAR(20) NOT NULL,
+    quiet_hours_start TIME,
+    quiet_hours_end TIME,
+    utc_offset_minutes INTEGER NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL,
+    PRIMARY KEY (tenant_id, member_id),
+    CONSTRAINT notification_preferences_channel_check
+        CHECK (channel IN ('email', 'in_app', 'none')),
+    CONSTRAINT notification_preferences_language_check CHECK (language IN ('ja', 'en')),
+    CONSTRAINT notification_preferences_digest_check CHECK (digest IN ('immediate', 'daily')),
+    CONSTRAINT notification_preferences_quiet_hours_check
+        CHECK ((quiet_hours_start IS NULL) = (quiet_hours_end IS NULL))
+);
+
+CREATE TABLE deferred_notices (
+    tenant_id UUID NOT NULL,
+    dedup_key VARCHAR(255) NOT NULL,
+    member_id UUID NOT NULL,
+    notice JSONB NOT NULL,
+    queued_at TIMESTAMPTZ NOT NULL,
+    deliver_after TIMESTAMPTZ NOT NULL,
+    PRIMARY KEY (tenant_id, dedup_key),
+    FOREIGN KEY (tenant_id, member_id)
+        REFERENCES notification_preferences(tenant_id, member_id) ON DELETE CASCADE
+);
+
+-- 送る日時を迎えた通知の検索用
+CREATE INDEX idx_deferred_notices_tenant_deliver_after
+    ON deferred_notices(tenant_id, deliver_after);
+
+ALTER TABLE notification_preferences ENABLE ROW LEVEL SECURITY;
+ALTER TABLE notification_preferences FORCE ROW LEVEL SECURITY;
+
+CREATE POLICY tenant_isolation ON notification_preferences
+    USING (tenant_id = NULLIF(current_setting('app.tenant_id', true), '')::uuid)
+    WITH CHECK (tenant_id = NULLIF(current_setting('app.tenant_id', true), '')::uuid);
+
+ALTER TABLE deferred_notices ENABLE ROW LEVEL SECURITY;
+ALTER TABLE deferred_notices FORCE ROW LEVEL SECURITY;
+
+CREATE POLICY tenant_isolation ON deferred_notices
+    USING (tenant_id = NULLIF(current_setting('app.tenant_id', true), '')::uuid)
+    WITH CHECK (tenant_id = NULLIF(current_setting('app.tenant_id', true), '')::uuid);
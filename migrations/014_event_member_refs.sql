@@ -0,0 +1,57 @@
+-- イベントが参照する会員の索引
+--
+-- member_ref に、イベントの会員IDを暗号化した鍵の key_id を記録する
+-- （暗号化の導入前の平文の行は、もともと平文の会員ID）。
+-- 鍵IDは会員IDではないため、鍵を削除（crypto-shredding）すれば会員とは結び付かない。
+-- 会員のデータの開示請求では、この列の索引でその会員のイベントを探す
+-- （バイナリのコーデックで保存された行の中身は Postgres からは検索できないため）。
+-- 会員を含まないイベントと匿名化されたイベントは NULL。
+--
+-- 既存の JSON の行は event_data から埋める。既存のバイナリの行は member_ref_pending とし、
+-- 開示請求のときにアプリケーションで復号して照合する（convert-events で変換し直すと埋まる）。
+
+ALTER TABLE events
+    ADD COLUMN member_ref UUID,
+    ADD COLUMN member_ref_pending BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE event_archive.events
+    ADD COLUMN member_ref UUID,
+    ADD COLUMN member_ref_pending BOOLEAN NOT NULL DEFAULT FALSE;
+
+-- 全テナントの行を埋めるため、一時的にテーブル所有者への行レベルセキュリティの適用を外す
+ALTER TABLE events NO FORCE ROW LEVEL SECURITY;
+ALTER TABLE event_archive.events NO FORCE ROW LEVEL SECURITY;
+
+CREATE FUNCTION pg_temp.member_ref(event_data JSONB) RETURNS UUID
+LANGUAGE sql IMMUTABLE AS $$
+    SELECT NULLIF(
+        COALESCE(
+            jsonb_path_query_first(event_data, '$.*.member_id.key_id') #>> '{}',
+            jsonb_path_query_first(event_data, '$.*.member_id ? (@.type() == "string")') #>> '{}'
+        )::uuid,
+        '00000000-0000-0000-0000-000000000000'
+    )
+$$;
+
+UPDATE events SET member_ref = pg_temp.member_ref(event_data) WHERE event_codec = 'json';
+UPDATE events SET member_ref_pending = TRUE WHERE event_codec <> 'json';
+UPDATE event_archive.events
+SET member_ref = pg_temp.member_ref(event_data) WHERE event_codec = 'json';
+UPDATE event_archive.events SET member_ref_pending = TRUE WHERE event_codec <> 'json';
+
+ALTER TABLE events FORCE ROW LEVEL SECURITY;
+ALTER TABLE event_archive.events FORCE ROW LEVEL SECURITY;
+
+CREATE INDEX idx_events_tenant_member_ref
+    ON events(tenant_id, member_ref) WHERE member_ref IS NOT NULL;
+CREATE INDEX idx_events_tenant_member_ref_pending
+    ON events(tenant_id, sequence_number) WHERE member_ref_pending;
+CREATE INDEX idx_archived_events_tenant_member_ref
+    ON event_archive.events(tenant_id, member_ref) WHERE member_ref IS NOT NULL;
+CREATE INDEX idx_archived_events_tenant_member_ref_pending
+    ON event_archive.events(tenant_id, sequence_number) WHERE member_ref_pending;
+
+-- ビューの列は作成時に固定されるため、追加した列を含めて作り直す
+CREATE OR REPLACE VIEW event_log WITH (security_invoker = true) AS
+SELECT * FROM events
+UNION ALL
+SELECT * FROM event_archive.events;
@@ -0,0 +1,16 @@
+-- 集約のバージョンの一意性
+--
+-- パーティション化（007）で (aggregate_id, aggregate_version) の一意制約を廃止したため、
+-- パーティションキー（occurred_at）を含む一意索引で代わりに守る。
+--
+-- パーティションキーを含まない一意索引は作成できないため、この索引が防ぐのは
+-- 同じ発生日時の重複（同じイベントの二重書き込みなど）だけである。
+-- 発生日時の異なる重複は次の2つで防ぐ：
+--   - 集約のバージョンはテナント単位のチェーンロック（hash_chain）の下で採番される
+--   - イベントの追加は、既に存在するバージョンの行を挿入せず、競合として失敗する
+-- チェーンロックを取らずに events へ直接書き込む処理を追加してはならない。
+
+CREATE UNIQUE INDEX idx_events_aggregate_version_unique
+    ON events(tenant_id, aggregate_id, aggregate_version, occurred_at);
+CREATE UNIQUE INDEX idx_archived_events_aggregate_version_unique
+    ON event_archive.events(tenant_id, aggregate_id, aggregate_version, occurred_at);
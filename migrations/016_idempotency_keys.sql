@@ -0,0 +1,29 @@
+-- コマンドの冪等キー
+--
+-- 同じ冪等キー（HTTPの Idempotency-Key）で再送されたコマンドを一度だけ実行するため、
+-- テナントごとにキーとコマンドの結果を記録する。
+-- インスタンスをまたいでも、再起動後も再送を判定できるようにデータベースに保持する。
+-- completed_at が NULL の行は実行中のコマンド（確保したまま一定時間を過ぎた行は、
+-- 実行中に停止したインスタンスのものとして破棄する）。
+-- 保持期間を過ぎた行は、そのテナントで次にキーを確保するときに削除する。
+CREATE TABLE idempotency_keys (
+    tenant_id UUID NOT NULL REFERENCES tenants(tenant_id),
+    idempotency_key VARCHAR(255) NOT NULL,
+    fingerprint TEXT NOT NULL,
+    loan_id UUID,
+    claimed_at TIMESTAMPTZ NOT NULL,
+    completed_at TIMESTAMPTZ,
+    PRIMARY KEY (tenant_id, idempotency_key),
+    CONSTRAINT idempotency_keys_result_check CHECK ((loan_id IS NULL) = (completed_at IS NULL))
+);
+
+-- 期限切れの行の削除用
+CREATE INDEX idx_idempotency_keys_tenant_completed_at
+    ON idempotency_keys(tenant_id, completed_at);
+
+ALTER TABLE idempotency_keys ENABLE ROW LEVEL SECURITY;
+ALTER TABLE idempotency_keys FORCE ROW LEVEL SECURITY;
+
+CREATE POLICY tenant_isolation ON idempotency_keys
+    USING (tenant_id = NULLIF(current_setting('app.tenant_id', true), '')::uuid)
+    WITH CHECK (tenant_id = NULLIF(current_setting('app.tenant_id', true), '')::uuid);
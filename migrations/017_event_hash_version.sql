@@ -0,0 +1,18 @@
+-- ハッシュチェーンのハッシュの形式
+--
+-- hash_version に行ごとのハッシュの形式を記録する。
+--   1 : テナントと発生日時（occurred_at）を含まない（既定、既存の行）
+--   2 : テナントと発生日時（マイクロ秒単位）を含む
+-- 既存の行のハッシュは変更しないため、既存のチェーンとアンカーはそのまま検証できる。
+-- EventStore は新しい行を常に最新の形式で書き込む。
+
+ALTER TABLE events ADD COLUMN hash_version SMALLINT NOT NULL DEFAULT 1;
+
+-- アーカイブにも同じ列を追加する（パーティションを付け替えられるように）
+ALTER TABLE event_archive.events ADD COLUMN hash_version SMALLINT NOT NULL DEFAULT 1;
+
+-- ビューの列は作成時に固定されるため、追加した列を含めて作り直す
+CREATE OR REPLACE VIEW event_log WITH (security_invoker = true) AS
+SELECT * FROM events
+UNION ALL
+SELECT * FROM event_archive.events;
@@ -0,0 +1,304 @@
+use crate::ports::event_bus::{EventBus as EventBusTrait, EventHandler, PublishedEvent};
+use async_trait::async_trait;
+use futures::FutureExt;
+use std::panic::AssertUnwindSafe;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, RwLock};
+use tokio::sync::{Notify, mpsc};
+
+/// 登録されたハンドラー（配信のたびに複製して、配信中の登録を妨げない）
+type Handlers = Arc<RwLock<Vec<Arc<dyn EventHandler>>>>;
+
+/// 配信の方式
+enum Delivery {
+    /// 発行した処理の中でハンドラーを実行する（テスト用）
+    Synchronous,
+    /// 集約IDで振り分けたレーンごとのタスクで配信する
+    Lanes(Vec<mpsc::UnboundedSender<PublishedEvent>>),
+}
+
+/// 配信待ちのイベントの件数
+#[derive(Default)]
+struct Pending {
+    count: AtomicUsize,
+    idle: Notify,
+}
+
+impl Pending {
+    fn add(&self, n: usize) {
+        self.count.fetch_add(n, Ordering::SeqCst);
+    }
+
+    fn done(&self) {
+        if self.count.fetch_sub(1, Ordering::SeqCst) == 1 {
+            self.idle.notify_waiters();
+        }
+    }
+}
+
+/// EventBusのインメモリ実装
+///
+/// イベントは集約IDで複数のレーンに振り分けられ、レーンごとのタスクが
+/// 発行された順に1件ずつ、登録されたすべてのハンドラーに配信する。
+/// 同じ集約のイベントは常に同じレーンに入るため、集約内の順序が保たれる。
+/// 異なるレーンの集約は並行して配信される。
+///
+/// ハンドラーのエラーとパニックはログに記録し、他のハンドラーと後続のイベントの配信を続ける。
+#[allow(dead_code)]
+pub struct EventBus {
+    handlers: Handlers,
+    delivery: Delivery,
+    pending: Arc<Pending>,
+}
+
+#[allow(dead_code)]
+impl EventBus {
+    /// レーンごとの配信タスクを起動したイベントバスを作成
+    ///
+    /// tokioのランタイム内で呼び出すこと。
+    ///
+    /// # 引数
+    /// * `lanes` - 並行して配信するレーンの数（1以上）
+    pub fn new(lanes: usize) -> Self {
+        let handlers: Handlers = Arc::default();
+        let pending = Arc::new(Pending::default());
+        let senders = (0..lanes.max(1))
+            .map(|_| {
+                let (tx, mut rx) = mpsc::unbounded_channel::<PublishedEvent>();
+                let handlers = handlers.clone();
+                let pending = pending.clone();
+                tokio::spawn(async move {
+                    while let Some(event) = rx.recv().await {
+                        deliver(&handlers, &event).await;
+                        pending.done();
+                    }
+                });
+                tx
+            })
+            .collect();
+
+        Self {
+            handlers,
+            delivery: Delivery::Lanes(senders),
+            pending,
+        }
+    }
+
+    /// 発行した処理の中でハンドラーを実行するイベントバスを作成（テスト用）
+    ///
+    /// `publish`はすべてのハンドラーの処理が終わってから戻るため、
+    /// テストでは発行の直後にハンドラーの結果を確認できる。
+    pub fn synchronous() -> Self {
+        Self {
+            handlers: Arc::default(),
+            delivery: Delivery::Synchronous,
+            pending: Arc::default(),
+        }
+    }
+
+    /// 発行済みのイベントがすべて配信されるまで待つ
+    ///
+    /// 停止時に配信を終えてから終了するために使う。
+    pub async fn flush(&self) {
+        loop {
+            let idle = self.pending.idle.notified();
+            if self.pending.count.load(Ordering::SeqCst) == 0 {
+                return;
+            }
+            idle.await;
+        }
+    }
+}
+
+/// 1件のイベントをすべてのハンドラーに配信する
+async fn deliver(handlers: &Handlers, event: &PublishedEvent) {
+    let handlers = handlers.read().unwrap().clone();
+    for handler in handlers {
+        match AssertUnwindSafe(handler.handle(event)).catch_unwind().await {
+            Ok(Ok(())) => {}
+            Ok(Err(e)) => tracing::warn!(
+                "Event handler {} failed on {} of {}: {}",
+                handler.name(),
+                event.event.event_type(),
+                event.aggregate_id,
+                e
+            ),
+            Err(_) => tracing::error!(
+                "Event handler {} panicked on {} of {}",
+                handler.name(),
+                event.event.event_type(),
+                event.aggregate_id
+            ),
+        }
+    }
+}
+
+#[async_trait]
+impl EventBusTrait for EventBus {
+    fn subscribe(&self, handler: Arc<dyn EventHandler>) {
+        self.handlers.write().unwrap().push(handler);
+    }
+
+    async fn publish(&self, events: Vec<PublishedEvent>) {
+        match &self.delivery {
+            Delivery::Synchronous => {
+                for event in &events {
+                    deliver(&self.handlers, event).await;
+                }
+            }
+            Delivery::Lanes(lanes) => {
+                self.pending.add(events.len());
+                for event in events {
+                    let lane = (event.aggregate_id.as_u128() % lanes.len() as u128) as usize;
+                    if lanes[lane].send(event).is_err() {
+                        // 配信タスクが終了している（ランタイムの停止中）
+                        self.pending.done();
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::events::{DomainEvent, LoanExtended};
+    use crate::domain::value_objects::{LoanId, TenantId};
+    use crate::ports::event_bus::HandlerResult;
+    use crate::ports::event_store::AppendedEvent;
+    use chrono::Utc;
+    use std::sync::Mutex;
+    use std::time::Duration;
+    use uuid::Uuid;
+
+    /// 受け取ったイベントを記録するハンドラー（失敗・パニックも再現する）
+    struct Recorder {
+        seen: Mutex<Vec<(Uuid, u32)>>,
+        fail: bool,
+        panic: bool,
+    }
+
+    impl Recorder {
+        fn new(fail: bool, panic: bool) -> Arc<Self> {
+            Arc::new(Self {
+                seen: Mutex::new(Vec::new()),
+                fail,
+                panic,
+            })
+        }
+    }
+
+    #[async_trait]
+    impl EventHandler for Recorder {
+        fn name(&self) -> &str {
+            "recorder"
+        }
+
+        async fn handle(&self, event: &PublishedEvent) -> HandlerResult {
+            // 後のイベントほど早く終わるようにして、並行に配信されると順序が入れ替わるようにする
+            tokio::time::sleep(Duration::from_millis(u64::from(
+                5 - event.aggregate_version,
+            )))
+            .await;
+            self.seen
+                .lock()
+                .unwrap()
+                .push((event.aggregate_id, event.aggregate_version));
+            if self.panic {
+                panic!("handler bug");
+            }
+            if self.fail {
+                return Err("downstream is unavailable".into());
+            }
+            Ok(())
+        }
+    }
+
+    fn events(aggregate_id: Uuid) -> Vec<PublishedEvent> {
+        (1..=4)
+            .map(|version| {
+                PublishedEvent::new(
+                    TenantId::DEFAULT,
+                    aggregate_id,
+                    "Loan",
+                    version,
+                    AppendedEvent {
+                        event_id: Uuid::new_v4(),
+                        sequence_number: i64::from(version),
+                        recorded_at: Utc::now(),
+                    },
+                    DomainEvent::LoanExtended(LoanExtended {
+                        loan_id: LoanId::from_uuid(aggregate_id),
+                        old_due_date: Utc::now(),
+                        new_due_date: Utc::now(),
+                        extended_at: Utc::now(),
+                        extension_count: 1,
+                    }),
+                )
+            })
+            .collect()
+    }
+
+    fn versions(recorder: &Recorder, aggregate_id: Uuid) -> Vec<u32> {
+        recorder
+            .seen
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(id, _)| *id == aggregate_id)
+            .map(|(_, version)| *version)
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_events_of_an_aggregate_are_delivered_in_order() {
+        let bus = EventBus::new(4);
+        let recorder = Recorder::new(false, false);
+        bus.subscribe(recorder.clone());
+
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+        for (event_a, event_b) in events(a).into_iter().zip(events(b)) {
+            bus.publish(vec![event_a]).await;
+            bus.publish(vec![event_b]).await;
+        }
+        bus.flush().await;
+
+        assert_eq!(versions(&recorder, a), vec![1, 2, 3, 4]);
+        assert_eq!(versions(&recorder, b), vec![1, 2, 3, 4]);
+    }
+
+    #[tokio::test]
+    async fn test_failing_handlers_do_not_affect_others() {
+        let bus = EventBus::new(2);
+        let failing = Recorder::new(true, false);
+        let panicking = Recorder::new(false, true);
+        let healthy = Recorder::new(false, false);
+        bus.subscribe(failing.clone());
+        bus.subscribe(panicking.clone());
+        bus.subscribe(healthy.clone());
+
+        let aggregate_id = Uuid::new_v4();
+        bus.publish(events(aggregate_id)).await;
+        bus.flush().await;
+
+        // 失敗・パニックしたハンドラーにも後続のイベントは配信される
+        assert_eq!(versions(&failing, aggregate_id), vec![1, 2, 3, 4]);
+        assert_eq!(versions(&panicking, aggregate_id), vec![1, 2, 3, 4]);
+        assert_eq!(versions(&healthy, aggregate_id), vec![1, 2, 3, 4]);
+    }
+
+    #[tokio::test]
+    async fn test_synchronous_bus_delivers_before_publish_returns() {
+        let bus = EventBus::synchronous();
+        let panicking = Recorder::new(false, true);
+        let healthy = Recorder::new(false, false);
+        bus.subscribe(panicking);
+        bus.subscribe(healthy.clone());
+
+        let aggregate_id = Uuid::new_v4();
+        bus.publish(events(aggregate_id)).await;
+
+        assert_eq!(versions(&healthy, aggregate_id), vec![1, 2, 3, 4]);
+    }
+}
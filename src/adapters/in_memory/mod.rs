@@ -0,0 +1,4 @@
+pub mod event_bus;
+
+#[allow(unused_imports)]
+pub use event_bus::EventBus as InMemoryEventBus;
@@ -1,7 +1,7 @@
 use crate::domain::value_objects::BookId;
 use crate::ports::book_service::{BookService as BookServiceTrait, Result};
 use async_trait::async_trait;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::sync::Mutex;
 
 /// BookServiceのモック実装
@@ -11,6 +11,7 @@ use std::sync::Mutex;
 #[allow(dead_code)]
 pub struct BookService {
     available_books: Mutex<HashSet<BookId>>,
+    barcodes: Mutex<HashMap<String, BookId>>,
 }
 
 #[allow(dead_code)]
@@ -18,6 +19,7 @@ impl BookService {
     pub fn new() -> Self {
         Self {
             available_books: Mutex::new(HashSet::new()),
+            barcodes: Mutex::new(HashMap::new()),
         }
     }
 
@@ -25,6 +27,14 @@ impl BookService {
     pub fn add_available_book(&self, book_id: BookId) {
         self.available_books.lock().unwrap().insert(book_id);
     }
+
+    /// テスト用に資料バーコードを登録
+    pub fn add_barcode(&self, barcode: impl Into<String>, book_id: BookId) {
+        self.barcodes
+            .lock()
+            .unwrap()
+            .insert(barcode.into(), book_id);
+    }
 }
 
 impl Default for BookService {
@@ -44,4 +54,9 @@ impl BookServiceTrait for BookService {
     async fn get_book_title(&self, _book_id: BookId) -> Result<String> {
         Ok("Mock Book Title".to_string())
     }
+
+    /// 登録された資料バーコードから書籍を検索
+    async fn find_by_barcode(&self, barcode: &str) -> Result<Option<BookId>> {
+        Ok(self.barcodes.lock().unwrap().get(barcode).copied())
+    }
 }
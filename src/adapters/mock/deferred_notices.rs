@@ -0,0 +1,95 @@
+use crate::domain::value_objects::MemberId;
+use crate::ports::deferred_notices::{
+    DeferredNotice, DeferredNoticeQueue as DeferredNoticeQueueTrait, Result,
+};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::sync::Mutex;
+
+/// DeferredNoticeQueueのモック実装
+///
+/// 保留中の通知をメモリに保持する。
+#[allow(dead_code)]
+pub struct DeferredNoticeQueue {
+    notices: Mutex<Vec<DeferredNotice>>,
+}
+
+#[allow(dead_code)]
+impl DeferredNoticeQueue {
+    pub fn new() -> Self {
+        Self {
+            notices: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// 保留中の通知の件数
+    pub fn len(&self) -> usize {
+        self.notices.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl Default for DeferredNoticeQueue {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl DeferredNoticeQueueTrait for DeferredNoticeQueue {
+    async fn enqueue(&self, notice: &DeferredNotice) -> Result<()> {
+        let mut notices = self.notices.lock().unwrap();
+        if !notices
+            .iter()
+            .any(|n| n.notice.dedup_key == notice.notice.dedup_key)
+        {
+            notices.push(notice.clone());
+        }
+        Ok(())
+    }
+
+    async fn due(&self, until: DateTime<Utc>) -> Result<Vec<DeferredNotice>> {
+        let mut due: Vec<_> = self
+            .notices
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|n| n.deliver_after <= until)
+            .cloned()
+            .collect();
+        due.sort_by_key(|n| (n.member_id.value(), n.queued_at));
+        Ok(due)
+    }
+
+    async fn remove(&self, dedup_keys: &[String]) -> Result<()> {
+        self.notices
+            .lock()
+            .unwrap()
+            .retain(|n| !dedup_keys.contains(&n.notice.dedup_key));
+        Ok(())
+    }
+
+    async fn pending_for(&self, member_id: MemberId) -> Result<Vec<DeferredNotice>> {
+        let mut pending: Vec<_> = self
+            .notices
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|n| n.member_id == member_id)
+            .cloned()
+            .collect();
+        pending.sort_by_key(|n| n.queued_at);
+        Ok(pending)
+    }
+
+    async fn remove_for(&self, member_id: MemberId) -> Result<()> {
+        self.notices
+            .lock()
+            .unwrap()
+            .retain(|n| n.member_id != member_id);
+        Ok(())
+    }
+}
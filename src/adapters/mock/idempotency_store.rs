@@ -0,0 +1,97 @@
+use crate::domain::value_objects::LoanId;
+use crate::ports::idempotency_store::{
+    IdempotencyClaim, IdempotencyStore as IdempotencyStoreTrait, Result,
+};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// 冪等キーの記録
+struct Entry {
+    fingerprint: String,
+    loan_id: Option<LoanId>,
+    claimed_at: DateTime<Utc>,
+    completed_at: Option<DateTime<Utc>>,
+}
+
+/// IdempotencyStoreのモック実装
+///
+/// 冪等キーをメモリに保持する。
+#[allow(dead_code)]
+pub struct IdempotencyStore {
+    entries: Mutex<HashMap<String, Entry>>,
+}
+
+#[allow(dead_code)]
+impl IdempotencyStore {
+    pub fn new() -> Self {
+        Self {
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+impl Default for IdempotencyStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl IdempotencyStoreTrait for IdempotencyStore {
+    async fn claim(
+        &self,
+        key: &str,
+        fingerprint: &str,
+        claimed_at: DateTime<Utc>,
+        expired_before: DateTime<Utc>,
+        abandoned_before: DateTime<Utc>,
+    ) -> Result<IdempotencyClaim> {
+        let mut entries = self.entries.lock().unwrap();
+        entries.retain(|_, entry| match entry.completed_at {
+            Some(completed_at) => completed_at >= expired_before,
+            None => entry.claimed_at >= abandoned_before,
+        });
+        if let Some(entry) = entries.get(key) {
+            return Ok(IdempotencyClaim::Taken {
+                fingerprint: entry.fingerprint.clone(),
+                loan_id: entry.loan_id,
+            });
+        }
+        entries.insert(
+            key.to_string(),
+            Entry {
+                fingerprint: fingerprint.to_string(),
+                loan_id: None,
+                claimed_at,
+                completed_at: None,
+            },
+        );
+        Ok(IdempotencyClaim::Claimed)
+    }
+
+    async fn complete(
+        &self,
+        key: &str,
+        loan_id: LoanId,
+        completed_at: DateTime<Utc>,
+    ) -> Result<()> {
+        if let Some(entry) = self.entries.lock().unwrap().get_mut(key) {
+            entry.loan_id = Some(loan_id);
+            entry.completed_at = Some(completed_at);
+        }
+        Ok(())
+    }
+
+    async fn release(&self, key: &str) -> Result<()> {
+        let mut entries = self.entries.lock().unwrap();
+        if entries
+            .get(key)
+            .is_some_and(|entry| entry.completed_at.is_none())
+        {
+            entries.remove(key);
+        }
+        Ok(())
+    }
+}
@@ -1,17 +1,19 @@
 use crate::domain::value_objects::MemberId;
 use crate::ports::member_service::{MemberService as MemberServiceTrait, Result};
 use async_trait::async_trait;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::sync::Mutex;
 
 /// MemberServiceのモック実装
 ///
 /// 会員IDを保存することで状態を持ったテストをサポート。
-/// 会員登録や延滞マークが可能。
+/// 会員登録や延滞・利用停止のマークが可能。
 #[allow(dead_code)]
 pub struct MemberService {
     existing_members: Mutex<HashSet<MemberId>>,
     overdue_members: Mutex<HashSet<MemberId>>,
+    suspended_members: Mutex<HashSet<MemberId>>,
+    card_numbers: Mutex<HashMap<String, MemberId>>,
 }
 
 #[allow(dead_code)]
@@ -20,6 +22,8 @@ impl MemberService {
         Self {
             existing_members: Mutex::new(HashSet::new()),
             overdue_members: Mutex::new(HashSet::new()),
+            suspended_members: Mutex::new(HashSet::new()),
+            card_numbers: Mutex::new(HashMap::new()),
         }
     }
 
@@ -28,10 +32,24 @@ impl MemberService {
         self.existing_members.lock().unwrap().insert(member_id);
     }
 
+    /// テスト用に利用者カード番号を登録（会員も登録される）
+    pub fn add_card_number(&self, card_number: impl Into<String>, member_id: MemberId) {
+        self.add_member(member_id);
+        self.card_numbers
+            .lock()
+            .unwrap()
+            .insert(card_number.into(), member_id);
+    }
+
     /// テスト用に会員を延滞状態にマーク
     pub fn mark_overdue(&self, member_id: MemberId) {
         self.overdue_members.lock().unwrap().insert(member_id);
     }
+
+    /// テスト用に会員を利用停止状態にマーク
+    pub fn mark_suspended(&self, member_id: MemberId) {
+        self.suspended_members.lock().unwrap().insert(member_id);
+    }
 }
 
 impl Default for MemberService {
@@ -51,4 +69,14 @@ impl MemberServiceTrait for MemberService {
     async fn has_overdue_loans(&self, member_id: MemberId) -> Result<bool> {
         Ok(self.overdue_members.lock().unwrap().contains(&member_id))
     }
+
+    /// 会員が利用停止中かチェック
+    async fn is_suspended(&self, member_id: MemberId) -> Result<bool> {
+        Ok(self.suspended_members.lock().unwrap().contains(&member_id))
+    }
+
+    /// 登録された利用者カード番号から会員を検索
+    async fn find_by_card_number(&self, card_number: &str) -> Result<Option<MemberId>> {
+        Ok(self.card_numbers.lock().unwrap().get(card_number).copied())
+    }
 }
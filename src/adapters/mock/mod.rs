@@ -1,10 +1,28 @@
 pub mod book_service;
+pub mod deferred_notices;
+pub mod idempotency_store;
 pub mod member_service;
+pub mod notification_gateway;
+pub mod notification_log;
+pub mod notification_preferences;
 pub mod notification_service;
+pub mod staff_service;
 
 #[allow(unused_imports)]
 pub use book_service::BookService;
 #[allow(unused_imports)]
+pub use deferred_notices::DeferredNoticeQueue;
+#[allow(unused_imports)]
+pub use idempotency_store::IdempotencyStore;
+#[allow(unused_imports)]
 pub use member_service::MemberService;
 #[allow(unused_imports)]
-pub use notification_service::NotificationService;
+pub use notification_gateway::NotificationGateway;
+#[allow(unused_imports)]
+pub use notification_log::NotificationLog;
+#[allow(unused_imports)]
+pub use notification_preferences::NotificationPreferenceStore;
+#[allow(unused_imports)]
+pub use notification_service::{NotificationService, SentNotice};
+#[allow(unused_imports)]
+pub use staff_service::StaffService;
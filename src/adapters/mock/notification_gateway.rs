@@ -0,0 +1,57 @@
+use crate::ports::notification_gateway::{
+    NotificationGateway as NotificationGatewayTrait, OutgoingMessage,
+};
+use crate::ports::notification_service::{NotificationError, Result};
+use async_trait::async_trait;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// NotificationGatewayのモック実装
+///
+/// 実際のメッセージは送信せず、受け付けたメッセージを記録する。
+/// 配信手段の障害を再現できる。
+#[allow(dead_code)]
+pub struct NotificationGateway {
+    sent: Mutex<Vec<OutgoingMessage>>,
+    unavailable: AtomicBool,
+}
+
+#[allow(dead_code)]
+impl NotificationGateway {
+    pub fn new() -> Self {
+        Self {
+            sent: Mutex::new(Vec::new()),
+            unavailable: AtomicBool::new(false),
+        }
+    }
+
+    /// 受け付けたメッセージ（受け付けた順）
+    pub fn sent(&self) -> Vec<OutgoingMessage> {
+        self.sent.lock().unwrap().clone()
+    }
+
+    /// テスト用に配信手段の障害を再現する（`false`で復旧）
+    pub fn set_unavailable(&self, unavailable: bool) {
+        self.unavailable.store(unavailable, Ordering::SeqCst);
+    }
+}
+
+impl Default for NotificationGateway {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl NotificationGatewayTrait for NotificationGateway {
+    /// モックの配信（記録のみ）
+    async fn deliver(&self, message: &OutgoingMessage) -> Result<()> {
+        if self.unavailable.load(Ordering::SeqCst) {
+            return Err(NotificationError::Unavailable(
+                "notification gateway is down".into(),
+            ));
+        }
+        self.sent.lock().unwrap().push(message.clone());
+        Ok(())
+    }
+}
@@ -0,0 +1,69 @@
+use crate::ports::notification_log::{
+    DeliveredNotice, NotificationLog as NotificationLogTrait, Result,
+};
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use uuid::Uuid;
+
+/// NotificationLogのモック実装
+///
+/// 配信記録をメモリに保持する。
+#[allow(dead_code)]
+pub struct NotificationLog {
+    deliveries: Mutex<HashMap<String, DeliveredNotice>>,
+}
+
+#[allow(dead_code)]
+impl NotificationLog {
+    pub fn new() -> Self {
+        Self {
+            deliveries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// 記録された配信の件数
+    pub fn len(&self) -> usize {
+        self.deliveries.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl Default for NotificationLog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl NotificationLogTrait for NotificationLog {
+    async fn is_delivered(&self, dedup_key: &str) -> Result<bool> {
+        Ok(self.deliveries.lock().unwrap().contains_key(dedup_key))
+    }
+
+    /// 同じキーの記録は上書きしない
+    async fn record_delivered(&self, notice: &DeliveredNotice) -> Result<()> {
+        self.deliveries
+            .lock()
+            .unwrap()
+            .entry(notice.dedup_key.clone())
+            .or_insert_with(|| notice.clone());
+        Ok(())
+    }
+
+    async fn find_by_events(&self, event_ids: &[Uuid]) -> Result<Vec<DeliveredNotice>> {
+        let mut found: Vec<_> = self
+            .deliveries
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|d| event_ids.contains(&d.event_id))
+            .cloned()
+            .collect();
+        found.sort_by(|a, b| (a.delivered_at, &a.dedup_key).cmp(&(b.delivered_at, &b.dedup_key)));
+        Ok(found)
+    }
+}
@@ -0,0 +1,56 @@
+use crate::domain::{notification_preferences::NotificationPreferences, value_objects::MemberId};
+use crate::ports::notification_preferences::{
+    NotificationPreferenceStore as NotificationPreferenceStoreTrait, Result,
+};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// NotificationPreferenceStoreのモック実装
+///
+/// 会員の通知設定をメモリに保持する。
+#[allow(dead_code)]
+pub struct NotificationPreferenceStore {
+    preferences: Mutex<HashMap<MemberId, NotificationPreferences>>,
+}
+
+#[allow(dead_code)]
+impl NotificationPreferenceStore {
+    pub fn new() -> Self {
+        Self {
+            preferences: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+impl Default for NotificationPreferenceStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl NotificationPreferenceStoreTrait for NotificationPreferenceStore {
+    async fn get(&self, member_id: MemberId) -> Result<Option<NotificationPreferences>> {
+        Ok(self.preferences.lock().unwrap().get(&member_id).copied())
+    }
+
+    async fn save(
+        &self,
+        member_id: MemberId,
+        preferences: &NotificationPreferences,
+        _updated_at: DateTime<Utc>,
+    ) -> Result<()> {
+        self.preferences
+            .lock()
+            .unwrap()
+            .insert(member_id, *preferences);
+        Ok(())
+    }
+
+    async fn delete(&self, member_id: MemberId) -> Result<()> {
+        self.preferences.lock().unwrap().remove(&member_id);
+        Ok(())
+    }
+}
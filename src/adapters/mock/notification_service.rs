@@ -1,18 +1,70 @@
-use crate::domain::value_objects::MemberId;
-use crate::ports::notification_service::{NotificationService as NotificationServiceTrait, Result};
+use crate::domain::value_objects::{MemberId, OverdueNoticeLevel};
+use crate::ports::notification_service::{
+    NotificationError, NotificationService as NotificationServiceTrait, Result,
+};
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// モックが受け付けた通知
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SentNotice {
+    pub dedup_key: String,
+    pub member_id: MemberId,
+    pub book_title: String,
+    /// 通知の内容（例: "overdue due 2025-01-29T10:30:00+00:00"）
+    pub body: String,
+}
 
 /// NotificationServiceのモック実装
 ///
-/// 実際の通知は送信せず、常に成功を返す。
+/// 実際の通知は送信せず、受け付けた通知を記録する。
+/// 配信手段の障害を再現できる。
 #[allow(dead_code)]
-pub struct NotificationService;
+pub struct NotificationService {
+    sent: Mutex<Vec<SentNotice>>,
+    unavailable: AtomicBool,
+}
 
 #[allow(dead_code)]
 impl NotificationService {
     pub fn new() -> Self {
-        Self
+        Self {
+            sent: Mutex::new(Vec::new()),
+            unavailable: AtomicBool::new(false),
+        }
+    }
+
+    /// 受け付けた通知（受け付けた順）
+    pub fn sent(&self) -> Vec<SentNotice> {
+        self.sent.lock().unwrap().clone()
+    }
+
+    /// テスト用に配信手段の障害を再現する（`false`で復旧）
+    pub fn set_unavailable(&self, unavailable: bool) {
+        self.unavailable.store(unavailable, Ordering::SeqCst);
+    }
+
+    fn accept(
+        &self,
+        dedup_key: &str,
+        member_id: MemberId,
+        book_title: &str,
+        body: String,
+    ) -> Result<()> {
+        if self.unavailable.load(Ordering::SeqCst) {
+            return Err(NotificationError::Unavailable(
+                "notification gateway is down".into(),
+            ));
+        }
+        self.sent.lock().unwrap().push(SentNotice {
+            dedup_key: dedup_key.to_string(),
+            member_id,
+            book_title: book_title.to_string(),
+            body,
+        });
+        Ok(())
     }
 }
 
@@ -24,33 +76,84 @@ impl Default for NotificationService {
 
 #[async_trait]
 impl NotificationServiceTrait for NotificationService {
-    /// モックの延滞通知（何もしない）
+    /// モックの延滞通知（記録のみ）
     async fn send_overdue_notification(
         &self,
-        _member_id: MemberId,
-        _book_title: &str,
-        _due_date: DateTime<Utc>,
+        dedup_key: &str,
+        member_id: MemberId,
+        book_title: &str,
+        due_date: DateTime<Utc>,
     ) -> Result<()> {
-        Ok(())
+        let body = format!("overdue due {}", due_date.to_rfc3339());
+        self.accept(dedup_key, member_id, book_title, body)
     }
 
-    /// モックの延長確認通知（何もしない）
+    /// モックの返却期限のリマインダー（記録のみ）
+    async fn send_due_soon_reminder(
+        &self,
+        dedup_key: &str,
+        member_id: MemberId,
+        book_title: &str,
+        due_date: DateTime<Utc>,
+    ) -> Result<()> {
+        let body = format!("due soon on {}", due_date.to_rfc3339());
+        self.accept(dedup_key, member_id, book_title, body)
+    }
+
+    /// モックの延滞の督促（記録のみ）
+    async fn send_overdue_notice(
+        &self,
+        dedup_key: &str,
+        member_id: MemberId,
+        book_title: &str,
+        due_date: DateTime<Utc>,
+        level: OverdueNoticeLevel,
+    ) -> Result<()> {
+        let body = format!(
+            "overdue notice {} due {}",
+            level.as_str(),
+            due_date.to_rfc3339()
+        );
+        self.accept(dedup_key, member_id, book_title, body)
+    }
+
+    /// モックの紛失の通知（記録のみ）
+    async fn send_lost_declaration(
+        &self,
+        dedup_key: &str,
+        member_id: MemberId,
+        book_title: &str,
+        declared_at: DateTime<Utc>,
+    ) -> Result<()> {
+        let body = format!("declared lost {}", declared_at.to_rfc3339());
+        self.accept(dedup_key, member_id, book_title, body)
+    }
+
+    /// モックの延長確認通知（記録のみ）
     async fn send_extension_confirmation(
         &self,
-        _member_id: MemberId,
-        _book_title: &str,
-        _new_due_date: DateTime<Utc>,
+        dedup_key: &str,
+        member_id: MemberId,
+        book_title: &str,
+        new_due_date: DateTime<Utc>,
     ) -> Result<()> {
-        Ok(())
+        let body = format!("extended until {}", new_due_date.to_rfc3339());
+        self.accept(dedup_key, member_id, book_title, body)
     }
 
-    /// モックの返却確認通知（何もしない）
+    /// モックの返却確認通知（記録のみ）
     async fn send_return_confirmation(
         &self,
-        _member_id: MemberId,
-        _book_title: &str,
-        _was_overdue: bool,
+        dedup_key: &str,
+        member_id: MemberId,
+        book_title: &str,
+        was_overdue: bool,
     ) -> Result<()> {
-        Ok(())
+        let body = if was_overdue {
+            "returned late".to_string()
+        } else {
+            "returned".to_string()
+        };
+        self.accept(dedup_key, member_id, book_title, body)
     }
 }
@@ -0,0 +1,44 @@
+use crate::domain::value_objects::StaffId;
+use crate::ports::staff_service::{Result, StaffRole, StaffService as StaffServiceTrait};
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// StaffServiceのモック実装
+///
+/// 職員IDと役割を保存することで状態を持ったテストをサポート。
+#[allow(dead_code)]
+pub struct StaffService {
+    staff: Mutex<HashMap<StaffId, Vec<StaffRole>>>,
+}
+
+#[allow(dead_code)]
+impl StaffService {
+    pub fn new() -> Self {
+        Self {
+            staff: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// テスト用に職員を登録（既に登録されている場合は役割を置き換える）
+    pub fn add_staff(&self, staff_id: StaffId, roles: impl IntoIterator<Item = StaffRole>) {
+        self.staff
+            .lock()
+            .unwrap()
+            .insert(staff_id, roles.into_iter().collect());
+    }
+}
+
+impl Default for StaffService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl StaffServiceTrait for StaffService {
+    /// 登録された職員の役割を返す
+    async fn roles(&self, staff_id: StaffId) -> Result<Option<Vec<StaffRole>>> {
+        Ok(self.staff.lock().unwrap().get(&staff_id).cloned())
+    }
+}
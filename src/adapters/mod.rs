@@ -1,2 +1,3 @@
+pub mod in_memory;
 pub mod mock;
 pub mod postgres;
@@ -0,0 +1,150 @@
+use crate::domain::value_objects::{MemberId, TenantId};
+use crate::ports::deferred_notices::{
+    DeferredNotice, DeferredNoticeQueue as DeferredNoticeQueueTrait, Result,
+};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::{PgPool, Row, postgres::PgRow, types::Json};
+
+use super::tenant::begin_tenant_transaction;
+
+/// PostgreSQL implementation of DeferredNoticeQueue
+///
+/// Deferred notices are kept in `deferred_notices` (migration 013), keyed by
+/// tenant and dedup key, with the notice itself stored as JSON. Only members
+/// with notification preferences have deferred notices, and they are deleted
+/// together with the preferences. An instance is scoped to one tenant and
+/// every query runs in a tenant-scoped transaction under row-level security.
+#[allow(dead_code)]
+pub struct DeferredNoticeQueue {
+    pool: PgPool,
+    tenant_id: TenantId,
+}
+
+#[allow(dead_code)]
+impl DeferredNoticeQueue {
+    /// Create a DeferredNoticeQueue scoped to the default tenant
+    pub fn new(pool: PgPool) -> Self {
+        Self::for_tenant(pool, TenantId::DEFAULT)
+    }
+
+    /// Create a DeferredNoticeQueue scoped to the given tenant
+    pub fn for_tenant(pool: PgPool, tenant_id: TenantId) -> Self {
+        Self { pool, tenant_id }
+    }
+}
+
+fn notice_from_row(row: &PgRow) -> Result<DeferredNotice> {
+    let Json(notice) = row.try_get("notice")?;
+    Ok(DeferredNotice {
+        member_id: MemberId::from_uuid(row.try_get("member_id")?),
+        notice,
+        queued_at: row.try_get("queued_at")?,
+        deliver_after: row.try_get("deliver_after")?,
+    })
+}
+
+#[async_trait]
+impl DeferredNoticeQueueTrait for DeferredNoticeQueue {
+    /// Insert the notice; a notice already deferred by an earlier dispatch is kept as is
+    async fn enqueue(&self, notice: &DeferredNotice) -> Result<()> {
+        let mut tx = begin_tenant_transaction(&self.pool, self.tenant_id).await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO deferred_notices (
+                tenant_id, dedup_key, member_id, notice, queued_at, deliver_after
+            )
+            VALUES ($1, $2, $3, $4, $5, $6)
+            ON CONFLICT (tenant_id, dedup_key) DO NOTHING
+            "#,
+        )
+        .bind(self.tenant_id.value())
+        .bind(&notice.notice.dedup_key)
+        .bind(notice.member_id.value())
+        .bind(Json(&notice.notice))
+        .bind(notice.queued_at)
+        .bind(notice.deliver_after)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn due(&self, until: DateTime<Utc>) -> Result<Vec<DeferredNotice>> {
+        let mut tx = begin_tenant_transaction(&self.pool, self.tenant_id).await?;
+
+        let rows = sqlx::query(
+            r#"
+            SELECT member_id, notice, queued_at, deliver_after
+            FROM deferred_notices
+            WHERE tenant_id = $1 AND deliver_after <= $2
+            ORDER BY member_id, queued_at, dedup_key
+            "#,
+        )
+        .bind(self.tenant_id.value())
+        .bind(until)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        rows.iter().map(notice_from_row).collect()
+    }
+
+    async fn remove(&self, dedup_keys: &[String]) -> Result<()> {
+        let mut tx = begin_tenant_transaction(&self.pool, self.tenant_id).await?;
+
+        sqlx::query(
+            r#"
+            DELETE FROM deferred_notices
+            WHERE tenant_id = $1 AND dedup_key = ANY($2)
+            "#,
+        )
+        .bind(self.tenant_id.value())
+        .bind(dedup_keys)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn pending_for(&self, member_id: MemberId) -> Result<Vec<DeferredNotice>> {
+        let mut tx = begin_tenant_transaction(&self.pool, self.tenant_id).await?;
+
+        let rows = sqlx::query(
+            r#"
+            SELECT member_id, notice, queued_at, deliver_after
+            FROM deferred_notices
+            WHERE tenant_id = $1 AND member_id = $2
+            ORDER BY queued_at, dedup_key
+            "#,
+        )
+        .bind(self.tenant_id.value())
+        .bind(member_id.value())
+        .fetch_all(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        rows.iter().map(notice_from_row).collect()
+    }
+
+    async fn remove_for(&self, member_id: MemberId) -> Result<()> {
+        let mut tx = begin_tenant_transaction(&self.pool, self.tenant_id).await?;
+
+        sqlx::query(
+            r#"
+            DELETE FROM deferred_notices
+            WHERE tenant_id = $1 AND member_id = $2
+            "#,
+        )
+        .bind(self.tenant_id.value())
+        .bind(member_id.value())
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,299 @@
+use crate::domain::event_schema::EventSchemaError;
+use crate::ports::deferred_notices::DeferredNoticeError;
+use crate::ports::errors::BoxError;
+use crate::ports::event_archive::EventArchiveError;
+use crate::ports::event_audit::EventAuditError;
+use crate::ports::event_store::EventStoreError;
+use crate::ports::idempotency_store::IdempotencyStoreError;
+use crate::ports::job_store::JobStoreError;
+use crate::ports::loan_read_model::LoanReadModelError;
+use crate::ports::member_key_store::MemberKeyStoreError;
+use crate::ports::notification_log::NotificationLogError;
+use crate::ports::notification_preferences::NotificationPreferenceError;
+use crate::ports::tenant_directory::TenantDirectoryError;
+
+use super::event_codec::EventCodecError;
+use super::member_keys::MemberKeyError;
+
+/// How a database failure should be surfaced by the adapters
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Failure {
+    /// The database could not be reached or refused the connection
+    Unavailable,
+    /// The statement lost a race with a concurrent transaction
+    Conflict,
+    /// A stored value could not be decoded
+    Corrupted,
+    /// Anything else: a bad query, a violated constraint, a bug
+    Internal,
+}
+
+/// SQLSTATE codes for failures that a retry can recover from
+///
+/// - `40001` serialization_failure, `40P01` deadlock_detected
+/// - `55P03` lock_not_available
+/// - `23505` unique_violation (a concurrent append took the same version)
+const CONFLICT_CODES: &[&str] = &["40001", "40P01", "55P03", "23505"];
+
+/// SQLSTATE codes for a server that is going away or overloaded
+///
+/// - `57P01` admin_shutdown, `57P02` crash_shutdown, `57P03` cannot_connect_now
+/// - `53300` too_many_connections
+const UNAVAILABLE_CODES: &[&str] = &["57P01", "57P02", "57P03", "53300"];
+
+/// Classify a sqlx error
+pub(crate) fn classify(error: &sqlx::Error) -> Failure {
+    match error {
+        sqlx::Error::Io(_)
+        | sqlx::Error::Tls(_)
+        | sqlx::Error::PoolTimedOut
+        | sqlx::Error::PoolClosed
+        | sqlx::Error::WorkerCrashed => Failure::Unavailable,
+        sqlx::Error::ColumnDecode { .. } | sqlx::Error::Decode(_) => Failure::Corrupted,
+        sqlx::Error::Database(db) => match db.code() {
+            // Class 08: connection exception
+            Some(code) if code.starts_with("08") => Failure::Unavailable,
+            Some(code) if UNAVAILABLE_CODES.contains(&code.as_ref()) => Failure::Unavailable,
+            Some(code) if CONFLICT_CODES.contains(&code.as_ref()) => Failure::Conflict,
+            _ => Failure::Internal,
+        },
+        _ => Failure::Internal,
+    }
+}
+
+/// Classify an error raised through a `BoxError` (e.g. by the member keyring)
+fn classify_boxed(error: &BoxError) -> Failure {
+    if let Some(e) = error.downcast_ref::<sqlx::Error>() {
+        classify(e)
+    } else if let Some(e) = error.downcast_ref::<MemberKeyError>() {
+        match e {
+            MemberKeyError::Decryption(_) | MemberKeyError::MalformedEnvelope => Failure::Corrupted,
+            MemberKeyError::KeyGeneration | MemberKeyError::Encryption => Failure::Internal,
+            // A concurrent erasure removed the key; retrying loads the keyring again
+            MemberKeyError::MissingKey(_) => Failure::Conflict,
+        }
+    } else if error.is::<serde_json::Error>() || error.is::<EventCodecError>() {
+        Failure::Corrupted
+    } else {
+        Failure::Internal
+    }
+}
+
+impl EventStoreError {
+    fn from_failure(failure: Failure, source: BoxError) -> Self {
+        match failure {
+            Failure::Unavailable => EventStoreError::Unavailable(source),
+            Failure::Conflict => EventStoreError::Conflict(source),
+            Failure::Corrupted => EventStoreError::Corrupted(source),
+            Failure::Internal => EventStoreError::Internal(source),
+        }
+    }
+}
+
+impl From<sqlx::Error> for EventStoreError {
+    fn from(error: sqlx::Error) -> Self {
+        Self::from_failure(classify(&error), error.into())
+    }
+}
+
+impl From<BoxError> for EventStoreError {
+    fn from(error: BoxError) -> Self {
+        Self::from_failure(classify_boxed(&error), error)
+    }
+}
+
+impl From<serde_json::Error> for EventStoreError {
+    fn from(error: serde_json::Error) -> Self {
+        EventStoreError::Corrupted(error.into())
+    }
+}
+
+impl From<EventCodecError> for EventStoreError {
+    fn from(error: EventCodecError) -> Self {
+        EventStoreError::Corrupted(error.into())
+    }
+}
+
+impl From<EventSchemaError> for EventStoreError {
+    /// Writing an event that does not match its schema is a bug, not bad data
+    fn from(error: EventSchemaError) -> Self {
+        EventStoreError::Internal(error.into())
+    }
+}
+
+impl LoanReadModelError {
+    fn from_failure(failure: Failure, source: BoxError) -> Self {
+        match failure {
+            Failure::Unavailable => LoanReadModelError::Unavailable(source),
+            Failure::Conflict => LoanReadModelError::Conflict(source),
+            Failure::Corrupted => LoanReadModelError::Corrupted(source),
+            Failure::Internal => LoanReadModelError::Internal(source),
+        }
+    }
+}
+
+impl From<sqlx::Error> for LoanReadModelError {
+    fn from(error: sqlx::Error) -> Self {
+        Self::from_failure(classify(&error), error.into())
+    }
+}
+
+impl From<sqlx::Error> for JobStoreError {
+    /// Jobs are retried on their next scheduled run, so a lost race is as
+    /// transient as a lost connection
+    fn from(error: sqlx::Error) -> Self {
+        match classify(&error) {
+            Failure::Unavailable | Failure::Conflict => JobStoreError::Unavailable(error.into()),
+            Failure::Corrupted | Failure::Internal => JobStoreError::Internal(error.into()),
+        }
+    }
+}
+
+impl From<sqlx::Error> for IdempotencyStoreError {
+    /// A claim that lost a race is seen as taken on the next attempt, so a
+    /// lost race is as transient as a lost connection
+    fn from(error: sqlx::Error) -> Self {
+        match classify(&error) {
+            Failure::Unavailable | Failure::Conflict => {
+                IdempotencyStoreError::Unavailable(error.into())
+            }
+            Failure::Corrupted | Failure::Internal => IdempotencyStoreError::Internal(error.into()),
+        }
+    }
+}
+
+impl From<sqlx::Error> for NotificationLogError {
+    /// Undelivered notices are picked up again by the next dispatch, so a
+    /// lost race is as transient as a lost connection
+    fn from(error: sqlx::Error) -> Self {
+        match classify(&error) {
+            Failure::Unavailable | Failure::Conflict => {
+                NotificationLogError::Unavailable(error.into())
+            }
+            Failure::Corrupted | Failure::Internal => NotificationLogError::Internal(error.into()),
+        }
+    }
+}
+
+impl From<sqlx::Error> for NotificationPreferenceError {
+    fn from(error: sqlx::Error) -> Self {
+        match classify(&error) {
+            Failure::Unavailable | Failure::Conflict => {
+                NotificationPreferenceError::Unavailable(error.into())
+            }
+            Failure::Corrupted | Failure::Internal => {
+                NotificationPreferenceError::Internal(error.into())
+            }
+        }
+    }
+}
+
+impl From<sqlx::Error> for DeferredNoticeError {
+    /// Deferred notices stay queued until delivered, so a lost race is as
+    /// transient as a lost connection
+    fn from(error: sqlx::Error) -> Self {
+        match classify(&error) {
+            Failure::Unavailable | Failure::Conflict => {
+                DeferredNoticeError::Unavailable(error.into())
+            }
+            Failure::Corrupted | Failure::Internal => DeferredNoticeError::Internal(error.into()),
+        }
+    }
+}
+
+impl From<sqlx::Error> for EventArchiveError {
+    /// Archiving locks `events`, so a lost race is retried like a lost connection
+    fn from(error: sqlx::Error) -> Self {
+        match classify(&error) {
+            Failure::Unavailable | Failure::Conflict => {
+                EventArchiveError::Unavailable(error.into())
+            }
+            Failure::Corrupted | Failure::Internal => EventArchiveError::Internal(error.into()),
+        }
+    }
+}
+
+impl From<sqlx::Error> for EventAuditError {
+    /// Verification only reads, so a lost race is as transient as a lost connection
+    fn from(error: sqlx::Error) -> Self {
+        match classify(&error) {
+            Failure::Unavailable | Failure::Conflict => EventAuditError::Unavailable(error.into()),
+            Failure::Corrupted => EventAuditError::Corrupted(error.into()),
+            Failure::Internal => EventAuditError::Internal(error.into()),
+        }
+    }
+}
+
+impl From<sqlx::Error> for MemberKeyStoreError {
+    /// Shredding is idempotent, so a lost race can simply be retried
+    fn from(error: sqlx::Error) -> Self {
+        match classify(&error) {
+            Failure::Unavailable | Failure::Conflict => {
+                MemberKeyStoreError::Unavailable(error.into())
+            }
+            Failure::Corrupted | Failure::Internal => MemberKeyStoreError::Internal(error.into()),
+        }
+    }
+}
+
+impl From<sqlx::Error> for TenantDirectoryError {
+    fn from(error: sqlx::Error) -> Self {
+        match classify(&error) {
+            Failure::Unavailable | Failure::Conflict => {
+                TenantDirectoryError::Unavailable(error.into())
+            }
+            Failure::Corrupted => TenantDirectoryError::Corrupted(error.into()),
+            Failure::Internal => TenantDirectoryError::Internal(error.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ports::errors::Classified;
+
+    #[test]
+    fn test_connection_failures_are_transient() {
+        let io = sqlx::Error::Io(std::io::Error::from(std::io::ErrorKind::ConnectionReset));
+        assert_eq!(classify(&io), Failure::Unavailable);
+        assert_eq!(classify(&sqlx::Error::PoolTimedOut), Failure::Unavailable);
+
+        let error = EventStoreError::from(sqlx::Error::PoolClosed);
+        assert!(matches!(error, EventStoreError::Unavailable(_)));
+        assert!(error.is_retryable());
+    }
+
+    #[test]
+    fn test_decode_failures_are_permanent() {
+        let error = LoanReadModelError::from(sqlx::Error::Decode("bad status".into()));
+        assert!(matches!(error, LoanReadModelError::Corrupted(_)));
+        assert!(!error.is_retryable());
+
+        let error = EventStoreError::from(sqlx::Error::RowNotFound);
+        assert!(matches!(error, EventStoreError::Internal(_)));
+        assert!(!error.is_retryable());
+
+        let error = EventAuditError::from(sqlx::Error::Decode("short hash".into()));
+        assert!(matches!(error, EventAuditError::Corrupted(_)));
+        assert!(!error.is_retryable());
+    }
+
+    #[test]
+    fn test_boxed_errors_keep_their_classification() {
+        let boxed: BoxError = sqlx::Error::PoolTimedOut.into();
+        assert!(EventStoreError::from(boxed).is_retryable());
+
+        let boxed: BoxError = MemberKeyError::MalformedEnvelope.into();
+        assert!(matches!(
+            EventStoreError::from(boxed),
+            EventStoreError::Corrupted(_)
+        ));
+
+        let boxed: BoxError = "unexpected".into();
+        assert!(matches!(
+            EventStoreError::from(boxed),
+            EventStoreError::Internal(_)
+        ));
+    }
+}
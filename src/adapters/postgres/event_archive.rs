@@ -0,0 +1,258 @@
+use crate::ports::event_archive::{
+    EventArchive as EventArchiveTrait, EventArchiveError, EventPartition, PartitionArchival, Result,
+};
+use async_trait::async_trait;
+use chrono::{DateTime, TimeZone, Utc};
+use sqlx::{PgPool, Row};
+use uuid::Uuid;
+
+/// Prefix of the yearly partitions of `events` (e.g. `events_y2020`)
+const PARTITION_PREFIX: &str = "events_y";
+
+/// Schema that archived partitions are moved to
+const ARCHIVE_SCHEMA: &str = "event_archive";
+
+/// PostgreSQL implementation of EventArchive
+///
+/// `events` is range-partitioned by `occurred_at` into one partition per
+/// UTC year, plus a default partition (migration 007). Archiving a year
+/// detaches its partition from `events`, moves it to the `event_archive`
+/// schema and attaches it to `event_archive.events`, so the rows are never
+/// copied. The `event_log` view covers both, which is how `EventStore`
+/// still finds archived aggregates.
+///
+/// Partitions are shared by all tenants, so this adapter is not scoped to
+/// a tenant; it switches `app.tenant_id` per tenant where it reads events.
+#[allow(dead_code)]
+pub struct EventArchive {
+    pool: PgPool,
+}
+
+#[allow(dead_code)]
+impl EventArchive {
+    /// Create a new EventArchive with a PostgreSQL connection pool
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Parse the year out of a yearly partition name
+    fn partition_year(name: &str) -> Option<i32> {
+        let year = name.strip_prefix(PARTITION_PREFIX)?;
+        if year.len() != 4 || !year.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        year.parse().ok()
+    }
+
+    /// The `[from, to)` range covered by a year's partition
+    fn year_range(year: i32) -> Result<(DateTime<Utc>, DateTime<Utc>)> {
+        let start = |y: i32| {
+            Utc.with_ymd_and_hms(y, 1, 1, 0, 0, 0)
+                .single()
+                .ok_or_else(|| {
+                    EventArchiveError::Internal(format!("Year {y} is out of range").into())
+                })
+        };
+        Ok((start(year)?, start(year + 1)?))
+    }
+}
+
+#[async_trait]
+impl EventArchiveTrait for EventArchive {
+    /// Create the yearly partitions in the given range
+    ///
+    /// Also creates the partitions of years whose events have landed in the
+    /// default partition, moving those events into them.
+    /// Years that have been archived are skipped by `create_event_partition`.
+    async fn ensure_partitions(&self, from_year: i32, through_year: i32) -> Result<Vec<String>> {
+        let mut tx = self.pool.begin().await?;
+
+        // The default partition has no row-level security of its own
+        let default_years: Vec<i32> = sqlx::query_scalar(
+            r#"
+            SELECT DISTINCT EXTRACT(YEAR FROM occurred_at AT TIME ZONE 'UTC')::INTEGER
+            FROM events_default
+            "#,
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+
+        let mut years: Vec<i32> = (from_year..=through_year).chain(default_years).collect();
+        years.sort_unstable();
+        years.dedup();
+
+        let mut created = Vec::new();
+        for year in years {
+            let was_created: bool = sqlx::query_scalar("SELECT create_event_partition($1)")
+                .bind(year)
+                .fetch_one(&mut *tx)
+                .await?;
+            if was_created {
+                created.push(format!("{PARTITION_PREFIX}{year}"));
+            }
+        }
+
+        tx.commit().await?;
+        Ok(created)
+    }
+
+    /// List the yearly partitions of `events` and `event_archive.events`
+    async fn partitions(&self) -> Result<Vec<EventPartition>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT c.relname::text AS name, n.nspname = $1 AS archived
+            FROM pg_inherits i
+            JOIN pg_class c ON c.oid = i.inhrelid
+            JOIN pg_namespace n ON n.oid = c.relnamespace
+            WHERE i.inhparent IN ('events'::regclass, 'event_archive.events'::regclass)
+            "#,
+        )
+        .bind(ARCHIVE_SCHEMA)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut partitions = Vec::with_capacity(rows.len());
+        for row in rows {
+            let name: String = row.get("name");
+            let Some(year) = Self::partition_year(&name) else {
+                continue;
+            };
+            let (from, to) = Self::year_range(year)?;
+            partitions.push(EventPartition {
+                name,
+                year,
+                from,
+                to,
+                archived: row.get("archived"),
+            });
+        }
+        partitions.sort_by_key(|p| p.year);
+
+        Ok(partitions)
+    }
+
+    /// Move a live yearly partition to the archive
+    ///
+    /// Writes to `events` are blocked while the partition is checked and
+    /// moved, so a loan cannot be reopened in between. A loan counts as
+    /// closed when it has a `BookReturned` event anywhere in the event log,
+    /// since no event follows a return.
+    async fn archive_partition(&self, name: &str) -> Result<PartitionArchival> {
+        let year = Self::partition_year(name)
+            .ok_or_else(|| EventArchiveError::NotALivePartition(name.to_string()))?;
+        let (from, to) = Self::year_range(year)?;
+
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("LOCK TABLE events IN SHARE ROW EXCLUSIVE MODE")
+            .execute(&mut *tx)
+            .await?;
+
+        let is_live: bool = sqlx::query_scalar(
+            r#"
+            SELECT EXISTS (
+                SELECT 1 FROM pg_inherits
+                WHERE inhparent = 'events'::regclass AND inhrelid = to_regclass($1)
+            )
+            "#,
+        )
+        .bind(name)
+        .fetch_one(&mut *tx)
+        .await?;
+        if !is_live {
+            return Err(EventArchiveError::NotALivePartition(name.to_string()));
+        }
+
+        // Row-level security applies to `events`, so check tenant by tenant
+        let tenants: Vec<Uuid> = sqlx::query_scalar("SELECT tenant_id FROM tenants")
+            .fetch_all(&mut *tx)
+            .await?;
+
+        let mut open_loans: i64 = 0;
+        let mut event_count: i64 = 0;
+        for tenant_id in tenants {
+            sqlx::query("SELECT set_config('app.tenant_id', $1, true)")
+                .bind(tenant_id.to_string())
+                .execute(&mut *tx)
+                .await?;
+
+            let row = sqlx::query(
+                r#"
+                SELECT
+                    COUNT(*) AS event_count,
+                    COUNT(DISTINCT e.aggregate_id) FILTER (
+                        WHERE e.aggregate_type = 'Loan'
+                          AND NOT EXISTS (
+                              SELECT 1 FROM event_log r
+                              WHERE r.tenant_id = $1
+                                AND r.aggregate_id = e.aggregate_id
+                                AND r.event_type = 'BookReturned'
+                          )
+                    ) AS open_loans
+                FROM events e
+                WHERE e.tenant_id = $1 AND e.occurred_at >= $2 AND e.occurred_at < $3
+                "#,
+            )
+            .bind(tenant_id)
+            .bind(from)
+            .bind(to)
+            .fetch_one(&mut *tx)
+            .await?;
+
+            event_count += row.get::<i64, _>("event_count");
+            open_loans += row.get::<i64, _>("open_loans");
+        }
+
+        if open_loans > 0 {
+            tx.rollback().await?;
+            return Ok(PartitionArchival::OpenLoans {
+                loan_count: open_loans as u64,
+            });
+        }
+
+        // `name` has been validated as events_yNNNN, so it is safe to interpolate
+        sqlx::query(&format!("ALTER TABLE events DETACH PARTITION {name}"))
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query(&format!("ALTER TABLE {name} SET SCHEMA {ARCHIVE_SCHEMA}"))
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query(&format!(
+            "ALTER TABLE {ARCHIVE_SCHEMA}.events ATTACH PARTITION {ARCHIVE_SCHEMA}.{name} \
+             FOR VALUES FROM ('{}') TO ('{}')",
+            from.to_rfc3339(),
+            to.to_rfc3339()
+        ))
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(PartitionArchival::Archived {
+            event_count: event_count as u64,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_partition_year() {
+        assert_eq!(EventArchive::partition_year("events_y2020"), Some(2020));
+        assert_eq!(EventArchive::partition_year("events_default"), None);
+        assert_eq!(EventArchive::partition_year("events_y20"), None);
+        assert_eq!(
+            EventArchive::partition_year("events_y2020; DROP TABLE x"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_year_range() {
+        let (from, to) = EventArchive::year_range(2020).unwrap();
+        assert_eq!(from.to_rfc3339(), "2020-01-01T00:00:00+00:00");
+        assert_eq!(to.to_rfc3339(), "2021-01-01T00:00:00+00:00");
+    }
+}
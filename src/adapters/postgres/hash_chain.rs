@@ -0,0 +1,551 @@
+use crate::domain::value_objects::TenantId;
+use crate::ports::event_audit::{
+    BrokenLink, BrokenLinkReason, ChainHash, ChainHead, ChainVerification,
+    EventAudit as EventAuditTrait, Result,
+};
+use async_trait::async_trait;
+use chrono::{DateTime, SubsecRound, Utc};
+use sha2::{Digest, Sha256};
+use sqlx::{PgPool, Postgres, Row, Transaction};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use super::event_codec::EventCodec;
+use super::tenant::begin_tenant_transaction;
+
+/// Hash used as the previous link of the first event in a chain
+pub(crate) const GENESIS_HASH: ChainHash = [0; 32];
+
+/// Version of the hash written for new events (the `hash_version` column)
+///
+/// Version 1 did not cover the tenant and the occurrence time. Events hashed
+/// with it keep their hashes, so existing chains and anchors still verify.
+pub(crate) const HASH_VERSION: i16 = 2;
+
+/// Domain separators so chain hashes cannot be confused with other SHA-256
+/// values, or with hashes of the other version
+const HASH_DOMAIN_V1: &[u8] = b"rusty-library/event-chain/v1";
+const HASH_DOMAIN_V2: &[u8] = b"rusty-library/event-chain/v2";
+
+/// Number of events fetched per page while verifying
+const VERIFY_PAGE_SIZE: i64 = 1000;
+
+/// The fields of an event that are covered by its hash
+///
+/// The hash covers the stored `event_data` (with member identifiers
+/// encrypted), so the chain still verifies after a member key is shredded.
+/// Rows stored with a binary codec are hashed as their decoded JSON value,
+/// so converting a row between codecs keeps its hash.
+/// `occurred_at` is covered at the microsecond precision Postgres stores;
+/// use `stored_occurred_at` for the value that is both hashed and inserted.
+pub(crate) struct LinkInput<'a> {
+    pub tenant_id: TenantId,
+    pub event_id: Uuid,
+    pub aggregate_id: Uuid,
+    pub aggregate_type: &'a str,
+    pub aggregate_version: i32,
+    pub event_type: &'a str,
+    pub event_data: &'a serde_json::Value,
+    pub occurred_at: DateTime<Utc>,
+}
+
+/// The occurrence time as stored, truncated to microseconds
+pub(crate) fn stored_occurred_at(occurred_at: DateTime<Utc>) -> DateTime<Utc> {
+    occurred_at.trunc_subsecs(6)
+}
+
+/// The chain columns of one event
+pub(crate) struct Link {
+    pub prev_hash: ChainHash,
+    pub aggregate_prev_hash: ChainHash,
+    pub event_hash: ChainHash,
+}
+
+/// Compute the hash of an event from its content and both previous links
+///
+/// Variable-length fields are length-prefixed so that field boundaries
+/// cannot be shifted without changing the hash.
+/// `serde_json::Value` serializes object keys in sorted order, so the JSON
+/// is the same before insertion and after reading it back from JSONB.
+/// Returns None for an unknown hash version.
+pub(crate) fn compute_event_hash(
+    hash_version: i16,
+    prev_hash: &ChainHash,
+    aggregate_prev_hash: &ChainHash,
+    input: &LinkInput<'_>,
+) -> serde_json::Result<Option<ChainHash>> {
+    let event_json = serde_json::to_vec(input.event_data)?;
+
+    let mut hasher = Sha256::new();
+    match hash_version {
+        1 => hasher.update(HASH_DOMAIN_V1),
+        2 => {
+            hasher.update(HASH_DOMAIN_V2);
+            hasher.update(input.tenant_id.value().as_bytes());
+        }
+        _ => return Ok(None),
+    }
+    hasher.update(prev_hash);
+    hasher.update(aggregate_prev_hash);
+    hasher.update(input.event_id.as_bytes());
+    hasher.update(input.aggregate_id.as_bytes());
+    update_prefixed(&mut hasher, input.aggregate_type.as_bytes());
+    hasher.update(input.aggregate_version.to_be_bytes());
+    update_prefixed(&mut hasher, input.event_type.as_bytes());
+    update_prefixed(&mut hasher, &event_json);
+    if hash_version >= 2 {
+        hasher.update(input.occurred_at.timestamp_micros().to_be_bytes());
+    }
+
+    Ok(Some(hasher.finalize().into()))
+}
+
+fn update_prefixed(hasher: &mut Sha256, bytes: &[u8]) {
+    hasher.update((bytes.len() as u64).to_be_bytes());
+    hasher.update(bytes);
+}
+
+/// The current heads of the tenant chain and of the aggregates being written
+///
+/// Linking an event advances both heads, so a batch of events can be
+/// linked one after another before being inserted.
+pub(crate) struct ChainLinks {
+    global: ChainHash,
+    aggregates: HashMap<Uuid, ChainHash>,
+}
+
+impl ChainLinks {
+    /// Take the tenant's chain lock and load the current heads
+    ///
+    /// The lock is a transaction-level advisory lock, so appends within a
+    /// tenant are serialized until the surrounding transaction ends.
+    /// This keeps the global chain in sequence_number order, and is what
+    /// keeps aggregate versions unique now that the partitioned `events`
+    /// table cannot enforce it. Heads are read from `event_log`, so archived
+    /// events still anchor the chain.
+    pub(crate) async fn lock(
+        tx: &mut Transaction<'_, Postgres>,
+        tenant_id: TenantId,
+        aggregate_ids: &[Uuid],
+    ) -> sqlx::Result<Self> {
+        sqlx::query(
+            "SELECT pg_advisory_xact_lock(hashtextextended('event_chain:' || $1::text, 0))",
+        )
+        .bind(tenant_id.value())
+        .execute(&mut **tx)
+        .await?;
+
+        let global: Option<Vec<u8>> = sqlx::query_scalar(
+            r#"
+            SELECT event_hash
+            FROM event_log
+            WHERE tenant_id = $1 AND event_hash IS NOT NULL
+            ORDER BY sequence_number DESC
+            LIMIT 1
+            "#,
+        )
+        .bind(tenant_id.value())
+        .fetch_optional(&mut **tx)
+        .await?;
+
+        let rows = sqlx::query(
+            r#"
+            SELECT DISTINCT ON (aggregate_id) aggregate_id, event_hash
+            FROM event_log
+            WHERE tenant_id = $1 AND aggregate_id = ANY($2) AND event_hash IS NOT NULL
+            ORDER BY aggregate_id, aggregate_version DESC
+            "#,
+        )
+        .bind(tenant_id.value())
+        .bind(aggregate_ids)
+        .fetch_all(&mut **tx)
+        .await?;
+
+        let mut aggregates = HashMap::with_capacity(rows.len());
+        for row in rows {
+            let hash: Vec<u8> = row.get("event_hash");
+            aggregates.insert(row.get("aggregate_id"), to_hash(hash)?);
+        }
+
+        Ok(Self {
+            global: global.map(to_hash).transpose()?.unwrap_or(GENESIS_HASH),
+            aggregates,
+        })
+    }
+
+    /// Link the next event to the current heads and advance them
+    ///
+    /// The event is hashed with `HASH_VERSION`.
+    pub(crate) fn link(&mut self, input: &LinkInput<'_>) -> serde_json::Result<Link> {
+        let prev_hash = self.global;
+        let aggregate_prev_hash = self.aggregate_head(input.aggregate_id);
+        let event_hash = compute_event_hash(HASH_VERSION, &prev_hash, &aggregate_prev_hash, input)?
+            .expect("HASH_VERSION is a known hash version");
+
+        self.global = event_hash;
+        self.aggregates.insert(input.aggregate_id, event_hash);
+
+        Ok(Link {
+            prev_hash,
+            aggregate_prev_hash,
+            event_hash,
+        })
+    }
+
+    fn aggregate_head(&self, aggregate_id: Uuid) -> ChainHash {
+        self.aggregates
+            .get(&aggregate_id)
+            .copied()
+            .unwrap_or(GENESIS_HASH)
+    }
+}
+
+fn to_hash(bytes: Vec<u8>) -> sqlx::Result<ChainHash> {
+    bytes
+        .try_into()
+        .map_err(|_| sqlx::Error::Decode("event hash must be 32 bytes".into()))
+}
+
+/// A stored event together with its chain columns, as read for verification
+struct ChainRow {
+    sequence_number: i64,
+    event_id: Uuid,
+    aggregate_id: Uuid,
+    aggregate_type: String,
+    aggregate_version: i32,
+    event_type: String,
+    /// None when the stored data cannot be decoded with its codec
+    event_data: Option<serde_json::Value>,
+    occurred_at: DateTime<Utc>,
+    hash_version: i16,
+    event_hash: Option<Vec<u8>>,
+    prev_hash: Option<Vec<u8>>,
+    aggregate_prev_hash: Option<Vec<u8>>,
+}
+
+/// PostgreSQL implementation of EventAudit
+///
+/// Verifies the hash chain written by `EventStore::append` and `import`.
+/// Scoped to a single tenant like the EventStore.
+/// Reads the `event_log` view, so archived partitions are verified too.
+#[allow(dead_code)]
+pub struct EventAudit {
+    pool: PgPool,
+    tenant_id: TenantId,
+}
+
+#[allow(dead_code)]
+impl EventAudit {
+    /// Create a new EventAudit scoped to the default tenant
+    pub fn new(pool: PgPool) -> Self {
+        Self::for_tenant(pool, TenantId::DEFAULT)
+    }
+
+    /// Create a new EventAudit scoped to the given tenant
+    pub fn for_tenant(pool: PgPool, tenant_id: TenantId) -> Self {
+        Self { pool, tenant_id }
+    }
+
+    /// Fetch one page of the tenant's events with their chain columns
+    async fn fetch_page(&self, after_sequence: i64) -> Result<Vec<ChainRow>> {
+        let mut tx = begin_tenant_transaction(&self.pool, self.tenant_id).await?;
+
+        let rows = sqlx::query(
+            r#"
+            SELECT
+                sequence_number,
+                event_id,
+                aggregate_id,
+                aggregate_type,
+                aggregate_version,
+                event_type,
+                event_codec,
+                event_data,
+                event_payload,
+                occurred_at,
+                hash_version,
+                event_hash,
+                prev_hash,
+                aggregate_prev_hash
+            FROM event_log
+            WHERE tenant_id = $1 AND sequence_number > $2
+            ORDER BY sequence_number ASC
+            LIMIT $3
+            "#,
+        )
+        .bind(self.tenant_id.value())
+        .bind(after_sequence)
+        .bind(VERIFY_PAGE_SIZE)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| ChainRow {
+                sequence_number: row.get("sequence_number"),
+                event_id: row.get("event_id"),
+                aggregate_id: row.get("aggregate_id"),
+                aggregate_type: row.get("aggregate_type"),
+                aggregate_version: row.get("aggregate_version"),
+                event_type: row.get("event_type"),
+                event_data: EventCodec::decode_row(&row).ok(),
+                occurred_at: row.get("occurred_at"),
+                hash_version: row.get("hash_version"),
+                event_hash: row.get("event_hash"),
+                prev_hash: row.get("prev_hash"),
+                aggregate_prev_hash: row.get("aggregate_prev_hash"),
+            })
+            .collect())
+    }
+}
+
+/// Check one event against the expected heads
+///
+/// Returns the event's hash when the link is intact. The hash covers the
+/// tenant being verified, so an event copied from another tenant does not
+/// verify.
+fn check_link(
+    tenant_id: TenantId,
+    links: &ChainLinks,
+    row: ChainRow,
+) -> std::result::Result<ChainHash, BrokenLinkReason> {
+    let (Some(event_hash), Some(prev_hash), Some(aggregate_prev_hash)) =
+        (row.event_hash, row.prev_hash, row.aggregate_prev_hash)
+    else {
+        return Err(BrokenLinkReason::MissingHash);
+    };
+
+    if prev_hash != links.global {
+        return Err(BrokenLinkReason::GlobalLinkMismatch);
+    }
+    if aggregate_prev_hash != links.aggregate_head(row.aggregate_id) {
+        return Err(BrokenLinkReason::AggregateLinkMismatch);
+    }
+    // Undecodable data has been tampered with just like edited data
+    let Some(event_data) = &row.event_data else {
+        return Err(BrokenLinkReason::HashMismatch);
+    };
+
+    let input = LinkInput {
+        tenant_id,
+        event_id: row.event_id,
+        aggregate_id: row.aggregate_id,
+        aggregate_type: &row.aggregate_type,
+        aggregate_version: row.aggregate_version,
+        event_type: &row.event_type,
+        event_data,
+        occurred_at: row.occurred_at,
+    };
+    // An unknown hash version has been tampered with as well
+    let Ok(Some(expected)) = compute_event_hash(
+        row.hash_version,
+        &links.global,
+        &links.aggregate_head(row.aggregate_id),
+        &input,
+    ) else {
+        return Err(BrokenLinkReason::HashMismatch);
+    };
+
+    if event_hash != expected {
+        return Err(BrokenLinkReason::HashMismatch);
+    }
+    Ok(expected)
+}
+
+#[async_trait]
+impl EventAuditTrait for EventAudit {
+    /// Walk the tenant's events in sequence order and report the first broken link
+    ///
+    /// Events recorded before the chain was introduced have no hashes;
+    /// they are counted as unchained as long as they precede the first
+    /// hashed event.
+    async fn verify_chain(&self) -> Result<ChainVerification> {
+        let mut links = ChainLinks {
+            global: GENESIS_HASH,
+            aggregates: HashMap::new(),
+        };
+        let mut verification = ChainVerification {
+            verified_events: 0,
+            unchained_events: 0,
+            first_broken_link: None,
+        };
+        let mut after = 0;
+
+        loop {
+            let page = self.fetch_page(after).await?;
+            let finished = (page.len() as i64) < VERIFY_PAGE_SIZE;
+
+            for row in page {
+                after = row.sequence_number;
+
+                if row.event_hash.is_none() && verification.verified_events == 0 {
+                    verification.unchained_events += 1;
+                    continue;
+                }
+
+                let (sequence_number, event_id, aggregate_id) =
+                    (row.sequence_number, row.event_id, row.aggregate_id);
+                match check_link(self.tenant_id, &links, row) {
+                    Ok(event_hash) => {
+                        links.global = event_hash;
+                        links.aggregates.insert(aggregate_id, event_hash);
+                        verification.verified_events += 1;
+                    }
+                    Err(reason) => {
+                        verification.first_broken_link = Some(BrokenLink {
+                            sequence_number,
+                            event_id,
+                            aggregate_id,
+                            reason,
+                        });
+                        return Ok(verification);
+                    }
+                }
+            }
+
+            if finished {
+                return Ok(verification);
+            }
+        }
+    }
+
+    /// Get the latest hashed event of the tenant
+    async fn chain_head(&self) -> Result<Option<ChainHead>> {
+        let mut tx = begin_tenant_transaction(&self.pool, self.tenant_id).await?;
+
+        let row = sqlx::query(
+            r#"
+            SELECT
+                sequence_number,
+                event_hash,
+                COUNT(*) OVER () AS event_count
+            FROM event_log
+            WHERE tenant_id = $1 AND event_hash IS NOT NULL
+            ORDER BY sequence_number DESC
+            LIMIT 1
+            "#,
+        )
+        .bind(self.tenant_id.value())
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        match row {
+            Some(row) => Ok(Some(ChainHead {
+                sequence_number: row.get("sequence_number"),
+                event_hash: to_hash(row.get("event_hash"))?,
+                event_count: row.get("event_count"),
+            })),
+            None => Ok(None),
+        }
+    }
+
+    /// Get the hash of the event with the given sequence number
+    async fn hash_at(&self, sequence_number: i64) -> Result<Option<ChainHash>> {
+        let mut tx = begin_tenant_transaction(&self.pool, self.tenant_id).await?;
+
+        let hash: Option<Option<Vec<u8>>> = sqlx::query_scalar(
+            "SELECT event_hash FROM event_log WHERE tenant_id = $1 AND sequence_number = $2",
+        )
+        .bind(self.tenant_id.value())
+        .bind(sequence_number)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(hash.flatten().map(to_hash).transpose()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::events::{BookReturned, DomainEvent};
+    use crate::domain::value_objects::{BookId, LoanId, MemberId};
+    use chrono::Utc;
+
+    #[test]
+    fn test_event_hash_covers_previous_links_and_content() {
+        let loan_id = LoanId::new();
+        let event = DomainEvent::BookReturned(BookReturned {
+            loan_id,
+            book_id: BookId::new(),
+            member_id: MemberId::new(),
+            returned_at: Utc::now(),
+            was_overdue: false,
+        });
+        let event_data = serde_json::to_value(&event).unwrap();
+        let input = LinkInput {
+            tenant_id: TenantId::DEFAULT,
+            event_id: Uuid::new_v4(),
+            aggregate_id: loan_id.value(),
+            aggregate_type: "Loan",
+            aggregate_version: 2,
+            event_type: "BookReturned",
+            event_data: &event_data,
+            occurred_at: stored_occurred_at(Utc::now()),
+        };
+        let hash_of = |prev: &ChainHash, aggregate_prev: &ChainHash, input: &LinkInput<'_>| {
+            compute_event_hash(HASH_VERSION, prev, aggregate_prev, input)
+                .unwrap()
+                .unwrap()
+        };
+
+        let hash = hash_of(&GENESIS_HASH, &GENESIS_HASH, &input);
+        assert_eq!(hash, hash_of(&GENESIS_HASH, &GENESIS_HASH, &input));
+        assert_ne!(hash, hash_of(&[1; 32], &GENESIS_HASH, &input));
+        assert_ne!(hash, hash_of(&GENESIS_HASH, &[1; 32], &input));
+
+        let tampered = [
+            LinkInput {
+                aggregate_version: 3,
+                ..input
+            },
+            LinkInput {
+                tenant_id: TenantId::new(),
+                ..input
+            },
+            LinkInput {
+                occurred_at: input.occurred_at + chrono::Duration::microseconds(1),
+                ..input
+            },
+        ];
+        for tampered in &tampered {
+            assert_ne!(hash, hash_of(&GENESIS_HASH, &GENESIS_HASH, tampered));
+        }
+    }
+
+    #[test]
+    fn test_event_hash_versions_are_distinct() {
+        let event_data = serde_json::json!({});
+        let input = LinkInput {
+            tenant_id: TenantId::DEFAULT,
+            event_id: Uuid::new_v4(),
+            aggregate_id: Uuid::new_v4(),
+            aggregate_type: "Loan",
+            aggregate_version: 1,
+            event_type: "BookLoaned",
+            event_data: &event_data,
+            occurred_at: stored_occurred_at(Utc::now()),
+        };
+        let hash = |version| compute_event_hash(version, &GENESIS_HASH, &GENESIS_HASH, &input);
+
+        // 旧バージョンのハッシュは発生日時とテナントを含まない
+        let v1 = hash(1).unwrap().unwrap();
+        let other_tenant = LinkInput {
+            tenant_id: TenantId::new(),
+            occurred_at: input.occurred_at - chrono::Duration::days(1),
+            ..input
+        };
+        assert_eq!(
+            Some(v1),
+            compute_event_hash(1, &GENESIS_HASH, &GENESIS_HASH, &other_tenant).unwrap()
+        );
+        assert_ne!(Some(v1), hash(HASH_VERSION).unwrap());
+        assert_eq!(hash(0).unwrap(), None);
+    }
+}
@@ -0,0 +1,149 @@
+use crate::domain::value_objects::{LoanId, TenantId};
+use crate::ports::idempotency_store::{
+    IdempotencyClaim, IdempotencyStore as IdempotencyStoreTrait, Result,
+};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use super::tenant::begin_tenant_transaction;
+
+/// PostgreSQL implementation of IdempotencyStore
+///
+/// Keys are kept in `idempotency_keys` (migration 016), one row per tenant
+/// and key. A claim inserts the row with `ON CONFLICT DO NOTHING`, so of two
+/// instances claiming the same key at once exactly one gets it; the other
+/// sees the claim once the first commits. Expired and abandoned rows of the
+/// tenant are deleted in the same transaction, just before the insert. An
+/// instance is scoped to one tenant and every query runs in a tenant-scoped
+/// transaction under row-level security.
+#[allow(dead_code)]
+pub struct IdempotencyStore {
+    pool: PgPool,
+    tenant_id: TenantId,
+}
+
+#[allow(dead_code)]
+impl IdempotencyStore {
+    /// Create an IdempotencyStore scoped to the default tenant
+    pub fn new(pool: PgPool) -> Self {
+        Self::for_tenant(pool, TenantId::DEFAULT)
+    }
+
+    /// Create an IdempotencyStore scoped to the given tenant
+    pub fn for_tenant(pool: PgPool, tenant_id: TenantId) -> Self {
+        Self { pool, tenant_id }
+    }
+}
+
+#[async_trait]
+impl IdempotencyStoreTrait for IdempotencyStore {
+    async fn claim(
+        &self,
+        key: &str,
+        fingerprint: &str,
+        claimed_at: DateTime<Utc>,
+        expired_before: DateTime<Utc>,
+        abandoned_before: DateTime<Utc>,
+    ) -> Result<IdempotencyClaim> {
+        let mut tx = begin_tenant_transaction(&self.pool, self.tenant_id).await?;
+
+        sqlx::query(
+            r#"
+            DELETE FROM idempotency_keys
+            WHERE tenant_id = $1
+              AND (completed_at < $2 OR (completed_at IS NULL AND claimed_at < $3))
+            "#,
+        )
+        .bind(self.tenant_id.value())
+        .bind(expired_before)
+        .bind(abandoned_before)
+        .execute(&mut *tx)
+        .await?;
+
+        let claimed = sqlx::query(
+            r#"
+            INSERT INTO idempotency_keys (tenant_id, idempotency_key, fingerprint, claimed_at)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (tenant_id, idempotency_key) DO NOTHING
+            "#,
+        )
+        .bind(self.tenant_id.value())
+        .bind(key)
+        .bind(fingerprint)
+        .bind(claimed_at)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected()
+            == 1;
+
+        let claim = if claimed {
+            IdempotencyClaim::Claimed
+        } else {
+            let (fingerprint, loan_id): (String, Option<Uuid>) = sqlx::query_as(
+                r#"
+                SELECT fingerprint, loan_id
+                FROM idempotency_keys
+                WHERE tenant_id = $1 AND idempotency_key = $2
+                "#,
+            )
+            .bind(self.tenant_id.value())
+            .bind(key)
+            .fetch_one(&mut *tx)
+            .await?;
+            IdempotencyClaim::Taken {
+                fingerprint,
+                loan_id: loan_id.map(LoanId::from_uuid),
+            }
+        };
+
+        tx.commit().await?;
+        Ok(claim)
+    }
+
+    async fn complete(
+        &self,
+        key: &str,
+        loan_id: LoanId,
+        completed_at: DateTime<Utc>,
+    ) -> Result<()> {
+        let mut tx = begin_tenant_transaction(&self.pool, self.tenant_id).await?;
+
+        sqlx::query(
+            r#"
+            UPDATE idempotency_keys
+            SET loan_id = $3, completed_at = $4
+            WHERE tenant_id = $1 AND idempotency_key = $2
+            "#,
+        )
+        .bind(self.tenant_id.value())
+        .bind(key)
+        .bind(loan_id.value())
+        .bind(completed_at)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Delete the claim unless it has been completed
+    async fn release(&self, key: &str) -> Result<()> {
+        let mut tx = begin_tenant_transaction(&self.pool, self.tenant_id).await?;
+
+        sqlx::query(
+            r#"
+            DELETE FROM idempotency_keys
+            WHERE tenant_id = $1 AND idempotency_key = $2 AND completed_at IS NULL
+            "#,
+        )
+        .bind(self.tenant_id.value())
+        .bind(key)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,183 @@
+use crate::ports::job_store::{
+    JobLease as JobLeaseTrait, JobRun, JobRunStatus, JobStore as JobStoreTrait, JobStoreError,
+    Result,
+};
+use async_trait::async_trait;
+use sqlx::{Connection, PgConnection, PgPool, Row, postgres::PgRow};
+
+/// Namespace of the advisory lock keys taken for jobs
+///
+/// Hashed together with the job name so that job locks cannot collide with
+/// advisory locks taken for other purposes.
+const LOCK_NAMESPACE: &str = "rusty-library-ddd:job:";
+
+/// PostgreSQL implementation of JobStore
+///
+/// Leases are session-level advisory locks (`pg_try_advisory_lock`) held on
+/// a dedicated connection opened with the pool's connect options, so a lease
+/// is released when it is released explicitly and also when the instance
+/// holding it dies and its connection drops. Lease connections are not taken
+/// from the pool, so a running job never starves the requests (or its own
+/// queries) of pooled connections; each running job costs one extra
+/// connection on top of the pool.
+///
+/// Run history is kept in `job_runs` (migration 009). The table is shared by
+/// all tenants and has no row-level security.
+#[allow(dead_code)]
+pub struct JobStore {
+    pool: PgPool,
+}
+
+#[allow(dead_code)]
+impl JobStore {
+    /// Create a new JobStore with a PostgreSQL connection pool
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+/// The advisory lock key of a job
+fn lock_key(job_name: &str) -> String {
+    format!("{LOCK_NAMESPACE}{job_name}")
+}
+
+/// A job lease backed by a dedicated connection holding the advisory lock
+struct JobLease {
+    conn: PgConnection,
+    key: String,
+}
+
+#[async_trait]
+impl JobLeaseTrait for JobLease {
+    /// Unlock and close the connection
+    ///
+    /// Closing the connection releases the lock even if the unlock fails.
+    async fn release(self: Box<Self>) -> Result<()> {
+        let JobLease { mut conn, key } = *self;
+        let unlocked = sqlx::query("SELECT pg_advisory_unlock(hashtextextended($1, 0))")
+            .bind(&key)
+            .execute(&mut conn)
+            .await;
+        conn.close().await?;
+        unlocked?;
+        Ok(())
+    }
+}
+
+fn status_to_str(status: JobRunStatus) -> &'static str {
+    match status {
+        JobRunStatus::Running => "running",
+        JobRunStatus::Succeeded => "succeeded",
+        JobRunStatus::Failed => "failed",
+    }
+}
+
+fn status_from_str(status: &str) -> Result<JobRunStatus> {
+    match status {
+        "running" => Ok(JobRunStatus::Running),
+        "succeeded" => Ok(JobRunStatus::Succeeded),
+        "failed" => Ok(JobRunStatus::Failed),
+        other => Err(JobStoreError::Internal(
+            format!("Unknown job run status: {other}").into(),
+        )),
+    }
+}
+
+fn run_from_row(row: &PgRow) -> Result<JobRun> {
+    Ok(JobRun {
+        run_id: row.try_get("run_id")?,
+        job_name: row.try_get("job_name")?,
+        scheduled_for: row.try_get("scheduled_for")?,
+        instance_id: row.try_get("instance_id")?,
+        started_at: row.try_get("started_at")?,
+        finished_at: row.try_get("finished_at")?,
+        status: status_from_str(row.try_get("status")?)?,
+        summary: row.try_get("summary")?,
+    })
+}
+
+#[async_trait]
+impl JobStoreTrait for JobStore {
+    /// Try to take the job's advisory lock without waiting
+    async fn try_acquire(&self, job_name: &str) -> Result<Option<Box<dyn JobLeaseTrait>>> {
+        let mut conn = PgConnection::connect_with(&self.pool.connect_options()).await?;
+        let key = lock_key(job_name);
+
+        let acquired: bool =
+            sqlx::query_scalar("SELECT pg_try_advisory_lock(hashtextextended($1, 0))")
+                .bind(&key)
+                .fetch_one(&mut conn)
+                .await?;
+
+        if !acquired {
+            conn.close().await?;
+            return Ok(None);
+        }
+        Ok(Some(Box::new(JobLease { conn, key })))
+    }
+
+    /// Insert the run unless its `(job_name, scheduled_for)` slot is taken
+    async fn record_started(&self, run: &JobRun) -> Result<bool> {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO job_runs (
+                run_id, job_name, scheduled_for, instance_id, started_at,
+                finished_at, status, summary
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            ON CONFLICT (job_name, scheduled_for) DO NOTHING
+            "#,
+        )
+        .bind(run.run_id)
+        .bind(&run.job_name)
+        .bind(run.scheduled_for)
+        .bind(&run.instance_id)
+        .bind(run.started_at)
+        .bind(run.finished_at)
+        .bind(status_to_str(run.status))
+        .bind(&run.summary)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    /// Update the outcome of a recorded run
+    async fn record_finished(&self, run: &JobRun) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE job_runs
+            SET finished_at = $2, status = $3, summary = $4
+            WHERE run_id = $1
+            "#,
+        )
+        .bind(run.run_id)
+        .bind(run.finished_at)
+        .bind(status_to_str(run.status))
+        .bind(&run.summary)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// List the most recent runs of a job, newest first
+    async fn list_runs(&self, job_name: &str, limit: usize) -> Result<Vec<JobRun>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT run_id, job_name, scheduled_for, instance_id, started_at,
+                   finished_at, status, summary
+            FROM job_runs
+            WHERE job_name = $1
+            ORDER BY started_at DESC
+            LIMIT $2
+            "#,
+        )
+        .bind(job_name)
+        .bind(i64::try_from(limit).unwrap_or(i64::MAX))
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter().map(run_from_row).collect()
+    }
+}
@@ -1,12 +1,14 @@
-use crate::domain::value_objects::{BookId, LoanId, MemberId};
+use crate::domain::value_objects::{BookId, LoanId, MemberId, TenantId};
 use crate::ports::loan_read_model::{
-    LoanReadModel as LoanReadModelTrait, LoanStatus, LoanView, Result,
+    LoanReadModel as LoanReadModelTrait, LoanReadModelError, LoanStatus, LoanView, Result,
 };
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
-use sqlx::{PgPool, Row, postgres::PgRow};
+use sqlx::{PgPool, Postgres, Row, Transaction, postgres::PgRow};
 use std::str::FromStr;
 
+use super::tenant::begin_tenant_transaction;
+
 /// PostgreSQLの行データをLoanViewに変換する
 ///
 /// データベースから取得した行を、ドメインの値オブジェクトとLoanViewに変換する。
@@ -15,17 +17,14 @@ use std::str::FromStr;
 fn map_row_to_loan_view(row: &PgRow) -> Result<LoanView> {
     let extension_count_i16: i16 = row.get("extension_count");
     let extension_count: u8 = extension_count_i16.try_into().map_err(|_| {
-        Box::new(std::io::Error::new(
-            std::io::ErrorKind::InvalidData,
-            format!("extension_count out of range: {}", extension_count_i16),
-        )) as Box<dyn std::error::Error + Send + Sync>
+        LoanReadModelError::Corrupted(
+            format!("extension_count out of range: {}", extension_count_i16).into(),
+        )
     })?;
 
     let status_str: &str = row.get("status");
-    let status = LoanStatus::from_str(status_str).map_err(|e| {
-        Box::new(std::io::Error::new(std::io::ErrorKind::InvalidData, e))
-            as Box<dyn std::error::Error + Send + Sync>
-    })?;
+    let status =
+        LoanStatus::from_str(status_str).map_err(|e| LoanReadModelError::Corrupted(e.into()))?;
 
     Ok(LoanView {
         loan_id: LoanId::from_uuid(row.get("loan_id")),
@@ -45,30 +44,41 @@ fn map_row_to_loan_view(row: &PgRow) -> Result<LoanView> {
 ///
 /// CQRSパターンの読み取り側として、クエリに最適化された
 /// 非正規化ビューを提供する。
+/// インスタンスは1つのテナントにスコープされ、すべてのクエリは
+/// 行レベルセキュリティが有効なテナントスコープのトランザクションで実行される。
 #[allow(dead_code)]
 pub struct LoanReadModel {
     pool: PgPool,
+    tenant_id: TenantId,
 }
 
 #[allow(dead_code)]
 impl LoanReadModel {
     /// PostgreSQLコネクションプールから新しいLoanReadModelを作成
+    ///
+    /// 既定テナントにスコープされる。
     pub fn new(pool: PgPool) -> Self {
-        Self { pool }
+        Self::for_tenant(pool, TenantId::DEFAULT)
     }
-}
 
-#[async_trait]
-impl LoanReadModelTrait for LoanReadModel {
-    /// 貸出ビューをRead Modelに保存（upsert）
+    /// 指定したテナントにスコープされたLoanReadModelを作成
+    pub fn for_tenant(pool: PgPool, tenant_id: TenantId) -> Self {
+        Self { pool, tenant_id }
+    }
+
+    /// 指定したテナントスコープのトランザクション内で貸出ビューを保存（upsert）
     ///
-    /// INSERT ... ON CONFLICT UPDATEを使用して冪等性を保証する。
-    /// これにより、Read Modelは常にイベントストリームから再構築された
-    /// 完全な状態を反映する。
-    async fn save(&self, loan_view: LoanView) -> Result<()> {
+    /// コミットは呼び出し側が行う（イベントの追加と同じトランザクションで
+    /// 保存するために使用する。`unit_of_work`を参照）。
+    pub(crate) async fn save_in(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        loan_view: &LoanView,
+    ) -> Result<()> {
         sqlx::query(
             r#"
             INSERT INTO loans_view (
+                tenant_id,
                 loan_id,
                 book_id,
                 member_id,
@@ -80,7 +90,7 @@ impl LoanReadModelTrait for LoanReadModel {
                 created_at,
                 updated_at
             )
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
             ON CONFLICT (loan_id)
             DO UPDATE SET
                 book_id = EXCLUDED.book_id,
@@ -91,8 +101,10 @@ impl LoanReadModelTrait for LoanReadModel {
                 extension_count = EXCLUDED.extension_count,
                 status = EXCLUDED.status,
                 updated_at = EXCLUDED.updated_at
+            WHERE loans_view.tenant_id = EXCLUDED.tenant_id
             "#,
         )
+        .bind(self.tenant_id.value())
         .bind(loan_view.loan_id.value())
         .bind(loan_view.book_id.value())
         .bind(loan_view.member_id.value())
@@ -103,16 +115,33 @@ impl LoanReadModelTrait for LoanReadModel {
         .bind(loan_view.status.as_str())
         .bind(loan_view.created_at)
         .bind(loan_view.updated_at)
-        .execute(&self.pool)
+        .execute(&mut **tx)
         .await?;
 
         Ok(())
     }
+}
+
+#[async_trait]
+impl LoanReadModelTrait for LoanReadModel {
+    /// 貸出ビューをRead Modelに保存（upsert）
+    ///
+    /// INSERT ... ON CONFLICT UPDATEを使用して冪等性を保証する。
+    /// これにより、Read Modelは常にイベントストリームから再構築された
+    /// 完全な状態を反映する。
+    async fn save(&self, loan_view: LoanView) -> Result<()> {
+        let mut tx = begin_tenant_transaction(&self.pool, self.tenant_id).await?;
+        self.save_in(&mut tx, &loan_view).await?;
+        tx.commit().await?;
+        Ok(())
+    }
 
     /// 会員の貸出中の貸出を取得（貸出上限確認用）
     ///
     /// (member_id, status)の部分インデックスを使用してパフォーマンスを最適化。
     async fn get_active_loans_for_member(&self, member_id: MemberId) -> Result<Vec<LoanView>> {
+        let mut tx = begin_tenant_transaction(&self.pool, self.tenant_id).await?;
+
         let rows = sqlx::query(
             r#"
             SELECT
@@ -127,14 +156,16 @@ impl LoanReadModelTrait for LoanReadModel {
                 created_at,
                 updated_at
             FROM loans_view
-            WHERE member_id = $1 AND status = 'active'
+            WHERE tenant_id = $1 AND member_id = $2 AND status = 'active'
             ORDER BY loaned_at DESC
             "#,
         )
+        .bind(self.tenant_id.value())
         .bind(member_id.value())
-        .fetch_all(&self.pool)
+        .fetch_all(&mut *tx)
         .await?;
 
+        tx.commit().await?;
         rows.iter().map(map_row_to_loan_view).collect()
     }
 
@@ -143,6 +174,8 @@ impl LoanReadModelTrait for LoanReadModel {
     /// 返却期限を過ぎた貸出中の貸出を返す。
     /// (status, due_date)の部分インデックスを使用してパフォーマンスを最適化。
     async fn find_overdue_candidates(&self, cutoff_date: DateTime<Utc>) -> Result<Vec<LoanView>> {
+        let mut tx = begin_tenant_transaction(&self.pool, self.tenant_id).await?;
+
         let rows = sqlx::query(
             r#"
             SELECT
@@ -157,19 +190,94 @@ impl LoanReadModelTrait for LoanReadModel {
                 created_at,
                 updated_at
             FROM loans_view
-            WHERE status = 'active' AND due_date < $1
+            WHERE tenant_id = $1 AND status = 'active' AND due_date < $2
             ORDER BY due_date ASC
             "#,
         )
+        .bind(self.tenant_id.value())
         .bind(cutoff_date)
-        .fetch_all(&self.pool)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        rows.iter().map(map_row_to_loan_view).collect()
+    }
+
+    /// 返却期限が近い貸出を検索（返却期限のリマインダー用）
+    ///
+    /// (tenant_id, due_date)の部分インデックスを使用して返却期限の範囲を検索する。
+    async fn find_due_soon_candidates(
+        &self,
+        from: DateTime<Utc>,
+        until: DateTime<Utc>,
+    ) -> Result<Vec<LoanView>> {
+        let mut tx = begin_tenant_transaction(&self.pool, self.tenant_id).await?;
+
+        let rows = sqlx::query(
+            r#"
+            SELECT
+                loan_id,
+                book_id,
+                member_id,
+                loaned_at,
+                due_date,
+                returned_at,
+                extension_count,
+                status,
+                created_at,
+                updated_at
+            FROM loans_view
+            WHERE tenant_id = $1 AND status = 'active' AND due_date > $2 AND due_date <= $3
+            ORDER BY due_date ASC
+            "#,
+        )
+        .bind(self.tenant_id.value())
+        .bind(from)
+        .bind(until)
+        .fetch_all(&mut *tx)
         .await?;
 
+        tx.commit().await?;
+        rows.iter().map(map_row_to_loan_view).collect()
+    }
+
+    /// 延滞中の貸出を検索（延滞の督促用）
+    ///
+    /// (tenant_id, due_date)の部分インデックスを使用して返却期限の範囲を検索する。
+    async fn find_overdue_loans(&self, due_before: DateTime<Utc>) -> Result<Vec<LoanView>> {
+        let mut tx = begin_tenant_transaction(&self.pool, self.tenant_id).await?;
+
+        let rows = sqlx::query(
+            r#"
+            SELECT
+                loan_id,
+                book_id,
+                member_id,
+                loaned_at,
+                due_date,
+                returned_at,
+                extension_count,
+                status,
+                created_at,
+                updated_at
+            FROM loans_view
+            WHERE tenant_id = $1 AND status = 'overdue' AND due_date <= $2
+            ORDER BY due_date ASC
+            "#,
+        )
+        .bind(self.tenant_id.value())
+        .bind(due_before)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
         rows.iter().map(map_row_to_loan_view).collect()
     }
 
     /// IDで貸出を取得
     async fn get_by_id(&self, loan_id: LoanId) -> Result<Option<LoanView>> {
+        let mut tx = begin_tenant_transaction(&self.pool, self.tenant_id).await?;
+
         let row = sqlx::query(
             r#"
             SELECT
@@ -184,18 +292,29 @@ impl LoanReadModelTrait for LoanReadModel {
                 created_at,
                 updated_at
             FROM loans_view
-            WHERE loan_id = $1
+            WHERE tenant_id = $1 AND loan_id = $2
             "#,
         )
+        .bind(self.tenant_id.value())
         .bind(loan_id.value())
-        .fetch_optional(&self.pool)
+        .fetch_optional(&mut *tx)
         .await?;
 
+        tx.commit().await?;
         row.as_ref().map(map_row_to_loan_view).transpose()
     }
 
     /// 会員の全貸出を検索（貸出履歴）
-    async fn find_by_member_id(&self, member_id: MemberId) -> Result<Vec<LoanView>> {
+    ///
+    /// `history_cutoff`より前に返却された貸出は、会員が読書履歴の保持を
+    /// 選択していない限り除外する。
+    async fn find_by_member_id(
+        &self,
+        member_id: MemberId,
+        history_cutoff: Option<DateTime<Utc>>,
+    ) -> Result<Vec<LoanView>> {
+        let mut tx = begin_tenant_transaction(&self.pool, self.tenant_id).await?;
+
         let rows = sqlx::query(
             r#"
             SELECT
@@ -209,15 +328,116 @@ impl LoanReadModelTrait for LoanReadModel {
                 status,
                 created_at,
                 updated_at
-            FROM loans_view
-            WHERE member_id = $1
+            FROM loans_view lv
+            WHERE tenant_id = $1 AND member_id = $2
+              AND (
+                  $3::timestamptz IS NULL
+                  OR status <> 'returned'
+                  OR returned_at >= $3
+                  OR EXISTS (
+                      SELECT 1 FROM reading_history_opt_ins o
+                      WHERE o.tenant_id = lv.tenant_id AND o.member_id = lv.member_id
+                  )
+              )
             ORDER BY loaned_at DESC
             "#,
         )
+        .bind(self.tenant_id.value())
         .bind(member_id.value())
-        .fetch_all(&self.pool)
+        .bind(history_cutoff)
+        .fetch_all(&mut *tx)
         .await?;
 
+        tx.commit().await?;
         rows.iter().map(map_row_to_loan_view).collect()
     }
+
+    /// 読書履歴の保持設定を保存（選択時はupsert、取り消し時はdelete）
+    async fn save_history_opt_in(
+        &self,
+        member_id: MemberId,
+        opted_in_at: Option<DateTime<Utc>>,
+    ) -> Result<()> {
+        let mut tx = begin_tenant_transaction(&self.pool, self.tenant_id).await?;
+
+        match opted_in_at {
+            Some(opted_in_at) => {
+                sqlx::query(
+                    r#"
+                    INSERT INTO reading_history_opt_ins (tenant_id, member_id, opted_in_at)
+                    VALUES ($1, $2, $3)
+                    ON CONFLICT (tenant_id, member_id)
+                    DO UPDATE SET opted_in_at = EXCLUDED.opted_in_at
+                    "#,
+                )
+                .bind(self.tenant_id.value())
+                .bind(member_id.value())
+                .bind(opted_in_at)
+                .execute(&mut *tx)
+                .await?;
+            }
+            None => {
+                sqlx::query(
+                    "DELETE FROM reading_history_opt_ins WHERE tenant_id = $1 AND member_id = $2",
+                )
+                .bind(self.tenant_id.value())
+                .bind(member_id.value())
+                .execute(&mut *tx)
+                .await?;
+            }
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// 読書履歴の保持を選択した日時を取得
+    async fn get_history_opt_in(&self, member_id: MemberId) -> Result<Option<DateTime<Utc>>> {
+        let mut tx = begin_tenant_transaction(&self.pool, self.tenant_id).await?;
+
+        let opted_in_at = sqlx::query_scalar(
+            r#"
+            SELECT opted_in_at
+            FROM reading_history_opt_ins
+            WHERE tenant_id = $1 AND member_id = $2
+            "#,
+        )
+        .bind(self.tenant_id.value())
+        .bind(member_id.value())
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(opted_in_at)
+    }
+
+    /// 保持期間を過ぎた返却済み貸出から会員との紐付けを消す
+    ///
+    /// (tenant_id, returned_at)の部分インデックスを使用する。
+    async fn anonymise_returned_loans(&self, returned_before: DateTime<Utc>) -> Result<u64> {
+        let mut tx = begin_tenant_transaction(&self.pool, self.tenant_id).await?;
+
+        let result = sqlx::query(
+            r#"
+            UPDATE loans_view lv
+            SET member_id = $3
+            WHERE tenant_id = $1
+              AND status = 'returned'
+              AND returned_at < $2
+              AND member_id <> $3
+              AND NOT EXISTS (
+                  SELECT 1 FROM reading_history_opt_ins o
+                  WHERE o.tenant_id = lv.tenant_id AND o.member_id = lv.member_id
+              )
+            "#,
+        )
+        .bind(self.tenant_id.value())
+        .bind(returned_before)
+        .bind(MemberId::ANONYMISED.value())
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(result.rows_affected())
+    }
 }
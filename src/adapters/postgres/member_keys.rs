@@ -0,0 +1,418 @@
+use crate::domain::events::DomainEvent;
+use crate::domain::value_objects::{MemberId, TenantId};
+use crate::ports::errors::BoxError;
+use crate::ports::member_key_store::{self, MemberKeyStore as MemberKeyStoreTrait};
+use async_trait::async_trait;
+use ring::aead::{AES_256_GCM, Aad, LessSafeKey, NONCE_LEN, Nonce, UnboundKey};
+use ring::rand::{SecureRandom, SystemRandom};
+use serde_json::{Value, json};
+use sqlx::{PgPool, Postgres, Row, Transaction};
+use std::collections::HashMap;
+use thiserror::Error;
+use uuid::Uuid;
+
+use super::tenant::begin_tenant_transaction;
+
+/// The event field holding the member identifier
+const MEMBER_FIELD: &str = "member_id";
+
+/// Keyring failures are sqlx, serde or `MemberKeyError` errors, which
+/// `EventStoreError` classifies when they surface (see `errors::classify_boxed`)
+type Result<T> = std::result::Result<T, BoxError>;
+
+/// Errors raised while sealing or opening member identifiers
+#[derive(Debug, Error)]
+pub enum MemberKeyError {
+    #[error("Failed to generate a member key")]
+    KeyGeneration,
+
+    #[error("Failed to encrypt member identifier")]
+    Encryption,
+
+    #[error("Failed to decrypt member identifier with key {0}")]
+    Decryption(Uuid),
+
+    #[error("Malformed member identifier envelope")]
+    MalformedEnvelope,
+
+    /// The member's key was shredded after the keyring was loaded
+    #[error("No key for member {0}")]
+    MissingKey(Uuid),
+}
+
+/// Per-member keys needed to seal or open a set of events
+///
+/// Member identifiers are stored in `event_data` as an envelope
+/// `{"key_id": ..., "ciphertext": ...}` encrypted with AES-256-GCM under
+/// the member's key. The key id, not the member id, is what links an event
+/// to its key, so deleting a key row leaves nothing that identifies the member.
+pub(crate) struct MemberKeyring {
+    key_ids: HashMap<Uuid, Uuid>,
+    keys: HashMap<Uuid, LessSafeKey>,
+}
+
+impl MemberKeyring {
+    /// Load the keys of the members referenced by `events`, creating missing ones
+    ///
+    /// Anonymised member identifiers are not encrypted and need no key.
+    pub(crate) async fn for_events<'a>(
+        tx: &mut Transaction<'_, Postgres>,
+        tenant_id: TenantId,
+        events: impl IntoIterator<Item = &'a DomainEvent>,
+    ) -> Result<Self> {
+        let mut member_ids: Vec<Uuid> = events
+            .into_iter()
+            .filter_map(member_of)
+            .filter(|m| !m.is_anonymised())
+            .map(|m| m.value())
+            .collect();
+        member_ids.sort_unstable();
+        member_ids.dedup();
+
+        if member_ids.is_empty() {
+            return Ok(Self::empty());
+        }
+
+        let rng = SystemRandom::new();
+        let mut key_ids = Vec::with_capacity(member_ids.len());
+        let mut keys = Vec::with_capacity(member_ids.len());
+        for _ in &member_ids {
+            let mut key = vec![0u8; 32];
+            rng.fill(&mut key)
+                .map_err(|_| MemberKeyError::KeyGeneration)?;
+            key_ids.push(Uuid::new_v4());
+            keys.push(key);
+        }
+
+        // Concurrent appends for the same member keep whichever key was inserted first
+        sqlx::query(
+            r#"
+            INSERT INTO member_keys (tenant_id, key_id, member_id, key)
+            SELECT $1, * FROM UNNEST($2::uuid[], $3::uuid[], $4::bytea[])
+            ON CONFLICT (tenant_id, member_id) DO NOTHING
+            "#,
+        )
+        .bind(tenant_id.value())
+        .bind(&key_ids)
+        .bind(&member_ids)
+        .bind(&keys)
+        .execute(&mut **tx)
+        .await?;
+
+        let rows = sqlx::query(
+            r#"
+            SELECT key_id, member_id, key
+            FROM member_keys
+            WHERE tenant_id = $1 AND member_id = ANY($2)
+            "#,
+        )
+        .bind(tenant_id.value())
+        .bind(&member_ids)
+        .fetch_all(&mut **tx)
+        .await?;
+
+        Self::from_rows(rows)
+    }
+
+    /// Load the keys referenced by stored event data
+    ///
+    /// Keys that have been shredded are simply absent from the keyring.
+    pub(crate) async fn for_stored<'a>(
+        tx: &mut Transaction<'_, Postgres>,
+        tenant_id: TenantId,
+        event_data: impl IntoIterator<Item = &'a Value>,
+    ) -> Result<Self> {
+        let mut key_ids: Vec<Uuid> = event_data.into_iter().filter_map(envelope_key_id).collect();
+        key_ids.sort_unstable();
+        key_ids.dedup();
+
+        if key_ids.is_empty() {
+            return Ok(Self::empty());
+        }
+
+        let rows = sqlx::query(
+            r#"
+            SELECT key_id, member_id, key
+            FROM member_keys
+            WHERE tenant_id = $1 AND key_id = ANY($2)
+            "#,
+        )
+        .bind(tenant_id.value())
+        .bind(&key_ids)
+        .fetch_all(&mut **tx)
+        .await?;
+
+        Self::from_rows(rows)
+    }
+
+    fn empty() -> Self {
+        Self {
+            key_ids: HashMap::new(),
+            keys: HashMap::new(),
+        }
+    }
+
+    fn from_rows(rows: Vec<sqlx::postgres::PgRow>) -> Result<Self> {
+        let mut keyring = Self::empty();
+        for row in rows {
+            let key_id: Uuid = row.get("key_id");
+            let key: Vec<u8> = row.get("key");
+            let key = UnboundKey::new(&AES_256_GCM, &key)
+                .map_err(|_| MemberKeyError::Decryption(key_id))?;
+            keyring.key_ids.insert(row.get("member_id"), key_id);
+            keyring.keys.insert(key_id, LessSafeKey::new(key));
+        }
+        Ok(keyring)
+    }
+
+    /// Serialize an event, encrypting its member identifier
+    pub(crate) fn seal(&self, event: &DomainEvent) -> Result<Value> {
+        let mut value = serde_json::to_value(event)?;
+
+        let Some(member_id) = member_of(event).filter(|m| !m.is_anonymised()) else {
+            return Ok(value);
+        };
+        let (key_id, key) = self
+            .key_ids
+            .get(&member_id.value())
+            .and_then(|key_id| Some((*key_id, self.keys.get(key_id)?)))
+            .ok_or(MemberKeyError::MissingKey(member_id.value()))?;
+
+        let mut nonce = [0u8; NONCE_LEN];
+        SystemRandom::new()
+            .fill(&mut nonce)
+            .map_err(|_| MemberKeyError::Encryption)?;
+        let mut sealed = member_id.value().as_bytes().to_vec();
+        key.seal_in_place_append_tag(
+            Nonce::assume_unique_for_key(nonce),
+            Aad::from(key_id.as_bytes()),
+            &mut sealed,
+        )
+        .map_err(|_| MemberKeyError::Encryption)?;
+
+        if let Some(field) = member_field_mut(&mut value) {
+            *field = json!({
+                "key_id": key_id,
+                "ciphertext": hex::encode([nonce.as_slice(), &sealed].concat()),
+            });
+        }
+        Ok(value)
+    }
+
+    /// Deserialize stored event data, decrypting its member identifier
+    ///
+    /// Events whose key has been shredded are returned with
+    /// `MemberId::ANONYMISED`. Plain identifiers written before encryption
+    /// was introduced are returned as they are.
+    pub(crate) fn open(&self, value: Value) -> Result<DomainEvent> {
+        Ok(serde_json::from_value(self.open_value(value)?)?)
+    }
+
+    /// Decrypt the member identifier of stored event data, keeping it as JSON
+    ///
+    /// Used to check stored data against the event schemas before it is
+    /// deserialized.
+    pub(crate) fn open_value(&self, mut value: Value) -> Result<Value> {
+        if let Some(key_id) = envelope_key_id(&value) {
+            let member_id = match self.keys.get(&key_id) {
+                Some(key) => decrypt(key, key_id, &value)?,
+                None => MemberId::ANONYMISED.value(),
+            };
+            if let Some(field) = member_field_mut(&mut value) {
+                *field = json!(member_id);
+            }
+        }
+        Ok(value)
+    }
+}
+
+fn decrypt(key: &LessSafeKey, key_id: Uuid, value: &Value) -> Result<Uuid> {
+    let ciphertext = member_field(value)
+        .and_then(|field| field.get("ciphertext"))
+        .and_then(Value::as_str)
+        .ok_or(MemberKeyError::MalformedEnvelope)?;
+    let bytes = hex::decode(ciphertext).map_err(|_| MemberKeyError::MalformedEnvelope)?;
+    if bytes.len() < NONCE_LEN {
+        return Err(MemberKeyError::MalformedEnvelope.into());
+    }
+
+    let (nonce, sealed) = bytes.split_at(NONCE_LEN);
+    let nonce =
+        Nonce::try_assume_unique_for_key(nonce).map_err(|_| MemberKeyError::MalformedEnvelope)?;
+    let mut sealed = sealed.to_vec();
+    let plain = key
+        .open_in_place(nonce, Aad::from(key_id.as_bytes()), &mut sealed)
+        .map_err(|_| MemberKeyError::Decryption(key_id))?;
+
+    Ok(Uuid::from_slice(plain).map_err(|_| MemberKeyError::Decryption(key_id))?)
+}
+
+/// The member referenced by an event, if any
+fn member_of(event: &DomainEvent) -> Option<MemberId> {
+    match event {
+        DomainEvent::BookLoaned(e) => Some(e.member_id),
+        DomainEvent::BookReturned(e) => Some(e.member_id),
+        DomainEvent::LoanBecameOverdue(e) => Some(e.member_id),
+        DomainEvent::LoanDueSoonReminded(e) => Some(e.member_id),
+        DomainEvent::OverdueNoticeSent(e) => Some(e.member_id),
+        DomainEvent::LoanDeclaredLost(e) => Some(e.member_id),
+        DomainEvent::ReadingHistoryPreferenceChanged(e) => Some(e.member_id),
+        DomainEvent::MemberDataExported(e) => Some(e.member_id),
+        DomainEvent::LoanExtended(_) => None,
+    }
+}
+
+/// The member field of a serialized event (`{"Variant": {"member_id": ...}}`)
+fn member_field(value: &Value) -> Option<&Value> {
+    value
+        .as_object()?
+        .values()
+        .next()?
+        .as_object()?
+        .get(MEMBER_FIELD)
+}
+
+fn member_field_mut(value: &mut Value) -> Option<&mut Value> {
+    value
+        .as_object_mut()?
+        .values_mut()
+        .next()?
+        .as_object_mut()?
+        .get_mut(MEMBER_FIELD)
+}
+
+/// The key id of an encrypted member field
+fn envelope_key_id(value: &Value) -> Option<Uuid> {
+    member_field(value)?.get("key_id")?.as_str()?.parse().ok()
+}
+
+/// The member reference stored in the `member_ref` column
+///
+/// The key id of an encrypted member field, or the plain id of an event
+/// written before encryption. Lets a member's events be found in SQL
+/// without decoding them. Anonymised members have no reference.
+pub(crate) fn member_ref(value: &Value) -> Option<Uuid> {
+    envelope_key_id(value).or_else(|| {
+        member_field(value)?
+            .as_str()?
+            .parse()
+            .ok()
+            .filter(|id: &Uuid| !id.is_nil())
+    })
+}
+
+/// Whether stored event data refers to a member
+///
+/// Matches the member's plain id (events written before encryption) or an
+/// envelope sealed with the member's key, like the JSON path query that
+/// `EventStore::load_member_events` runs against JSON rows.
+pub(crate) fn references_member(value: &Value, member_id: MemberId, key_id: Option<Uuid>) -> bool {
+    match envelope_key_id(value) {
+        Some(envelope_key) => Some(envelope_key) == key_id,
+        None => member_field(value)
+            .and_then(Value::as_str)
+            .is_some_and(|plain| plain == member_id.value().to_string()),
+    }
+}
+
+/// PostgreSQL implementation of MemberKeyStore
+#[allow(dead_code)]
+pub struct MemberKeyStore {
+    pool: PgPool,
+    tenant_id: TenantId,
+}
+
+#[allow(dead_code)]
+impl MemberKeyStore {
+    /// Create a new MemberKeyStore scoped to the default tenant
+    pub fn new(pool: PgPool) -> Self {
+        Self::for_tenant(pool, TenantId::DEFAULT)
+    }
+
+    /// Create a new MemberKeyStore scoped to the given tenant
+    pub fn for_tenant(pool: PgPool, tenant_id: TenantId) -> Self {
+        Self { pool, tenant_id }
+    }
+}
+
+#[async_trait]
+impl MemberKeyStoreTrait for MemberKeyStore {
+    /// Delete the member's key, making their events unattributable
+    async fn shred(&self, member_id: MemberId) -> member_key_store::Result<bool> {
+        let mut tx = begin_tenant_transaction(&self.pool, self.tenant_id).await?;
+
+        let result = sqlx::query("DELETE FROM member_keys WHERE tenant_id = $1 AND member_id = $2")
+            .bind(self.tenant_id.value())
+            .bind(member_id.value())
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::events::BookReturned;
+    use crate::domain::value_objects::{BookId, LoanId};
+    use chrono::Utc;
+
+    fn keyring_for(member_id: MemberId) -> MemberKeyring {
+        let key_id = Uuid::new_v4();
+        let key = UnboundKey::new(&AES_256_GCM, &[3; 32]).unwrap();
+        MemberKeyring {
+            key_ids: HashMap::from([(member_id.value(), key_id)]),
+            keys: HashMap::from([(key_id, LessSafeKey::new(key))]),
+        }
+    }
+
+    fn returned(member_id: MemberId) -> DomainEvent {
+        DomainEvent::BookReturned(BookReturned {
+            loan_id: LoanId::new(),
+            book_id: BookId::new(),
+            member_id,
+            returned_at: Utc::now(),
+            was_overdue: false,
+        })
+    }
+
+    #[test]
+    fn test_seal_hides_member_and_shredding_anonymises() {
+        let member_id = MemberId::new();
+        let keyring = keyring_for(member_id);
+        let event = returned(member_id);
+
+        let sealed = keyring.seal(&event).unwrap();
+        assert!(!sealed.to_string().contains(&member_id.value().to_string()));
+        assert_eq!(keyring.open(sealed.clone()).unwrap(), event);
+
+        // 鍵がなければ匿名化された会員として復元される
+        let DomainEvent::BookReturned(shredded) = MemberKeyring::empty().open(sealed).unwrap()
+        else {
+            panic!("expected BookReturned");
+        };
+        assert!(shredded.member_id.is_anonymised());
+
+        // 暗号化前の平文のイベントはそのまま読める
+        let plain = serde_json::to_value(&event).unwrap();
+        assert_eq!(MemberKeyring::empty().open(plain).unwrap(), event);
+    }
+
+    #[test]
+    fn test_seal_without_key_is_a_retryable_conflict() {
+        // 鍵を読み込んだ後に会員が消去された場合
+        let error = MemberKeyring::empty()
+            .seal(&returned(MemberId::new()))
+            .unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<MemberKeyError>(),
+            Some(MemberKeyError::MissingKey(_))
+        ));
+
+        let error = crate::ports::EventStoreError::from(error);
+        assert!(matches!(error, crate::ports::EventStoreError::Conflict(_)));
+        assert!(crate::ports::Classified::is_retryable(&error));
+    }
+}
@@ -1,7 +1,31 @@
+pub mod deferred_notices;
+mod errors;
+pub mod event_archive;
+pub mod event_codec;
 pub mod event_store;
+pub mod hash_chain;
+pub mod idempotency_store;
+pub mod job_store;
 pub mod loan_read_model;
+pub mod member_keys;
+pub mod notification_log;
+pub mod notification_preferences;
 pub mod projector;
+pub mod tenant;
+pub mod unit_of_work;
 
 // パブリックに型を再エクスポート
+pub use deferred_notices::DeferredNoticeQueue as PostgresDeferredNoticeQueue;
+pub use event_archive::EventArchive as PostgresEventArchive;
+pub use event_codec::EventCodec;
 pub use event_store::EventStore as PostgresEventStore;
+pub use event_store::{SchemaScan, SchemaViolation};
+pub use hash_chain::EventAudit as PostgresEventAudit;
+pub use idempotency_store::IdempotencyStore as PostgresIdempotencyStore;
+pub use job_store::JobStore as PostgresJobStore;
 pub use loan_read_model::LoanReadModel as PostgresLoanReadModel;
+pub use member_keys::MemberKeyStore as PostgresMemberKeyStore;
+pub use notification_log::NotificationLog as PostgresNotificationLog;
+pub use notification_preferences::NotificationPreferenceStore as PostgresNotificationPreferenceStore;
+pub use tenant::TenantDirectory as PostgresTenantDirectory;
+pub use unit_of_work::UnitOfWork as PostgresUnitOfWork;
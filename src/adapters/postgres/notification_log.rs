@@ -0,0 +1,123 @@
+use crate::domain::value_objects::TenantId;
+use crate::ports::notification_log::{
+    DeliveredNotice, NoticeKind, NotificationLog as NotificationLogTrait, NotificationLogError,
+    Result,
+};
+use async_trait::async_trait;
+use sqlx::{PgPool, Row};
+use uuid::Uuid;
+
+use super::tenant::begin_tenant_transaction;
+
+/// PostgreSQL implementation of NotificationLog
+///
+/// Deliveries are kept in `notification_deliveries` (migration 010), keyed by
+/// tenant and dedup key. An instance is scoped to one tenant and every query
+/// runs in a tenant-scoped transaction under row-level security.
+#[allow(dead_code)]
+pub struct NotificationLog {
+    pool: PgPool,
+    tenant_id: TenantId,
+}
+
+#[allow(dead_code)]
+impl NotificationLog {
+    /// Create a NotificationLog scoped to the default tenant
+    pub fn new(pool: PgPool) -> Self {
+        Self::for_tenant(pool, TenantId::DEFAULT)
+    }
+
+    /// Create a NotificationLog scoped to the given tenant
+    pub fn for_tenant(pool: PgPool, tenant_id: TenantId) -> Self {
+        Self { pool, tenant_id }
+    }
+}
+
+fn kind_from_str(kind: &str) -> Result<NoticeKind> {
+    match kind {
+        "overdue" => Ok(NoticeKind::Overdue),
+        "due_soon_reminder" => Ok(NoticeKind::DueSoonReminder),
+        "overdue_notice" => Ok(NoticeKind::OverdueNotice),
+        "lost_declaration" => Ok(NoticeKind::LostDeclaration),
+        "extension_confirmation" => Ok(NoticeKind::ExtensionConfirmation),
+        "return_confirmation" => Ok(NoticeKind::ReturnConfirmation),
+        other => Err(NotificationLogError::Internal(
+            format!("Unknown kind: {other}").into(),
+        )),
+    }
+}
+
+#[async_trait]
+impl NotificationLogTrait for NotificationLog {
+    async fn is_delivered(&self, dedup_key: &str) -> Result<bool> {
+        let mut tx = begin_tenant_transaction(&self.pool, self.tenant_id).await?;
+
+        let delivered: bool = sqlx::query_scalar(
+            r#"
+            SELECT EXISTS (
+                SELECT 1 FROM notification_deliveries
+                WHERE tenant_id = $1 AND dedup_key = $2
+            )
+            "#,
+        )
+        .bind(self.tenant_id.value())
+        .bind(dedup_key)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(delivered)
+    }
+
+    /// Insert the delivery; a key recorded by a concurrent dispatch is kept as is
+    async fn record_delivered(&self, notice: &DeliveredNotice) -> Result<()> {
+        let mut tx = begin_tenant_transaction(&self.pool, self.tenant_id).await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO notification_deliveries (tenant_id, dedup_key, kind, event_id, delivered_at)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (tenant_id, dedup_key) DO NOTHING
+            "#,
+        )
+        .bind(self.tenant_id.value())
+        .bind(&notice.dedup_key)
+        .bind(notice.kind.as_str())
+        .bind(notice.event_id)
+        .bind(notice.delivered_at)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn find_by_events(&self, event_ids: &[Uuid]) -> Result<Vec<DeliveredNotice>> {
+        let mut tx = begin_tenant_transaction(&self.pool, self.tenant_id).await?;
+
+        let rows = sqlx::query(
+            r#"
+            SELECT dedup_key, kind, event_id, delivered_at
+            FROM notification_deliveries
+            WHERE tenant_id = $1 AND event_id = ANY($2)
+            ORDER BY delivered_at, dedup_key
+            "#,
+        )
+        .bind(self.tenant_id.value())
+        .bind(event_ids)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        rows.iter()
+            .map(|row| {
+                Ok(DeliveredNotice {
+                    dedup_key: row.try_get("dedup_key")?,
+                    kind: kind_from_str(row.try_get("kind")?)?,
+                    event_id: row.try_get("event_id")?,
+                    delivered_at: row.try_get("delivered_at")?,
+                })
+            })
+            .collect()
+    }
+}
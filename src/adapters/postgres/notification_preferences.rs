@@ -0,0 +1,162 @@
+use crate::domain::notification_preferences::{
+    DigestMode, NotificationChannel, NotificationLanguage, NotificationPreferences, QuietHours,
+};
+use crate::domain::value_objects::{MemberId, TenantId};
+use crate::ports::notification_preferences::{
+    NotificationPreferenceError, NotificationPreferenceStore as NotificationPreferenceStoreTrait,
+    Result,
+};
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveTime, Utc};
+use sqlx::{PgPool, Row, postgres::PgRow};
+
+use super::tenant::begin_tenant_transaction;
+
+/// PostgreSQL implementation of NotificationPreferenceStore
+///
+/// Preferences are kept in `notification_preferences` (migration 013), one
+/// row per tenant and member. Deleting a member's row also deletes their
+/// deferred notices. An instance is scoped to one tenant and every query runs
+/// in a tenant-scoped transaction under row-level security.
+#[allow(dead_code)]
+pub struct NotificationPreferenceStore {
+    pool: PgPool,
+    tenant_id: TenantId,
+}
+
+#[allow(dead_code)]
+impl NotificationPreferenceStore {
+    /// Create a NotificationPreferenceStore scoped to the default tenant
+    pub fn new(pool: PgPool) -> Self {
+        Self::for_tenant(pool, TenantId::DEFAULT)
+    }
+
+    /// Create a NotificationPreferenceStore scoped to the given tenant
+    pub fn for_tenant(pool: PgPool, tenant_id: TenantId) -> Self {
+        Self { pool, tenant_id }
+    }
+}
+
+fn unknown(column: &str, value: &str) -> NotificationPreferenceError {
+    NotificationPreferenceError::Internal(format!("Unknown {column}: {value}").into())
+}
+
+fn channel_from_str(channel: &str) -> Result<NotificationChannel> {
+    match channel {
+        "email" => Ok(NotificationChannel::Email),
+        "in_app" => Ok(NotificationChannel::InApp),
+        "none" => Ok(NotificationChannel::None),
+        other => Err(unknown("channel", other)),
+    }
+}
+
+fn language_from_str(language: &str) -> Result<NotificationLanguage> {
+    match language {
+        "ja" => Ok(NotificationLanguage::Ja),
+        "en" => Ok(NotificationLanguage::En),
+        other => Err(unknown("language", other)),
+    }
+}
+
+fn digest_from_str(digest: &str) -> Result<DigestMode> {
+    match digest {
+        "immediate" => Ok(DigestMode::Immediate),
+        "daily" => Ok(DigestMode::Daily),
+        other => Err(unknown("digest mode", other)),
+    }
+}
+
+fn preferences_from_row(row: &PgRow) -> Result<NotificationPreferences> {
+    let start: Option<NaiveTime> = row.try_get("quiet_hours_start")?;
+    let end: Option<NaiveTime> = row.try_get("quiet_hours_end")?;
+    Ok(NotificationPreferences {
+        channel: channel_from_str(row.try_get("channel")?)?,
+        language: language_from_str(row.try_get("language")?)?,
+        digest: digest_from_str(row.try_get("digest")?)?,
+        quiet_hours: start.zip(end).map(|(start, end)| QuietHours { start, end }),
+        utc_offset_minutes: row.try_get("utc_offset_minutes")?,
+    })
+}
+
+#[async_trait]
+impl NotificationPreferenceStoreTrait for NotificationPreferenceStore {
+    async fn get(&self, member_id: MemberId) -> Result<Option<NotificationPreferences>> {
+        let mut tx = begin_tenant_transaction(&self.pool, self.tenant_id).await?;
+
+        let row = sqlx::query(
+            r#"
+            SELECT channel, language, digest, quiet_hours_start, quiet_hours_end,
+                   utc_offset_minutes
+            FROM notification_preferences
+            WHERE tenant_id = $1 AND member_id = $2
+            "#,
+        )
+        .bind(self.tenant_id.value())
+        .bind(member_id.value())
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        row.as_ref().map(preferences_from_row).transpose()
+    }
+
+    async fn save(
+        &self,
+        member_id: MemberId,
+        preferences: &NotificationPreferences,
+        updated_at: DateTime<Utc>,
+    ) -> Result<()> {
+        let mut tx = begin_tenant_transaction(&self.pool, self.tenant_id).await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO notification_preferences (
+                tenant_id, member_id, channel, language, digest,
+                quiet_hours_start, quiet_hours_end, utc_offset_minutes, updated_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            ON CONFLICT (tenant_id, member_id) DO UPDATE SET
+                channel = EXCLUDED.channel,
+                language = EXCLUDED.language,
+                digest = EXCLUDED.digest,
+                quiet_hours_start = EXCLUDED.quiet_hours_start,
+                quiet_hours_end = EXCLUDED.quiet_hours_end,
+                utc_offset_minutes = EXCLUDED.utc_offset_minutes,
+                updated_at = EXCLUDED.updated_at
+            "#,
+        )
+        .bind(self.tenant_id.value())
+        .bind(member_id.value())
+        .bind(preferences.channel.as_str())
+        .bind(preferences.language.as_str())
+        .bind(preferences.digest.as_str())
+        .bind(preferences.quiet_hours.map(|q| q.start))
+        .bind(preferences.quiet_hours.map(|q| q.end))
+        .bind(preferences.utc_offset_minutes)
+        .bind(updated_at)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Delete the preferences; the foreign key cascades to deferred notices
+    async fn delete(&self, member_id: MemberId) -> Result<()> {
+        let mut tx = begin_tenant_transaction(&self.pool, self.tenant_id).await?;
+
+        sqlx::query(
+            r#"
+            DELETE FROM notification_preferences
+            WHERE tenant_id = $1 AND member_id = $2
+            "#,
+        )
+        .bind(self.tenant_id.value())
+        .bind(member_id.value())
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(())
+    }
+}
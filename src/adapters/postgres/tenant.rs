@@ -0,0 +1,79 @@
+use crate::domain::{CirculationPolicy, value_objects::TenantId};
+use crate::ports::tenant_directory::{
+    Result, TenantConfig, TenantDirectory as TenantDirectoryTrait, TenantDirectoryError,
+};
+use async_trait::async_trait;
+use sqlx::{PgPool, Postgres, Row, Transaction};
+
+/// Begin a transaction scoped to a tenant
+///
+/// Sets `app.tenant_id` for the lifetime of the transaction so that the
+/// row-level security policies on `events` and `loans_view` only expose
+/// (and only accept) rows belonging to the tenant.
+pub(crate) async fn begin_tenant_transaction(
+    pool: &PgPool,
+    tenant_id: TenantId,
+) -> sqlx::Result<Transaction<'static, Postgres>> {
+    let mut tx = pool.begin().await?;
+
+    sqlx::query("SELECT set_config('app.tenant_id', $1, true)")
+        .bind(tenant_id.value().to_string())
+        .execute(&mut *tx)
+        .await?;
+
+    Ok(tx)
+}
+
+/// PostgreSQL implementation of TenantDirectory
+///
+/// Reads the tenants table, which holds one row per library together
+/// with its circulation policy.
+#[allow(dead_code)]
+pub struct TenantDirectory {
+    pool: PgPool,
+}
+
+#[allow(dead_code)]
+impl TenantDirectory {
+    /// Create a new TenantDirectory with a PostgreSQL connection pool
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl TenantDirectoryTrait for TenantDirectory {
+    /// List all tenants ordered by subdomain
+    async fn list_tenants(&self) -> Result<Vec<TenantConfig>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT tenant_id, name, subdomain, max_active_loans, loan_period_days,
+                   history_retention_days
+            FROM tenants
+            ORDER BY subdomain ASC
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter()
+            .map(|row| {
+                let max_active_loans: i32 = row.get("max_active_loans");
+                let loan_period_days: i32 = row.get("loan_period_days");
+                let history_retention_days: Option<i32> = row.get("history_retention_days");
+
+                Ok(TenantConfig {
+                    tenant_id: TenantId::from_uuid(row.get("tenant_id")),
+                    name: row.get("name"),
+                    subdomain: row.get("subdomain"),
+                    policy: CirculationPolicy {
+                        max_active_loans: usize::try_from(max_active_loans)
+                            .map_err(|e| TenantDirectoryError::Corrupted(e.into()))?,
+                        loan_period_days: i64::from(loan_period_days),
+                        history_retention_days: history_retention_days.map(i64::from),
+                    },
+                })
+            })
+            .collect()
+    }
+}
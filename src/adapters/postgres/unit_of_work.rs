@@ -0,0 +1,87 @@
+use crate::domain::events::DomainEvent;
+use crate::domain::value_objects::TenantId;
+use crate::ports::event_store::{AppendedEvent, EventStoreError};
+use crate::ports::loan_read_model::LoanView;
+use crate::ports::unit_of_work::{Result, UnitOfWork as UnitOfWorkTrait};
+use async_trait::async_trait;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use super::event_codec::EventCodec;
+use super::event_store::EventStore;
+use super::loan_read_model::LoanReadModel;
+use super::tenant::begin_tenant_transaction;
+
+/// PostgreSQL implementation of UnitOfWork
+///
+/// The event store and the loan read model live in the same database, so
+/// the append and the read-model upsert run in one tenant-scoped
+/// transaction: either both are committed or neither is.
+#[allow(dead_code)]
+pub struct UnitOfWork {
+    pool: PgPool,
+    tenant_id: TenantId,
+    event_store: EventStore,
+    loan_read_model: LoanReadModel,
+}
+
+#[allow(dead_code)]
+impl UnitOfWork {
+    /// Create a new UnitOfWork scoped to the default tenant
+    pub fn new(pool: PgPool) -> Self {
+        Self::for_tenant(pool, TenantId::DEFAULT)
+    }
+
+    /// Create a new UnitOfWork scoped to the given tenant
+    pub fn for_tenant(pool: PgPool, tenant_id: TenantId) -> Self {
+        Self {
+            event_store: EventStore::for_tenant(pool.clone(), tenant_id),
+            loan_read_model: LoanReadModel::for_tenant(pool.clone(), tenant_id),
+            pool,
+            tenant_id,
+        }
+    }
+
+    /// Write new events with the given codec (see `EventStore::with_codec`)
+    pub fn with_codec(mut self, codec: EventCodec) -> Self {
+        self.event_store = self.event_store.with_codec(codec);
+        self
+    }
+}
+
+#[async_trait]
+impl UnitOfWorkTrait for UnitOfWork {
+    /// Append events and save the loan view in a single transaction
+    ///
+    /// The append takes the tenant's hash-chain lock first, so concurrent
+    /// commits for the tenant are serialized before the read model is
+    /// touched.
+    async fn commit(
+        &self,
+        aggregate_id: Uuid,
+        aggregate_type: &str,
+        expected_version: u32,
+        events: Vec<DomainEvent>,
+        loan_view: LoanView,
+    ) -> Result<Vec<AppendedEvent>> {
+        // Failures of the transaction itself are reported as event store errors
+        let mut tx = begin_tenant_transaction(&self.pool, self.tenant_id)
+            .await
+            .map_err(EventStoreError::from)?;
+
+        let appended = self
+            .event_store
+            .append_in(
+                &mut tx,
+                aggregate_id,
+                aggregate_type,
+                Some(expected_version),
+                &events,
+            )
+            .await?;
+        self.loan_read_model.save_in(&mut tx, &loan_view).await?;
+
+        tx.commit().await.map_err(EventStoreError::from)?;
+        Ok(appended)
+    }
+}
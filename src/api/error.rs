@@ -1,12 +1,41 @@
 use crate::application::loan::LoanApplicationError;
+use crate::ports::Classified;
 use axum::{
     Json,
-    http::StatusCode,
+    http::{StatusCode, header},
     response::{IntoResponse, Response},
 };
 
 use super::types::ErrorResponse;
 
+/// 一時的な障害で503を返すときに`Retry-After`で示す秒数
+pub(crate) const RETRY_AFTER_SECONDS: u64 = 5;
+
+/// システム障害のステータスコード
+///
+/// 再試行で回復しうる障害（接続断・競合など）は503、それ以外（データの破損・バグなど）は500。
+pub(crate) fn failure_status(error: &impl Classified) -> StatusCode {
+    if error.is_retryable() {
+        StatusCode::SERVICE_UNAVAILABLE
+    } else {
+        StatusCode::INTERNAL_SERVER_ERROR
+    }
+}
+
+/// ステータスとボディからレスポンスを作る（503には`Retry-After`を付ける）
+pub(crate) fn error_response(status: StatusCode, body: ErrorResponse) -> Response {
+    if status == StatusCode::SERVICE_UNAVAILABLE {
+        (
+            status,
+            [(header::RETRY_AFTER, RETRY_AFTER_SECONDS.to_string())],
+            Json(body),
+        )
+            .into_response()
+    } else {
+        (status, Json(body)).into_response()
+    }
+}
+
 /// API層のエラー型
 ///
 /// アプリケーション層のエラーをラップし、HTTPレスポンスへのマッピングを提供する。
@@ -27,12 +56,27 @@ impl IntoResponse for ApiError {
                 (StatusCode::NOT_FOUND, "LOAN_NOT_FOUND", "Loan not found")
             }
 
+            // 400 Bad Request - 実行前の検証で拒否されたコマンド
+            LoanApplicationError::InvalidCommand(ref msg) => {
+                (StatusCode::BAD_REQUEST, "INVALID_COMMAND", msg.as_str())
+            }
+
+            // 403 Forbidden - 実行者に許可されていないコマンド
+            LoanApplicationError::Forbidden(ref msg) => {
+                (StatusCode::FORBIDDEN, "FORBIDDEN", msg.as_str())
+            }
+
             // 422 Unprocessable Entity - ビジネスルール違反
             LoanApplicationError::MemberNotFound => (
                 StatusCode::UNPROCESSABLE_ENTITY,
                 "MEMBER_NOT_FOUND",
                 "Member not found",
             ),
+            LoanApplicationError::MemberSuspended => (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                "MEMBER_SUSPENDED",
+                "Member is suspended and cannot borrow books",
+            ),
             LoanApplicationError::BookNotAvailable => (
                 StatusCode::UNPROCESSABLE_ENTITY,
                 "BOOK_NOT_AVAILABLE",
@@ -46,7 +90,7 @@ impl IntoResponse for ApiError {
             LoanApplicationError::LoanLimitExceeded => (
                 StatusCode::UNPROCESSABLE_ENTITY,
                 "LOAN_LIMIT_EXCEEDED",
-                "Loan limit exceeded (max 5 books per member)",
+                "Loan limit exceeded for this member",
             ),
             LoanApplicationError::InvalidLoanState(ref msg) => (
                 StatusCode::UNPROCESSABLE_ENTITY,
@@ -58,13 +102,26 @@ impl IntoResponse for ApiError {
                 "DOMAIN_ERROR",
                 msg.as_str(),
             ),
+            LoanApplicationError::IdempotencyKeyReused => (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                "IDEMPOTENCY_KEY_REUSED",
+                "Idempotency key was already used for a different request",
+            ),
 
-            // 500 Internal Server Error - システム障害
+            // 409 Conflict - 同じ冪等キーのリクエストが処理中（完了後に再送すれば結果が返る）
+            LoanApplicationError::IdempotencyKeyInProgress => (
+                StatusCode::CONFLICT,
+                "IDEMPOTENCY_KEY_IN_PROGRESS",
+                "A request with the same idempotency key is still being processed",
+            ),
+
+            // 503 Service Unavailable / 500 Internal Server Error - システム障害
+            // 一時的な障害は503（Retry-After付き）、それ以外は500を返す。
             // 内部エラーの詳細はログに記録し、クライアントには一般的なメッセージのみを返す
             LoanApplicationError::EventStoreError(ref e) => {
                 tracing::error!("Event store error: {}", e);
                 (
-                    StatusCode::INTERNAL_SERVER_ERROR,
+                    failure_status(e),
                     "EVENT_STORE_ERROR",
                     "Failed to store event",
                 )
@@ -72,7 +129,7 @@ impl IntoResponse for ApiError {
             LoanApplicationError::ReadModelError(ref e) => {
                 tracing::error!("Read model error: {}", e);
                 (
-                    StatusCode::INTERNAL_SERVER_ERROR,
+                    failure_status(e),
                     "READ_MODEL_ERROR",
                     "Failed to update read model",
                 )
@@ -80,7 +137,7 @@ impl IntoResponse for ApiError {
             LoanApplicationError::MemberServiceError(ref e) => {
                 tracing::error!("Member service error: {}", e);
                 (
-                    StatusCode::INTERNAL_SERVER_ERROR,
+                    failure_status(e),
                     "MEMBER_SERVICE_ERROR",
                     "Member service error",
                 )
@@ -88,14 +145,59 @@ impl IntoResponse for ApiError {
             LoanApplicationError::BookServiceError(ref e) => {
                 tracing::error!("Book service error: {}", e);
                 (
-                    StatusCode::INTERNAL_SERVER_ERROR,
+                    failure_status(e),
                     "BOOK_SERVICE_ERROR",
                     "Book service error",
                 )
             }
+            LoanApplicationError::StaffServiceError(ref e) => {
+                tracing::error!("Staff service error: {}", e);
+                (
+                    failure_status(e),
+                    "STAFF_SERVICE_ERROR",
+                    "Staff service error",
+                )
+            }
+            LoanApplicationError::IdempotencyStoreError(ref e) => {
+                tracing::error!("Idempotency key store error: {}", e);
+                (
+                    failure_status(e),
+                    "IDEMPOTENCY_STORE_ERROR",
+                    "Idempotency key store error",
+                )
+            }
         };
 
-        let body = Json(ErrorResponse::new(error_type, message));
-        (status, body).into_response()
+        error_response(status, ErrorResponse::new(error_type, message))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ports::EventStoreError;
+
+    #[test]
+    fn test_transient_failure_maps_to_503_with_retry_after() {
+        let error = LoanApplicationError::EventStoreError(EventStoreError::Unavailable(
+            "connection reset".into(),
+        ));
+        let response = ApiError::from(error).into_response();
+
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(
+            response.headers()[header::RETRY_AFTER],
+            RETRY_AFTER_SECONDS.to_string()
+        );
+    }
+
+    #[test]
+    fn test_permanent_failure_maps_to_500() {
+        let error =
+            LoanApplicationError::EventStoreError(EventStoreError::Corrupted("bad row".into()));
+        let response = ApiError::from(error).into_response();
+
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert!(response.headers().get(header::RETRY_AFTER).is_none());
     }
 }
@@ -1,22 +1,39 @@
+use crate::application::authorization::{Permission, authorize_staff};
+use crate::application::command_bus::{CommandBus, CommandEnvelope, CommandMetrics};
 use crate::application::loan::{
-    LoanApplicationError, ServiceDependencies, extend_loan as execute_extend_loan,
-    loan_book as execute_loan_book, return_book as execute_return_book,
+    LoanApplicationError, ServiceDependencies, check_loan_eligibility, detect_overdue_loans,
+    list_loan_overrides as execute_list_loan_overrides,
+    set_reading_history_preference as execute_set_reading_history_preference,
 };
-use crate::domain::value_objects::{LoanId, MemberId};
+use crate::application::notification::{
+    NotificationPreferencesError,
+    get_notification_preferences as execute_get_notification_preferences,
+    set_notification_preferences as execute_set_notification_preferences,
+};
+use crate::application::privacy::{PrivacyError, export_member_data as execute_export_member_data};
+use crate::domain::InvalidNotificationPreferences;
+use crate::domain::event_schema::{EventSchemaRegistry, schema_id};
+use crate::domain::value_objects::{BookId, LoanId, MemberId, StaffId};
+use crate::ports::Classified;
 use axum::{
     Json,
     extract::{Path, Query, State},
-    http::StatusCode,
+    http::{HeaderMap, StatusCode, header},
     response::{IntoResponse, Response},
 };
 use std::sync::Arc;
 use uuid::Uuid;
 
 use super::{
-    error::ApiError,
+    error::{ApiError, error_response},
+    tenant::{Tenant, TenantRegistry},
     types::{
-        BookReturnedResponse, ListLoansQuery, LoanBookRequest, LoanCreatedResponse,
-        LoanExtendedResponse, LoanResponse,
+        BookReturnedResponse, EligibilityQuery, EligibilityResponse, EventSchemaSummary,
+        ExportMemberDataQuery, ListLoansQuery, LoanBookRequest, LoanCreatedResponse,
+        LoanExtendedResponse, LoanOverrideResponse, LoanOverridesQuery, LoanResponse,
+        NotificationPreferencesRequest, NotificationPreferencesResponse, OverdueDetectionQuery,
+        OverdueDetectionResponse, ReadingHistoryPreferenceRequest,
+        ReadingHistoryPreferenceResponse, ReturnBookQuery,
     },
 };
 
@@ -27,7 +44,140 @@ use super::{
 /// ハンドラー間で共有されるアプリケーション状態
 #[derive(Clone)]
 pub struct AppState {
-    pub service_deps: ServiceDependencies,
+    /// テナントごとのサービス依存関係
+    pub tenants: TenantRegistry,
+    /// 貸出管理のコマンドを実行するコマンドバス（全テナントで共有）
+    pub commands: CommandBus,
+    /// コマンドバスの実行統計
+    pub command_metrics: Arc<CommandMetrics>,
+}
+
+impl AppState {
+    /// 標準のコマンドバスを持つアプリケーション状態を作成
+    pub fn new(tenants: TenantRegistry) -> Self {
+        let command_metrics = Arc::new(CommandMetrics::default());
+        Self {
+            tenants,
+            commands: CommandBus::standard(command_metrics.clone()),
+            command_metrics,
+        }
+    }
+
+    /// 単一館運用（既定テナントのみ）のアプリケーション状態を作成
+    pub fn single_tenant(service_deps: ServiceDependencies) -> Self {
+        let mut tenants = TenantRegistry::new();
+        tenants.register("default", service_deps);
+        Self::new(tenants)
+    }
+}
+
+/// 再送されたリクエストを一度だけ実行するためのHTTPヘッダー
+pub const IDEMPOTENCY_KEY_HEADER: &str = "idempotency-key";
+
+/// コマンドを実行する職員を示すHTTPヘッダー
+pub const STAFF_HEADER: &str = "x-staff-id";
+
+/// 貸出条件の例外を承認した職員を示すHTTPヘッダー
+pub const APPROVER_HEADER: &str = "x-approver-id";
+
+/// リクエストした会員を示すHTTPヘッダー（会員向けの画面から送られる）
+pub const MEMBER_HEADER: &str = "x-member-id";
+
+/// UUIDを値とするヘッダーを取得する（`label`はエラーメッセージ用のヘッダー名）
+fn uuid_from_headers(
+    headers: &HeaderMap,
+    name: &str,
+    label: &str,
+) -> Result<Option<Uuid>, LoanApplicationError> {
+    let Some(value) = headers.get(name) else {
+        return Ok(None);
+    };
+    let uuid = value
+        .to_str()
+        .ok()
+        .and_then(|v| Uuid::parse_str(v.trim()).ok())
+        .ok_or_else(|| {
+            LoanApplicationError::InvalidCommand(format!("{label} header must be a UUID"))
+        })?;
+    Ok(Some(uuid))
+}
+
+/// リクエストの実行者（`X-Staff-Id`ヘッダー）を取得する
+fn staff_from_headers(headers: &HeaderMap) -> Result<Option<StaffId>, LoanApplicationError> {
+    Ok(uuid_from_headers(headers, STAFF_HEADER, "X-Staff-Id")?.map(StaffId::from_uuid))
+}
+
+/// 貸出条件の例外の承認者（`X-Approver-Id`ヘッダー）を取得する
+///
+/// 承認者はリクエストの本文では受け付けない（実行者が他の職員の名前で承認できないように）。
+fn approver_from_headers(headers: &HeaderMap) -> Result<StaffId, LoanApplicationError> {
+    uuid_from_headers(headers, APPROVER_HEADER, "X-Approver-Id")?
+        .map(StaffId::from_uuid)
+        .ok_or_else(|| {
+            LoanApplicationError::InvalidCommand(
+                "an override must be approved with the X-Approver-Id header".to_string(),
+            )
+        })
+}
+
+/// クエリの実行者（`X-Staff-Id`ヘッダー）が権限を持つか確認する
+///
+/// ヘッダーがない場合や、職員が不明・権限がない場合はForbiddenとする。
+async fn authorize_query(
+    deps: &ServiceDependencies,
+    headers: &HeaderMap,
+    permission: Permission,
+) -> Result<StaffId, QueryError> {
+    let staff_id = staff_from_headers(headers)
+        .map_err(|_| QueryError::BadRequest("X-Staff-Id header must be a UUID".to_string()))?
+        .ok_or_else(|| {
+            QueryError::Forbidden("the acting staff member must be identified".to_string())
+        })?;
+    authorize_staff(deps, Some(staff_id), permission)
+        .await
+        .map_err(QueryError::from_authorization)?;
+    Ok(staff_id)
+}
+
+/// 会員の設定の参照・変更を認可する
+///
+/// 会員本人（`X-Member-Id`ヘッダーがパスの会員と一致する）か、
+/// `ManageMemberSettings`の権限を持つ職員（`X-Staff-Id`ヘッダー）のみ許可する。
+async fn authorize_member_settings(
+    deps: &ServiceDependencies,
+    headers: &HeaderMap,
+    member_id: MemberId,
+) -> Result<(), LoanApplicationError> {
+    if uuid_from_headers(headers, MEMBER_HEADER, "X-Member-Id")? == Some(member_id.value()) {
+        return Ok(());
+    }
+    let Some(staff_id) = staff_from_headers(headers)? else {
+        return Err(LoanApplicationError::Forbidden(
+            "only the member or authorized staff can access the member's settings".to_string(),
+        ));
+    };
+    authorize_staff(deps, Some(staff_id), Permission::ManageMemberSettings).await
+}
+
+/// コマンドにリクエストの実行者を付ける
+///
+/// ヘッダーがない場合、貸出の作成ではリクエストの`staff_id`が実行者になる。
+fn with_actor(envelope: CommandEnvelope, headers: &HeaderMap) -> Result<CommandEnvelope, ApiError> {
+    Ok(match staff_from_headers(headers)? {
+        Some(staff_id) => envelope.with_actor(staff_id),
+        None => envelope,
+    })
+}
+
+/// コマンドにリクエストの冪等キーを付ける
+fn with_idempotency_key(envelope: CommandEnvelope, headers: &HeaderMap) -> CommandEnvelope {
+    match headers
+        .get(IDEMPOTENCY_KEY_HEADER)
+        .map(|v| v.to_str().unwrap_or_default())
+    {
+        Some(key) => envelope.with_idempotency_key(key),
+        None => envelope,
+    }
 }
 
 // ============================================================================
@@ -40,20 +190,36 @@ pub struct AppState {
 ///
 /// 強制されるビジネスルール:
 /// - 会員が存在すること
+/// - 会員が利用停止中でないこと
 /// - 書籍が貸出可能であること
 /// - 会員に延滞中の貸出がないこと
 /// - 会員の貸出数が上限（5冊）を超えないこと
+/// - 実行者（`X-Staff-Id`、省略時は`staff_id`）に貸出の権限があること
+///
+/// - 実行者と`staff_id`が同じ職員であること
+/// - `loaned_at`が未来でないこと（24時間より前の日時での記録は訂正の権限が必要）
+///
+/// `override`で監督者の承認を付けると、承認された条件（貸出上限・延滞）は判定しない。
+/// 承認者（`X-Approver-Id`）は例外を承認する権限を持つ職員であること。
 pub async fn create_loan(
     State(state): State<Arc<AppState>>,
+    Tenant(deps): Tenant,
+    headers: HeaderMap,
     Json(req): Json<LoanBookRequest>,
 ) -> Result<(StatusCode, Json<LoanCreatedResponse>), ApiError> {
-    let cmd = req.to_command();
+    let cmd = req.to_command(req.loaned_at.unwrap_or_else(chrono::Utc::now));
+    let envelope = match &req.override_token {
+        Some(token) => {
+            CommandEnvelope::new(token.to_command(cmd.clone(), approver_from_headers(&headers)?))
+        }
+        None => CommandEnvelope::new(cmd.clone()),
+    };
 
-    let loan_id = execute_loan_book(&state.service_deps, cmd.clone()).await?;
+    let envelope = with_idempotency_key(with_actor(envelope, &headers)?, &headers);
+    let loan_id = state.commands.dispatch(&deps, envelope).await?;
 
     // 作成された貸出を取得して完全な情報を返す
-    let loan_view = state
-        .service_deps
+    let loan_view = deps
         .loan_read_model
         .get_by_id(loan_id)
         .await
@@ -79,8 +245,11 @@ pub async fn create_loan(
 /// - 貸出が存在すること
 /// - 貸出がActive状態であること（OverdueまたはReturnedでないこと）
 /// - 延長回数が1未満であること（最大1回まで延長可能）
+/// - 実行者（`X-Staff-Id`）に延長の権限があること
 pub async fn extend_loan(
     State(state): State<Arc<AppState>>,
+    Tenant(deps): Tenant,
+    headers: HeaderMap,
     Path(loan_id): Path<Uuid>,
 ) -> Result<(StatusCode, Json<LoanExtendedResponse>), ApiError> {
     let loan_id = LoanId::from_uuid(loan_id);
@@ -90,11 +259,11 @@ pub async fn extend_loan(
         extended_at: chrono::Utc::now(),
     };
 
-    execute_extend_loan(&state.service_deps, cmd).await?;
+    let envelope = with_idempotency_key(with_actor(CommandEnvelope::new(cmd), &headers)?, &headers);
+    state.commands.dispatch(&deps, envelope).await?;
 
     // 更新された貸出を取得して新しい情報を返す
-    let loan_view = state
-        .service_deps
+    let loan_view = deps
         .loan_read_model
         .get_by_id(loan_id)
         .await
@@ -118,22 +287,27 @@ pub async fn extend_loan(
 /// - 貸出が存在すること
 /// - 既に返却済みでないこと
 /// - 延滞中の貸出も返却可能（公立図書館のため延滞料金なし）
+/// - 実行者（`X-Staff-Id`）に返却の権限があること
+/// - `returned_at`が未来でないこと（24時間より前の日時での記録は訂正の権限が必要）
 pub async fn return_book(
     State(state): State<Arc<AppState>>,
+    Tenant(deps): Tenant,
+    headers: HeaderMap,
     Path(loan_id): Path<Uuid>,
+    Query(query): Query<ReturnBookQuery>,
 ) -> Result<(StatusCode, Json<BookReturnedResponse>), ApiError> {
     let loan_id = LoanId::from_uuid(loan_id);
 
     let cmd = crate::domain::commands::ReturnBook {
         loan_id,
-        returned_at: chrono::Utc::now(),
+        returned_at: query.returned_at.unwrap_or_else(chrono::Utc::now),
     };
 
-    execute_return_book(&state.service_deps, cmd).await?;
+    let envelope = with_idempotency_key(with_actor(CommandEnvelope::new(cmd), &headers)?, &headers);
+    state.commands.dispatch(&deps, envelope).await?;
 
     // 更新された貸出を取得して返却を確認
-    let loan_view = state
-        .service_deps
+    let loan_view = deps
         .loan_read_model
         .get_by_id(loan_id)
         .await
@@ -148,26 +322,190 @@ pub async fn return_book(
     Ok((StatusCode::OK, Json(response)))
 }
 
+/// PUT /members/:id/reading-history - 読書履歴の保持設定を変更
+///
+/// 保持を選択した会員の返却済み貸出は、館の保持期間を過ぎても
+/// 会員の貸出履歴として残る。
+///
+/// 会員本人か、会員の設定を扱う権限を持つ職員のみ変更できる。
+///
+/// 強制されるビジネスルール:
+/// - 会員が存在すること
+pub async fn set_reading_history_preference(
+    Tenant(deps): Tenant,
+    Path(member_id): Path<Uuid>,
+    headers: HeaderMap,
+    Json(req): Json<ReadingHistoryPreferenceRequest>,
+) -> Result<Json<ReadingHistoryPreferenceResponse>, ApiError> {
+    let member_id = MemberId::from_uuid(member_id);
+    authorize_member_settings(&deps, &headers, member_id).await?;
+
+    let cmd = crate::domain::commands::SetReadingHistoryPreference {
+        member_id,
+        keep_history: req.keep_history,
+        changed_at: chrono::Utc::now(),
+    };
+
+    execute_set_reading_history_preference(&deps, cmd).await?;
+
+    // 更新された設定を取得して返す
+    let opted_in_at = deps
+        .loan_read_model
+        .get_history_opt_in(member_id)
+        .await
+        .map_err(|e| ApiError::from(LoanApplicationError::ReadModelError(e)))?;
+
+    Ok(Json(ReadingHistoryPreferenceResponse::new(
+        member_id,
+        opted_in_at,
+    )))
+}
+
+/// PUT /members/:id/notification-preferences - 通知設定を変更
+///
+/// 省略した項目には既定値を使う（設定全体を置き換える）。
+/// 会員本人か、会員の設定を扱う権限を持つ職員のみ変更できる。
+///
+/// 強制されるビジネスルール:
+/// - 会員が存在すること
+/// - 時差が-720〜840分であること
+/// - 通知を送らない時間帯の開始と終了が異なること
+pub async fn set_notification_preferences(
+    Tenant(deps): Tenant,
+    Path(member_id): Path<Uuid>,
+    headers: HeaderMap,
+    Json(req): Json<NotificationPreferencesRequest>,
+) -> Result<Json<NotificationPreferencesResponse>, QueryError> {
+    let member_id = MemberId::from_uuid(member_id);
+    authorize_member_settings(&deps, &headers, member_id)
+        .await
+        .map_err(QueryError::from_authorization)?;
+
+    let preferences = execute_set_notification_preferences(
+        &deps,
+        member_id,
+        req.to_preferences(),
+        chrono::Utc::now(),
+    )
+    .await
+    .map_err(QueryError::from_preferences)?;
+
+    Ok(Json(NotificationPreferencesResponse::new(
+        member_id,
+        preferences,
+    )))
+}
+
 // ============================================================================
 // Query handlers (GET)
 // ============================================================================
 
+/// GET /members/:id/notification-preferences - 通知設定を取得
+///
+/// 設定していない会員には既定の設定を返す。会員が存在しない場合は404を返す。
+/// 会員本人か、会員の設定を扱う権限を持つ職員のみ参照できる。
+pub async fn get_notification_preferences(
+    Tenant(deps): Tenant,
+    Path(member_id): Path<Uuid>,
+    headers: HeaderMap,
+) -> Result<Json<NotificationPreferencesResponse>, QueryError> {
+    let member_id = MemberId::from_uuid(member_id);
+    authorize_member_settings(&deps, &headers, member_id)
+        .await
+        .map_err(QueryError::from_authorization)?;
+
+    let preferences = execute_get_notification_preferences(&deps, member_id)
+        .await
+        .map_err(QueryError::from_preferences)?;
+
+    Ok(Json(NotificationPreferencesResponse::new(
+        member_id,
+        preferences,
+    )))
+}
+
+/// GET /members/:id/reading-history - 読書履歴の保持設定を取得
+///
+/// 会員本人か、会員の設定を扱う権限を持つ職員のみ参照できる。
+/// 会員が存在しない場合は404を返す。
+pub async fn get_reading_history_preference(
+    Tenant(deps): Tenant,
+    Path(member_id): Path<Uuid>,
+    headers: HeaderMap,
+) -> Result<Json<ReadingHistoryPreferenceResponse>, QueryError> {
+    let member_id = MemberId::from_uuid(member_id);
+    authorize_member_settings(&deps, &headers, member_id)
+        .await
+        .map_err(QueryError::from_authorization)?;
+
+    let member_exists = deps
+        .member_service
+        .exists(member_id)
+        .await
+        .map_err(QueryError::from_port)?;
+    if !member_exists {
+        return Err(QueryError::NotFound(format!(
+            "Member {} not found",
+            member_id.value()
+        )));
+    }
+
+    let opted_in_at = deps
+        .loan_read_model
+        .get_history_opt_in(member_id)
+        .await
+        .map_err(QueryError::from_port)?;
+
+    Ok(Json(ReadingHistoryPreferenceResponse::new(
+        member_id,
+        opted_in_at,
+    )))
+}
+
+/// GET /members/:id/eligibility - 会員が書籍を借りられるか確認
+///
+/// 貸出の作成（POST /loans）と同じ条件を判定し、満たされていない条件をすべて返す。
+/// 何も保存しない。借りられない場合も200を返し、`eligible`がfalseになる。
+///
+/// クエリパラメータ:
+/// - book_id: 借りようとしている書籍のID（必須）
+pub async fn get_loan_eligibility(
+    Tenant(deps): Tenant,
+    Path(member_id): Path<Uuid>,
+    Query(query): Query<EligibilityQuery>,
+) -> Result<Json<EligibilityResponse>, QueryError> {
+    let book_id = query
+        .book_id
+        .ok_or_else(|| QueryError::BadRequest("book_id query parameter is required".to_string()))?;
+
+    let member_id = MemberId::from_uuid(member_id);
+    let book_id = BookId::from_uuid(book_id);
+
+    let violations = check_loan_eligibility(&deps, member_id, book_id)
+        .await
+        .map_err(QueryError::from_port)?;
+
+    Ok(Json(EligibilityResponse::new(
+        member_id, book_id, violations,
+    )))
+}
+
 /// GET /loans/:id - 貸出詳細をIDで取得
 ///
 /// 見つかった場合は貸出情報を返し、見つからない場合は404を返す。
 pub async fn get_loan_by_id(
-    State(state): State<Arc<AppState>>,
+    Tenant(deps): Tenant,
     Path(loan_id): Path<Uuid>,
 ) -> Result<Json<LoanResponse>, QueryError> {
     let loan_id = LoanId::from_uuid(loan_id);
 
-    match state.service_deps.loan_read_model.get_by_id(loan_id).await {
+    match deps.loan_read_model.get_by_id(loan_id).await {
         Ok(Some(loan_view)) => Ok(Json(LoanResponse::from(loan_view))),
         Ok(None) => Err(QueryError::NotFound(format!(
             "Loan {} not found",
             loan_id.value()
         ))),
-        Err(e) => Err(QueryError::InternalError(e.to_string())),
+        Err(e) => Err(QueryError::from_port(e)),
     }
 }
 
@@ -175,12 +513,14 @@ pub async fn get_loan_by_id(
 ///
 /// クエリパラメータ:
 /// - member_id: 会員IDでフィルタリング（必須）
-/// - status: ステータスでフィルタリング（active, overdue, returned）（オプション）
+/// - status: ステータスでフィルタリング（active, overdue, returned, lost）（オプション）
 ///
 /// フィルタが指定されない場合は、会員の全貸出を返す。
+/// 館の保持期間を過ぎた返却済みの貸出は、会員が読書履歴の保持を
+/// 選択していない限り含まれない。
 /// 現在はmember_idパラメータが必須。
 pub async fn list_loans(
-    State(state): State<Arc<AppState>>,
+    Tenant(deps): Tenant,
     Query(query): Query<ListLoansQuery>,
 ) -> Result<Json<Vec<LoanResponse>>, QueryError> {
     // member_idを必須とする
@@ -189,14 +529,18 @@ pub async fn list_loans(
     })?;
 
     let member_id = MemberId::from_uuid(member_id);
+    if member_id.is_anonymised() {
+        return Err(QueryError::BadRequest(
+            "member_id must identify a member".to_string(),
+        ));
+    }
 
     // 会員の貸出を取得
-    let loans = state
-        .service_deps
+    let loans = deps
         .loan_read_model
-        .find_by_member_id(member_id)
+        .find_by_member_id(member_id, deps.policy.history_cutoff(chrono::Utc::now()))
         .await
-        .map_err(|e| QueryError::InternalError(e.to_string()))?;
+        .map_err(QueryError::from_port)?;
 
     // ステータスフィルタが指定されている場合は適用
     let filtered_loans: Vec<LoanResponse> = if let Some(status_str) = &query.status {
@@ -215,6 +559,179 @@ pub async fn list_loans(
     Ok(Json(filtered_loans))
 }
 
+/// GET /reports/loan-overrides - 貸出条件の例外を認めた貸出の一覧（監査用）
+///
+/// クエリパラメータ:
+/// - from: 期間の開始（既定: 終了の30日前）
+/// - to: 期間の終了（含まない。既定: 現在）
+///
+/// 実行者（`X-Staff-Id`）に監査用レポートの閲覧権限が必要。
+pub async fn list_loan_overrides(
+    Tenant(deps): Tenant,
+    headers: HeaderMap,
+    Query(query): Query<LoanOverridesQuery>,
+) -> Result<Json<Vec<LoanOverrideResponse>>, QueryError> {
+    authorize_query(&deps, &headers, Permission::ViewAuditReports).await?;
+
+    let to = query.to.unwrap_or_else(chrono::Utc::now);
+    let from = query.from.unwrap_or(to - chrono::Duration::days(30));
+    if from > to {
+        return Err(QueryError::BadRequest(
+            "from must not be later than to".to_string(),
+        ));
+    }
+
+    let records = execute_list_loan_overrides(&deps, from, to)
+        .await
+        .map_err(QueryError::from_port)?;
+
+    Ok(Json(
+        records
+            .into_iter()
+            .map(LoanOverrideResponse::from)
+            .collect(),
+    ))
+}
+
+/// POST /admin/overdue-detection - 延滞検出を手動で実行（管理者のみ）
+///
+/// 定期実行のジョブと同じ処理を行い、検出・見送り・失敗した貸出を返す。
+/// 一部の貸出の失敗はエラーにせず、`failed`として返す。
+///
+/// クエリパラメータ:
+/// - as_of: 判定の基準日時（既定: 現在。未来の日時は指定できない）
+///
+/// 実行者（`X-Staff-Id`）に一括処理の実行権限が必要。
+pub async fn run_overdue_detection(
+    Tenant(deps): Tenant,
+    headers: HeaderMap,
+    Query(query): Query<OverdueDetectionQuery>,
+) -> Result<Json<OverdueDetectionResponse>, ApiError> {
+    let staff_id = staff_from_headers(&headers)?;
+    authorize_staff(&deps, staff_id, Permission::RunBatchJobs).await?;
+
+    let now = chrono::Utc::now();
+    let as_of = query.as_of.unwrap_or(now);
+    if as_of > now {
+        return Err(LoanApplicationError::InvalidCommand(
+            "as_of must not be in the future".to_string(),
+        )
+        .into());
+    }
+
+    let report = detect_overdue_loans(&deps, as_of).await?;
+    Ok(Json(OverdueDetectionResponse::from(report)))
+}
+
+/// GET /members/:id/export - 会員データの写しを作成
+///
+/// 会員に関するイベント・貸出・読書履歴の保持設定をまとめて返す。
+/// 写しの作成はイベントとして記録される。
+///
+/// クエリパラメータ:
+/// - format: "json"（機械可読なアーカイブ、既定）または"summary"（人が読む要約）
+///
+/// 実行者（`X-Staff-Id`）に会員データの写しを作成する権限が必要。
+pub async fn export_member_data(
+    Tenant(deps): Tenant,
+    headers: HeaderMap,
+    Path(member_id): Path<Uuid>,
+    Query(query): Query<ExportMemberDataQuery>,
+) -> Result<Response, QueryError> {
+    let staff_id = authorize_query(&deps, &headers, Permission::ExportMemberData).await?;
+
+    let summary = match query.format.as_deref() {
+        None | Some("json") => false,
+        Some("summary") => true,
+        Some(other) => {
+            return Err(QueryError::BadRequest(format!(
+                "Invalid export format: {}",
+                other
+            )));
+        }
+    };
+
+    let cmd = crate::domain::commands::ExportMemberData {
+        member_id: MemberId::from_uuid(member_id),
+        staff_id,
+        requested_at: chrono::Utc::now(),
+    };
+
+    let archive = execute_export_member_data(&deps, cmd)
+        .await
+        .map_err(|e| match e {
+            PrivacyError::MemberNotFound => {
+                QueryError::NotFound(format!("Member {} not found", member_id))
+            }
+            e => QueryError::from_port(e),
+        })?;
+
+    if summary {
+        Ok((
+            [(header::CONTENT_TYPE, "text/plain; charset=utf-8")],
+            archive.summary(),
+        )
+            .into_response())
+    } else {
+        Ok(Json(archive).into_response())
+    }
+}
+
+/// GET /schemas/events - 登録済みのイベントスキーマの一覧
+///
+/// テナントに依存しないため、テナントの指定は不要。
+pub async fn list_event_schemas() -> Json<Vec<EventSchemaSummary>> {
+    let registry = EventSchemaRegistry::global();
+    let summaries = registry
+        .schemas()
+        .iter()
+        .map(|schema| EventSchemaSummary {
+            event_type: schema.event_type.to_string(),
+            version: schema.version,
+            id: schema_id(schema.event_type, schema.version),
+            href: format!("/schemas/events/{}/{}", schema.event_type, schema.version),
+            latest: registry
+                .latest(schema.event_type)
+                .is_some_and(|latest| latest.version == schema.version),
+        })
+        .collect();
+    Json(summaries)
+}
+
+/// GET /schemas/events/:event_type - イベント型の最新バージョンのスキーマ
+pub async fn get_latest_event_schema(
+    Path(event_type): Path<String>,
+) -> Result<Response, QueryError> {
+    let schema = EventSchemaRegistry::global()
+        .latest(&event_type)
+        .ok_or_else(|| QueryError::NotFound(format!("Unknown event type {}", event_type)))?;
+    Ok(schema_response(&schema.schema))
+}
+
+/// GET /schemas/events/:event_type/:version - イベント型の指定バージョンのスキーマ
+pub async fn get_event_schema(
+    Path((event_type, version)): Path<(String, u32)>,
+) -> Result<Response, QueryError> {
+    let schema = EventSchemaRegistry::global()
+        .get(&event_type, version)
+        .ok_or_else(|| {
+            QueryError::NotFound(format!(
+                "Schema {} v{} is not registered",
+                event_type, version
+            ))
+        })?;
+    Ok(schema_response(&schema.schema))
+}
+
+/// スキーマ本体を`application/schema+json`で返す
+fn schema_response(schema: &serde_json::Value) -> Response {
+    (
+        [(header::CONTENT_TYPE, "application/schema+json")],
+        schema.to_string(),
+    )
+        .into_response()
+}
+
 // ============================================================================
 // Error types
 // ============================================================================
@@ -224,14 +741,68 @@ pub async fn list_loans(
 pub enum QueryError {
     NotFound(String),
     BadRequest(String),
+    /// 実行者が不明、または権限がない
+    Forbidden(String),
+    /// 再試行で回復しうる障害（503、Retry-After付き）
+    ServiceUnavailable(String),
     InternalError(String),
 }
 
+impl QueryError {
+    /// ポートのエラーを分類に応じて503または500にする
+    fn from_port<E: Classified + std::fmt::Display>(error: E) -> Self {
+        if error.is_retryable() {
+            QueryError::ServiceUnavailable(error.to_string())
+        } else {
+            QueryError::InternalError(error.to_string())
+        }
+    }
+
+    /// 認可のエラーを403・400・503・500にする
+    fn from_authorization(error: LoanApplicationError) -> Self {
+        match error {
+            LoanApplicationError::Forbidden(msg) => QueryError::Forbidden(msg),
+            LoanApplicationError::InvalidCommand(msg) => QueryError::BadRequest(msg),
+            LoanApplicationError::StaffServiceError(e) => QueryError::from_port(e),
+            e => QueryError::InternalError(e.to_string()),
+        }
+    }
+
+    /// 通知設定のエラーを404・400・503・500にする
+    fn from_preferences(error: NotificationPreferencesError) -> Self {
+        match error {
+            NotificationPreferencesError::MemberNotFound(member_id) => {
+                QueryError::NotFound(format!("Member {} not found", member_id))
+            }
+            NotificationPreferencesError::InvalidPreferences(e) => {
+                QueryError::BadRequest(match e {
+                    InvalidNotificationPreferences::UtcOffsetOutOfRange => {
+                        "utc_offset_minutes must be between -720 and 840".to_string()
+                    }
+                    InvalidNotificationPreferences::EmptyQuietHours => {
+                        "quiet_hours must start and end at different times".to_string()
+                    }
+                })
+            }
+            e => QueryError::from_port(e),
+        }
+    }
+}
+
 impl IntoResponse for QueryError {
     fn into_response(self) -> Response {
         let (status, error_type, message) = match self {
             QueryError::NotFound(msg) => (StatusCode::NOT_FOUND, "not_found", msg),
             QueryError::BadRequest(msg) => (StatusCode::BAD_REQUEST, "bad_request", msg),
+            QueryError::Forbidden(msg) => (StatusCode::FORBIDDEN, "forbidden", msg),
+            QueryError::ServiceUnavailable(msg) => {
+                tracing::warn!("Service unavailable in query handler: {}", msg);
+                (
+                    StatusCode::SERVICE_UNAVAILABLE,
+                    "service_unavailable",
+                    "The service is temporarily unavailable, please retry".to_string(),
+                )
+            }
             QueryError::InternalError(msg) => {
                 // 内部エラーの詳細はログに記録し、クライアントには一般的なメッセージのみを返す
                 tracing::error!("Internal error in query handler: {}", msg);
@@ -243,7 +814,29 @@ impl IntoResponse for QueryError {
             }
         };
 
-        let body = Json(super::types::ErrorResponse::new(error_type, message));
-        (status, body).into_response()
+        error_response(
+            status,
+            super::types::ErrorResponse::new(error_type, message),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::error::RETRY_AFTER_SECONDS;
+    use crate::ports::EventStoreError;
+
+    #[test]
+    fn test_transient_privacy_failure_maps_to_503_with_retry_after() {
+        let error =
+            PrivacyError::EventStoreError(EventStoreError::Unavailable("connection reset".into()));
+        let response = QueryError::from_port(error).into_response();
+
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(
+            response.headers()[header::RETRY_AFTER],
+            RETRY_AFTER_SECONDS.to_string()
+        );
     }
 }
@@ -1,6 +1,7 @@
 pub mod error;
 pub mod handlers;
 pub mod router;
+pub mod tenant;
 pub mod types;
 
 pub use error::ApiError;
@@ -6,7 +6,11 @@ use std::sync::Arc;
 use tower_http::trace::TraceLayer;
 
 use super::handlers::{
-    AppState, create_loan, extend_loan, get_loan_by_id, list_loans, return_book,
+    AppState, create_loan, export_member_data, extend_loan, get_event_schema,
+    get_latest_event_schema, get_loan_by_id, get_loan_eligibility, get_notification_preferences,
+    get_reading_history_preference, list_event_schemas, list_loan_overrides, list_loans,
+    return_book, run_overdue_detection, set_notification_preferences,
+    set_reading_history_preference,
 };
 
 /// 貸出管理の全エンドポイントを持つAPIルーターを作成
@@ -15,10 +19,25 @@ use super::handlers::{
 /// - POST /loans - 新しい貸出を作成
 /// - POST /loans/:id/extend - 貸出を延長
 /// - POST /loans/:id/return - 書籍を返却
+/// - PUT /members/:id/reading-history - 読書履歴の保持設定を変更
+/// - PUT /members/:id/notification-preferences - 通知設定を変更
 ///
 /// クエリエンドポイント（Read操作）:
 /// - GET /loans - フィルタ付き貸出一覧
 /// - GET /loans/:id - 貸出詳細
+/// - GET /members/:id/reading-history - 読書履歴の保持設定
+/// - GET /members/:id/notification-preferences - 通知設定（未設定の場合は既定値）
+/// - GET /members/:id/eligibility - 貸出可否の確認（満たされていない条件をすべて返す）
+/// - GET /members/:id/export - 会員データの写し（作成はイベントとして記録される）
+/// - GET /reports/loan-overrides - 貸出条件の例外を認めた貸出（監査用）
+///
+/// 管理者向けのエンドポイント:
+/// - POST /admin/overdue-detection - 延滞検出を手動で実行し、結果の詳細を返す
+///
+/// 連携先向けのエンドポイント（テナントの指定は不要）:
+/// - GET /schemas/events - 登録済みのイベントスキーマの一覧
+/// - GET /schemas/events/:event_type - イベント型の最新バージョンのスキーマ
+/// - GET /schemas/events/:event_type/:version - 指定バージョンのスキーマ
 pub fn create_router(state: Arc<AppState>) -> Router {
     Router::new()
         // ヘルスチェックエンドポイント
@@ -29,6 +48,26 @@ pub fn create_router(state: Arc<AppState>) -> Router {
         .route("/loans/:id/return", post(return_book))
         // クエリエンドポイント（Read操作）
         .route("/loans/:id", get(get_loan_by_id))
+        .route(
+            "/members/:id/reading-history",
+            get(get_reading_history_preference).put(set_reading_history_preference),
+        )
+        .route(
+            "/members/:id/notification-preferences",
+            get(get_notification_preferences).put(set_notification_preferences),
+        )
+        .route("/members/:id/eligibility", get(get_loan_eligibility))
+        .route("/members/:id/export", get(export_member_data))
+        .route("/reports/loan-overrides", get(list_loan_overrides))
+        // 管理者向けの一括処理
+        .route("/admin/overdue-detection", post(run_overdue_detection))
+        // 連携先向けのイベントスキーマ
+        .route("/schemas/events", get(list_event_schemas))
+        .route("/schemas/events/:event_type", get(get_latest_event_schema))
+        .route(
+            "/schemas/events/:event_type/:version",
+            get(get_event_schema),
+        )
         // トレーシングミドルウェアを追加
         .layer(TraceLayer::new_for_http())
         // アプリケーション状態を追加
@@ -0,0 +1,142 @@
+use crate::application::loan::ServiceDependencies;
+use crate::domain::value_objects::TenantId;
+use axum::{
+    Json, async_trait,
+    extract::FromRequestParts,
+    http::{StatusCode, header, request::Parts},
+    response::{IntoResponse, Response},
+};
+use std::collections::HashMap;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use super::{handlers::AppState, types::ErrorResponse};
+
+/// テナントを明示的に指定するHTTPヘッダー
+pub const TENANT_HEADER: &str = "x-tenant-id";
+
+/// テナントごとのサービス依存関係の一覧
+///
+/// 起動時にテナント（図書館）ごとにスコープされたアダプターで
+/// `ServiceDependencies`を構築して登録する。
+/// リクエストはヘッダーまたはサブドメインからテナントに解決される。
+#[derive(Clone, Default)]
+pub struct TenantRegistry {
+    tenants: HashMap<TenantId, ServiceDependencies>,
+    subdomains: HashMap<String, TenantId>,
+}
+
+impl TenantRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// テナントの依存関係をサブドメインと共に登録する
+    pub fn register(&mut self, subdomain: impl Into<String>, deps: ServiceDependencies) {
+        self.subdomains.insert(subdomain.into(), deps.tenant_id);
+        self.tenants.insert(deps.tenant_id, deps);
+    }
+
+    /// テナントIDから依存関係を取得する
+    pub fn get(&self, tenant_id: TenantId) -> Option<&ServiceDependencies> {
+        self.tenants.get(&tenant_id)
+    }
+
+    /// Hostヘッダーの値（例: "minato.library.example.jp:3000"）からテナントを解決する
+    ///
+    /// 先頭のラベルを登録済みサブドメインと照合する。
+    pub fn resolve_host(&self, host: &str) -> Option<TenantId> {
+        let hostname = host.split(':').next().unwrap_or(host);
+        let (subdomain, _) = hostname.split_once('.')?;
+        self.subdomains.get(subdomain).copied()
+    }
+}
+
+/// リクエストのテナントに対応するサービス依存関係（エクストラクター）
+///
+/// テナントは以下の順で解決される：
+/// 1. `X-Tenant-Id`ヘッダー（UUID）
+/// 2. Hostヘッダーのサブドメイン
+/// 3. 既定テナント（登録されている場合のみ。単一館運用向け）
+///
+/// ヘッダーとサブドメインの両方がテナントを示す場合、両者が一致しなければ拒否する
+/// （ある館のサブドメインから別の館のデータを操作させない）。
+pub struct Tenant(pub ServiceDependencies);
+
+#[async_trait]
+impl FromRequestParts<Arc<AppState>> for Tenant {
+    type Rejection = TenantError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &Arc<AppState>,
+    ) -> Result<Self, Self::Rejection> {
+        let registry = &state.tenants;
+
+        let from_header = parts
+            .headers
+            .get(TENANT_HEADER)
+            .map(|value| {
+                value
+                    .to_str()
+                    .ok()
+                    .and_then(|v| Uuid::parse_str(v.trim()).ok())
+                    .map(TenantId::from_uuid)
+                    .ok_or(TenantError::InvalidTenantId)
+            })
+            .transpose()?;
+        let from_host = parts
+            .headers
+            .get(header::HOST)
+            .and_then(|host| host.to_str().ok())
+            .and_then(|host| registry.resolve_host(host));
+
+        let tenant_id = match (from_header, from_host) {
+            (Some(header), Some(host)) if header != host => {
+                return Err(TenantError::TenantMismatch);
+            }
+            (Some(tenant_id), _) | (None, Some(tenant_id)) => tenant_id,
+            (None, None) => TenantId::DEFAULT,
+        };
+
+        registry
+            .get(tenant_id)
+            .cloned()
+            .map(Tenant)
+            .ok_or(TenantError::UnknownTenant)
+    }
+}
+
+/// テナント解決のエラー
+#[derive(Debug)]
+pub enum TenantError {
+    /// X-Tenant-IdヘッダーがUUIDではない
+    InvalidTenantId,
+    /// X-Tenant-Idヘッダーとサブドメインが別のテナントを示す
+    TenantMismatch,
+    /// テナントが登録されていない
+    UnknownTenant,
+}
+
+impl IntoResponse for TenantError {
+    fn into_response(self) -> Response {
+        let (status, error_type, message) = match self {
+            TenantError::InvalidTenantId => (
+                StatusCode::BAD_REQUEST,
+                "INVALID_TENANT_ID",
+                "X-Tenant-Id header must be a UUID",
+            ),
+            TenantError::TenantMismatch => (
+                StatusCode::BAD_REQUEST,
+                "TENANT_MISMATCH",
+                "X-Tenant-Id header does not match the subdomain",
+            ),
+            TenantError::UnknownTenant => {
+                (StatusCode::NOT_FOUND, "UNKNOWN_TENANT", "Tenant not found")
+            }
+        };
+
+        let body = Json(ErrorResponse::new(error_type, message));
+        (status, body).into_response()
+    }
+}
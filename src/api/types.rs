@@ -1,4 +1,13 @@
+use crate::application::loan::{
+    DetectedOverdueLoan, FailedLoanCandidate, LoanOverrideRecord, OverdueDetectionReport,
+    OverdueSkipReason, SkippedOverdueCandidate,
+};
+use crate::domain::commands::{LoanBook, LoanBookWithOverride};
 use crate::domain::value_objects::{BookId, MemberId, StaffId};
+use crate::domain::{
+    DigestMode, EligibilityRule, EligibilityViolation, NotificationChannel, NotificationLanguage,
+    NotificationPreferences, OverrideToken, QuietHours,
+};
 use crate::ports::loan_read_model::{LoanStatus, LoanView};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
@@ -14,15 +23,45 @@ pub struct LoanBookRequest {
     pub book_id: Uuid,
     pub member_id: Uuid,
     pub staff_id: Uuid,
+    /// 貸し出した日時（記録漏れの訂正用。省略時は現在）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub loaned_at: Option<DateTime<Utc>>,
+    /// 貸出条件の例外の承認（監督者のみ）
+    #[serde(default, rename = "override", skip_serializing_if = "Option::is_none")]
+    pub override_token: Option<OverrideRequest>,
+}
+
+/// 貸出条件の例外の承認
+///
+/// 承認者はリクエストの本文ではなく`X-Approver-Id`ヘッダーで示す。
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct OverrideRequest {
+    /// 例外を認める条件（"loan_limit", "overdue_loans"）
+    pub rules: Vec<EligibilityRule>,
+    pub reason: String,
+}
+
+impl OverrideRequest {
+    /// 承認者を付けて、例外の承認付きのドメインコマンドへ変換
+    pub fn to_command(&self, loan: LoanBook, supervisor_id: StaffId) -> LoanBookWithOverride {
+        LoanBookWithOverride {
+            loan,
+            override_token: OverrideToken {
+                rules: self.rules.clone(),
+                reason: self.reason.clone(),
+                supervisor_id,
+            },
+        }
+    }
 }
 
 impl LoanBookRequest {
     /// ドメインコマンドへ変換
-    pub fn to_command(&self) -> crate::domain::commands::LoanBook {
-        crate::domain::commands::LoanBook {
+    pub fn to_command(&self, loaned_at: DateTime<Utc>) -> LoanBook {
+        LoanBook {
             book_id: BookId::from_uuid(self.book_id),
             member_id: MemberId::from_uuid(self.member_id),
-            loaned_at: Utc::now(),
+            loaned_at,
             staff_id: StaffId::from_uuid(self.staff_id),
         }
     }
@@ -46,6 +85,13 @@ pub struct LoanExtendedResponse {
     pub extension_count: u8,
 }
 
+/// 返却のクエリパラメータ
+#[derive(Debug, Deserialize)]
+pub struct ReturnBookQuery {
+    /// 返却された日時（記録漏れの訂正用。省略時は現在）
+    pub returned_at: Option<DateTime<Utc>>,
+}
+
 /// 返却成功レスポンス
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BookReturnedResponse {
@@ -53,6 +99,50 @@ pub struct BookReturnedResponse {
     pub returned_at: DateTime<Utc>,
 }
 
+/// 読書履歴の保持設定の変更リクエスト
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ReadingHistoryPreferenceRequest {
+    pub keep_history: bool,
+}
+
+/// 通知設定の変更リクエスト
+///
+/// 省略した項目には既定値（メール・日本語・すぐ送る・時間帯の制限なし・+09:00）を使う。
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct NotificationPreferencesRequest {
+    /// 通知を受け取る手段（"email", "in_app", "none"）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub channel: Option<NotificationChannel>,
+    /// 通知の言語（"ja", "en"）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<NotificationLanguage>,
+    /// 通知のまとめ方（"immediate", "daily"）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub digest: Option<DigestMode>,
+    /// 通知を送らない時間帯（会員の現地時刻、例: {"start": "22:00", "end": "07:00"}）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quiet_hours: Option<QuietHours>,
+    /// 会員の現地時刻のUTCからの時差（分）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub utc_offset_minutes: Option<i32>,
+}
+
+impl NotificationPreferencesRequest {
+    /// ドメインの通知設定へ変換（省略した項目は既定値）
+    pub fn to_preferences(&self) -> NotificationPreferences {
+        let defaults = NotificationPreferences::default();
+        NotificationPreferences {
+            channel: self.channel.unwrap_or(defaults.channel),
+            language: self.language.unwrap_or(defaults.language),
+            digest: self.digest.unwrap_or(defaults.digest),
+            quiet_hours: self.quiet_hours,
+            utc_offset_minutes: self
+                .utc_offset_minutes
+                .unwrap_or(defaults.utc_offset_minutes),
+        }
+    }
+}
+
 // ============================================================================
 // Query operations (GET) - Request/Response types
 // ============================================================================
@@ -66,12 +156,163 @@ pub struct ListLoansQuery {
     pub status: Option<String>,
 }
 
+/// 会員データの写しのクエリパラメータ
+#[derive(Debug, Deserialize)]
+pub struct ExportMemberDataQuery {
+    /// 出力形式（"json"（既定）または"summary"）
+    pub format: Option<String>,
+}
+
+/// 貸出条件の例外のレポートのクエリパラメータ
+#[derive(Debug, Deserialize)]
+pub struct LoanOverridesQuery {
+    /// 期間の開始（既定: 終了の30日前）
+    pub from: Option<DateTime<Utc>>,
+    /// 期間の終了（含まない。既定: 現在）
+    pub to: Option<DateTime<Utc>>,
+}
+
+/// 貸出条件の例外のレポートの項目（GET /reports/loan-overrides）
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LoanOverrideResponse {
+    pub loan_id: Uuid,
+    pub book_id: Uuid,
+    /// 会員ID（匿名化された貸出ではnull）
+    pub member_id: Option<Uuid>,
+    pub loaned_at: DateTime<Utc>,
+    /// 貸出を行った職員
+    pub loaned_by: Uuid,
+    /// 例外を認めた条件
+    pub rules: Vec<EligibilityRule>,
+    pub reason: String,
+    /// 承認した監督者
+    pub supervisor_id: Uuid,
+}
+
+impl From<LoanOverrideRecord> for LoanOverrideResponse {
+    fn from(record: LoanOverrideRecord) -> Self {
+        Self {
+            loan_id: record.loan_id.value(),
+            book_id: record.book_id.value(),
+            member_id: (!record.member_id.is_anonymised()).then(|| record.member_id.value()),
+            loaned_at: record.loaned_at,
+            loaned_by: record.loaned_by.value(),
+            rules: record.override_token.rules,
+            reason: record.override_token.reason,
+            supervisor_id: record.override_token.supervisor_id.value(),
+        }
+    }
+}
+
+/// 延滞検出の手動実行のクエリパラメータ
+#[derive(Debug, Deserialize)]
+pub struct OverdueDetectionQuery {
+    /// 判定の基準日時（既定: 現在。未来の日時は指定できない）
+    pub as_of: Option<DateTime<Utc>>,
+}
+
+/// 延滞検出の結果（POST /admin/overdue-detection）
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OverdueDetectionResponse {
+    pub as_of: DateTime<Utc>,
+    /// Read Modelから取得した候補の件数
+    pub candidates: usize,
+    /// 延滞にした貸出
+    pub detected: Vec<DetectedOverdueLoanResponse>,
+    /// 延滞にしなかった候補と理由
+    pub skipped: Vec<SkippedOverdueCandidateResponse>,
+    /// 処理に失敗した候補（次回の検出で改めて処理される）
+    pub failed: Vec<FailedOverdueCandidateResponse>,
+}
+
+impl From<OverdueDetectionReport> for OverdueDetectionResponse {
+    fn from(report: OverdueDetectionReport) -> Self {
+        Self {
+            as_of: report.as_of,
+            candidates: report.candidates(),
+            detected: report.detected.into_iter().map(Into::into).collect(),
+            skipped: report.skipped.into_iter().map(Into::into).collect(),
+            failed: report.failed.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+/// 延滞にした貸出
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DetectedOverdueLoanResponse {
+    pub loan_id: Uuid,
+    pub book_id: Uuid,
+    pub member_id: Uuid,
+    pub due_date: DateTime<Utc>,
+}
+
+impl From<DetectedOverdueLoan> for DetectedOverdueLoanResponse {
+    fn from(loan: DetectedOverdueLoan) -> Self {
+        Self {
+            loan_id: loan.loan_id.value(),
+            book_id: loan.book_id.value(),
+            member_id: loan.member_id.value(),
+            due_date: loan.due_date,
+        }
+    }
+}
+
+/// 延滞にしなかった候補
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SkippedOverdueCandidateResponse {
+    pub loan_id: Uuid,
+    /// "no_events", "not_due", "already_overdue", "returned", "lost", "changed_concurrently"
+    pub reason: OverdueSkipReason,
+}
+
+impl From<SkippedOverdueCandidate> for SkippedOverdueCandidateResponse {
+    fn from(candidate: SkippedOverdueCandidate) -> Self {
+        Self {
+            loan_id: candidate.loan_id.value(),
+            reason: candidate.reason,
+        }
+    }
+}
+
+/// 処理に失敗した候補
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FailedOverdueCandidateResponse {
+    pub loan_id: Uuid,
+    pub error: String,
+    /// 一時的な障害による失敗か
+    pub retryable: bool,
+}
+
+impl From<FailedLoanCandidate> for FailedOverdueCandidateResponse {
+    fn from(candidate: FailedLoanCandidate) -> Self {
+        Self {
+            loan_id: candidate.loan_id.value(),
+            error: candidate.error,
+            retryable: candidate.retryable,
+        }
+    }
+}
+
+/// 登録済みイベントスキーマの一覧の項目（GET /schemas/events）
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EventSchemaSummary {
+    pub event_type: String,
+    pub version: u32,
+    /// スキーマの`$id`
+    pub id: String,
+    /// スキーマ本体の取得先
+    pub href: String,
+    /// イベント型の最新バージョンかどうか
+    pub latest: bool,
+}
+
 /// 貸出レスポンス（GET /loans/:id と GET /loans）
 #[derive(Debug, Serialize, Deserialize)]
 pub struct LoanResponse {
     pub loan_id: Uuid,
     pub book_id: Uuid,
-    pub member_id: Uuid,
+    /// 会員ID（削除請求などで匿名化された貸出ではnull）
+    pub member_id: Option<Uuid>,
     pub loaned_at: DateTime<Utc>,
     pub due_date: DateTime<Utc>,
     pub returned_at: Option<DateTime<Utc>>,
@@ -86,7 +327,7 @@ impl From<LoanView> for LoanResponse {
         Self {
             loan_id: view.loan_id.value(),
             book_id: view.book_id.value(),
-            member_id: view.member_id.value(),
+            member_id: (!view.member_id.is_anonymised()).then(|| view.member_id.value()),
             loaned_at: view.loaned_at,
             due_date: view.due_date,
             returned_at: view.returned_at,
@@ -98,6 +339,106 @@ impl From<LoanView> for LoanResponse {
     }
 }
 
+/// 読書履歴の保持設定レスポンス（GET/PUT /members/:id/reading-history）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReadingHistoryPreferenceResponse {
+    pub member_id: Uuid,
+    /// 返却済みの貸出を保持期間を過ぎても履歴として残すか
+    pub keep_history: bool,
+    /// 保持を選択した日時（未選択の場合はnull）
+    pub opted_in_at: Option<DateTime<Utc>>,
+}
+
+impl ReadingHistoryPreferenceResponse {
+    pub fn new(member_id: MemberId, opted_in_at: Option<DateTime<Utc>>) -> Self {
+        Self {
+            member_id: member_id.value(),
+            keep_history: opted_in_at.is_some(),
+            opted_in_at,
+        }
+    }
+}
+
+/// 通知設定のレスポンス（GET/PUT /members/:id/notification-preferences）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotificationPreferencesResponse {
+    pub member_id: Uuid,
+    pub channel: NotificationChannel,
+    pub language: NotificationLanguage,
+    pub digest: DigestMode,
+    /// 通知を送らない時間帯（制限なしの場合はnull）
+    pub quiet_hours: Option<QuietHours>,
+    pub utc_offset_minutes: i32,
+}
+
+impl NotificationPreferencesResponse {
+    pub fn new(member_id: MemberId, preferences: NotificationPreferences) -> Self {
+        Self {
+            member_id: member_id.value(),
+            channel: preferences.channel,
+            language: preferences.language,
+            digest: preferences.digest,
+            quiet_hours: preferences.quiet_hours,
+            utc_offset_minutes: preferences.utc_offset_minutes,
+        }
+    }
+}
+
+/// 貸出可否の確認のクエリパラメータ
+#[derive(Debug, Deserialize)]
+pub struct EligibilityQuery {
+    pub book_id: Option<Uuid>,
+}
+
+/// 貸出可否の確認レスポンス（GET /members/:id/eligibility）
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EligibilityResponse {
+    pub member_id: Uuid,
+    pub book_id: Uuid,
+    /// 満たされていない条件がなければtrue
+    pub eligible: bool,
+    /// 満たされていない条件（すべて）
+    pub violations: Vec<EligibilityViolationResponse>,
+}
+
+impl EligibilityResponse {
+    pub fn new(
+        member_id: MemberId,
+        book_id: BookId,
+        violations: Vec<EligibilityViolation>,
+    ) -> Self {
+        Self {
+            member_id: member_id.value(),
+            book_id: book_id.value(),
+            eligible: violations.is_empty(),
+            violations: violations
+                .into_iter()
+                .map(EligibilityViolationResponse::from)
+                .collect(),
+        }
+    }
+}
+
+/// 満たされていない貸出条件
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EligibilityViolationResponse {
+    /// 貸出時のエラーコードと同じ（例: "LOAN_LIMIT_EXCEEDED"）
+    pub code: String,
+    pub message: String,
+    /// 監督者の承認で例外にできる場合の条件（`override.rules`に指定する値）
+    pub overridable_rule: Option<EligibilityRule>,
+}
+
+impl From<EligibilityViolation> for EligibilityViolationResponse {
+    fn from(violation: EligibilityViolation) -> Self {
+        Self {
+            code: violation.code().to_string(),
+            message: violation.to_string(),
+            overridable_rule: violation.overridable_rule(),
+        }
+    }
+}
+
 // ============================================================================
 // Common types
 // ============================================================================
@@ -0,0 +1,173 @@
+use chrono::{DateTime, Datelike, Months, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::ports::{EventArchive, PartitionArchival};
+
+use super::errors::{ArchiveError, Result};
+
+/// アーカイブ処理の結果
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArchiveReport {
+    /// 新たに作成したパーティション
+    pub created_partitions: Vec<String>,
+    /// アーカイブしたパーティションと移したイベント数
+    pub archived_partitions: Vec<(String, u64)>,
+    /// 返却されていない貸出を含むためアーカイブしなかったパーティションと該当する貸出の件数
+    pub skipped_partitions: Vec<(String, u64)>,
+}
+
+/// 古いパーティションをアーカイブに移す
+///
+/// 範囲の終了が`older_than_years`年より前のパーティションのうち、
+/// 含まれる貸出がすべて返却済みのものをアーカイブに移す。
+/// あわせて今年と来年のパーティションを作成し、既定パーティションに溜まったイベントを移す
+/// （定期実行することで、新しい年のイベントが既定パーティションに入り続けることを防ぐ）。
+pub async fn archive_closed_partitions(
+    archive: &dyn EventArchive,
+    now: DateTime<Utc>,
+    older_than_years: u32,
+) -> Result<ArchiveReport> {
+    if older_than_years == 0 {
+        return Err(ArchiveError::InvalidRetention(older_than_years));
+    }
+    let cutoff = older_than_years
+        .checked_mul(12)
+        .and_then(|months| now.checked_sub_months(Months::new(months)))
+        .ok_or(ArchiveError::InvalidRetention(older_than_years))?;
+
+    let mut report = ArchiveReport {
+        created_partitions: archive
+            .ensure_partitions(now.year(), now.year() + 1)
+            .await
+            .map_err(ArchiveError::EventArchiveError)?,
+        ..ArchiveReport::default()
+    };
+
+    let partitions = archive
+        .partitions()
+        .await
+        .map_err(ArchiveError::EventArchiveError)?;
+
+    for partition in partitions
+        .into_iter()
+        .filter(|p| !p.archived && p.to <= cutoff)
+    {
+        match archive
+            .archive_partition(&partition.name)
+            .await
+            .map_err(ArchiveError::EventArchiveError)?
+        {
+            PartitionArchival::Archived { event_count } => report
+                .archived_partitions
+                .push((partition.name, event_count)),
+            PartitionArchival::OpenLoans { loan_count } => {
+                report.skipped_partitions.push((partition.name, loan_count))
+            }
+        }
+    }
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ports::EventPartition;
+    use crate::ports::event_archive::Result as ArchiveResult;
+    use async_trait::async_trait;
+    use chrono::TimeZone;
+    use std::sync::Mutex;
+
+    /// 年ごとのパーティションと未返却の貸出件数を持つテスト用のアーカイブ
+    struct FakeArchive {
+        partitions: Mutex<Vec<(i32, bool, u64)>>,
+    }
+
+    #[async_trait]
+    impl EventArchive for FakeArchive {
+        async fn ensure_partitions(
+            &self,
+            from_year: i32,
+            through_year: i32,
+        ) -> ArchiveResult<Vec<String>> {
+            let mut partitions = self.partitions.lock().unwrap();
+            let mut created = Vec::new();
+            for year in from_year..=through_year {
+                if !partitions.iter().any(|(y, _, _)| *y == year) {
+                    partitions.push((year, false, 0));
+                    created.push(format!("events_y{year}"));
+                }
+            }
+            Ok(created)
+        }
+
+        async fn partitions(&self) -> ArchiveResult<Vec<EventPartition>> {
+            Ok(self
+                .partitions
+                .lock()
+                .unwrap()
+                .iter()
+                .map(|(year, archived, _)| EventPartition {
+                    name: format!("events_y{year}"),
+                    year: *year,
+                    from: Utc.with_ymd_and_hms(*year, 1, 1, 0, 0, 0).unwrap(),
+                    to: Utc.with_ymd_and_hms(*year + 1, 1, 1, 0, 0, 0).unwrap(),
+                    archived: *archived,
+                })
+                .collect())
+        }
+
+        async fn archive_partition(&self, name: &str) -> ArchiveResult<PartitionArchival> {
+            let mut partitions = self.partitions.lock().unwrap();
+            let partition = partitions
+                .iter_mut()
+                .find(|(year, _, _)| format!("events_y{year}") == name)
+                .unwrap();
+            if partition.2 > 0 {
+                return Ok(PartitionArchival::OpenLoans {
+                    loan_count: partition.2,
+                });
+            }
+            partition.1 = true;
+            Ok(PartitionArchival::Archived { event_count: 10 })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_archives_only_closed_partitions_older_than_cutoff() {
+        let archive = FakeArchive {
+            partitions: Mutex::new(vec![
+                (2018, true, 0),
+                (2019, false, 0),
+                (2020, false, 2),
+                (2021, false, 0),
+                (2026, false, 5),
+            ]),
+        };
+        let now = Utc.with_ymd_and_hms(2026, 10, 18, 0, 0, 0).unwrap();
+
+        let report = archive_closed_partitions(&archive, now, 5).await.unwrap();
+
+        // 2021年のパーティションは2022-01-01に終わるため、5年前（2021-10-18）より新しい
+        assert_eq!(report.created_partitions, vec!["events_y2027".to_string()]);
+        assert_eq!(
+            report.archived_partitions,
+            vec![("events_y2019".to_string(), 10)]
+        );
+        assert_eq!(
+            report.skipped_partitions,
+            vec![("events_y2020".to_string(), 2)]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rejects_zero_retention() {
+        let archive = FakeArchive {
+            partitions: Mutex::new(vec![]),
+        };
+
+        let result = archive_closed_partitions(&archive, Utc::now(), 0).await;
+
+        assert!(matches!(result, Err(ArchiveError::InvalidRetention(0))));
+    }
+}
@@ -0,0 +1,17 @@
+use crate::ports::EventArchiveError;
+use thiserror::Error;
+
+/// イベントログのアーカイブのエラー
+#[derive(Debug, Error)]
+pub enum ArchiveError {
+    /// 保持年数が不正（1年以上を指定する）
+    #[error("Invalid retention: {0} years")]
+    InvalidRetention(u32),
+
+    /// EventArchiveのエラー
+    #[error("Event archive error")]
+    EventArchiveError(#[source] EventArchiveError),
+}
+
+/// アーカイブ処理の Result型
+pub type Result<T> = std::result::Result<T, ArchiveError>;
@@ -0,0 +1,7 @@
+mod archive_service;
+mod errors;
+
+#[allow(unused_imports)]
+pub use archive_service::{ArchiveReport, archive_closed_partitions};
+#[allow(unused_imports)]
+pub use errors::{ArchiveError, Result};
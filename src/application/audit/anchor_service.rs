@@ -0,0 +1,255 @@
+use chrono::{DateTime, Utc};
+use ring::signature::{ED25519, Ed25519KeyPair, KeyPair, UnparsedPublicKey};
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+
+use crate::domain::value_objects::TenantId;
+use crate::ports::EventAudit;
+
+use super::errors::{AuditError, Result};
+
+/// アンカーファイルの形式バージョン
+pub const ANCHOR_FORMAT_VERSION: u32 = 1;
+
+/// ハッシュチェーンのアンカー
+///
+/// ある時点のチェーンの先頭（ルートハッシュ）に署名したもの。
+/// データベースの外（監査用ストレージ等）に保管しておくことで、
+/// チェーン全体が再計算されて書き換えられた場合でも改ざんを検出できる。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChainAnchor {
+    pub format_version: u32,
+    pub tenant_id: TenantId,
+    /// アンカー時点の先頭イベントのシーケンス番号
+    pub sequence_number: i64,
+    /// アンカー時点でチェーンに含まれるイベント数
+    pub event_count: i64,
+    /// 先頭イベントのハッシュ（16進数）
+    pub root_hash: String,
+    pub anchored_at: DateTime<Utc>,
+    /// 署名に使用したEd25519公開鍵（16進数）
+    pub public_key: String,
+    /// Ed25519署名（16進数）
+    pub signature: String,
+}
+
+impl ChainAnchor {
+    /// 署名対象のメッセージ
+    ///
+    /// 署名と公開鍵以外のすべてのフィールドを含む。
+    fn signed_message(&self) -> Vec<u8> {
+        format!(
+            "rusty-library/chain-anchor/v{}\n{}\n{}\n{}\n{}\n{}",
+            self.format_version,
+            self.tenant_id.value(),
+            self.sequence_number,
+            self.event_count,
+            self.root_hash,
+            self.anchored_at.to_rfc3339(),
+        )
+        .into_bytes()
+    }
+
+    /// 署名が指定した公開鍵で検証できるか
+    pub fn verify_signature(&self, public_key: &[u8]) -> bool {
+        let Ok(signature) = hex::decode(&self.signature) else {
+            return false;
+        };
+        UnparsedPublicKey::new(&ED25519, public_key)
+            .verify(&self.signed_message(), &signature)
+            .is_ok()
+    }
+
+    /// アンカーをJSONとして書き出す
+    pub fn write_to<W: Write>(&self, out: W) -> Result<()> {
+        serde_json::to_writer_pretty(out, self).map_err(AuditError::InvalidAnchor)
+    }
+
+    /// JSONからアンカーを読み込む
+    pub fn read_from<R: std::io::Read>(input: R) -> Result<Self> {
+        let anchor: Self = serde_json::from_reader(input).map_err(AuditError::InvalidAnchor)?;
+        if anchor.format_version != ANCHOR_FORMAT_VERSION {
+            return Err(AuditError::UnsupportedFormat(anchor.format_version));
+        }
+        Ok(anchor)
+    }
+}
+
+/// アンカーの署名鍵（Ed25519）
+pub struct AnchorSigner {
+    key_pair: Ed25519KeyPair,
+}
+
+impl AnchorSigner {
+    /// 32バイトのシード（16進数）から署名鍵を作成する
+    pub fn from_seed_hex(seed: &str) -> Result<Self> {
+        let seed = hex::decode(seed.trim()).map_err(|_| AuditError::InvalidSigningKey)?;
+        let key_pair = Ed25519KeyPair::from_seed_unchecked(&seed)
+            .map_err(|_| AuditError::InvalidSigningKey)?;
+        Ok(Self { key_pair })
+    }
+
+    /// 公開鍵（アンカーの検証に使用する）
+    pub fn public_key(&self) -> &[u8] {
+        self.key_pair.public_key().as_ref()
+    }
+}
+
+/// アンカーの検証結果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnchorStatus {
+    /// 署名が正しく、ルートハッシュが現在のチェーンに残っている
+    Valid,
+    /// 署名が検証できない（アンカーファイルが改ざんされた、または鍵が異なる）
+    InvalidSignature,
+    /// アンカー時点の先頭イベントが存在しない（イベントが削除された）
+    EventMissing,
+    /// アンカー時点の先頭イベントのハッシュが一致しない（チェーンが再計算された）
+    RootMismatch,
+}
+
+/// チェーンの現在の先頭に署名してアンカーを作成する
+///
+/// 定期的に実行し、出力したアンカーをデータベースの外に保管する。
+///
+/// # エラー
+/// - EmptyChain: ハッシュ化済みのイベントがない
+/// - EventAuditError: ポート層のエラー
+pub async fn anchor_chain(
+    audit: &dyn EventAudit,
+    signer: &AnchorSigner,
+    tenant_id: TenantId,
+    anchored_at: DateTime<Utc>,
+) -> Result<ChainAnchor> {
+    let head = audit
+        .chain_head()
+        .await
+        .map_err(AuditError::EventAuditError)?
+        .ok_or(AuditError::EmptyChain)?;
+
+    let mut anchor = ChainAnchor {
+        format_version: ANCHOR_FORMAT_VERSION,
+        tenant_id,
+        sequence_number: head.sequence_number,
+        event_count: head.event_count,
+        root_hash: hex::encode(head.event_hash),
+        anchored_at,
+        public_key: hex::encode(signer.public_key()),
+        signature: String::new(),
+    };
+    anchor.signature = hex::encode(signer.key_pair.sign(&anchor.signed_message()));
+
+    Ok(anchor)
+}
+
+/// アンカーを検証する
+///
+/// 署名を信頼できる公開鍵で検証し、アンカーされたルートハッシュが
+/// 現在のイベントストアに残っていることを確認する。
+/// アンカー以降のチェーンの完全性は`EventAudit::verify_chain`で確認する。
+pub async fn verify_anchor(
+    audit: &dyn EventAudit,
+    anchor: &ChainAnchor,
+    trusted_public_key: &[u8],
+) -> Result<AnchorStatus> {
+    if !anchor.verify_signature(trusted_public_key) {
+        return Ok(AnchorStatus::InvalidSignature);
+    }
+
+    let current = audit
+        .hash_at(anchor.sequence_number)
+        .await
+        .map_err(AuditError::EventAuditError)?;
+
+    Ok(match current {
+        None => AnchorStatus::EventMissing,
+        Some(hash) if hex::encode(hash) == anchor.root_hash => AnchorStatus::Valid,
+        Some(_) => AnchorStatus::RootMismatch,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ports::event_audit::{self, ChainHash, ChainHead, ChainVerification};
+    use async_trait::async_trait;
+
+    const SEED: &str = "0101010101010101010101010101010101010101010101010101010101010101";
+
+    struct FixedAudit {
+        head: ChainHead,
+    }
+
+    #[async_trait]
+    impl EventAudit for FixedAudit {
+        async fn verify_chain(&self) -> event_audit::Result<ChainVerification> {
+            unimplemented!("verify_chain not needed for these tests")
+        }
+
+        async fn chain_head(&self) -> event_audit::Result<Option<ChainHead>> {
+            Ok(Some(self.head.clone()))
+        }
+
+        async fn hash_at(&self, sequence_number: i64) -> event_audit::Result<Option<ChainHash>> {
+            Ok((sequence_number == self.head.sequence_number).then_some(self.head.event_hash))
+        }
+    }
+
+    fn audit() -> FixedAudit {
+        FixedAudit {
+            head: ChainHead {
+                sequence_number: 42,
+                event_hash: [7; 32],
+                event_count: 10,
+            },
+        }
+    }
+
+    #[tokio::test]
+    async fn test_anchor_round_trip_and_tamper_detection() {
+        let audit = audit();
+        let signer = AnchorSigner::from_seed_hex(SEED).unwrap();
+        let anchor = anchor_chain(&audit, &signer, TenantId::DEFAULT, Utc::now())
+            .await
+            .unwrap();
+
+        let mut buf = Vec::new();
+        anchor.write_to(&mut buf).unwrap();
+        let restored = ChainAnchor::read_from(buf.as_slice()).unwrap();
+        assert_eq!(restored, anchor);
+        assert_eq!(
+            verify_anchor(&audit, &restored, signer.public_key())
+                .await
+                .unwrap(),
+            AnchorStatus::Valid
+        );
+
+        // アンカーファイルの改ざん
+        let tampered = ChainAnchor {
+            root_hash: hex::encode([8; 32]),
+            ..anchor.clone()
+        };
+        assert_eq!(
+            verify_anchor(&audit, &tampered, signer.public_key())
+                .await
+                .unwrap(),
+            AnchorStatus::InvalidSignature
+        );
+
+        // チェーンの書き換え
+        let rewritten = FixedAudit {
+            head: ChainHead {
+                event_hash: [9; 32],
+                ..audit.head.clone()
+            },
+        };
+        assert_eq!(
+            verify_anchor(&rewritten, &anchor, signer.public_key())
+                .await
+                .unwrap(),
+            AnchorStatus::RootMismatch
+        );
+
+        assert!(AnchorSigner::from_seed_hex("abcd").is_err());
+    }
+}
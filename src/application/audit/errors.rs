@@ -0,0 +1,33 @@
+use crate::ports::EventAuditError;
+use thiserror::Error;
+
+/// ハッシュチェーンのアンカリングのエラー
+#[derive(Debug, Error)]
+pub enum AuditError {
+    /// 署名鍵が不正（32バイトのシードを16進数で指定する）
+    #[error("Invalid anchor signing key")]
+    InvalidSigningKey,
+
+    /// ハッシュ化済みのイベントがない
+    #[error("The hash chain is empty")]
+    EmptyChain,
+
+    /// 未対応のアンカー形式
+    #[error("Unsupported anchor format version: {0}")]
+    UnsupportedFormat(u32),
+
+    /// アンカーファイルのシリアライズ・デシリアライズ失敗
+    #[error("Invalid anchor")]
+    InvalidAnchor(#[source] serde_json::Error),
+
+    /// 入出力エラー
+    #[error("I/O error")]
+    Io(#[from] std::io::Error),
+
+    /// EventAuditのエラー
+    #[error("Event audit error")]
+    EventAuditError(#[source] EventAuditError),
+}
+
+/// 監査処理の Result型
+pub type Result<T> = std::result::Result<T, AuditError>;
@@ -0,0 +1,9 @@
+mod anchor_service;
+mod errors;
+
+#[allow(unused_imports)]
+pub use anchor_service::{
+    ANCHOR_FORMAT_VERSION, AnchorSigner, AnchorStatus, ChainAnchor, anchor_chain, verify_anchor,
+};
+#[allow(unused_imports)]
+pub use errors::{AuditError, Result};
@@ -0,0 +1,7 @@
+mod policy;
+mod role_authorizer;
+
+#[allow(unused_imports)]
+pub use policy::{AuthorizationPolicy, Permission};
+#[allow(unused_imports)]
+pub use role_authorizer::{RoleBasedAuthorizer, authorize_staff};
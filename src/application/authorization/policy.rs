@@ -0,0 +1,276 @@
+use chrono::{DateTime, Duration, Utc};
+use std::fmt;
+
+use crate::application::command_bus::LoanCommand;
+use crate::ports::StaffRole;
+
+/// 認可の単位となる権限
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Permission {
+    /// 書籍を貸し出す
+    LoanBooks,
+    /// 貸出を延長する
+    ExtendLoans,
+    /// 返却を受け付ける
+    ReturnBooks,
+    /// 過去の出来事を遡って記録する（記録漏れの訂正）
+    CorrectRecords,
+    /// 貸出条件（貸出上限など）の例外を認める
+    OverrideRules,
+    /// 延滞検出などの一括処理を手動で実行する
+    RunBatchJobs,
+    /// 会員データの写しを作成する（開示請求への対応）
+    ExportMemberData,
+    /// 貸出条件の例外などの監査用レポートを閲覧する
+    ViewAuditReports,
+    /// 会員に代わって会員の設定（読書履歴の保持など）を参照・変更する
+    ManageMemberSettings,
+}
+
+impl fmt::Display for Permission {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let action = match self {
+            Permission::LoanBooks => "lend books",
+            Permission::ExtendLoans => "extend loans",
+            Permission::ReturnBooks => "accept returns",
+            Permission::CorrectRecords => "correct past records",
+            Permission::OverrideRules => "override loan rules",
+            Permission::RunBatchJobs => "run batch jobs",
+            Permission::ExportMemberData => "export member data",
+            Permission::ViewAuditReports => "view audit reports",
+            Permission::ManageMemberSettings => "manage member settings",
+        };
+        f.write_str(action)
+    }
+}
+
+/// 役割に与えられる権限
+///
+/// カウンター担当とセルフサービス端末は通常の貸出・延長・返却のみを行える。
+/// 会員に代わっての設定の変更はカウンター担当以上（セルフサービス端末は不可）。
+/// 訂正・例外の承認・会員データの写しの作成・監査用レポートの閲覧には監督者（または管理者）が必要。
+/// 一括処理の手動実行は管理者のみ。
+fn permissions(role: StaffRole) -> &'static [Permission] {
+    use Permission::*;
+    match role {
+        StaffRole::CounterClerk => &[LoanBooks, ExtendLoans, ReturnBooks, ManageMemberSettings],
+        StaffRole::Kiosk => &[LoanBooks, ExtendLoans, ReturnBooks],
+        StaffRole::Supervisor => &[
+            LoanBooks,
+            ExtendLoans,
+            ReturnBooks,
+            CorrectRecords,
+            OverrideRules,
+            ExportMemberData,
+            ViewAuditReports,
+            ManageMemberSettings,
+        ],
+        StaffRole::Administrator => &[
+            LoanBooks,
+            ExtendLoans,
+            ReturnBooks,
+            CorrectRecords,
+            OverrideRules,
+            RunBatchJobs,
+            ExportMemberData,
+            ViewAuditReports,
+            ManageMemberSettings,
+        ],
+    }
+}
+
+/// 貸出管理のコマンドの認可ポリシー
+///
+/// どの役割がどのコマンドを実行できるかを決める純粋なルール。
+/// 職員の役割の取得は呼び出し側（`RoleBasedAuthorizer`）が行う。
+#[derive(Debug, Clone, Copy)]
+pub struct AuthorizationPolicy {
+    /// 通常の操作として扱う遡りの範囲
+    ///
+    /// これより前の日時で記録するコマンドは訂正とみなし、`CorrectRecords`が必要になる。
+    pub correction_window: Duration,
+}
+
+impl Default for AuthorizationPolicy {
+    fn default() -> Self {
+        Self {
+            correction_window: Duration::hours(24),
+        }
+    }
+}
+
+impl AuthorizationPolicy {
+    /// コマンドの実行者に必要な権限
+    ///
+    /// 貸出条件の例外の承認者に必要な権限は`authorize_approver`で判定する。
+    pub fn required_permissions(
+        &self,
+        command: &LoanCommand,
+        now: DateTime<Utc>,
+    ) -> Vec<Permission> {
+        let mut required = vec![match command {
+            LoanCommand::LoanBook(_) | LoanCommand::LoanBookWithOverride(_) => {
+                Permission::LoanBooks
+            }
+            LoanCommand::ExtendLoan(_) => Permission::ExtendLoans,
+            LoanCommand::ReturnBook(_) => Permission::ReturnBooks,
+        }];
+        if command.occurred_at() < now - self.correction_window {
+            required.push(Permission::CorrectRecords);
+        }
+        required
+    }
+
+    /// 役割でコマンドを実行できるか判定する
+    ///
+    /// 実行できない場合は、不足している最初の権限を返す。
+    pub fn authorize(
+        &self,
+        roles: &[StaffRole],
+        command: &LoanCommand,
+        now: DateTime<Utc>,
+    ) -> Result<(), Permission> {
+        self.required_permissions(command, now)
+            .into_iter()
+            .find(|required| {
+                !roles
+                    .iter()
+                    .any(|role| permissions(*role).contains(required))
+            })
+            .map_or(Ok(()), Err)
+    }
+
+    /// 役割で貸出条件の例外を承認できるか判定する
+    ///
+    /// 承認者は実行者と別の職員でもよい（カウンター担当の貸出を監督者が承認する）。
+    pub fn authorize_approver(&self, roles: &[StaffRole]) -> Result<(), Permission> {
+        self.authorize_permission(roles, Permission::OverrideRules)
+    }
+
+    /// 役割が権限を持つか判定する（コマンド以外の操作に使う）
+    pub fn authorize_permission(
+        &self,
+        roles: &[StaffRole],
+        required: Permission,
+    ) -> Result<(), Permission> {
+        if roles
+            .iter()
+            .any(|role| permissions(*role).contains(&required))
+        {
+            Ok(())
+        } else {
+            Err(required)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::commands::{ExtendLoan, ReturnBook};
+    use crate::domain::value_objects::LoanId;
+
+    fn return_book(returned_at: DateTime<Utc>) -> LoanCommand {
+        ReturnBook {
+            loan_id: LoanId::new(),
+            returned_at,
+        }
+        .into()
+    }
+
+    #[test]
+    fn test_circulation_roles_can_run_everyday_commands() {
+        let now = Utc::now();
+        let policy = AuthorizationPolicy::default();
+        let extend = ExtendLoan {
+            loan_id: LoanId::new(),
+            extended_at: now,
+        }
+        .into();
+
+        for role in [
+            StaffRole::CounterClerk,
+            StaffRole::Kiosk,
+            StaffRole::Supervisor,
+            StaffRole::Administrator,
+        ] {
+            assert!(policy.authorize(&[role], &extend, now).is_ok());
+            assert!(policy.authorize(&[role], &return_book(now), now).is_ok());
+        }
+        assert_eq!(
+            policy.authorize(&[], &extend, now),
+            Err(Permission::ExtendLoans)
+        );
+    }
+
+    #[test]
+    fn test_corrections_and_overrides_require_a_supervisor() {
+        let now = Utc::now();
+        let policy = AuthorizationPolicy::default();
+        let backdated = return_book(now - Duration::days(3));
+
+        assert_eq!(
+            policy.authorize(
+                &[StaffRole::CounterClerk, StaffRole::Kiosk],
+                &backdated,
+                now
+            ),
+            Err(Permission::CorrectRecords)
+        );
+        assert_eq!(
+            policy.authorize_approver(&[StaffRole::CounterClerk, StaffRole::Kiosk]),
+            Err(Permission::OverrideRules)
+        );
+        assert!(policy.authorize_approver(&[StaffRole::Supervisor]).is_ok());
+        assert!(
+            policy
+                .authorize(
+                    &[StaffRole::CounterClerk, StaffRole::Supervisor],
+                    &backdated,
+                    now
+                )
+                .is_ok()
+        );
+
+        // 一括処理の手動実行は管理者のみ
+        assert_eq!(
+            policy.authorize_permission(&[StaffRole::Supervisor], Permission::RunBatchJobs),
+            Err(Permission::RunBatchJobs)
+        );
+        assert!(
+            policy
+                .authorize_permission(&[StaffRole::Administrator], Permission::RunBatchJobs)
+                .is_ok()
+        );
+
+        // 会員データの写しの作成は監督者以上
+        assert_eq!(
+            policy.authorize_permission(&[StaffRole::CounterClerk], Permission::ExportMemberData),
+            Err(Permission::ExportMemberData)
+        );
+        assert!(
+            policy
+                .authorize_permission(&[StaffRole::Supervisor], Permission::ExportMemberData)
+                .is_ok()
+        );
+
+        // 会員に代わっての設定の変更は、セルフサービス端末には認めない
+        assert_eq!(
+            policy.authorize_permission(&[StaffRole::Kiosk], Permission::ManageMemberSettings),
+            Err(Permission::ManageMemberSettings)
+        );
+        assert!(
+            policy
+                .authorize_permission(&[StaffRole::CounterClerk], Permission::ManageMemberSettings)
+                .is_ok()
+        );
+
+        // 遡りの範囲内（同日中の処理など）は訂正ではない
+        let earlier_today = return_book(now - Duration::hours(2));
+        assert!(
+            policy
+                .authorize(&[StaffRole::CounterClerk], &earlier_today, now)
+                .is_ok()
+        );
+    }
+}
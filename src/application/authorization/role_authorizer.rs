@@ -0,0 +1,106 @@
+use async_trait::async_trait;
+use chrono::Utc;
+
+use crate::application::command_bus::{CommandAuthorizer, CommandEnvelope, LoanCommand};
+use crate::application::loan::{LoanApplicationError, Result, ServiceDependencies};
+use crate::domain::value_objects::StaffId;
+use crate::ports::StaffRole;
+
+use super::policy::{AuthorizationPolicy, Permission};
+
+/// 実行者の役割に基づいてコマンドを認可する
+///
+/// 実行者（`CommandMetadata::actor`）の役割を職員サービスから取得し、
+/// 認可ポリシーで判定する。実行者が不明なコマンドは拒否する。
+/// 貸出の作成では、記録される貸出担当（`staff_id`）が実行者と異なるコマンドも拒否する。
+/// 貸出条件の例外を含むコマンドは、承認者（監督者）の役割も確認する。
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RoleBasedAuthorizer {
+    policy: AuthorizationPolicy,
+}
+
+impl RoleBasedAuthorizer {
+    pub fn new(policy: AuthorizationPolicy) -> Self {
+        Self { policy }
+    }
+}
+
+#[async_trait]
+impl CommandAuthorizer for RoleBasedAuthorizer {
+    async fn authorize(
+        &self,
+        deps: &ServiceDependencies,
+        envelope: &CommandEnvelope,
+    ) -> Result<()> {
+        let Some(actor) = envelope.metadata.actor else {
+            return Err(LoanApplicationError::Forbidden(
+                "the acting staff member must be identified".to_string(),
+            ));
+        };
+        let loaned_by = match &envelope.command {
+            LoanCommand::LoanBook(cmd) => Some(cmd.staff_id),
+            LoanCommand::LoanBookWithOverride(cmd) => Some(cmd.loan.staff_id),
+            _ => None,
+        };
+        if let Some(loaned_by) = loaned_by
+            && loaned_by != actor
+        {
+            return Err(LoanApplicationError::Forbidden(format!(
+                "staff member {} cannot record a loan as staff member {}",
+                actor.value(),
+                loaned_by.value()
+            )));
+        }
+        let roles = staff_roles(deps, actor).await?;
+        self.policy
+            .authorize(&roles, &envelope.command, Utc::now())
+            .map_err(|missing| not_permitted(actor, missing))?;
+
+        if let LoanCommand::LoanBookWithOverride(cmd) = &envelope.command {
+            let supervisor = cmd.override_token.supervisor_id;
+            let roles = staff_roles(deps, supervisor).await?;
+            self.policy
+                .authorize_approver(&roles)
+                .map_err(|missing| not_permitted(supervisor, missing))?;
+        }
+        Ok(())
+    }
+}
+
+/// 職員がコマンド以外の操作（一括処理の手動実行など）の権限を持つか確認する
+///
+/// 職員が不明な場合や権限がない場合はForbiddenとする。
+pub async fn authorize_staff(
+    deps: &ServiceDependencies,
+    staff_id: Option<StaffId>,
+    permission: Permission,
+) -> Result<()> {
+    let Some(staff_id) = staff_id else {
+        return Err(LoanApplicationError::Forbidden(
+            "the acting staff member must be identified".to_string(),
+        ));
+    };
+    let roles = staff_roles(deps, staff_id).await?;
+    AuthorizationPolicy::default()
+        .authorize_permission(&roles, permission)
+        .map_err(|missing| not_permitted(staff_id, missing))
+}
+
+/// 職員の役割を取得する（登録されていない職員は拒否する）
+async fn staff_roles(deps: &ServiceDependencies, staff_id: StaffId) -> Result<Vec<StaffRole>> {
+    deps.staff_service
+        .roles(staff_id)
+        .await
+        .map_err(LoanApplicationError::StaffServiceError)?
+        .ok_or_else(|| {
+            LoanApplicationError::Forbidden(format!("unknown staff member {}", staff_id.value()))
+        })
+}
+
+fn not_permitted(staff_id: StaffId, missing: Permission) -> LoanApplicationError {
+    LoanApplicationError::Forbidden(format!(
+        "staff member {} is not permitted to {}",
+        staff_id.value(),
+        missing
+    ))
+}
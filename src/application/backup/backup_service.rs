@@ -0,0 +1,378 @@
+use crate::application::loan::build_loan_view;
+use crate::application::repository::EventSourcedRepository;
+use crate::domain::{self, loan::Loan};
+use crate::ports::{EventStore, LoanReadModel, StoredEvent};
+use chrono::{DateTime, Utc};
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+use std::io::{BufRead, Write};
+use uuid::Uuid;
+
+use super::errors::{BackupError, Result};
+
+/// バックアップ形式のバージョン
+pub const BACKUP_FORMAT_VERSION: u32 = 1;
+
+/// バックアップのマニフェスト
+///
+/// NDJSON本体と対で保存され、インポート時の整合性検証に使用される。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BackupManifest {
+    pub format_version: u32,
+    pub exported_at: DateTime<Utc>,
+    pub event_count: usize,
+    pub aggregate_count: usize,
+    /// 最初と最後のシーケンス番号（イベントがない場合はNone）
+    pub first_sequence_number: Option<i64>,
+    pub last_sequence_number: Option<i64>,
+    /// NDJSON本体のSHA-256（16進数）
+    pub sha256: String,
+}
+
+impl BackupManifest {
+    /// マニフェストをJSONとして書き出す
+    pub fn write_to<W: Write>(&self, out: W) -> Result<()> {
+        serde_json::to_writer_pretty(out, self).map_err(BackupError::InvalidManifest)
+    }
+
+    /// JSONからマニフェストを読み込む
+    pub fn read_from<R: std::io::Read>(input: R) -> Result<Self> {
+        serde_json::from_reader(input).map_err(BackupError::InvalidManifest)
+    }
+}
+
+/// インポート結果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportSummary {
+    pub event_count: usize,
+    pub aggregate_count: usize,
+    /// Read Modelを再構築した貸出の件数（再構築しなかった場合はNone）
+    pub rebuilt_loans: Option<usize>,
+}
+
+/// イベントログ全体をNDJSONとしてエクスポートする
+///
+/// 1行に1イベント（`StoredEvent`）をシーケンス番号順に書き出し、
+/// 件数とSHA-256チェックサムを含むマニフェストを返す。
+/// EventStoreポート経由で読み出すため、どのバックエンドからでもエクスポートできる。
+///
+/// # 引数
+/// * `event_store` - エクスポート元のイベントストア
+/// * `out` - NDJSONの書き出し先
+/// * `exported_at` - エクスポート日時（マニフェストに記録）
+pub async fn export_event_log<W: Write>(
+    event_store: &dyn EventStore,
+    out: &mut W,
+    exported_at: DateTime<Utc>,
+) -> Result<BackupManifest> {
+    let mut hasher = Sha256::new();
+    let mut event_count = 0;
+    let mut aggregates = HashSet::new();
+    let mut first_sequence_number = None;
+    let mut last_sequence_number = None;
+
+    let mut stream = event_store.stream_stored();
+    while let Some(stored) = stream.next().await {
+        let stored = stored.map_err(BackupError::EventStoreError)?;
+
+        let mut line = serde_json::to_vec(&stored).map_err(|source| BackupError::InvalidLine {
+            line: event_count + 1,
+            source,
+        })?;
+        line.push(b'\n');
+
+        hasher.update(&line);
+        out.write_all(&line)?;
+
+        event_count += 1;
+        aggregates.insert(stored.aggregate_id);
+        first_sequence_number.get_or_insert(stored.sequence_number);
+        last_sequence_number = Some(stored.sequence_number);
+    }
+    out.flush()?;
+
+    Ok(BackupManifest {
+        format_version: BACKUP_FORMAT_VERSION,
+        exported_at,
+        event_count,
+        aggregate_count: aggregates.len(),
+        first_sequence_number,
+        last_sequence_number,
+        sha256: hex::encode(hasher.finalize()),
+    })
+}
+
+/// NDJSONのバックアップを空のイベントストアに復元する
+///
+/// 書き込み前に以下を検証し、1つでも失敗した場合は何も書き込まない：
+/// - 形式バージョンとイベント件数がマニフェストと一致すること
+/// - NDJSON本体のSHA-256がマニフェストと一致すること
+/// - 各集約のバージョンが1からの連番であること
+/// - 復元先のイベントストアが空であること
+///
+/// # 引数
+/// * `event_store` - 復元先のイベントストア
+/// * `input` - NDJSONの読み込み元
+/// * `manifest` - エクスポート時に作成されたマニフェスト
+/// * `rebuild_read_model` - 指定した場合、復元後にRead Modelを再構築する
+pub async fn import_event_log<R: BufRead>(
+    event_store: &dyn EventStore,
+    input: R,
+    manifest: &BackupManifest,
+    rebuild_read_model: Option<&dyn LoanReadModel>,
+) -> Result<ImportSummary> {
+    if manifest.format_version != BACKUP_FORMAT_VERSION {
+        return Err(BackupError::UnsupportedFormat(manifest.format_version));
+    }
+
+    // 1. 読み込みとチェックサム計算
+    let mut hasher = Sha256::new();
+    let mut events = Vec::with_capacity(manifest.event_count);
+    for (index, line) in input.lines().enumerate() {
+        let line = line?;
+        hasher.update(line.as_bytes());
+        hasher.update(b"\n");
+
+        let stored: StoredEvent =
+            serde_json::from_str(&line).map_err(|source| BackupError::InvalidLine {
+                line: index + 1,
+                source,
+            })?;
+        events.push(stored);
+    }
+
+    let actual = hex::encode(hasher.finalize());
+    if actual != manifest.sha256 {
+        return Err(BackupError::ChecksumMismatch {
+            expected: manifest.sha256.clone(),
+            actual,
+        });
+    }
+    if events.len() != manifest.event_count {
+        return Err(BackupError::EventCountMismatch {
+            expected: manifest.event_count,
+            actual: events.len(),
+        });
+    }
+
+    // 2. 集約ごとのバージョン連続性の検証
+    verify_version_continuity(&events)?;
+
+    // 3. 復元先が空であることの確認
+    if event_store.stream_stored().next().await.is_some() {
+        return Err(BackupError::StoreNotEmpty);
+    }
+
+    // 4. 1回のEventStore::importで取り込む（途中で失敗しても何も書き込まれない）
+    let aggregate_count = events
+        .iter()
+        .map(|e| e.aggregate_id)
+        .collect::<HashSet<_>>()
+        .len();
+    let event_count = events.len();
+
+    event_store
+        .import(events)
+        .await
+        .map_err(BackupError::EventStoreError)?;
+
+    // 5. 必要に応じてRead Modelを再構築
+    let rebuilt_loans = match rebuild_read_model {
+        Some(read_model) => Some(rebuild_loan_read_model(event_store, read_model).await?),
+        None => None,
+    };
+
+    Ok(ImportSummary {
+        event_count,
+        aggregate_count,
+        rebuilt_loans,
+    })
+}
+
+/// 集約ごとのバージョンが1からの連番であることを検証する（純粋関数）
+///
+/// イベントはシーケンス番号順に並んでいることを前提とする。
+pub fn verify_version_continuity(events: &[StoredEvent]) -> Result<()> {
+    let mut last_versions: HashMap<Uuid, i32> = HashMap::new();
+
+    for stored in events {
+        let expected = last_versions
+            .get(&stored.aggregate_id)
+            .copied()
+            .unwrap_or(0)
+            + 1;
+        if stored.aggregate_version != expected {
+            return Err(BackupError::VersionGap {
+                aggregate_id: stored.aggregate_id,
+                expected,
+                found: stored.aggregate_version,
+            });
+        }
+        last_versions.insert(stored.aggregate_id, stored.aggregate_version);
+    }
+
+    Ok(())
+}
+
+/// イベントストアの全イベントから貸出のRead Modelを再構築する
+///
+/// Loan集約のイベントを集約ごとにまとめて再生し、完全な状態を保存する。
+/// 会員の読書履歴の保持設定も、記録された順に投影し直す。
+/// 不正な状態遷移を含む貸出があれば、その集約IDとともにエラーを返す。
+///
+/// # 戻り値
+/// 再構築した貸出の件数
+pub async fn rebuild_loan_read_model(
+    event_store: &dyn EventStore,
+    read_model: &dyn LoanReadModel,
+) -> Result<usize> {
+    // 集約の初出順にイベント列をまとめる
+    let mut indexes: HashMap<Uuid, usize> = HashMap::new();
+    let mut loans: Vec<(Uuid, Vec<domain::DomainEvent>)> = Vec::new();
+
+    let mut stream = event_store.stream_stored();
+    while let Some(stored) = stream.next().await {
+        let stored = stored.map_err(BackupError::EventStoreError)?;
+        if let domain::DomainEvent::ReadingHistoryPreferenceChanged(e) = &stored.event {
+            read_model
+                .save_history_opt_in(e.member_id, e.keep_history.then_some(e.changed_at))
+                .await
+                .map_err(BackupError::ReadModelError)?;
+            continue;
+        }
+        if stored.aggregate_type != "Loan" {
+            continue;
+        }
+        let index = *indexes.entry(stored.aggregate_id).or_insert_with(|| {
+            loans.push((stored.aggregate_id, Vec::new()));
+            loans.len() - 1
+        });
+        loans[index].1.push(stored.event);
+    }
+
+    let mut rebuilt = 0;
+    for (aggregate_id, events) in loans {
+        let restored = EventSourcedRepository::<Loan>::restore(events).map_err(|source| {
+            BackupError::InvalidAggregate {
+                aggregate_id,
+                source,
+            }
+        })?;
+        if let Some(loan) = restored.map(|v| v.aggregate) {
+            read_model
+                .save(build_loan_view(&loan))
+                .await
+                .map_err(BackupError::ReadModelError)?;
+            rebuilt += 1;
+        }
+    }
+
+    Ok(rebuilt)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::events::{BookLoaned, BookReturned, DomainEvent};
+    use crate::domain::value_objects::{BookId, LoanId, MemberId, StaffId};
+
+    fn stored(aggregate_id: Uuid, version: i32, sequence_number: i64) -> StoredEvent {
+        let now = Utc::now();
+        let loan_id = LoanId::from_uuid(aggregate_id);
+        let event = if version == 1 {
+            DomainEvent::BookLoaned(BookLoaned {
+                loan_id,
+                book_id: BookId::new(),
+                member_id: MemberId::new(),
+                loaned_at: now,
+                due_date: now + chrono::Duration::days(14),
+                loaned_by: StaffId::new(),
+                override_token: None,
+            })
+        } else {
+            DomainEvent::BookReturned(BookReturned {
+                loan_id,
+                book_id: BookId::new(),
+                member_id: MemberId::new(),
+                returned_at: now,
+                was_overdue: false,
+            })
+        };
+
+        StoredEvent {
+            event_id: Uuid::new_v4(),
+            aggregate_id,
+            aggregate_type: "Loan".to_string(),
+            aggregate_version: version,
+            sequence_number,
+            occurred_at: now,
+            recorded_at: now,
+            event,
+        }
+    }
+
+    #[test]
+    fn test_verify_version_continuity_accepts_interleaved_aggregates() {
+        let a = Uuid::new_v4();
+        let b = Uuid::new_v4();
+        let events = vec![
+            stored(a, 1, 1),
+            stored(b, 1, 2),
+            stored(a, 2, 3),
+            stored(b, 2, 4),
+        ];
+
+        assert!(verify_version_continuity(&events).is_ok());
+    }
+
+    #[test]
+    fn test_verify_version_continuity_detects_gap() {
+        let a = Uuid::new_v4();
+        let events = vec![stored(a, 1, 1), stored(a, 3, 2)];
+
+        match verify_version_continuity(&events) {
+            Err(BackupError::VersionGap {
+                aggregate_id,
+                expected,
+                found,
+            }) => {
+                assert_eq!(aggregate_id, a);
+                assert_eq!(expected, 2);
+                assert_eq!(found, 3);
+            }
+            other => panic!("Expected VersionGap, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_verify_version_continuity_requires_version_one_first() {
+        let a = Uuid::new_v4();
+        let events = vec![stored(a, 2, 1)];
+
+        assert!(matches!(
+            verify_version_continuity(&events),
+            Err(BackupError::VersionGap { expected: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn test_manifest_round_trip() {
+        let manifest = BackupManifest {
+            format_version: BACKUP_FORMAT_VERSION,
+            exported_at: Utc::now(),
+            event_count: 3,
+            aggregate_count: 2,
+            first_sequence_number: Some(1),
+            last_sequence_number: Some(3),
+            sha256: "00".repeat(32),
+        };
+
+        let mut buf = Vec::new();
+        manifest.write_to(&mut buf).unwrap();
+        let restored = BackupManifest::read_from(buf.as_slice()).unwrap();
+
+        assert_eq!(restored, manifest);
+    }
+}
@@ -0,0 +1,66 @@
+use crate::ports::{EventStoreError, LoanReadModelError};
+use thiserror::Error;
+use uuid::Uuid;
+
+/// イベントログのバックアップ（エクスポート・インポート）のエラー
+#[derive(Debug, Error)]
+pub enum BackupError {
+    /// 未対応のバックアップ形式
+    #[error("Unsupported backup format version: {0}")]
+    UnsupportedFormat(u32),
+
+    /// NDJSONのチェックサムがマニフェストと一致しない
+    #[error("Checksum mismatch: manifest={expected}, actual={actual}")]
+    ChecksumMismatch { expected: String, actual: String },
+
+    /// イベント件数がマニフェストと一致しない
+    #[error("Event count mismatch: manifest={expected}, actual={actual}")]
+    EventCountMismatch { expected: usize, actual: usize },
+
+    /// 集約のバージョンが1からの連番になっていない
+    #[error("Version gap in aggregate {aggregate_id}: expected {expected}, found {found}")]
+    VersionGap {
+        aggregate_id: Uuid,
+        expected: i32,
+        found: i32,
+    },
+
+    /// 集約のイベント列を再生できない（不正な状態遷移など）
+    #[error("Cannot replay aggregate {aggregate_id}")]
+    InvalidAggregate {
+        aggregate_id: Uuid,
+        #[source]
+        source: EventStoreError,
+    },
+
+    /// 復元先のイベントストアが空ではない
+    #[error("Target event store is not empty")]
+    StoreNotEmpty,
+
+    /// NDJSONの行が不正
+    #[error("Invalid event at line {line}")]
+    InvalidLine {
+        line: usize,
+        #[source]
+        source: serde_json::Error,
+    },
+
+    /// マニフェストのシリアライズ・デシリアライズ失敗
+    #[error("Invalid manifest")]
+    InvalidManifest(#[source] serde_json::Error),
+
+    /// 入出力エラー
+    #[error("I/O error")]
+    Io(#[from] std::io::Error),
+
+    /// EventStoreのエラー
+    #[error("Event store error")]
+    EventStoreError(#[source] EventStoreError),
+
+    /// ReadModelのエラー
+    #[error("Read model error")]
+    ReadModelError(#[source] LoanReadModelError),
+}
+
+/// バックアップ処理の Result型
+pub type Result<T> = std::result::Result<T, BackupError>;
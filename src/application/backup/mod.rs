@@ -0,0 +1,10 @@
+mod backup_service;
+mod errors;
+
+#[allow(unused_imports)]
+pub use backup_service::{
+    BACKUP_FORMAT_VERSION, BackupManifest, ImportSummary, export_event_log, import_event_log,
+    rebuild_loan_read_model, verify_version_continuity,
+};
+#[allow(unused_imports)]
+pub use errors::{BackupError, Result};
@@ -0,0 +1,134 @@
+use async_trait::async_trait;
+use futures::future::BoxFuture;
+use std::sync::Arc;
+
+use crate::application::authorization::RoleBasedAuthorizer;
+use crate::application::loan::{
+    Result, ServiceDependencies, extend_loan, loan_book, loan_book_with_override, return_book,
+};
+use crate::domain::value_objects::LoanId;
+
+use super::command::{CommandEnvelope, LoanCommand};
+use super::middleware::{
+    AuthorizationMiddleware, CommandMetrics, IdempotencyMiddleware, LoggingMiddleware,
+    MetricsMiddleware, RetryMiddleware, ValidationMiddleware,
+};
+
+/// コマンドバスのミドルウェア
+///
+/// コマンドの実行の前後に横断的な処理を挟む。
+/// `next.run`を呼ぶと後続のミドルウェア（最後はコマンドの実行）に進み、
+/// 呼ばずに返せばコマンドは実行されない。
+#[async_trait]
+pub trait Middleware: Send + Sync {
+    async fn handle(
+        &self,
+        deps: &ServiceDependencies,
+        envelope: CommandEnvelope,
+        next: Next<'_>,
+    ) -> Result<LoanId>;
+}
+
+/// ミドルウェアチェーンの残り
+///
+/// `Copy`なので、再試行のために複数回`run`できる。
+#[derive(Clone, Copy)]
+pub struct Next<'a> {
+    chain: &'a [Arc<dyn Middleware>],
+}
+
+impl<'a> Next<'a> {
+    /// 後続のミドルウェアを実行し、最後にコマンドを実行する
+    pub fn run<'b>(
+        self,
+        deps: &'b ServiceDependencies,
+        envelope: CommandEnvelope,
+    ) -> BoxFuture<'b, Result<LoanId>>
+    where
+        'a: 'b,
+    {
+        Box::pin(async move {
+            match self.chain.split_first() {
+                Some((middleware, rest)) => {
+                    middleware
+                        .handle(deps, envelope, Next { chain: rest })
+                        .await
+                }
+                None => execute(deps, envelope.command).await,
+            }
+        })
+    }
+}
+
+/// コマンドを対応するアプリケーションサービスで実行する
+///
+/// 作成または更新された貸出のIDを返す。
+async fn execute(deps: &ServiceDependencies, command: LoanCommand) -> Result<LoanId> {
+    match command {
+        LoanCommand::LoanBook(cmd) => loan_book(deps, cmd).await,
+        LoanCommand::LoanBookWithOverride(cmd) => loan_book_with_override(deps, cmd).await,
+        LoanCommand::ExtendLoan(cmd) => {
+            let loan_id = cmd.loan_id;
+            extend_loan(deps, cmd).await?;
+            Ok(loan_id)
+        }
+        LoanCommand::ReturnBook(cmd) => {
+            let loan_id = cmd.loan_id;
+            return_book(deps, cmd).await?;
+            Ok(loan_id)
+        }
+    }
+}
+
+/// コマンドバス
+///
+/// 貸出管理のコマンドをミドルウェアチェーンを通して実行する。
+/// HTTP・CLIなどのトランスポートはコマンドを組み立てて送るだけで、
+/// ログ・認可・冪等性などの横断的な処理はここで共通に行われる。
+///
+/// テナントごとの依存関係はコマンドごとに渡すため、1つのバスを全テナントで共有できる。
+#[derive(Clone, Default)]
+pub struct CommandBus {
+    middlewares: Vec<Arc<dyn Middleware>>,
+}
+
+impl CommandBus {
+    /// ミドルウェアのないバス
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 標準のミドルウェアチェーンを持つバス
+    ///
+    /// 外側から順に、ログ → メトリクス → 認可（実行者の役割） → 検証 → 冪等性 → 競合時の再試行。
+    pub fn standard(metrics: Arc<CommandMetrics>) -> Self {
+        Self::new()
+            .with(LoggingMiddleware)
+            .with(MetricsMiddleware::new(metrics))
+            .with(AuthorizationMiddleware::new(Arc::new(
+                RoleBasedAuthorizer::default(),
+            )))
+            .with(ValidationMiddleware::default())
+            .with(IdempotencyMiddleware::default())
+            .with(RetryMiddleware::default())
+    }
+
+    /// ミドルウェアを追加する（先に追加したものほど外側で実行される）
+    pub fn with(mut self, middleware: impl Middleware + 'static) -> Self {
+        self.middlewares.push(Arc::new(middleware));
+        self
+    }
+
+    /// コマンドを実行し、作成または更新された貸出のIDを返す
+    pub async fn dispatch(
+        &self,
+        deps: &ServiceDependencies,
+        envelope: CommandEnvelope,
+    ) -> Result<LoanId> {
+        Next {
+            chain: &self.middlewares,
+        }
+        .run(deps, envelope)
+        .await
+    }
+}
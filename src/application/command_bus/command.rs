@@ -0,0 +1,138 @@
+use crate::domain::commands::{ExtendLoan, LoanBook, LoanBookWithOverride, ReturnBook};
+use crate::domain::value_objects::{LoanId, StaffId};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// コマンドバスで実行できる貸出管理のコマンド
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LoanCommand {
+    LoanBook(LoanBook),
+    LoanBookWithOverride(LoanBookWithOverride),
+    ExtendLoan(ExtendLoan),
+    ReturnBook(ReturnBook),
+}
+
+impl LoanCommand {
+    /// コマンド名（ログやメトリクスのラベル）
+    pub fn name(&self) -> &'static str {
+        match self {
+            LoanCommand::LoanBook(_) => "LoanBook",
+            LoanCommand::LoanBookWithOverride(_) => "LoanBookWithOverride",
+            LoanCommand::ExtendLoan(_) => "ExtendLoan",
+            LoanCommand::ReturnBook(_) => "ReturnBook",
+        }
+    }
+
+    /// 対象の貸出（貸出の作成では実行前には決まっていない）
+    pub fn loan_id(&self) -> Option<LoanId> {
+        match self {
+            LoanCommand::LoanBook(_) | LoanCommand::LoanBookWithOverride(_) => None,
+            LoanCommand::ExtendLoan(cmd) => Some(cmd.loan_id),
+            LoanCommand::ReturnBook(cmd) => Some(cmd.loan_id),
+        }
+    }
+
+    /// コマンドが表す出来事の日時（貸出・延長・返却の日時）
+    pub fn occurred_at(&self) -> DateTime<Utc> {
+        match self {
+            LoanCommand::LoanBook(cmd) => cmd.loaned_at,
+            LoanCommand::LoanBookWithOverride(cmd) => cmd.loan.loaned_at,
+            LoanCommand::ExtendLoan(cmd) => cmd.extended_at,
+            LoanCommand::ReturnBook(cmd) => cmd.returned_at,
+        }
+    }
+
+    /// 冪等キーの再利用を判定するためのコマンドの内容
+    ///
+    /// 日時は送信ごとに異なるため含めない。
+    pub(crate) fn fingerprint(&self) -> String {
+        match self {
+            LoanCommand::LoanBook(cmd) => format!(
+                "LoanBook:{}:{}:{}",
+                cmd.book_id.value(),
+                cmd.member_id.value(),
+                cmd.staff_id.value()
+            ),
+            LoanCommand::LoanBookWithOverride(cmd) => format!(
+                "LoanBookWithOverride:{}:{}:{}:{}",
+                cmd.loan.book_id.value(),
+                cmd.loan.member_id.value(),
+                cmd.loan.staff_id.value(),
+                cmd.override_token.supervisor_id.value()
+            ),
+            LoanCommand::ExtendLoan(cmd) => format!("ExtendLoan:{}", cmd.loan_id.value()),
+            LoanCommand::ReturnBook(cmd) => format!("ReturnBook:{}", cmd.loan_id.value()),
+        }
+    }
+}
+
+impl From<LoanBook> for LoanCommand {
+    fn from(cmd: LoanBook) -> Self {
+        LoanCommand::LoanBook(cmd)
+    }
+}
+
+impl From<LoanBookWithOverride> for LoanCommand {
+    fn from(cmd: LoanBookWithOverride) -> Self {
+        LoanCommand::LoanBookWithOverride(cmd)
+    }
+}
+
+impl From<ExtendLoan> for LoanCommand {
+    fn from(cmd: ExtendLoan) -> Self {
+        LoanCommand::ExtendLoan(cmd)
+    }
+}
+
+impl From<ReturnBook> for LoanCommand {
+    fn from(cmd: ReturnBook) -> Self {
+        LoanCommand::ReturnBook(cmd)
+    }
+}
+
+/// コマンドに付随する情報（コマンド自体の内容ではないもの）
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CommandMetadata {
+    /// コマンドを実行する職員（認可に使う）
+    pub actor: Option<StaffId>,
+    /// 再送されたコマンドを一度だけ実行するためのキー（HTTPの`Idempotency-Key`など）
+    pub idempotency_key: Option<String>,
+}
+
+/// メタデータ付きのコマンド
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommandEnvelope {
+    pub command: LoanCommand,
+    pub metadata: CommandMetadata,
+}
+
+impl CommandEnvelope {
+    /// コマンドを包む
+    ///
+    /// 貸出の作成では、貸出を行う職員を実行者とする（例外の承認者ではない）。
+    pub fn new(command: impl Into<LoanCommand>) -> Self {
+        let command = command.into();
+        let actor = match &command {
+            LoanCommand::LoanBook(cmd) => Some(cmd.staff_id),
+            LoanCommand::LoanBookWithOverride(cmd) => Some(cmd.loan.staff_id),
+            _ => None,
+        };
+        Self {
+            command,
+            metadata: CommandMetadata {
+                actor,
+                idempotency_key: None,
+            },
+        }
+    }
+
+    pub fn with_actor(mut self, actor: StaffId) -> Self {
+        self.metadata.actor = Some(actor);
+        self
+    }
+
+    pub fn with_idempotency_key(mut self, key: impl Into<String>) -> Self {
+        self.metadata.idempotency_key = Some(key.into());
+        self
+    }
+}
@@ -0,0 +1,501 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::application::loan::{LoanApplicationError, Result, ServiceDependencies};
+use crate::domain::value_objects::{LoanId, TenantId};
+use crate::ports::{Classified, EventStoreError, IdempotencyClaim};
+
+use super::bus::{Middleware, Next};
+use super::command::{CommandEnvelope, LoanCommand};
+
+// ============================================================================
+// ログ
+// ============================================================================
+
+/// コマンドの実行と結果をログに記録する
+pub struct LoggingMiddleware;
+
+#[async_trait]
+impl Middleware for LoggingMiddleware {
+    async fn handle(
+        &self,
+        deps: &ServiceDependencies,
+        envelope: CommandEnvelope,
+        next: Next<'_>,
+    ) -> Result<LoanId> {
+        let name = envelope.command.name();
+        let tenant_id = deps.tenant_id.value();
+        let started = Instant::now();
+
+        let result = next.run(deps, envelope).await;
+        match &result {
+            Ok(loan_id) => tracing::info!(
+                "{} succeeded for loan {} (tenant {}, {:?})",
+                name,
+                loan_id.value(),
+                tenant_id,
+                started.elapsed()
+            ),
+            // システム障害以外（業務ルールの違反など）は想定内の結果
+            Err(e) if e.is_retryable() => {
+                tracing::warn!("{} failed (tenant {}): {}", name, tenant_id, e)
+            }
+            Err(e) => tracing::info!("{} rejected (tenant {}): {}", name, tenant_id, e),
+        }
+        result
+    }
+}
+
+// ============================================================================
+// メトリクス
+// ============================================================================
+
+/// コマンドごとの実行回数と所要時間
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CommandStats {
+    pub succeeded: u64,
+    pub failed: u64,
+    pub total_duration: Duration,
+}
+
+/// コマンドの実行統計（プロセス内で集計する）
+#[derive(Debug, Default)]
+pub struct CommandMetrics {
+    stats: Mutex<HashMap<&'static str, CommandStats>>,
+}
+
+impl CommandMetrics {
+    pub fn record(&self, command: &'static str, succeeded: bool, elapsed: Duration) {
+        let mut stats = self.stats.lock().unwrap();
+        let entry = stats.entry(command).or_default();
+        if succeeded {
+            entry.succeeded += 1;
+        } else {
+            entry.failed += 1;
+        }
+        entry.total_duration += elapsed;
+    }
+
+    /// コマンド名ごとの統計の写し
+    pub fn snapshot(&self) -> HashMap<&'static str, CommandStats> {
+        self.stats.lock().unwrap().clone()
+    }
+}
+
+/// コマンドの実行回数と所要時間を集計する
+pub struct MetricsMiddleware {
+    metrics: Arc<CommandMetrics>,
+}
+
+impl MetricsMiddleware {
+    pub fn new(metrics: Arc<CommandMetrics>) -> Self {
+        Self { metrics }
+    }
+}
+
+#[async_trait]
+impl Middleware for MetricsMiddleware {
+    async fn handle(
+        &self,
+        deps: &ServiceDependencies,
+        envelope: CommandEnvelope,
+        next: Next<'_>,
+    ) -> Result<LoanId> {
+        let name = envelope.command.name();
+        let started = Instant::now();
+        let result = next.run(deps, envelope).await;
+        self.metrics.record(name, result.is_ok(), started.elapsed());
+        result
+    }
+}
+
+// ============================================================================
+// 認可
+// ============================================================================
+
+/// コマンドの実行を許可するかを決める
+#[async_trait]
+pub trait CommandAuthorizer: Send + Sync {
+    /// 許可しない場合は`LoanApplicationError::Forbidden`を返す
+    async fn authorize(&self, deps: &ServiceDependencies, envelope: &CommandEnvelope)
+    -> Result<()>;
+}
+
+/// 全てのコマンドを許可する
+pub struct AllowAllCommands;
+
+#[async_trait]
+impl CommandAuthorizer for AllowAllCommands {
+    async fn authorize(
+        &self,
+        _deps: &ServiceDependencies,
+        _envelope: &CommandEnvelope,
+    ) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// 認可されたコマンドだけを実行する
+pub struct AuthorizationMiddleware {
+    authorizer: Arc<dyn CommandAuthorizer>,
+}
+
+impl AuthorizationMiddleware {
+    pub fn new(authorizer: Arc<dyn CommandAuthorizer>) -> Self {
+        Self { authorizer }
+    }
+}
+
+#[async_trait]
+impl Middleware for AuthorizationMiddleware {
+    async fn handle(
+        &self,
+        deps: &ServiceDependencies,
+        envelope: CommandEnvelope,
+        next: Next<'_>,
+    ) -> Result<LoanId> {
+        self.authorizer.authorize(deps, &envelope).await?;
+        next.run(deps, envelope).await
+    }
+}
+
+// ============================================================================
+// 検証
+// ============================================================================
+
+/// 冪等キーの最大長
+const MAX_IDEMPOTENCY_KEY_LENGTH: usize = 255;
+
+/// コマンドの形式を実行前に検証する
+///
+/// 業務ルール（貸出上限など）はアプリケーションサービスが判定する。
+/// ここでは、どの状態でも成立しない不正な内容（未来の日時、匿名化された会員など）を拒否する。
+pub struct ValidationMiddleware {
+    /// コマンドの日時として許容する、現在時刻からの進み
+    max_clock_skew: chrono::Duration,
+}
+
+impl Default for ValidationMiddleware {
+    fn default() -> Self {
+        Self {
+            max_clock_skew: chrono::Duration::minutes(5),
+        }
+    }
+}
+
+impl ValidationMiddleware {
+    fn validate(&self, envelope: &CommandEnvelope, now: DateTime<Utc>) -> Result<()> {
+        let invalid = |msg: &str| Err(LoanApplicationError::InvalidCommand(msg.to_string()));
+
+        if let Some(key) = &envelope.metadata.idempotency_key
+            && (key.is_empty()
+                || key.len() > MAX_IDEMPOTENCY_KEY_LENGTH
+                || !key.chars().all(|c| c.is_ascii_graphic()))
+        {
+            return invalid("idempotency key must be 1-255 visible ASCII characters");
+        }
+
+        let loan = match &envelope.command {
+            LoanCommand::LoanBook(cmd) => Some(cmd),
+            LoanCommand::LoanBookWithOverride(cmd) => {
+                if let Err(msg) = cmd.override_token.validate() {
+                    return invalid(msg);
+                }
+                Some(&cmd.loan)
+            }
+            _ => None,
+        };
+        if let Some(cmd) = loan {
+            if cmd.member_id.is_anonymised() {
+                return invalid("cannot lend to an anonymised member");
+            }
+            if cmd.book_id.value().is_nil() || cmd.staff_id.value().is_nil() {
+                return invalid("book and staff identifiers are required");
+            }
+        }
+        if envelope.command.occurred_at() > now + self.max_clock_skew {
+            return invalid("command timestamp is in the future");
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Middleware for ValidationMiddleware {
+    async fn handle(
+        &self,
+        deps: &ServiceDependencies,
+        envelope: CommandEnvelope,
+        next: Next<'_>,
+    ) -> Result<LoanId> {
+        self.validate(&envelope, Utc::now())?;
+        next.run(deps, envelope).await
+    }
+}
+
+// ============================================================================
+// 冪等性
+// ============================================================================
+
+type Slot = Arc<tokio::sync::Mutex<()>>;
+
+/// 同じ冪等キーのコマンドを一度だけ実行する
+///
+/// 冪等キーとコマンドの結果をテナントごとに冪等キーストア（`ServiceDependencies::idempotency_keys`）に
+/// 記録し、再送には同じ結果を返す。記録はデータベースに保持されるため、
+/// 別のインスタンスへの再送や再起動後の再送にも同じ結果を返す。
+/// 同じプロセスに同じキーのコマンドが同時に届いた場合、後のものは先のものの完了を待つ。
+/// 別のインスタンスで実行中のキーは`IdempotencyKeyInProgress`で拒否する。
+/// 失敗したコマンドは記録を残さないため、同じキーで再試行できる。
+/// 記録は保持期間を過ぎると破棄される。確保したまま`abandon_after`を過ぎた記録は、
+/// 実行中に停止したインスタンスのものとして破棄する。
+pub struct IdempotencyMiddleware {
+    running: Mutex<HashMap<(TenantId, String), Slot>>,
+    retention: Duration,
+    abandon_after: Duration,
+}
+
+impl Default for IdempotencyMiddleware {
+    fn default() -> Self {
+        Self::new(
+            Duration::from_secs(24 * 60 * 60),
+            Duration::from_secs(5 * 60),
+        )
+    }
+}
+
+impl IdempotencyMiddleware {
+    pub fn new(retention: Duration, abandon_after: Duration) -> Self {
+        Self {
+            running: Mutex::new(HashMap::new()),
+            retention,
+            abandon_after,
+        }
+    }
+
+    /// キーの実行の排他を取得する（使われていない排他はこのとき破棄する）
+    fn slot(&self, tenant_id: TenantId, key: String) -> Slot {
+        let mut running = self.running.lock().unwrap();
+        running.retain(|_, slot| Arc::strong_count(slot) > 1);
+        running.entry((tenant_id, key)).or_default().clone()
+    }
+}
+
+/// 現在時刻から`duration`前の時刻
+fn before(now: DateTime<Utc>, duration: Duration) -> DateTime<Utc> {
+    chrono::Duration::from_std(duration)
+        .ok()
+        .and_then(|duration| now.checked_sub_signed(duration))
+        .unwrap_or(DateTime::<Utc>::MIN_UTC)
+}
+
+#[async_trait]
+impl Middleware for IdempotencyMiddleware {
+    async fn handle(
+        &self,
+        deps: &ServiceDependencies,
+        envelope: CommandEnvelope,
+        next: Next<'_>,
+    ) -> Result<LoanId> {
+        let Some(key) = envelope.metadata.idempotency_key.clone() else {
+            return next.run(deps, envelope).await;
+        };
+
+        let slot = self.slot(deps.tenant_id, key.clone());
+        let _running = slot.lock().await;
+        let fingerprint = envelope.command.fingerprint();
+        let now = Utc::now();
+
+        let claim = deps
+            .idempotency_keys
+            .claim(
+                &key,
+                &fingerprint,
+                now,
+                before(now, self.retention),
+                before(now, self.abandon_after),
+            )
+            .await
+            .map_err(LoanApplicationError::IdempotencyStoreError)?;
+
+        if let IdempotencyClaim::Taken {
+            fingerprint: previous,
+            loan_id,
+        } = claim
+        {
+            return if previous != fingerprint {
+                Err(LoanApplicationError::IdempotencyKeyReused)
+            } else {
+                loan_id.ok_or(LoanApplicationError::IdempotencyKeyInProgress)
+            };
+        }
+
+        match next.run(deps, envelope).await {
+            Ok(loan_id) => {
+                // コマンドは成功しているため、記録に失敗しても結果は返す
+                // （再送は確保したままの記録が破棄されるまで拒否される）
+                if let Err(e) = deps
+                    .idempotency_keys
+                    .complete(&key, loan_id, Utc::now())
+                    .await
+                {
+                    tracing::warn!("Failed to record idempotency key {}: {}", key, e);
+                }
+                Ok(loan_id)
+            }
+            Err(error) => {
+                if let Err(e) = deps.idempotency_keys.release(&key).await {
+                    tracing::warn!("Failed to release idempotency key {}: {}", key, e);
+                }
+                Err(error)
+            }
+        }
+    }
+}
+
+// ============================================================================
+// 競合時の再試行
+// ============================================================================
+
+/// 同時更新の競合で失敗したコマンドを再試行する
+///
+/// 再試行するのはイベントストアへの書き込みが競合した場合のみ。
+/// このときイベントは保存されていないため、集約を読み直して実行し直せば安全である。
+/// 接続断などの他の一時的な障害は、何が保存されたか分からないため再試行しない。
+pub struct RetryMiddleware {
+    max_attempts: u32,
+    backoff: Duration,
+}
+
+impl Default for RetryMiddleware {
+    fn default() -> Self {
+        Self::new(3, Duration::from_millis(20))
+    }
+}
+
+impl RetryMiddleware {
+    /// `max_attempts`回まで実行し、`backoff`から倍々に待つ
+    pub fn new(max_attempts: u32, backoff: Duration) -> Self {
+        Self {
+            max_attempts: max_attempts.max(1),
+            backoff,
+        }
+    }
+}
+
+/// イベントストアへの書き込みが同時更新と競合したか
+fn is_write_conflict(error: &LoanApplicationError) -> bool {
+    matches!(
+        error,
+        LoanApplicationError::EventStoreError(
+            EventStoreError::VersionConflict { .. } | EventStoreError::Conflict(_)
+        )
+    )
+}
+
+#[async_trait]
+impl Middleware for RetryMiddleware {
+    async fn handle(
+        &self,
+        deps: &ServiceDependencies,
+        envelope: CommandEnvelope,
+        next: Next<'_>,
+    ) -> Result<LoanId> {
+        let mut attempt = 1;
+        loop {
+            match next.run(deps, envelope.clone()).await {
+                Err(e) if attempt < self.max_attempts && is_write_conflict(&e) => {
+                    tracing::debug!(
+                        "{} conflicted on attempt {}, retrying: {}",
+                        envelope.command.name(),
+                        attempt,
+                        e
+                    );
+                    tokio::time::sleep(self.backoff * 2u32.pow(attempt - 1)).await;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::commands::{LoanBook, ReturnBook};
+    use crate::domain::value_objects::{BookId, MemberId, StaffId};
+
+    fn loan_book(now: DateTime<Utc>) -> LoanBook {
+        LoanBook {
+            book_id: BookId::new(),
+            member_id: MemberId::new(),
+            loaned_at: now,
+            staff_id: StaffId::new(),
+        }
+    }
+
+    #[test]
+    fn test_validation_accepts_well_formed_commands() {
+        let now = Utc::now();
+        let validation = ValidationMiddleware::default();
+
+        let envelope = CommandEnvelope::new(loan_book(now)).with_idempotency_key("checkout-42");
+        assert!(validation.validate(&envelope, now).is_ok());
+    }
+
+    #[test]
+    fn test_validation_rejects_malformed_commands() {
+        let now = Utc::now();
+        let validation = ValidationMiddleware::default();
+
+        let mut anonymised = loan_book(now);
+        anonymised.member_id = MemberId::ANONYMISED;
+        let future_return = ReturnBook {
+            loan_id: LoanId::new(),
+            returned_at: now + chrono::Duration::hours(1),
+        };
+        let bad_key = CommandEnvelope::new(loan_book(now)).with_idempotency_key("two words");
+
+        for envelope in [
+            CommandEnvelope::new(anonymised),
+            CommandEnvelope::new(future_return),
+            bad_key,
+        ] {
+            assert!(matches!(
+                validation.validate(&envelope, now),
+                Err(LoanApplicationError::InvalidCommand(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_metrics_count_outcomes_per_command() {
+        let metrics = CommandMetrics::default();
+        metrics.record("LoanBook", true, Duration::from_millis(3));
+        metrics.record("LoanBook", false, Duration::from_millis(2));
+
+        let stats = metrics.snapshot()["LoanBook"];
+        assert_eq!(stats.succeeded, 1);
+        assert_eq!(stats.failed, 1);
+        assert_eq!(stats.total_duration, Duration::from_millis(5));
+    }
+
+    #[test]
+    fn test_only_write_conflicts_are_retried() {
+        let conflict = LoanApplicationError::EventStoreError(EventStoreError::VersionConflict {
+            aggregate_id: LoanId::new().value(),
+            expected: 1,
+            actual: 2,
+        });
+        let unavailable =
+            LoanApplicationError::EventStoreError(EventStoreError::Unavailable("down".into()));
+
+        assert!(is_write_conflict(&conflict));
+        assert!(!is_write_conflict(&unavailable));
+        assert!(!is_write_conflict(&LoanApplicationError::LoanNotFound));
+    }
+}
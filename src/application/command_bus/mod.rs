@@ -0,0 +1,14 @@
+mod bus;
+mod command;
+mod middleware;
+
+#[allow(unused_imports)]
+pub use bus::{CommandBus, Middleware, Next};
+#[allow(unused_imports)]
+pub use command::{CommandEnvelope, CommandMetadata, LoanCommand};
+#[allow(unused_imports)]
+pub use middleware::{
+    AllowAllCommands, AuthorizationMiddleware, CommandAuthorizer, CommandMetrics, CommandStats,
+    IdempotencyMiddleware, LoggingMiddleware, MetricsMiddleware, RetryMiddleware,
+    ValidationMiddleware,
+};
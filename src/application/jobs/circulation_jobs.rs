@@ -0,0 +1,323 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use std::fmt::Display;
+use std::sync::Arc;
+
+use crate::application::loan::{
+    ServiceDependencies, advance_overdue_notices, anonymise_loan_history, detect_overdue_loans,
+    remind_due_soon_loans,
+};
+use crate::application::notification::{NotificationRouter, dispatch_notifications};
+use crate::domain::{loan::DUE_SOON_REMINDER_DAYS, value_objects::TenantId};
+
+use super::errors::{JobError, Result};
+use super::scheduler::Job;
+
+/// 延滞検出ジョブの名前
+pub const OVERDUE_DETECTION_JOB: &str = "overdue-detection";
+
+/// 読書履歴の保持期間ジョブの名前
+pub const HISTORY_RETENTION_JOB: &str = "history-retention";
+
+/// 返却期限のリマインダージョブの名前
+pub const DUE_SOON_REMINDER_JOB: &str = "due-soon-reminder";
+
+/// 延滞の督促ジョブの名前
+pub const OVERDUE_NOTICE_JOB: &str = "overdue-notices";
+
+/// 通知の配信ジョブの名前
+pub const NOTIFICATION_DISPATCH_JOB: &str = "notification-dispatch";
+
+/// 通知のダイジェストジョブの名前
+pub const NOTIFICATION_DIGEST_JOB: &str = "notification-digest";
+
+/// 通知の配信ジョブが毎回さかのぼって処理する期間（日数）
+///
+/// 配信済みの通知は送らないため、期間が重なってもよい。
+/// ジョブが止まっていた場合も、この期間内のイベントは次の実行で通知される。
+const NOTIFICATION_LOOKBACK_DAYS: i64 = 2;
+
+/// 処理結果を要約する
+///
+/// 一部のテナント（または貸出・イベント）で失敗した場合も残りは処理し、失敗をまとめてエラーにする。
+fn summarise<E: Display>(
+    summary: String,
+    failed_items: Option<(usize, &str)>,
+    failures: Vec<(TenantId, E)>,
+) -> Result<String> {
+    let mut problems = Vec::new();
+    if let Some((count, items)) = failed_items.filter(|(count, _)| *count > 0) {
+        problems.push(format!("{} {} could not be processed", count, items));
+    }
+    problems.extend(
+        failures
+            .iter()
+            .map(|(tenant_id, e)| format!("tenant {}: {}", tenant_id.value(), e)),
+    );
+
+    if problems.is_empty() {
+        Ok(summary)
+    } else {
+        Err(JobError::Failed(format!(
+            "{}; {}",
+            summary,
+            problems.join("; ")
+        )))
+    }
+}
+
+/// 延滞検出ジョブ
+///
+/// すべてのテナントで返却期限を過ぎた貸出を延滞にする（`detect_overdue_loans()`）。
+/// スケジュール上の実行時刻を判定の基準日時とする。
+pub struct OverdueDetectionJob {
+    tenants: Vec<ServiceDependencies>,
+}
+
+impl OverdueDetectionJob {
+    pub fn new(tenants: Vec<ServiceDependencies>) -> Self {
+        Self { tenants }
+    }
+}
+
+#[async_trait]
+impl Job for OverdueDetectionJob {
+    fn name(&self) -> &str {
+        OVERDUE_DETECTION_JOB
+    }
+
+    async fn run(&self, scheduled_for: DateTime<Utc>) -> Result<String> {
+        let (mut detected, mut skipped, mut failed_loans) = (0, 0, 0);
+        let mut failures = Vec::new();
+        for deps in &self.tenants {
+            match detect_overdue_loans(deps, scheduled_for).await {
+                Ok(report) => {
+                    detected += report.detected.len();
+                    skipped += report.skipped.len();
+                    failed_loans += report.failed.len();
+                }
+                Err(e) => failures.push((deps.tenant_id, e)),
+            }
+        }
+        summarise(
+            format!(
+                "Marked overdue {} loans, skipped {} loans",
+                detected, skipped
+            ),
+            Some((failed_loans, "loans")),
+            failures,
+        )
+    }
+}
+
+/// 読書履歴の保持期間ジョブ
+///
+/// すべてのテナントで保持期間を過ぎた返却済みの貸出と会員の紐付けを外す
+/// （`anonymise_loan_history()`）。保持期間を設定していないテナントでは何もしない。
+pub struct HistoryRetentionJob {
+    tenants: Vec<ServiceDependencies>,
+}
+
+impl HistoryRetentionJob {
+    pub fn new(tenants: Vec<ServiceDependencies>) -> Self {
+        Self { tenants }
+    }
+}
+
+#[async_trait]
+impl Job for HistoryRetentionJob {
+    fn name(&self) -> &str {
+        HISTORY_RETENTION_JOB
+    }
+
+    async fn run(&self, scheduled_for: DateTime<Utc>) -> Result<String> {
+        let mut anonymised = 0;
+        let mut failures = Vec::new();
+        for deps in &self.tenants {
+            match anonymise_loan_history(deps, scheduled_for).await {
+                Ok(count) => anonymised += count,
+                Err(e) => failures.push((deps.tenant_id, e)),
+            }
+        }
+        summarise(format!("Anonymised {} loans", anonymised), None, failures)
+    }
+}
+
+/// 返却期限のリマインダージョブ
+///
+/// すべてのテナントで、返却期限まで`DUE_SOON_REMINDER_DAYS`日以内の貸出について
+/// リマインダーを記録する（`remind_due_soon_loans()`）。会員への通知は通知の配信ジョブが送る。
+/// スケジュール上の実行時刻を判定の基準日時とする。
+pub struct DueSoonReminderJob {
+    tenants: Vec<ServiceDependencies>,
+}
+
+impl DueSoonReminderJob {
+    pub fn new(tenants: Vec<ServiceDependencies>) -> Self {
+        Self { tenants }
+    }
+}
+
+#[async_trait]
+impl Job for DueSoonReminderJob {
+    fn name(&self) -> &str {
+        DUE_SOON_REMINDER_JOB
+    }
+
+    async fn run(&self, scheduled_for: DateTime<Utc>) -> Result<String> {
+        let within = Duration::days(DUE_SOON_REMINDER_DAYS);
+        let (mut reminded, mut skipped, mut failed_loans) = (0, 0, 0);
+        let mut failures = Vec::new();
+        for deps in &self.tenants {
+            match remind_due_soon_loans(deps, scheduled_for, within).await {
+                Ok(report) => {
+                    reminded += report.reminded.len();
+                    skipped += report.skipped;
+                    failed_loans += report.failed.len();
+                }
+                Err(e) => failures.push((deps.tenant_id, e)),
+            }
+        }
+        summarise(
+            format!("Reminded {} loans, skipped {} loans", reminded, skipped),
+            Some((failed_loans, "loans")),
+            failures,
+        )
+    }
+}
+
+/// 延滞の督促ジョブ
+///
+/// すべてのテナントで、延滞した貸出の督促を次の段階に進め、延滞が長引いた貸出を紛失にする
+/// （`advance_overdue_notices()`）。会員への通知は通知の配信ジョブが送る。
+/// スケジュール上の実行時刻を判定の基準日時とする。
+pub struct OverdueNoticeJob {
+    tenants: Vec<ServiceDependencies>,
+}
+
+impl OverdueNoticeJob {
+    pub fn new(tenants: Vec<ServiceDependencies>) -> Self {
+        Self { tenants }
+    }
+}
+
+#[async_trait]
+impl Job for OverdueNoticeJob {
+    fn name(&self) -> &str {
+        OVERDUE_NOTICE_JOB
+    }
+
+    async fn run(&self, scheduled_for: DateTime<Utc>) -> Result<String> {
+        let (mut notices, mut declared_lost, mut skipped, mut failed_loans) = (0, 0, 0, 0);
+        let mut failures = Vec::new();
+        for deps in &self.tenants {
+            match advance_overdue_notices(deps, scheduled_for).await {
+                Ok(report) => {
+                    notices += report.notices.len();
+                    declared_lost += report.declared_lost.len();
+                    skipped += report.skipped;
+                    failed_loans += report.failed.len();
+                }
+                Err(e) => failures.push((deps.tenant_id, e)),
+            }
+        }
+        summarise(
+            format!(
+                "Sent {} overdue notices, declared lost {} loans, skipped {} loans",
+                notices, declared_lost, skipped
+            ),
+            Some((failed_loans, "loans")),
+            failures,
+        )
+    }
+}
+
+/// 通知の配信ジョブ
+///
+/// すべてのテナントで、最近保存されたイベントに対する会員への通知を配信する
+/// （`dispatch_notifications()`）。スケジュール上の実行時刻から
+/// `NOTIFICATION_LOOKBACK_DAYS`日前までに発生したイベントを毎回処理する。
+/// 通知は通常`NotificationSubscriber`がコミットの直後に送るため、このジョブは
+/// イベントバスが取りこぼした（配信前に停止した）通知を送る。
+pub struct NotificationDispatchJob {
+    tenants: Vec<ServiceDependencies>,
+}
+
+impl NotificationDispatchJob {
+    pub fn new(tenants: Vec<ServiceDependencies>) -> Self {
+        Self { tenants }
+    }
+}
+
+#[async_trait]
+impl Job for NotificationDispatchJob {
+    fn name(&self) -> &str {
+        NOTIFICATION_DISPATCH_JOB
+    }
+
+    async fn run(&self, scheduled_for: DateTime<Utc>) -> Result<String> {
+        let from = scheduled_for - Duration::days(NOTIFICATION_LOOKBACK_DAYS);
+        let (mut delivered, mut failed_events) = (0, 0);
+        let mut failures = Vec::new();
+        for deps in &self.tenants {
+            match dispatch_notifications(deps, from, scheduled_for).await {
+                Ok(report) => {
+                    delivered += report.delivered;
+                    failed_events += report.failed.len();
+                }
+                Err(e) => failures.push((deps.tenant_id, e)),
+            }
+        }
+        summarise(
+            format!("Delivered {} notices", delivered),
+            Some((failed_events, "events")),
+            failures,
+        )
+    }
+}
+
+/// 通知のダイジェストジョブ
+///
+/// すべてのテナントで、送る日時になった保留中の通知（日次ダイジェストと、通知を送らない
+/// 時間帯に発生した通知）を会員ごとに1通にまとめて送る（`NotificationRouter::deliver_deferred()`）。
+/// スケジュール上の実行時刻までに送る日時になった通知を送る。
+pub struct NotificationDigestJob {
+    tenants: Vec<(TenantId, Arc<NotificationRouter>)>,
+}
+
+impl NotificationDigestJob {
+    pub fn new(tenants: Vec<(TenantId, Arc<NotificationRouter>)>) -> Self {
+        Self { tenants }
+    }
+}
+
+#[async_trait]
+impl Job for NotificationDigestJob {
+    fn name(&self) -> &str {
+        NOTIFICATION_DIGEST_JOB
+    }
+
+    async fn run(&self, scheduled_for: DateTime<Utc>) -> Result<String> {
+        let (mut messages, mut notices, mut suppressed, mut failed_members) = (0, 0, 0, 0);
+        let mut failures = Vec::new();
+        for (tenant_id, router) in &self.tenants {
+            match router.deliver_deferred(scheduled_for).await {
+                Ok(report) => {
+                    messages += report.messages;
+                    notices += report.notices;
+                    suppressed += report.suppressed;
+                    failed_members += report.failed.len();
+                }
+                Err(e) => failures.push((*tenant_id, e)),
+            }
+        }
+        summarise(
+            format!(
+                "Sent {} notices in {} messages, dropped {} notices",
+                notices, messages, suppressed
+            ),
+            Some((failed_members, "members")),
+            failures,
+        )
+    }
+}
@@ -0,0 +1,21 @@
+use crate::ports::JobStoreError;
+use thiserror::Error;
+
+/// バックグラウンドジョブのエラー
+#[derive(Debug, Error)]
+pub enum JobError {
+    /// スケジュールの書式が不正
+    #[error("Invalid schedule '{expression}': {reason}")]
+    InvalidSchedule { expression: String, reason: String },
+
+    /// ジョブの処理が失敗した（一部のテナントのみの失敗を含む）
+    #[error("Job failed: {0}")]
+    Failed(String),
+
+    /// JobStoreのエラー
+    #[error("Job store error")]
+    JobStoreError(#[source] JobStoreError),
+}
+
+/// バックグラウンドジョブの Result型
+pub type Result<T> = std::result::Result<T, JobError>;
@@ -0,0 +1,18 @@
+mod circulation_jobs;
+mod errors;
+mod schedule;
+mod scheduler;
+
+#[allow(unused_imports)]
+pub use circulation_jobs::{
+    DUE_SOON_REMINDER_JOB, DueSoonReminderJob, HISTORY_RETENTION_JOB, HistoryRetentionJob,
+    NOTIFICATION_DIGEST_JOB, NOTIFICATION_DISPATCH_JOB, NotificationDigestJob,
+    NotificationDispatchJob, OVERDUE_DETECTION_JOB, OVERDUE_NOTICE_JOB, OverdueDetectionJob,
+    OverdueNoticeJob,
+};
+#[allow(unused_imports)]
+pub use errors::{JobError, Result};
+#[allow(unused_imports)]
+pub use schedule::Schedule;
+#[allow(unused_imports)]
+pub use scheduler::{Job, Scheduler};
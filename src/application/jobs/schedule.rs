@@ -0,0 +1,244 @@
+use chrono::{DateTime, Datelike, Duration, TimeZone, Timelike, Utc};
+use std::fmt;
+use std::str::FromStr;
+
+use super::errors::JobError;
+
+/// 次の実行時刻を探す範囲（うるう日だけに一致するスケジュールも見つかるように4年より長くする）
+const SEARCH_HORIZON_DAYS: i64 = 5 * 366;
+
+/// ジョブの実行スケジュール（cron形式、UTC）
+///
+/// 「分 時 日 月 曜日」の5つのフィールドで指定する。
+/// 各フィールドには`*`、数値、範囲（`1-5`）、列挙（`1,15`）、間隔（`*/15`、`0-30/10`）を使える。
+/// 曜日は0（日曜）から6（土曜）で、7も日曜として扱う。
+/// 日と曜日の両方を指定した場合は、どちらかに一致すれば実行する（cronと同じ）。
+///
+/// 例:
+/// - `*/15 * * * *` - 15分ごと
+/// - `0 3 * * *` - 毎日3:00
+/// - `30 8 * * 1-5` - 平日の8:30
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Schedule {
+    expression: String,
+    minutes: u64,
+    hours: u64,
+    days: u64,
+    months: u64,
+    weekdays: u64,
+    days_restricted: bool,
+    weekdays_restricted: bool,
+}
+
+/// 1つのフィールドを、一致する値のビットの集合に変換する
+fn parse_field(field: &str, min: u32, max: u32) -> std::result::Result<u64, String> {
+    let mut bits = 0u64;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => {
+                let step: u32 = step
+                    .parse()
+                    .map_err(|_| format!("invalid step '{}'", step))?;
+                if step == 0 {
+                    return Err("step must be positive".to_string());
+                }
+                (range, step)
+            }
+            None => (part, 1),
+        };
+
+        let (start, end) = if range == "*" {
+            (min, max)
+        } else {
+            let parse = |value: &str| {
+                value
+                    .parse::<u32>()
+                    .ok()
+                    .filter(|v| (min..=max).contains(v))
+                    .ok_or_else(|| format!("'{}' is not between {} and {}", value, min, max))
+            };
+            match range.split_once('-') {
+                Some((start, end)) => (parse(start)?, parse(end)?),
+                // `5/10`は5から最大値まで10おき
+                None if step > 1 => (parse(range)?, max),
+                None => {
+                    let value = parse(range)?;
+                    (value, value)
+                }
+            }
+        };
+        if start > end {
+            return Err(format!("range '{}' is reversed", range));
+        }
+
+        for value in (start..=end).step_by(step as usize) {
+            bits |= 1 << value;
+        }
+    }
+    Ok(bits)
+}
+
+fn contains(bits: u64, value: u32) -> bool {
+    bits & (1 << value) != 0
+}
+
+impl Schedule {
+    /// 指定した時刻より後（その時刻を含まない）で最初の実行時刻
+    ///
+    /// 実行されることのないスケジュール（例: 2月30日）ではNone。
+    pub fn next_after(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let horizon = after + Duration::days(SEARCH_HORIZON_DAYS);
+        let mut t = after.with_second(0)?.with_nanosecond(0)? + Duration::minutes(1);
+
+        while t <= horizon {
+            if !contains(self.months, t.month()) {
+                let (year, month) = if t.month() == 12 {
+                    (t.year() + 1, 1)
+                } else {
+                    (t.year(), t.month() + 1)
+                };
+                t = Utc.with_ymd_and_hms(year, month, 1, 0, 0, 0).single()?;
+            } else if !self.matches_day(t) {
+                t = t.date_naive().succ_opt()?.and_hms_opt(0, 0, 0)?.and_utc();
+            } else if !contains(self.hours, t.hour()) {
+                t = t.with_minute(0)? + Duration::hours(1);
+            } else if !contains(self.minutes, t.minute()) {
+                t += Duration::minutes(1);
+            } else {
+                return Some(t);
+            }
+        }
+        None
+    }
+
+    fn matches_day(&self, t: DateTime<Utc>) -> bool {
+        let day = contains(self.days, t.day());
+        let weekday = contains(self.weekdays, t.weekday().num_days_from_sunday());
+        match (self.days_restricted, self.weekdays_restricted) {
+            (true, true) => day || weekday,
+            (true, false) => day,
+            (false, true) => weekday,
+            (false, false) => true,
+        }
+    }
+}
+
+impl FromStr for Schedule {
+    type Err = JobError;
+
+    fn from_str(expression: &str) -> std::result::Result<Self, Self::Err> {
+        let invalid = |reason: String| JobError::InvalidSchedule {
+            expression: expression.to_string(),
+            reason,
+        };
+
+        let fields: Vec<&str> = expression.split_whitespace().collect();
+        let [minute, hour, day, month, weekday] = fields[..] else {
+            return Err(invalid(format!("expected 5 fields, got {}", fields.len())));
+        };
+
+        let mut weekdays = parse_field(weekday, 0, 7).map_err(invalid)?;
+        // 7は日曜
+        if contains(weekdays, 7) {
+            weekdays = (weekdays & !(1 << 7)) | 1;
+        }
+
+        Ok(Self {
+            expression: fields.join(" "),
+            minutes: parse_field(minute, 0, 59).map_err(invalid)?,
+            hours: parse_field(hour, 0, 23).map_err(invalid)?,
+            days: parse_field(day, 1, 31).map_err(invalid)?,
+            months: parse_field(month, 1, 12).map_err(invalid)?,
+            weekdays,
+            days_restricted: !day.starts_with('*'),
+            weekdays_restricted: !weekday.starts_with('*'),
+        })
+    }
+}
+
+impl fmt::Display for Schedule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.expression)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(y: i32, mo: u32, d: u32, h: u32, mi: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(y, mo, d, h, mi, 0).unwrap()
+    }
+
+    fn schedule(expression: &str) -> Schedule {
+        expression.parse().unwrap()
+    }
+
+    #[test]
+    fn test_next_after_steps_and_fixed_times() {
+        let every_quarter = schedule("*/15 * * * *");
+        assert_eq!(
+            every_quarter.next_after(at(2025, 1, 15, 10, 7)),
+            Some(at(2025, 1, 15, 10, 15))
+        );
+        // 実行時刻ちょうどからは次の回
+        assert_eq!(
+            every_quarter.next_after(at(2025, 1, 15, 10, 15)),
+            Some(at(2025, 1, 15, 10, 30))
+        );
+
+        let nightly = schedule("0 3 * * *");
+        assert_eq!(
+            nightly.next_after(at(2025, 12, 31, 4, 0)),
+            Some(at(2026, 1, 1, 3, 0))
+        );
+    }
+
+    #[test]
+    fn test_next_after_weekdays_and_days() {
+        // 2025-01-17は金曜
+        let weekdays = schedule("30 8 * * 1-5");
+        assert_eq!(
+            weekdays.next_after(at(2025, 1, 17, 9, 0)),
+            Some(at(2025, 1, 20, 8, 30))
+        );
+
+        // 日と曜日の両方を指定した場合はどちらかに一致すればよい
+        let first_or_sunday = schedule("0 0 1 * 7");
+        assert_eq!(
+            first_or_sunday.next_after(at(2025, 1, 17, 0, 0)),
+            Some(at(2025, 1, 19, 0, 0))
+        );
+
+        assert_eq!(
+            schedule("0 0 30 2 *").next_after(at(2025, 1, 1, 0, 0)),
+            None
+        );
+        assert_eq!(
+            schedule("0 0 29 2 *").next_after(at(2025, 1, 1, 0, 0)),
+            Some(at(2028, 2, 29, 0, 0))
+        );
+    }
+
+    #[test]
+    fn test_invalid_schedules_are_rejected() {
+        for expression in [
+            "* * * *",
+            "60 * * * *",
+            "* 24 * * *",
+            "* * 0 * *",
+            "*/0 * * * *",
+            "5-1 * * * *",
+            "daily",
+        ] {
+            assert!(
+                matches!(
+                    expression.parse::<Schedule>(),
+                    Err(JobError::InvalidSchedule { .. })
+                ),
+                "{expression} should be rejected"
+            );
+        }
+        assert_eq!(schedule("  0  3 * * * ").to_string(), "0 3 * * *");
+    }
+}
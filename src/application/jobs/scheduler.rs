@@ -0,0 +1,185 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::sync::Arc;
+use tokio::sync::watch;
+use uuid::Uuid;
+
+use crate::ports::{JobRun, JobRunStatus, JobStore};
+
+use super::errors::{JobError, Result};
+use super::schedule::Schedule;
+
+/// 定期的に実行されるバックグラウンドジョブ
+#[async_trait]
+pub trait Job: Send + Sync {
+    /// ジョブ名（実行履歴と排他制御のキー）
+    fn name(&self) -> &str;
+
+    /// ジョブを実行し、処理結果の要約を返す
+    ///
+    /// # 引数
+    /// * `scheduled_for` - スケジュール上の実行時刻
+    async fn run(&self, scheduled_for: DateTime<Utc>) -> Result<String>;
+}
+
+/// バックグラウンドジョブのスケジューラー
+///
+/// 登録されたジョブをそれぞれのスケジュールで実行する。
+/// 複数のインスタンスが同じスケジュールで動いていても、各回を実行するのは1つだけ：
+/// 1. ジョブの実行権を取得する（他のインスタンスが実行中なら見送る）
+/// 2. 実行の開始を記録する（同じ回が既に記録されていれば見送る）
+/// 3. ジョブを実行し、結果を記録する
+/// 4. 実行権を手放す
+pub struct Scheduler {
+    store: Arc<dyn JobStore>,
+    instance_id: String,
+    jobs: Vec<(Arc<dyn Job>, Schedule)>,
+}
+
+impl Scheduler {
+    /// ジョブのないスケジューラーを作成
+    ///
+    /// # 引数
+    /// * `store` - 排他制御と実行履歴のジョブストア
+    /// * `instance_id` - 実行履歴に記録するこのインスタンスの識別子
+    pub fn new(store: Arc<dyn JobStore>, instance_id: impl Into<String>) -> Self {
+        Self {
+            store,
+            instance_id: instance_id.into(),
+            jobs: Vec::new(),
+        }
+    }
+
+    /// ジョブを登録する
+    pub fn with_job(mut self, job: Arc<dyn Job>, schedule: Schedule) -> Self {
+        self.jobs.push((job, schedule));
+        self
+    }
+
+    /// 停止を指示されるまでジョブを実行し続ける
+    ///
+    /// `shutdown`がtrueになる（または送信側が破棄される）と、次の実行を待つのをやめる。
+    /// 実行中のジョブは中断せず、終わるのを待ってから戻る。
+    pub async fn run(&self, shutdown: watch::Receiver<bool>) {
+        futures::future::join_all(
+            self.jobs
+                .iter()
+                .map(|(job, schedule)| self.run_job(job.as_ref(), schedule, shutdown.clone())),
+        )
+        .await;
+    }
+
+    /// 1つのジョブをスケジュールに従って繰り返し実行する
+    async fn run_job(
+        &self,
+        job: &dyn Job,
+        schedule: &Schedule,
+        mut shutdown: watch::Receiver<bool>,
+    ) {
+        tracing::info!("Scheduled job {} ({})", job.name(), schedule);
+
+        while !*shutdown.borrow() {
+            let now = Utc::now();
+            let Some(scheduled_for) = schedule.next_after(now) else {
+                tracing::warn!("Job {} has no upcoming run ({})", job.name(), schedule);
+                return;
+            };
+
+            let wait = (scheduled_for - now).to_std().unwrap_or_default();
+            tokio::select! {
+                _ = tokio::time::sleep(wait) => {}
+                _ = shutdown.changed() => break,
+            }
+
+            match self.run_once(job, scheduled_for).await {
+                Ok(Some(run)) => tracing::info!(
+                    "Job {} {:?}: {}",
+                    job.name(),
+                    run.status,
+                    run.summary.unwrap_or_default()
+                ),
+                Ok(None) => tracing::debug!(
+                    "Job {} at {} was run by another instance",
+                    job.name(),
+                    scheduled_for
+                ),
+                Err(e) => tracing::error!("Job {} could not be run: {}", job.name(), e),
+            }
+        }
+
+        tracing::info!("Stopped job {}", job.name());
+    }
+
+    /// ジョブのある回を実行する
+    ///
+    /// 他のインスタンスが実行中、または同じ回を実行済みの場合は実行せずNoneを返す。
+    /// ジョブ自体の失敗はエラーではなく、`Failed`の実行履歴として返す。
+    ///
+    /// # エラー
+    /// - JobStoreError: 実行権の取得や実行履歴の記録の失敗
+    pub async fn run_once(
+        &self,
+        job: &dyn Job,
+        scheduled_for: DateTime<Utc>,
+    ) -> Result<Option<JobRun>> {
+        let Some(lease) = self
+            .store
+            .try_acquire(job.name())
+            .await
+            .map_err(JobError::JobStoreError)?
+        else {
+            return Ok(None);
+        };
+
+        let result = self.run_leased(job, scheduled_for).await;
+
+        // 実行権はジョブの成否にかかわらず手放す（失敗してもインスタンスの停止時に手放される）
+        if let Err(e) = lease.release().await {
+            tracing::warn!("Failed to release the lease of job {}: {}", job.name(), e);
+        }
+
+        result
+    }
+
+    /// 実行権を持った状態でジョブを実行し、実行履歴を記録する
+    async fn run_leased(
+        &self,
+        job: &dyn Job,
+        scheduled_for: DateTime<Utc>,
+    ) -> Result<Option<JobRun>> {
+        let mut run = JobRun {
+            run_id: Uuid::new_v4(),
+            job_name: job.name().to_string(),
+            scheduled_for,
+            instance_id: self.instance_id.clone(),
+            started_at: Utc::now(),
+            finished_at: None,
+            status: JobRunStatus::Running,
+            summary: None,
+        };
+
+        let started = self
+            .store
+            .record_started(&run)
+            .await
+            .map_err(JobError::JobStoreError)?;
+        if !started {
+            return Ok(None);
+        }
+
+        let (status, summary) = match job.run(scheduled_for).await {
+            Ok(summary) => (JobRunStatus::Succeeded, summary),
+            Err(e) => (JobRunStatus::Failed, e.to_string()),
+        };
+        run.finished_at = Some(Utc::now());
+        run.status = status;
+        run.summary = Some(summary);
+
+        self.store
+            .record_finished(&run)
+            .await
+            .map_err(JobError::JobStoreError)?;
+
+        Ok(Some(run))
+    }
+}
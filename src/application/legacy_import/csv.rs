@@ -0,0 +1,59 @@
+//! 旧システムのエクスポートCSVを読むための最小限のパーサー
+//!
+//! RFC 4180のうち、旧システムが出力する範囲（カンマ区切り、
+//! ダブルクォートによる囲みと`""`によるエスケープ）のみをサポートする。
+//! フィールド内の改行はサポートしない。
+
+/// 1行をフィールドに分割する
+///
+/// 引用符が閉じられていない場合はエラーを返す。
+pub(super) fn parse_line(line: &str) -> Result<Vec<String>, String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut chars = line.chars().peekable();
+    let mut in_quotes = false;
+
+    while let Some(c) = chars.next() {
+        match (in_quotes, c) {
+            (true, '"') if chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            (true, '"') => in_quotes = false,
+            (false, '"') if field.is_empty() => in_quotes = true,
+            (false, ',') => fields.push(std::mem::take(&mut field)),
+            (_, c) => field.push(c),
+        }
+    }
+
+    if in_quotes {
+        return Err("unterminated quoted field".to_string());
+    }
+    fields.push(field);
+    Ok(fields)
+}
+
+/// フィールドをCSVとして出力できる形にする
+///
+/// カンマ・引用符・改行を含む場合のみ引用符で囲む。
+pub(super) fn escape_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_line_handles_quotes_and_escapes() {
+        let fields = parse_line(r#"2024-04-01,"A, B","say ""hi""",,x"#).unwrap();
+        assert_eq!(fields, vec!["2024-04-01", "A, B", r#"say "hi""#, "", "x"]);
+
+        assert!(parse_line(r#"a,"unterminated"#).is_err());
+        assert_eq!(escape_field(r#"say "hi", ok"#), r#""say ""hi"", ok""#);
+    }
+}
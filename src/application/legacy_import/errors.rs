@@ -0,0 +1,88 @@
+use crate::ports::{BookServiceError, EventStoreError, LoanReadModelError, MemberServiceError};
+use thiserror::Error;
+
+/// 旧システムからの貸出履歴移行のエラー
+///
+/// 行単位の不備は`RejectReason`としてリジェクトレポートに記録され、
+/// 移行処理全体は中断しない。ここに定義するのは処理を継続できないエラーのみ。
+#[derive(Debug, Error)]
+pub enum LegacyImportError {
+    /// ヘッダー行が存在しない、または必須列が欠けている
+    #[error("Invalid CSV header: {0}")]
+    InvalidHeader(String),
+
+    /// 入出力エラー
+    #[error("I/O error")]
+    Io(#[from] std::io::Error),
+
+    /// EventStoreのエラー
+    #[error("Event store error")]
+    EventStoreError(#[source] EventStoreError),
+
+    /// ReadModelのエラー
+    #[error("Read model error")]
+    ReadModelError(#[source] LoanReadModelError),
+
+    /// MemberServiceのエラー
+    #[error("Member service error")]
+    MemberServiceError(#[source] MemberServiceError),
+
+    /// BookServiceのエラー
+    #[error("Book service error")]
+    BookServiceError(#[source] BookServiceError),
+}
+
+/// 行を取り込めなかった理由
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum RejectReason {
+    /// CSVとして解析できない（引用符の不整合、列数の不一致など）
+    #[error("Malformed row: {0}")]
+    MalformedRow(String),
+
+    /// 必須項目が空
+    #[error("Missing value for {0}")]
+    MissingField(&'static str),
+
+    /// 日付を解析できない
+    #[error("Invalid date for {field}: {value}")]
+    InvalidDate { field: &'static str, value: String },
+
+    /// 延長回数を解析できない
+    #[error("Invalid renewal count: {0}")]
+    InvalidRenewals(String),
+
+    /// 延長回数が上限（1回）を超えている
+    #[error("Renewal count {0} exceeds the limit of 1")]
+    RenewalLimitExceeded(u8),
+
+    /// 返却期限が貸出日以前
+    #[error("Due date is not after the loan date")]
+    DueDateNotAfterLoanDate,
+
+    /// 返却日が貸出日より前
+    #[error("Return date is before the loan date")]
+    ReturnDateBeforeLoanDate,
+
+    /// 基準日時より後の日付
+    #[error("{0} is in the future")]
+    FutureDate(&'static str),
+
+    /// 返却期限が延長回数と整合しない（貸出期間を日単位で等分できない）
+    #[error("Due date is inconsistent with the renewal count")]
+    InconsistentDueDate,
+
+    /// 利用者カード番号に対応する会員が存在しない
+    #[error("Unknown member card number: {0}")]
+    UnknownMember(String),
+
+    /// 資料バーコードに対応する書籍が存在しない
+    #[error("Unknown item barcode: {0}")]
+    UnknownBook(String),
+
+    /// ドメインのビジネスルール違反
+    #[error("Domain rule violation: {0}")]
+    DomainRuleViolation(String),
+}
+
+/// 移行処理の Result型
+pub type Result<T> = std::result::Result<T, LegacyImportError>;
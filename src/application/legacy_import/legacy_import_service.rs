@@ -0,0 +1,569 @@
+use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, Utc};
+use std::collections::HashMap;
+use std::io::{BufRead, Write};
+
+use crate::application::loan::{ServiceDependencies, build_loan_view};
+use crate::application::repository::EventSourcedRepository;
+use crate::domain::{
+    self, Aggregate, CirculationPolicy, ExtensionCount, LoanBecameOverdue,
+    loan::{Loan, LoanEvent},
+    value_objects::*,
+};
+
+use super::csv;
+use super::errors::{LegacyImportError, RejectReason, Result};
+
+/// 必須列（旧システムのエクスポート形式）
+const COLUMNS: [&str; 6] = [
+    "loan_date",
+    "due_date",
+    "return_date",
+    "renewals",
+    "member_card_number",
+    "item_barcode",
+];
+
+/// 旧システムの貸出記録1行
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LegacyLoanRow {
+    pub loan_date: DateTime<Utc>,
+    /// 延長を含めた最終的な返却期限
+    pub due_date: DateTime<Utc>,
+    pub return_date: Option<DateTime<Utc>>,
+    pub renewals: u8,
+    pub member_card_number: String,
+    pub item_barcode: String,
+}
+
+/// 移行の実行オプション
+#[derive(Debug, Clone, Copy)]
+pub struct LegacyImportOptions {
+    /// 貸出イベントの`loaned_by`に記録する職員（移行作業者）
+    pub imported_by: StaffId,
+    /// 移行の基準日時（未返却の貸出の延滞判定に使用）
+    pub as_of: DateTime<Utc>,
+    /// trueの場合は検証とイベント生成のみ行い、書き込まない
+    pub dry_run: bool,
+}
+
+/// 取り込めなかった行
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RejectedRow {
+    /// CSVの行番号（ヘッダーを1行目とする）
+    pub line: usize,
+    /// 元の行
+    pub raw: String,
+    pub reason: RejectReason,
+}
+
+/// 移行結果
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct LegacyImportReport {
+    /// データ行の件数（空行を除く）
+    pub total_rows: usize,
+    /// 取り込んだ（dry-runでは取り込み可能な）貸出の件数
+    pub imported_loans: usize,
+    /// 生成したイベントの件数
+    pub event_count: usize,
+    pub rejected: Vec<RejectedRow>,
+    pub dry_run: bool,
+}
+
+/// 旧システムの貸出履歴CSVを取り込む
+///
+/// 各行を検証し、ドメインの純粋関数で貸出の履歴
+/// （BookLoaned → LoanExtended → LoanBecameOverdue → BookReturned）を合成して
+/// 元の日時のままイベントストアに追記し、Read Modelを更新する。
+///
+/// 不正な行は処理を中断せずにリジェクトレポートに記録する。
+/// 過去の事実の移行であるため、貸出上限や書籍の貸出可否は検証しない。
+///
+/// 同じCSVを2回取り込むと貸出が重複するため、
+/// 再実行する場合は先にdry-runで確認すること。
+///
+/// # エラー
+/// ヘッダー不正、入出力エラー、ポート層のエラー
+pub async fn import_legacy_loans<R: BufRead>(
+    deps: &ServiceDependencies,
+    input: R,
+    options: LegacyImportOptions,
+) -> Result<LegacyImportReport> {
+    let mut lines = input.lines().enumerate();
+    let header = loop {
+        match lines.next() {
+            Some((_, line)) => {
+                let line = line?;
+                if !line.trim().is_empty() {
+                    break line;
+                }
+            }
+            None => return Err(LegacyImportError::InvalidHeader("empty input".to_string())),
+        }
+    };
+    let columns = column_indexes(&header)?;
+
+    let mut report = LegacyImportReport {
+        dry_run: options.dry_run,
+        ..Default::default()
+    };
+
+    for (index, line) in lines {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        report.total_rows += 1;
+
+        let (loan, events) = match import_row(deps, &line, &columns, &options).await? {
+            Ok(synthesized) => synthesized,
+            Err(reason) => {
+                report.rejected.push(RejectedRow {
+                    line: index + 1,
+                    raw: line,
+                    reason,
+                });
+                continue;
+            }
+        };
+
+        report.imported_loans += 1;
+        report.event_count += events.len();
+        if options.dry_run {
+            continue;
+        }
+
+        let loan_id = match &loan {
+            Loan::Active(l) => l.loan_id,
+            Loan::Overdue(l) => l.loan_id,
+            Loan::Returned(l) => l.loan_id,
+            Loan::Lost(l) => l.loan_id,
+        };
+        // 既に存在する貸出IDへの取り込みはバージョンの不一致として拒否される
+        EventSourcedRepository::<Loan>::new(deps.event_store.clone())
+            .save_with_expected_version(&loan_id, 0, events)
+            .await
+            .map_err(LegacyImportError::EventStoreError)?;
+        deps.loan_read_model
+            .save(build_loan_view(&loan))
+            .await
+            .map_err(LegacyImportError::ReadModelError)?;
+    }
+
+    tracing::info!(
+        "Legacy import{}: {} rows, {} loans, {} events, {} rejected",
+        if options.dry_run { " (dry run)" } else { "" },
+        report.total_rows,
+        report.imported_loans,
+        report.event_count,
+        report.rejected.len()
+    );
+
+    Ok(report)
+}
+
+/// 1行を検証・解決してイベントを合成する
+///
+/// 外側のResultは処理を継続できないエラー、内側は行のリジェクト理由。
+async fn import_row(
+    deps: &ServiceDependencies,
+    line: &str,
+    columns: &HashMap<&'static str, usize>,
+    options: &LegacyImportOptions,
+) -> Result<std::result::Result<(Loan, Vec<LoanEvent>), RejectReason>> {
+    let row = match csv::parse_line(line)
+        .map_err(RejectReason::MalformedRow)
+        .and_then(|fields| parse_row(&fields, columns))
+    {
+        Ok(row) => row,
+        Err(reason) => return Ok(Err(reason)),
+    };
+
+    let Some(member_id) = deps
+        .member_service
+        .find_by_card_number(&row.member_card_number)
+        .await
+        .map_err(LegacyImportError::MemberServiceError)?
+    else {
+        return Ok(Err(RejectReason::UnknownMember(row.member_card_number)));
+    };
+
+    let Some(book_id) = deps
+        .book_service
+        .find_by_barcode(&row.item_barcode)
+        .await
+        .map_err(LegacyImportError::BookServiceError)?
+    else {
+        return Ok(Err(RejectReason::UnknownBook(row.item_barcode)));
+    };
+
+    Ok(synthesize_loan_events(&row, book_id, member_id, options))
+}
+
+/// 純粋関数：旧システムの貸出記録からイベント列を合成する
+///
+/// 旧システムには延長日時や延滞検出日時が記録されていないため、次のように補う：
+/// - 貸出期間：最終返却期限までの期間を（延長回数 + 1）で等分した日数
+/// - 延長日時：延長前の返却期限（それ以前に返却された場合は返却日時）
+/// - 延滞検出日時：最終返却期限の1日後（日次の延滞検出バッチで検出されたものとみなす）
+///
+/// 各イベントはドメインの純粋関数で生成し、`Loan::apply`で状態を進めるため、
+/// 合成されたイベント列は必ず`Loan::replay`で再生できる。
+///
+/// # 戻り値
+/// 最終的な貸出状態と、追記するイベント列
+pub fn synthesize_loan_events(
+    row: &LegacyLoanRow,
+    book_id: BookId,
+    member_id: MemberId,
+    options: &LegacyImportOptions,
+) -> std::result::Result<(Loan, Vec<LoanEvent>), RejectReason> {
+    // 1. 行の整合性を検証
+    ExtensionCount::try_from(row.renewals)
+        .map_err(|_| RejectReason::RenewalLimitExceeded(row.renewals))?;
+    if row.due_date <= row.loan_date {
+        return Err(RejectReason::DueDateNotAfterLoanDate);
+    }
+    if row.loan_date > options.as_of {
+        return Err(RejectReason::FutureDate("loan_date"));
+    }
+    if let Some(returned_at) = row.return_date {
+        if returned_at < row.loan_date {
+            return Err(RejectReason::ReturnDateBeforeLoanDate);
+        }
+        if returned_at > options.as_of {
+            return Err(RejectReason::FutureDate("return_date"));
+        }
+    }
+
+    // 2. 延長回数から当時の貸出期間を逆算
+    let periods = i64::from(row.renewals) + 1;
+    let total = row.due_date - row.loan_date;
+    if total.num_seconds() % (Duration::days(1).num_seconds() * periods) != 0 {
+        return Err(RejectReason::InconsistentDueDate);
+    }
+    let policy = CirculationPolicy {
+        loan_period_days: total.num_days() / periods,
+        ..CirculationPolicy::default()
+    };
+
+    // 3. 純粋関数でイベントを生成し、Loan::applyで状態を進める
+    let mut events = Vec::new();
+    let mut record = |loan: Option<Loan>, event: LoanEvent| {
+        let next = Loan::apply(loan, &event);
+        events.push(event);
+        next
+    };
+
+    let (_, loaned) = domain::loan::loan_book_with_policy(
+        book_id,
+        member_id,
+        row.loan_date,
+        options.imported_by,
+        &policy,
+    )
+    .map_err(|e| RejectReason::DomainRuleViolation(format!("{:?}", e)))?;
+    let mut loan = record(None, LoanEvent::from(loaned));
+
+    if row.renewals > 0 {
+        let Loan::Active(active) = loan else {
+            unreachable!("a new loan is always active");
+        };
+        let extended_at = row
+            .return_date
+            .map_or(active.due_date, |r| r.min(active.due_date));
+        let (_, extended) =
+            domain::loan::extend_loan_with_policy(active.clone(), extended_at, &policy)
+                .map_err(|e| RejectReason::DomainRuleViolation(format!("{:?}", e)))?;
+        loan = record(Some(Loan::Active(active)), LoanEvent::from(extended));
+    }
+
+    let detected_at = row.due_date + Duration::days(1);
+    let became_overdue = match row.return_date {
+        Some(returned_at) => returned_at > detected_at,
+        None => options.as_of >= detected_at,
+    };
+    if let (true, Loan::Active(active)) = (became_overdue, &loan) {
+        let event = LoanBecameOverdue {
+            loan_id: active.loan_id,
+            book_id: active.book_id,
+            member_id: active.member_id,
+            due_date: active.due_date,
+            detected_at,
+        };
+        loan = record(Some(loan), LoanEvent::from(event));
+    }
+
+    if let Some(returned_at) = row.return_date {
+        let (_, returned) = domain::loan::return_book(loan.clone(), returned_at)
+            .map_err(|e| RejectReason::DomainRuleViolation(format!("{:?}", e)))?;
+        loan = record(Some(loan), LoanEvent::from(returned));
+    }
+
+    Ok((loan, events))
+}
+
+/// リジェクトレポートをCSVで書き出す
+///
+/// 列は`line,reason,row`。`row`には元の行をそのまま出力するため、
+/// 修正して再取り込みする際の入力として使える。
+pub fn write_reject_report<W: Write>(rejected: &[RejectedRow], mut out: W) -> std::io::Result<()> {
+    writeln!(out, "line,reason,row")?;
+    for row in rejected {
+        writeln!(
+            out,
+            "{},{},{}",
+            row.line,
+            csv::escape_field(&row.reason.to_string()),
+            csv::escape_field(&row.raw)
+        )?;
+    }
+    out.flush()
+}
+
+/// ヘッダー行から必須列の位置を求める
+fn column_indexes(header: &str) -> Result<HashMap<&'static str, usize>> {
+    let names = csv::parse_line(header).map_err(LegacyImportError::InvalidHeader)?;
+    COLUMNS
+        .iter()
+        .map(|&column| {
+            names
+                .iter()
+                .position(|name| name.trim().eq_ignore_ascii_case(column))
+                .map(|index| (column, index))
+                .ok_or_else(|| {
+                    LegacyImportError::InvalidHeader(format!("missing column {}", column))
+                })
+        })
+        .collect()
+}
+
+/// 行のフィールドを型付きの貸出記録に変換する
+fn parse_row(
+    fields: &[String],
+    columns: &HashMap<&'static str, usize>,
+) -> std::result::Result<LegacyLoanRow, RejectReason> {
+    let field = |name: &'static str| -> std::result::Result<&str, RejectReason> {
+        let index = columns[name];
+        fields.get(index).map(|value| value.trim()).ok_or_else(|| {
+            RejectReason::MalformedRow(format!(
+                "expected at least {} fields, found {}",
+                index + 1,
+                fields.len()
+            ))
+        })
+    };
+    let required = |name: &'static str| {
+        field(name).and_then(|value| {
+            if value.is_empty() {
+                Err(RejectReason::MissingField(name))
+            } else {
+                Ok(value)
+            }
+        })
+    };
+
+    let return_date = match field("return_date")? {
+        "" => None,
+        value => Some(parse_date("return_date", value)?),
+    };
+    let renewals = match field("renewals")? {
+        "" => 0,
+        value => value
+            .parse()
+            .map_err(|_| RejectReason::InvalidRenewals(value.to_string()))?,
+    };
+
+    Ok(LegacyLoanRow {
+        loan_date: parse_date("loan_date", required("loan_date")?)?,
+        due_date: parse_date("due_date", required("due_date")?)?,
+        return_date,
+        renewals,
+        member_card_number: required("member_card_number")?.to_string(),
+        item_barcode: required("item_barcode")?.to_string(),
+    })
+}
+
+/// 旧システムの日付を解析する
+///
+/// RFC 3339、`YYYY-MM-DD HH:MM:SS`（UTC）、`YYYY-MM-DD`（UTCの0時）を受け付ける。
+fn parse_date(
+    field: &'static str,
+    value: &str,
+) -> std::result::Result<DateTime<Utc>, RejectReason> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|dt| dt.with_timezone(&Utc))
+        .or_else(|_| {
+            NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S").map(|dt| dt.and_utc())
+        })
+        .or_else(|_| {
+            NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                .map(|d| d.and_hms_opt(0, 0, 0).expect("midnight is valid").and_utc())
+        })
+        .map_err(|_| RejectReason::InvalidDate {
+            field,
+            value: value.to_string(),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::DomainEvent;
+    use chrono::TimeZone;
+
+    fn date(y: i32, m: u32, d: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(y, m, d, 0, 0, 0).unwrap()
+    }
+
+    fn options() -> LegacyImportOptions {
+        LegacyImportOptions {
+            imported_by: StaffId::new(),
+            as_of: date(2024, 6, 1),
+            dry_run: false,
+        }
+    }
+
+    fn row(due: DateTime<Utc>, returned: Option<DateTime<Utc>>, renewals: u8) -> LegacyLoanRow {
+        LegacyLoanRow {
+            loan_date: date(2024, 4, 1),
+            due_date: due,
+            return_date: returned,
+            renewals,
+            member_card_number: "M-0001".to_string(),
+            item_barcode: "B-0001".to_string(),
+        }
+    }
+
+    fn event_names(events: &[LoanEvent]) -> Vec<&'static str> {
+        events
+            .iter()
+            .map(|e| e.as_domain_event().event_type())
+            .collect()
+    }
+
+    #[test]
+    fn test_synthesize_extended_overdue_returned_loan() {
+        // 14日 + 延長14日 = 4/29期限、5/10返却
+        let row = row(date(2024, 4, 29), Some(date(2024, 5, 10)), 1);
+        let (loan, events) =
+            synthesize_loan_events(&row, BookId::new(), MemberId::new(), &options()).unwrap();
+
+        assert_eq!(
+            event_names(&events),
+            vec![
+                "BookLoaned",
+                "LoanExtended",
+                "LoanBecameOverdue",
+                "BookReturned"
+            ]
+        );
+        assert_eq!(Loan::replay(&events).unwrap(), Some(loan.clone()));
+        let Loan::Returned(returned) = loan else {
+            panic!("expected a returned loan");
+        };
+        assert_eq!(returned.loaned_at, date(2024, 4, 1));
+        assert_eq!(returned.due_date, date(2024, 4, 29));
+        assert_eq!(returned.returned_at, date(2024, 5, 10));
+        assert_eq!(returned.extension_count.value(), 1);
+        let DomainEvent::BookReturned(event) = events[3].as_domain_event() else {
+            panic!("expected BookReturned");
+        };
+        assert!(event.was_overdue);
+    }
+
+    #[test]
+    fn test_synthesize_open_loans_use_as_of_for_overdue() {
+        let (loan, events) = synthesize_loan_events(
+            &row(date(2024, 4, 15), None, 0),
+            BookId::new(),
+            MemberId::new(),
+            &options(),
+        )
+        .unwrap();
+        assert_eq!(
+            event_names(&events),
+            vec!["BookLoaned", "LoanBecameOverdue"]
+        );
+        assert!(matches!(loan, Loan::Overdue(_)));
+
+        let (loan, events) = synthesize_loan_events(
+            &row(date(2024, 4, 15), None, 0),
+            BookId::new(),
+            MemberId::new(),
+            &LegacyImportOptions {
+                as_of: date(2024, 4, 10),
+                ..options()
+            },
+        )
+        .unwrap();
+        assert_eq!(event_names(&events), vec!["BookLoaned"]);
+        assert!(matches!(loan, Loan::Active(_)));
+    }
+
+    #[test]
+    fn test_synthesize_rejects_inconsistent_rows() {
+        let cases = [
+            (
+                row(date(2024, 4, 29), None, 2),
+                RejectReason::RenewalLimitExceeded(2),
+            ),
+            (
+                row(date(2024, 4, 1), None, 0),
+                RejectReason::DueDateNotAfterLoanDate,
+            ),
+            (
+                row(date(2024, 4, 15), Some(date(2024, 3, 31)), 0),
+                RejectReason::ReturnDateBeforeLoanDate,
+            ),
+            (
+                row(date(2024, 4, 15), Some(date(2024, 7, 1)), 0),
+                RejectReason::FutureDate("return_date"),
+            ),
+            // 15日間は2期間に等分できない
+            (
+                row(date(2024, 4, 16), None, 1),
+                RejectReason::InconsistentDueDate,
+            ),
+        ];
+
+        for (row, expected) in cases {
+            let result = synthesize_loan_events(&row, BookId::new(), MemberId::new(), &options());
+            assert_eq!(result.unwrap_err(), expected);
+        }
+    }
+
+    #[test]
+    fn test_parse_row_validates_fields() {
+        let columns: HashMap<_, _> = COLUMNS.iter().enumerate().map(|(i, &c)| (c, i)).collect();
+        let parse = |line: &str| parse_row(&csv::parse_line(line).unwrap(), &columns);
+
+        let row = parse("2024-04-01,2024-04-15T09:00:00+09:00,,,M-0001, B-0001 ").unwrap();
+        assert_eq!(
+            row.due_date,
+            Utc.with_ymd_and_hms(2024, 4, 15, 0, 0, 0).unwrap()
+        );
+        assert_eq!(row.return_date, None);
+        assert_eq!(row.renewals, 0);
+        assert_eq!(row.item_barcode, "B-0001");
+
+        assert_eq!(
+            parse("2024-04-01,2024-04-15,,,,B-0001").unwrap_err(),
+            RejectReason::MissingField("member_card_number")
+        );
+        assert_eq!(
+            parse("2024/04/01,2024-04-15,,,M-0001,B-0001").unwrap_err(),
+            RejectReason::InvalidDate {
+                field: "loan_date",
+                value: "2024/04/01".to_string()
+            }
+        );
+        assert_eq!(
+            parse("2024-04-01,2024-04-15,,x,M-0001,B-0001").unwrap_err(),
+            RejectReason::InvalidRenewals("x".to_string())
+        );
+        assert!(matches!(
+            parse("2024-04-01,2024-04-15").unwrap_err(),
+            RejectReason::MalformedRow(_)
+        ));
+    }
+}
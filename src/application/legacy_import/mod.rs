@@ -0,0 +1,11 @@
+mod csv;
+mod errors;
+mod legacy_import_service;
+
+#[allow(unused_imports)]
+pub use errors::{LegacyImportError, RejectReason, Result};
+#[allow(unused_imports)]
+pub use legacy_import_service::{
+    LegacyImportOptions, LegacyImportReport, LegacyLoanRow, RejectedRow, import_legacy_loans,
+    synthesize_loan_events, write_reject_report,
+};
@@ -0,0 +1,173 @@
+use futures::stream::{self, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use uuid::Uuid;
+
+use crate::application::repository::{EventSourcedRepository, Versioned};
+use crate::domain::{
+    Aggregate,
+    loan::{Loan, LoanEvent},
+    value_objects::LoanId,
+};
+use crate::ports::{Classified, EventStoreError};
+
+use super::errors::LoanApplicationError;
+use super::loan_service::{ServiceDependencies, commit_loan};
+
+/// 貸出のバッチ処理で1回に読み込む貸出の件数
+///
+/// 長期休館明けなどで候補が数万件になっても、メモリ使用量を抑える。
+const LOAN_BATCH_SIZE: usize = 500;
+
+/// 貸出のバッチ処理で同時に保存する貸出の件数
+///
+/// 貸出ごとに1トランザクションを使うため、コネクションプールを使い切らない程度に抑える。
+const LOAN_BATCH_CONCURRENCY: usize = 4;
+
+/// 処理に失敗した候補
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FailedLoanCandidate {
+    pub loan_id: LoanId,
+    /// 失敗の内容
+    pub error: String,
+    /// 再試行で回復しうる失敗か（次回の実行で改めて処理される）
+    pub retryable: bool,
+}
+
+impl FailedLoanCandidate {
+    pub(super) fn new(loan_id: LoanId, error: &LoanApplicationError) -> Self {
+        Self {
+            loan_id,
+            error: failure_message(error),
+            retryable: error.is_retryable(),
+        }
+    }
+}
+
+/// 失敗の内容（原因を含める）
+fn failure_message(error: &LoanApplicationError) -> String {
+    match std::error::Error::source(error) {
+        Some(source) => format!("{}: {}", error, source),
+        None => error.to_string(),
+    }
+}
+
+/// 1件の候補の処理結果
+///
+/// `D`は処理した候補、`S`は見送った候補の記録。
+pub(super) enum Outcome<D, S> {
+    Done(D),
+    Skipped(S),
+    Failed(FailedLoanCandidate),
+}
+
+/// 候補ごとの処理結果（候補の順）
+pub(super) struct BatchResults<D, S> {
+    pub done: Vec<D>,
+    pub skipped: Vec<S>,
+    pub failed: Vec<FailedLoanCandidate>,
+}
+
+/// 候補の貸出をまとめて読み込み、1件ずつ処理する
+///
+/// 候補を`LOAN_BATCH_SIZE`件ずつイベントストアからまとめて読み込み（N+1クエリを避ける）、
+/// 復元した貸出を`process`で`LOAN_BATCH_CONCURRENCY`件まで並行して処理する。
+/// 貸出のイベントがない候補は`None`として渡す。
+///
+/// バッチの読み込みや復元の失敗で全体を止めず、失敗した候補として報告する。
+pub(super) async fn run_loan_batches<D, S, F, Fut>(
+    deps: &ServiceDependencies,
+    loan_ids: Vec<LoanId>,
+    process: F,
+) -> BatchResults<D, S>
+where
+    F: Fn(LoanId, Option<Versioned<Loan>>) -> Fut,
+    Fut: Future<Output = Outcome<D, S>>,
+{
+    let mut results = BatchResults {
+        done: Vec::new(),
+        skipped: Vec::new(),
+        failed: Vec::new(),
+    };
+
+    for batch in loan_ids.chunks(LOAN_BATCH_SIZE) {
+        // イベントストアから候補の完全な履歴をまとめて取得
+        let ids: Vec<Uuid> = batch.iter().map(|id| id.value()).collect();
+        let mut histories = match deps
+            .event_store
+            .load_many(&ids, Loan::aggregate_type())
+            .await
+        {
+            Ok(histories) => histories,
+            Err(e) => {
+                let error = LoanApplicationError::EventStoreError(e);
+                tracing::warn!("Failed to load a batch of loans: {}", error);
+                results
+                    .failed
+                    .extend(batch.iter().map(|id| FailedLoanCandidate::new(*id, &error)));
+                continue;
+            }
+        };
+
+        // 各候補を並行して処理（結果は候補の順に集める）
+        let outcomes: Vec<Outcome<D, S>> = stream::iter(batch.iter().copied())
+            .map(|loan_id| {
+                let events = histories.remove(&loan_id.value()).unwrap_or_default();
+                let restored = EventSourcedRepository::<Loan>::restore(events);
+                let process = &process;
+                async move {
+                    match restored {
+                        Ok(loaded) => process(loan_id, loaded).await,
+                        Err(e) => Outcome::Failed(FailedLoanCandidate::new(
+                            loan_id,
+                            &LoanApplicationError::EventStoreError(e),
+                        )),
+                    }
+                }
+            })
+            .buffered(LOAN_BATCH_CONCURRENCY)
+            .collect()
+            .await;
+
+        for outcome in outcomes {
+            match outcome {
+                Outcome::Done(done) => results.done.push(done),
+                Outcome::Skipped(skipped) => results.skipped.push(skipped),
+                Outcome::Failed(failed) => results.failed.push(failed),
+            }
+        }
+    }
+
+    results
+}
+
+/// 候補の貸出のイベントを保存する
+///
+/// 読み込み後に返却・延長などで貸出が変わっていた場合は`conflict`として見送る
+/// （次回の実行で改めて判定される）。
+pub(super) async fn commit_candidate<D, S>(
+    deps: &ServiceDependencies,
+    loan_id: LoanId,
+    version: u32,
+    event: LoanEvent,
+    loan: &Loan,
+    done: D,
+    conflict: S,
+) -> Outcome<D, S> {
+    let event_type = event.as_domain_event().event_type();
+    match commit_loan(deps, loan_id, version, event, loan).await {
+        Ok(()) => Outcome::Done(done),
+        Err(LoanApplicationError::EventStoreError(EventStoreError::VersionConflict { .. })) => {
+            Outcome::Skipped(conflict)
+        }
+        Err(e) => {
+            tracing::warn!(
+                "Failed to record {} for loan {}: {}",
+                event_type,
+                loan_id.value(),
+                failure_message(&e)
+            );
+            Outcome::Failed(FailedLoanCandidate::new(loan_id, &e))
+        }
+    }
+}
@@ -0,0 +1,134 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::application::repository::Versioned;
+use crate::domain::{
+    self,
+    loan::{Loan, LoanEvent},
+    value_objects::*,
+};
+
+use super::batch_runner::{FailedLoanCandidate, Outcome, commit_candidate, run_loan_batches};
+use super::errors::{LoanApplicationError, Result};
+use super::loan_service::ServiceDependencies;
+
+/// 返却期限のリマインダーを記録した貸出
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RemindedLoan {
+    pub loan_id: LoanId,
+    pub member_id: MemberId,
+    pub due_date: DateTime<Utc>,
+}
+
+/// 返却期限のリマインダーの結果
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DueSoonReminderReport {
+    /// 判定の基準日時
+    pub as_of: DateTime<Utc>,
+    pub reminded: Vec<RemindedLoan>,
+    /// 対象外だった候補の件数（知らせ済み、読み込み後に返却・延長されたなど）
+    pub skipped: usize,
+    pub failed: Vec<FailedLoanCandidate>,
+}
+
+/// 返却期限が近い貸出の会員に知らせる
+///
+/// 基準日時（`as_of`）から`within`以内に返却期限を迎える貸出中の貸出について、
+/// LoanDueSoonRemindedイベントを記録する。会員への通知は、このイベントから
+/// 通知の配信（`dispatch_notifications()`）が送る。
+///
+/// ビジネスルール：
+/// - 返却期限ごとに1回だけ知らせる（知らせ済みの貸出は見送る）
+/// - 延長された貸出は、新しい返却期限について改めて知らせる
+///
+/// 処理フロー：
+/// 1. Read Modelから返却期限が近い貸出を取得
+/// 2. 候補をまとめて読み込み、並行して処理する（`run_loan_batches()`）：
+///    - イベントから現在の状態を復元し、返却期限が`within`以内か確かめる
+///    - LoanDueSoonRemindedイベントを生成・保存し、Read Modelを更新
+///
+/// 1件の失敗で全体を止めず、失敗した候補として報告する。
+/// 失敗した候補は次回の実行で改めて処理される。
+///
+/// # 引数
+/// * `deps` - サービスの依存関係
+/// * `as_of` - 判定の基準日時（`LoanDueSoonReminded`の日時になる）
+/// * `within` - 返却期限までの期間（例: `DUE_SOON_REMINDER_DAYS`日）
+///
+/// # エラー
+/// 候補を取得できない場合のみ（ReadModelError）
+#[allow(dead_code)]
+pub async fn remind_due_soon_loans(
+    deps: &ServiceDependencies,
+    as_of: DateTime<Utc>,
+    within: Duration,
+) -> Result<DueSoonReminderReport> {
+    let until = as_of + within;
+
+    // 1. Read Modelから返却期限が近い貸出を取得
+    let candidates = deps
+        .loan_read_model
+        .find_due_soon_candidates(as_of, until)
+        .await
+        .map_err(LoanApplicationError::ReadModelError)?;
+
+    // 2. 各候補の返却期限を確かめて知らせる
+    let loan_ids = candidates.iter().map(|l| l.loan_id).collect();
+    let results = run_loan_batches(deps, loan_ids, |loan_id, loan| {
+        remind_due_soon_loan(deps, loan_id, loan, as_of, until)
+    })
+    .await;
+
+    Ok(DueSoonReminderReport {
+        as_of,
+        reminded: results.done,
+        skipped: results.skipped.len(),
+        failed: results.failed,
+    })
+}
+
+/// 1件の候補の返却期限が近ければ、リマインダーを記録する
+async fn remind_due_soon_loan(
+    deps: &ServiceDependencies,
+    loan_id: LoanId,
+    loan: Option<Versioned<Loan>>,
+    as_of: DateTime<Utc>,
+    until: DateTime<Utc>,
+) -> Outcome<RemindedLoan, ()> {
+    let Some(Versioned {
+        aggregate: loan,
+        version,
+    }) = loan
+    else {
+        return Outcome::Skipped(());
+    };
+
+    // Read Modelの反映前に返却・延長された貸出は見送る
+    let Loan::Active(active) = loan else {
+        return Outcome::Skipped(());
+    };
+    if active.due_date > until {
+        return Outcome::Skipped(());
+    }
+    // 知らせ済み、または返却期限を過ぎた貸出は見送る
+    let Ok((reminded_loan, event)) = domain::loan::remind_due_soon(active, as_of) else {
+        return Outcome::Skipped(());
+    };
+
+    let reminded = RemindedLoan {
+        loan_id,
+        member_id: event.member_id,
+        due_date: event.due_date,
+    };
+    // 読み込み後に返却・延長された貸出は、次回の実行で改めて判定する
+    commit_candidate(
+        deps,
+        loan_id,
+        version,
+        LoanEvent::from(event),
+        &Loan::Active(reminded_loan),
+        reminded,
+        (),
+    )
+    .await
+}
@@ -0,0 +1,75 @@
+use crate::domain::{
+    EligibilitySnapshot, EligibilityViolation, check_eligibility, value_objects::*,
+};
+
+use super::errors::{LoanApplicationError, Result};
+use super::loan_service::ServiceDependencies;
+
+/// 貸出条件の判定に必要な情報を集める
+///
+/// 会員サービス・書籍サービス・Read Modelに問い合わせるだけで、何も保存しない。
+///
+/// # エラー
+/// - MemberServiceError / BookServiceError / ReadModelError: 問い合わせの失敗
+pub(crate) async fn eligibility_snapshot(
+    deps: &ServiceDependencies,
+    member_id: MemberId,
+    book_id: BookId,
+) -> Result<EligibilitySnapshot> {
+    let member_exists = deps
+        .member_service
+        .exists(member_id)
+        .await
+        .map_err(LoanApplicationError::MemberServiceError)?;
+
+    let member_suspended = deps
+        .member_service
+        .is_suspended(member_id)
+        .await
+        .map_err(LoanApplicationError::MemberServiceError)?;
+
+    let has_overdue_loans = deps
+        .member_service
+        .has_overdue_loans(member_id)
+        .await
+        .map_err(LoanApplicationError::MemberServiceError)?;
+
+    let active_loan_count = deps
+        .loan_read_model
+        .get_active_loans_for_member(member_id)
+        .await
+        .map_err(LoanApplicationError::ReadModelError)?
+        .len();
+
+    let book_available = deps
+        .book_service
+        .is_available_for_loan(book_id)
+        .await
+        .map_err(LoanApplicationError::BookServiceError)?;
+
+    Ok(EligibilitySnapshot {
+        member_exists,
+        member_suspended,
+        has_overdue_loans,
+        active_loan_count,
+        book_available,
+    })
+}
+
+/// 会員が書籍を借りられるか判定する（貸出は作成しない）
+///
+/// `loan_book()`と同じ判定を行い、満たされていない条件をすべて返す。
+/// 空であれば貸出可能。
+///
+/// # 引数
+/// * `deps` - サービスの依存関係
+/// * `member_id` - 会員ID
+/// * `book_id` - 書籍ID
+pub async fn check_loan_eligibility(
+    deps: &ServiceDependencies,
+    member_id: MemberId,
+    book_id: BookId,
+) -> Result<Vec<EligibilityViolation>> {
+    let snapshot = eligibility_snapshot(deps, member_id, book_id).await?;
+    Ok(check_eligibility(&snapshot, &deps.policy))
+}
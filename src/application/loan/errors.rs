@@ -1,3 +1,8 @@
+use crate::domain::EligibilityViolation;
+use crate::ports::{
+    BookServiceError, Classified, ErrorClass, EventStoreError, IdempotencyStoreError,
+    LoanReadModelError, MemberServiceError, StaffServiceError, UnitOfWorkError,
+};
 use thiserror::Error;
 
 /// 貸出管理アプリケーション層のエラー
@@ -7,6 +12,10 @@ pub enum LoanApplicationError {
     #[error("Member not found")]
     MemberNotFound,
 
+    /// 会員が利用停止中
+    #[error("Member is suspended")]
+    MemberSuspended,
+
     /// 書籍が貸出不可
     #[error("Book is not available for loan")]
     BookNotAvailable,
@@ -15,8 +24,8 @@ pub enum LoanApplicationError {
     #[error("Member has overdue loan")]
     MemberHasOverdueLoan,
 
-    /// 貸出上限（5冊）を超えている
-    #[error("Loan limit exceeded (max 5 books)")]
+    /// 貸出上限（テナントの貸出ポリシー、既定5冊）を超えている
+    #[error("Loan limit exceeded")]
     LoanLimitExceeded,
 
     /// 貸出が見つからない
@@ -31,21 +40,85 @@ pub enum LoanApplicationError {
     #[error("Domain error: {0}")]
     DomainError(String),
 
+    /// コマンドの実行が許可されていない
+    #[error("Forbidden: {0}")]
+    Forbidden(String),
+
+    /// コマンドの内容が不正（実行前の検証で拒否された）
+    #[error("Invalid command: {0}")]
+    InvalidCommand(String),
+
+    /// 冪等キーが別の内容のコマンドで既に使われている
+    #[error("Idempotency key was already used for a different command")]
+    IdempotencyKeyReused,
+
+    /// 同じ冪等キーのコマンドが他のインスタンスで実行中
+    #[error("A command with the same idempotency key is still in progress")]
+    IdempotencyKeyInProgress,
+
     /// EventStoreのエラー
     #[error("Event store error")]
-    EventStoreError(#[source] Box<dyn std::error::Error + Send + Sync>),
+    EventStoreError(#[source] EventStoreError),
 
     /// ReadModelのエラー
     #[error("Read model error")]
-    ReadModelError(#[source] Box<dyn std::error::Error + Send + Sync>),
+    ReadModelError(#[source] LoanReadModelError),
 
     /// MemberServiceのエラー
     #[error("Member service error")]
-    MemberServiceError(#[source] Box<dyn std::error::Error + Send + Sync>),
+    MemberServiceError(#[source] MemberServiceError),
 
     /// BookServiceのエラー
     #[error("Book service error")]
-    BookServiceError(#[source] Box<dyn std::error::Error + Send + Sync>),
+    BookServiceError(#[source] BookServiceError),
+
+    /// StaffServiceのエラー
+    #[error("Staff service error")]
+    StaffServiceError(#[source] StaffServiceError),
+
+    /// 冪等キーストアのエラー
+    #[error("Idempotency key store error")]
+    IdempotencyStoreError(#[source] IdempotencyStoreError),
+}
+
+impl From<UnitOfWorkError> for LoanApplicationError {
+    fn from(error: UnitOfWorkError) -> Self {
+        match error {
+            UnitOfWorkError::EventStore(e) => LoanApplicationError::EventStoreError(e),
+            UnitOfWorkError::ReadModel(e) => LoanApplicationError::ReadModelError(e),
+        }
+    }
+}
+
+impl From<EligibilityViolation> for LoanApplicationError {
+    fn from(violation: EligibilityViolation) -> Self {
+        match violation {
+            EligibilityViolation::MemberNotFound => LoanApplicationError::MemberNotFound,
+            EligibilityViolation::MemberSuspended => LoanApplicationError::MemberSuspended,
+            EligibilityViolation::BookNotAvailable => LoanApplicationError::BookNotAvailable,
+            EligibilityViolation::MemberHasOverdueLoan => {
+                LoanApplicationError::MemberHasOverdueLoan
+            }
+            EligibilityViolation::LoanLimitExceeded { .. } => {
+                LoanApplicationError::LoanLimitExceeded
+            }
+        }
+    }
+}
+
+impl Classified for LoanApplicationError {
+    /// 業務ルールの違反は再試行しても結果が変わらないため恒久的なものとする
+    fn class(&self) -> ErrorClass {
+        match self {
+            LoanApplicationError::EventStoreError(e) => e.class(),
+            LoanApplicationError::ReadModelError(e) => e.class(),
+            LoanApplicationError::MemberServiceError(e) => e.class(),
+            LoanApplicationError::BookServiceError(e) => e.class(),
+            LoanApplicationError::StaffServiceError(e) => e.class(),
+            LoanApplicationError::IdempotencyStoreError(e) => e.class(),
+            _ => ErrorClass::Permanent,
+        }
+    }
 }
 
 /// アプリケーション層の Result型
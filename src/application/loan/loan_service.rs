@@ -1,12 +1,16 @@
-use crate::domain::{self, DomainEvent, commands::*, value_objects::*};
+use crate::application::repository::{EventSourcedRepository, Versioned};
+use crate::domain::{
+    self, Aggregate, CirculationPolicy, DomainEvent, OverrideToken,
+    commands::*,
+    loan::{Loan, LoanEvent},
+    value_objects::*,
+};
 use crate::ports::*;
 use std::sync::Arc;
 
+use super::eligibility::check_loan_eligibility;
 use super::errors::{LoanApplicationError, Result};
 
-/// 会員1人あたりの最大貸出冊数
-const MAX_ACTIVE_LOANS: usize = 5;
-
 /// サービスの依存関係
 ///
 /// 関数型DDDの原則に従い、データ構造として定義。
@@ -17,39 +21,135 @@ const MAX_ACTIVE_LOANS: usize = 5;
 /// - データと振る舞いの分離
 /// - 関数合成が容易
 /// - テストが明確
+///
+/// # マルチテナント
+///
+/// 依存関係はテナント（図書館）ごとに構築される。
+/// 各アダプターは`tenant_id`のデータのみを読み書きするようにスコープされ、
+/// `policy`はそのテナントの貸出ポリシーを表す。
+///
+/// # ユニットオブワーク
+///
+/// `unit_of_work`が指定されている場合、イベントの追加とRead Modelの更新は
+/// 1つのトランザクションで行われる（どちらもコミットされるか、どちらもされない）。
+/// `None`の場合は両者を個別に更新する（結果整合性）。
+///
+/// # イベントバス
+///
+/// 貸出のイベントはコミットの後に`event_bus`へ発行され、
+/// 他のコンテキストはイベントを購読して協調する。
 #[derive(Clone)]
 #[allow(dead_code)]
 pub struct ServiceDependencies {
+    pub tenant_id: TenantId,
+    pub policy: CirculationPolicy,
     pub event_store: Arc<dyn EventStore>,
     pub loan_read_model: Arc<dyn LoanReadModel>,
+    pub unit_of_work: Option<Arc<dyn UnitOfWork>>,
     pub member_service: Arc<dyn MemberService>,
     pub book_service: Arc<dyn BookService>,
+    pub staff_service: Arc<dyn StaffService>,
+    pub notification_service: Arc<dyn NotificationService>,
+    pub notification_log: Arc<dyn NotificationLog>,
+    pub notification_preferences: Arc<dyn NotificationPreferenceStore>,
+    pub deferred_notices: Arc<dyn DeferredNoticeQueue>,
+    pub event_bus: Arc<dyn EventBus>,
+    pub idempotency_keys: Arc<dyn IdempotencyStore>,
+}
+
+/// 貸出集約のリポジトリ
+pub(crate) fn loan_repository(deps: &ServiceDependencies) -> EventSourcedRepository<Loan> {
+    EventSourcedRepository::new(deps.event_store.clone())
 }
 
 /// イベントストアから貸出集約を復元するヘルパー関数
 ///
-/// extend_loan, return_book, overdue_detectionで共通利用される。
+/// extend_loan, return_bookで共通利用される。
 ///
 /// # 引数
-/// * `event_store` - イベントストア
+/// * `deps` - サービスの依存関係
 /// * `loan_id` - 貸出ID
 ///
 /// # 戻り値
-/// 復元された貸出集約
+/// 復元された貸出集約と、保存時の期待バージョン
 ///
 /// # エラー
 /// - EventStoreError: イベント読み込み失敗
-/// - LoanNotFound: イベントが存在しない、または復元に失敗
-async fn load_loan(
-    event_store: &Arc<dyn EventStore>,
+/// - LoanNotFound: イベントが存在しない
+async fn load_loan(deps: &ServiceDependencies, loan_id: LoanId) -> Result<Versioned<Loan>> {
+    loan_repository(deps)
+        .load(&loan_id)
+        .await
+        .map_err(LoanApplicationError::EventStoreError)?
+        .ok_or(LoanApplicationError::LoanNotFound)
+}
+
+/// 貸出のイベントを保存し、Read Modelを更新するヘルパー関数
+///
+/// イベントは貸出が`expected_version`のときだけ保存される（楽観的排他制御）。
+/// 読み込み後に同じ貸出への書き込みがあった場合はEventStoreError（VersionConflict）となる。
+///
+/// ユニットオブワークがあれば両方を1つのトランザクションで保存する。
+/// 失敗した場合はイベントも保存されていないため、EventStoreErrorとして扱う。
+/// ユニットオブワークがなければイベントを保存してからRead Modelを更新する
+/// （Read Modelの更新に失敗した場合、イベントは保存済みのまま残る）。
+///
+/// 保存されたイベントはイベントバスに発行する（Read Modelの更新に失敗した場合も発行する）。
+///
+/// # エラー
+/// - EventStoreError: イベント（またはトランザクション全体）の保存失敗
+/// - ReadModelError: 結果整合性の構成でのRead Model更新失敗
+pub(crate) async fn commit_loan(
+    deps: &ServiceDependencies,
     loan_id: LoanId,
-) -> Result<domain::loan::Loan> {
-    let events = event_store
-        .load(loan_id.value())
+    expected_version: u32,
+    event: LoanEvent,
+    loan: &domain::loan::Loan,
+) -> Result<()> {
+    let loan_view = build_loan_view(loan);
+    let domain_event: DomainEvent = event.clone().into();
+    // 発行するイベントには、保存時に採番されたイベントIDとシーケンス番号を載せる
+    let published = |appended: Vec<AppendedEvent>| {
+        appended
+            .into_iter()
+            .map(|appended| {
+                PublishedEvent::new(
+                    deps.tenant_id,
+                    loan_id.value(),
+                    Loan::aggregate_type(),
+                    expected_version + 1,
+                    appended,
+                    domain_event.clone(),
+                )
+            })
+            .collect::<Vec<_>>()
+    };
+
+    if let Some(unit_of_work) = &deps.unit_of_work {
+        let appended = unit_of_work
+            .commit(
+                loan_id.value(),
+                Loan::aggregate_type(),
+                expected_version,
+                vec![domain_event.clone()],
+                loan_view,
+            )
+            .await
+            .map_err(LoanApplicationError::from)?;
+        deps.event_bus.publish(published(appended)).await;
+        return Ok(());
+    }
+
+    let appended = loan_repository(deps)
+        .save_with_expected_version(&loan_id, expected_version, vec![event])
         .await
         .map_err(LoanApplicationError::EventStoreError)?;
+    deps.event_bus.publish(published(appended)).await;
 
-    domain::loan::replay_events(&events).ok_or(LoanApplicationError::LoanNotFound)
+    deps.loan_read_model
+        .save(loan_view)
+        .await
+        .map_err(LoanApplicationError::ReadModelError)
 }
 
 /// 貸出集約からRead Model用のビューを構築するヘルパー関数
@@ -62,7 +162,7 @@ async fn load_loan(
 ///
 /// # 戻り値
 /// Read Model用の完全な貸出ビュー
-pub(super) fn build_loan_view(loan: &domain::loan::Loan) -> LoanView {
+pub(crate) fn build_loan_view(loan: &domain::loan::Loan) -> LoanView {
     match loan {
         domain::loan::Loan::Active(active) => LoanView {
             loan_id: active.loan_id,
@@ -100,6 +200,18 @@ pub(super) fn build_loan_view(loan: &domain::loan::Loan) -> LoanView {
             created_at: returned.created_at,
             updated_at: returned.updated_at,
         },
+        domain::loan::Loan::Lost(lost) => LoanView {
+            loan_id: lost.loan_id,
+            book_id: lost.book_id,
+            member_id: lost.member_id,
+            loaned_at: lost.loaned_at,
+            due_date: lost.due_date,
+            returned_at: None,
+            extension_count: lost.extension_count.value(),
+            status: LoanStatus::Lost,
+            created_at: lost.created_at,
+            updated_at: lost.updated_at,
+        },
     }
 }
 
@@ -107,15 +219,22 @@ pub(super) fn build_loan_view(loan: &domain::loan::Loan) -> LoanView {
 ///
 /// ビジネスルール：
 /// - 会員が存在すること
+/// - 会員が利用停止中でないこと
 /// - 書籍が貸出可能であること
 /// - 会員に延滞中の貸出がないこと
-/// - 会員の貸出中の冊数が5冊未満であること
+/// - 会員の貸出中の冊数が上限（テナントの貸出ポリシー、既定5冊）未満であること
+///
+/// 判定は`check_loan_eligibility()`と同じ。満たされていない条件が複数ある場合は
+/// 上の順で最初の条件のエラーを返す（すべての条件は貸出可否の確認で得られる）。
 ///
 /// すべての依存が引数として明示的に渡される（関数型の原則）。
 ///
 /// # 一貫性保証
 ///
-/// この関数は**結果整合性（Eventual Consistency）**を提供します。
+/// `deps.unit_of_work`が指定されていれば、イベントの保存とRead Modelの更新は
+/// 1つのトランザクションで行われ、不整合は発生しません。
+///
+/// 指定されていない場合は**結果整合性（Eventual Consistency）**を提供します。
 ///
 /// - EventStore（書き込み）とReadModel（読み取り）は独立して更新されます
 /// - ReadModel更新がEventStore保存後に失敗した場合、一時的に不整合が発生します
@@ -134,73 +253,78 @@ pub(super) fn build_loan_view(loan: &domain::loan::Loan) -> LoanView {
 /// 成功時は作成された貸出のID
 #[allow(dead_code)]
 pub async fn loan_book(deps: &ServiceDependencies, cmd: LoanBook) -> Result<LoanId> {
-    // 1. 会員の存在確認
-    let member_exists = deps
-        .member_service
-        .exists(cmd.member_id)
-        .await
-        .map_err(LoanApplicationError::MemberServiceError)?;
-
-    if !member_exists {
-        return Err(LoanApplicationError::MemberNotFound);
-    }
-
-    // 2. 書籍の貸出可能性確認
-    let book_available = deps
-        .book_service
-        .is_available_for_loan(cmd.book_id)
-        .await
-        .map_err(LoanApplicationError::BookServiceError)?;
-
-    if !book_available {
-        return Err(LoanApplicationError::BookNotAvailable);
-    }
+    lend(deps, cmd, None).await
+}
 
-    // 3. 会員の延滞確認
-    let has_overdue = deps
-        .member_service
-        .has_overdue_loans(cmd.member_id)
-        .await
-        .map_err(LoanApplicationError::MemberServiceError)?;
+/// 貸出条件の例外を認めて書籍を貸し出す
+///
+/// オーバーライドトークンで承認された条件（貸出上限・延滞）は判定しない。
+/// 会員の存在・利用停止と書籍の貸出可能性は常に確認する。
+/// 承認内容（条件・理由・監督者）は`BookLoaned`に記録される。
+///
+/// 監督者が承認の権限を持つかはコマンドバスの認可で確認する。
+///
+/// # 引数
+/// * `deps` - サービスの依存関係
+/// * `cmd` - 例外の承認付きの貸出コマンド
+///
+/// # 戻り値
+/// 成功時は作成された貸出のID
+#[allow(dead_code)]
+pub async fn loan_book_with_override(
+    deps: &ServiceDependencies,
+    cmd: LoanBookWithOverride,
+) -> Result<LoanId> {
+    lend(deps, cmd.loan, Some(cmd.override_token)).await
+}
 
-    if has_overdue {
-        return Err(LoanApplicationError::MemberHasOverdueLoan);
+/// 貸出条件を確認して貸出を作成する（例外を認められた条件は確認しない）
+async fn lend(
+    deps: &ServiceDependencies,
+    cmd: LoanBook,
+    override_token: Option<OverrideToken>,
+) -> Result<LoanId> {
+    // 1. 貸出条件の判定（例外を認められた条件は除く）
+    let violations = check_loan_eligibility(deps, cmd.member_id, cmd.book_id).await?;
+    if let Some(violation) = violations.into_iter().find(|v| {
+        !override_token
+            .as_ref()
+            .is_some_and(|t| v.is_overridden_by(t))
+    }) {
+        return Err(violation.into());
     }
 
-    // 4. 貸出上限確認（5冊まで）
-    let active_loans = deps
-        .loan_read_model
-        .get_active_loans_for_member(cmd.member_id)
-        .await
-        .map_err(LoanApplicationError::ReadModelError)?;
-
-    if active_loans.len() >= MAX_ACTIVE_LOANS {
-        return Err(LoanApplicationError::LoanLimitExceeded);
+    // 2. ドメイン層の純粋関数を呼び出し
+    let (active_loan, event) = match override_token {
+        Some(token) => domain::loan::loan_book_with_override(
+            cmd.book_id,
+            cmd.member_id,
+            cmd.loaned_at,
+            cmd.staff_id,
+            &deps.policy,
+            token,
+        ),
+        None => domain::loan::loan_book_with_policy(
+            cmd.book_id,
+            cmd.member_id,
+            cmd.loaned_at,
+            cmd.staff_id,
+            &deps.policy,
+        ),
     }
-
-    // 5. ドメイン層の純粋関数を呼び出し
-    let (active_loan, event) =
-        domain::loan::loan_book(cmd.book_id, cmd.member_id, cmd.loaned_at, cmd.staff_id)
-            .map_err(|e| LoanApplicationError::DomainError(format!("{:?}", e)))?;
+    .map_err(|e| LoanApplicationError::DomainError(format!("{:?}", e)))?;
 
     let loan_id = active_loan.loan_id;
 
-    // 6. イベントストアに保存
-    deps.event_store
-        .append(
-            loan_id.value(),
-            "Loan",
-            vec![DomainEvent::BookLoaned(event.clone())],
-        )
-        .await
-        .map_err(LoanApplicationError::EventStoreError)?;
-
-    // 7. Read Modelを更新（完全な状態を保存）
-    let loan_view = build_loan_view(&domain::loan::Loan::Active(active_loan));
-    deps.loan_read_model
-        .save(loan_view)
-        .await
-        .map_err(LoanApplicationError::ReadModelError)?;
+    // 3. イベントを保存し、Read Modelを更新（完全な状態を保存）
+    commit_loan(
+        deps,
+        loan_id,
+        0,
+        LoanEvent::from(event),
+        &domain::loan::Loan::Active(active_loan),
+    )
+    .await?;
 
     Ok(loan_id)
 }
@@ -209,14 +333,14 @@ pub async fn loan_book(deps: &ServiceDependencies, cmd: LoanBook) -> Result<Loan
 ///
 /// ビジネスルール：
 /// - 貸出が存在すること
-/// - 貸出がActive状態であること（Overdue, Returnedは延長不可）
+/// - 貸出がActive状態であること（Overdue, Returned, Lostは延長不可）
 /// - 延長回数が上限（1回）に達していないこと
 ///
 /// すべての依存が引数として明示的に渡される（関数型の原則）。
 ///
 /// # 一貫性保証
 ///
-/// 結果整合性を提供。詳細は`loan_book()`を参照。
+/// ユニットオブワークの有無に従う。詳細は`loan_book()`を参照。
 ///
 /// # 引数
 /// * `deps` - サービスの依存関係
@@ -224,10 +348,10 @@ pub async fn loan_book(deps: &ServiceDependencies, cmd: LoanBook) -> Result<Loan
 #[allow(dead_code)]
 pub async fn extend_loan(deps: &ServiceDependencies, cmd: ExtendLoan) -> Result<()> {
     // 1. イベントストアから貸出集約を復元
-    let loan = load_loan(&deps.event_store, cmd.loan_id).await?;
+    let Versioned { aggregate, version } = load_loan(deps, cmd.loan_id).await?;
 
     // 2. ActiveLoanであることを確認
-    let active_loan = match loan {
+    let active_loan = match aggregate {
         domain::loan::Loan::Active(active) => active,
         domain::loan::Loan::Overdue(_) => {
             return Err(LoanApplicationError::InvalidLoanState(
@@ -239,28 +363,27 @@ pub async fn extend_loan(deps: &ServiceDependencies, cmd: ExtendLoan) -> Result<
                 "Cannot extend returned loan".to_string(),
             ));
         }
+        domain::loan::Loan::Lost(_) => {
+            return Err(LoanApplicationError::InvalidLoanState(
+                "Cannot extend lost loan".to_string(),
+            ));
+        }
     };
 
     // 3. ドメイン層の純粋関数を呼び出し
-    let (updated_loan, event) = domain::loan::extend_loan(active_loan, cmd.extended_at)
-        .map_err(|e| LoanApplicationError::DomainError(format!("{:?}", e)))?;
+    let (updated_loan, event) =
+        domain::loan::extend_loan_with_policy(active_loan, cmd.extended_at, &deps.policy)
+            .map_err(|e| LoanApplicationError::DomainError(format!("{:?}", e)))?;
 
-    // 4. イベントストアに保存
-    deps.event_store
-        .append(
-            cmd.loan_id.value(),
-            "Loan",
-            vec![DomainEvent::LoanExtended(event.clone())],
-        )
-        .await
-        .map_err(LoanApplicationError::EventStoreError)?;
-
-    // 5. Read Modelを更新（完全な状態を保存）
-    let loan_view = build_loan_view(&domain::loan::Loan::Active(updated_loan));
-    deps.loan_read_model
-        .save(loan_view)
-        .await
-        .map_err(LoanApplicationError::ReadModelError)?;
+    // 4. イベントを保存し、Read Modelを更新（完全な状態を保存）
+    commit_loan(
+        deps,
+        cmd.loan_id,
+        version,
+        LoanEvent::from(event),
+        &domain::loan::Loan::Active(updated_loan),
+    )
+    .await?;
 
     Ok(())
 }
@@ -269,14 +392,14 @@ pub async fn extend_loan(deps: &ServiceDependencies, cmd: ExtendLoan) -> Result<
 ///
 /// ビジネスルール：
 /// - 貸出が存在すること
-/// - 貸出がActive, Overdue状態であること（Returnedは返却不可）
+/// - 貸出がActive, Overdue, Lost状態であること（Returnedは返却不可）
 /// - 延滞していても返却は受け付ける（公立図書館のため延滞料金なし）
 ///
 /// すべての依存が引数として明示的に渡される（関数型の原則）。
 ///
 /// # 一貫性保証
 ///
-/// 結果整合性を提供。詳細は`loan_book()`を参照。
+/// ユニットオブワークの有無に従う。詳細は`loan_book()`を参照。
 ///
 /// # 引数
 /// * `deps` - サービスの依存関係
@@ -284,28 +407,21 @@ pub async fn extend_loan(deps: &ServiceDependencies, cmd: ExtendLoan) -> Result<
 #[allow(dead_code)]
 pub async fn return_book(deps: &ServiceDependencies, cmd: ReturnBook) -> Result<()> {
     // 1. イベントストアから貸出集約を復元
-    let loan = load_loan(&deps.event_store, cmd.loan_id).await?;
+    let Versioned { aggregate, version } = load_loan(deps, cmd.loan_id).await?;
 
     // 2. ドメイン層の純粋関数を呼び出し
-    let (returned_loan, event) = domain::loan::return_book(loan, cmd.returned_at)
+    let (returned_loan, event) = domain::loan::return_book(aggregate, cmd.returned_at)
         .map_err(|e| LoanApplicationError::DomainError(format!("{:?}", e)))?;
 
-    // 3. イベントストアに保存
-    deps.event_store
-        .append(
-            cmd.loan_id.value(),
-            "Loan",
-            vec![DomainEvent::BookReturned(event.clone())],
-        )
-        .await
-        .map_err(LoanApplicationError::EventStoreError)?;
-
-    // 4. Read Modelを更新（完全な状態を保存）
-    let loan_view = build_loan_view(&domain::loan::Loan::Returned(returned_loan));
-    deps.loan_read_model
-        .save(loan_view)
-        .await
-        .map_err(LoanApplicationError::ReadModelError)?;
+    // 3. イベントを保存し、Read Modelを更新（完全な状態を保存）
+    commit_loan(
+        deps,
+        cmd.loan_id,
+        version,
+        LoanEvent::from(event),
+        &domain::loan::Loan::Returned(returned_loan),
+    )
+    .await?;
 
     Ok(())
 }
@@ -1,10 +1,37 @@
+mod batch_runner;
+mod due_soon_reminders;
+mod eligibility;
 mod errors;
 mod loan_service;
 mod overdue_detection;
+mod overdue_notices;
+mod override_report;
+mod reading_history;
 
+#[allow(unused_imports)]
+pub use batch_runner::FailedLoanCandidate;
+#[allow(unused_imports)]
+pub use due_soon_reminders::{DueSoonReminderReport, RemindedLoan, remind_due_soon_loans};
+#[allow(unused_imports)]
+pub use eligibility::check_loan_eligibility;
 #[allow(unused_imports)]
 pub use errors::{LoanApplicationError, Result};
+pub(crate) use loan_service::build_loan_view;
+#[allow(unused_imports)]
+pub use loan_service::{
+    ServiceDependencies, extend_loan, loan_book, loan_book_with_override, return_book,
+};
+#[allow(unused_imports)]
+pub use overdue_detection::{
+    DetectedOverdueLoan, OverdueDetectionReport, OverdueSkipReason, SkippedOverdueCandidate,
+    detect_overdue_loans,
+};
+#[allow(unused_imports)]
+pub use overdue_notices::{OverdueNoticeReport, SentOverdueNotice, advance_overdue_notices};
 #[allow(unused_imports)]
-pub use loan_service::{ServiceDependencies, extend_loan, loan_book, return_book};
+pub use override_report::{LoanOverrideRecord, list_loan_overrides};
+pub(crate) use reading_history::append_member_event;
 #[allow(unused_imports)]
-pub use overdue_detection::detect_overdue_loans;
+pub use reading_history::{
+    MEMBER_AGGREGATE_TYPE, anonymise_loan_history, set_reading_history_preference,
+};
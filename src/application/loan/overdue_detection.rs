@@ -1,14 +1,80 @@
-use crate::domain::{self, events::*};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
 
+use crate::application::repository::Versioned;
+use crate::domain::{
+    self, Aggregate,
+    events::*,
+    loan::{Loan, LoanEvent},
+    value_objects::*,
+};
+
+use super::batch_runner::{FailedLoanCandidate, Outcome, commit_candidate, run_loan_batches};
 use super::errors::{LoanApplicationError, Result};
-use super::loan_service::{ServiceDependencies, build_loan_view};
+use super::loan_service::ServiceDependencies;
+
+/// 延滞として検出した貸出
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DetectedOverdueLoan {
+    pub loan_id: LoanId,
+    pub book_id: BookId,
+    pub member_id: MemberId,
+    pub due_date: DateTime<Utc>,
+}
+
+/// 延滞候補を延滞にしなかった理由
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OverdueSkipReason {
+    /// 貸出のイベントがない（Read Modelだけに残っている）
+    NoEvents,
+    /// 基準日時の時点で返却期限を過ぎていない（Read Modelの反映前に延長された場合など）
+    NotDue,
+    /// 既に延滞になっている
+    AlreadyOverdue,
+    /// 返却済み
+    Returned,
+    /// 紛失として扱われている
+    Lost,
+    /// 読み込み後に返却・延長された（次回の検出で改めて判定する）
+    ChangedConcurrently,
+}
+
+/// 延滞にしなかった候補
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SkippedOverdueCandidate {
+    pub loan_id: LoanId,
+    pub reason: OverdueSkipReason,
+}
+
+/// 延滞検出の結果
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OverdueDetectionReport {
+    /// 判定の基準日時
+    pub as_of: DateTime<Utc>,
+    pub detected: Vec<DetectedOverdueLoan>,
+    pub skipped: Vec<SkippedOverdueCandidate>,
+    pub failed: Vec<FailedLoanCandidate>,
+}
+
+impl OverdueDetectionReport {
+    /// Read Modelから取得した候補の件数
+    pub fn candidates(&self) -> usize {
+        self.detected.len() + self.skipped.len() + self.failed.len()
+    }
+}
+
+/// 見送った候補の記録
+fn skipped(loan_id: LoanId, reason: OverdueSkipReason) -> SkippedOverdueCandidate {
+    SkippedOverdueCandidate { loan_id, reason }
+}
 
 /// 延滞検出バッチ（純粋な関数）
 ///
 /// 定期的に実行され、延滞した貸出を検出してLoanBecameOverdueイベントを発行する。
 ///
 /// ビジネスルール：
-/// - 返却期限（due_date）を過ぎたActive状態の貸出を延滞とする
+/// - 基準日時（`as_of`）の時点で返却期限（due_date）を過ぎたActive状態の貸出を延滞とする
 /// - 既にOverdue状態の貸出は処理しない（重複イベント防止）
 /// - Returned状態の貸出は処理しない
 ///
@@ -16,92 +82,107 @@ use super::loan_service::{ServiceDependencies, build_loan_view};
 ///
 /// 処理フロー：
 /// 1. Read Modelから延滞候補を取得
-/// 2. 各候補について：
-///    - イベントストアから完全な履歴を取得
+/// 2. 候補をまとめて読み込み、並行して処理する（`run_loan_batches()`）：
 ///    - イベントから現在の状態を復元
 ///    - Active状態かつ延滞している場合のみ処理
-///    - LoanBecameOverdueイベントを生成・保存
-///    - Read Modelを更新
-/// 3. 処理件数を返す
+///    - LoanBecameOverdueイベントを生成・保存し、Read Modelを更新
+///      （ユニットオブワークがあれば同一トランザクション）
+/// 3. 検出・見送り・失敗した候補を報告する
+///
+/// 1件の失敗（またはバッチの読み込みの失敗）で全体を止めず、失敗した候補として報告する。
+/// 失敗した候補は延滞になっていないため、次回の検出で改めて処理される。
 ///
 /// # 引数
 /// * `deps` - サービスの依存関係
+/// * `as_of` - 判定の基準日時（`LoanBecameOverdue`の検出日時になる）
 ///
 /// # 戻り値
-/// 延滞として検出した貸出の件数
+/// 候補ごとの処理結果（候補の順）
 ///
 /// # エラー
-/// ポート層のI/Oエラー（EventStore, LoanReadModel）
+/// 延滞候補を取得できない場合のみ（ReadModelError）
 #[allow(dead_code)]
-pub async fn detect_overdue_loans(deps: &ServiceDependencies) -> Result<usize> {
-    let now = chrono::Utc::now();
-    let mut detected_count = 0;
-
+pub async fn detect_overdue_loans(
+    deps: &ServiceDependencies,
+    as_of: DateTime<Utc>,
+) -> Result<OverdueDetectionReport> {
     // 1. Read Modelから延滞候補を取得
     let candidates = deps
         .loan_read_model
-        .find_overdue_candidates(now)
+        .find_overdue_candidates(as_of)
         .await
         .map_err(LoanApplicationError::ReadModelError)?;
 
-    // 2. 各候補について延滞判定
-    for loan_view in candidates {
-        // 2.1. イベントストアから完全な履歴を取得
-        let events = deps
-            .event_store
-            .load(loan_view.loan_id.value())
-            .await
-            .map_err(LoanApplicationError::EventStoreError)?;
+    // 2. 候補をまとめて読み込み、各候補について延滞判定
+    let loan_ids = candidates.iter().map(|l| l.loan_id).collect();
+    let results = run_loan_batches(deps, loan_ids, |loan_id, loan| {
+        detect_overdue_loan(deps, loan_id, loan, as_of)
+    })
+    .await;
 
-        // 2.2. イベントから現在の状態を復元
-        let loan = match domain::loan::replay_events(&events) {
-            Some(loan) => loan,
-            None => continue, // イベントがない場合はスキップ
-        };
-
-        // 2.3. ActiveLoanかつ延滞している場合のみ処理
-        match loan {
-            domain::loan::Loan::Active(active) => {
-                // 延滞判定
-                if domain::loan::is_overdue(&domain::loan::Loan::Active(active.clone()), now) {
-                    // LoanBecameOverdueイベントを生成
-                    let event = LoanBecameOverdue {
-                        loan_id: active.loan_id,
-                        book_id: active.book_id,
-                        member_id: active.member_id,
-                        due_date: active.due_date,
-                        detected_at: now,
-                    };
-
-                    // イベントストアに保存
-                    deps.event_store
-                        .append(
-                            active.loan_id.value(),
-                            "Loan",
-                            vec![DomainEvent::LoanBecameOverdue(event.clone())],
-                        )
-                        .await
-                        .map_err(LoanApplicationError::EventStoreError)?;
+    Ok(OverdueDetectionReport {
+        as_of,
+        detected: results.done,
+        skipped: results.skipped,
+        failed: results.failed,
+    })
+}
 
-                    // Read Modelを更新（完全な状態を保存）
-                    // イベントを適用して更新後の状態を取得
-                    let updated_loan = domain::loan::apply_event(
-                        Some(domain::loan::Loan::Active(active)),
-                        &DomainEvent::LoanBecameOverdue(event),
-                    );
-                    let loan_view = build_loan_view(&updated_loan);
-                    deps.loan_read_model
-                        .save(loan_view)
-                        .await
-                        .map_err(LoanApplicationError::ReadModelError)?;
+/// 1件の候補を判定し、延滞していれば延滞にする
+async fn detect_overdue_loan(
+    deps: &ServiceDependencies,
+    loan_id: LoanId,
+    loan: Option<Versioned<Loan>>,
+    as_of: DateTime<Utc>,
+) -> Outcome<DetectedOverdueLoan, SkippedOverdueCandidate> {
+    let Some(Versioned {
+        aggregate: loan,
+        version,
+    }) = loan
+    else {
+        return Outcome::Skipped(skipped(loan_id, OverdueSkipReason::NoEvents));
+    };
 
-                    detected_count += 1;
-                }
-            }
-            // Overdue, Returnedの場合はスキップ
-            _ => continue,
+    // ActiveLoanかつ延滞している場合のみ処理
+    let active = match loan {
+        Loan::Active(active) => active,
+        Loan::Overdue(_) => {
+            return Outcome::Skipped(skipped(loan_id, OverdueSkipReason::AlreadyOverdue));
         }
+        Loan::Lost(_) => return Outcome::Skipped(skipped(loan_id, OverdueSkipReason::Lost)),
+        Loan::Returned(_) => {
+            return Outcome::Skipped(skipped(loan_id, OverdueSkipReason::Returned));
+        }
+    };
+    if !domain::loan::is_overdue(&Loan::Active(active.clone()), as_of) {
+        return Outcome::Skipped(skipped(loan_id, OverdueSkipReason::NotDue));
     }
 
-    Ok(detected_count)
+    let detected = DetectedOverdueLoan {
+        loan_id: active.loan_id,
+        book_id: active.book_id,
+        member_id: active.member_id,
+        due_date: active.due_date,
+    };
+    let event = LoanEvent::from(LoanBecameOverdue {
+        loan_id: active.loan_id,
+        book_id: active.book_id,
+        member_id: active.member_id,
+        due_date: active.due_date,
+        detected_at: as_of,
+    });
+
+    // イベントを保存し、Read Modelを更新（完全な状態を保存）
+    // 読み込み後に返却・延長された貸出は、次回の検出で改めて判定する
+    let updated_loan = Loan::apply(Some(Loan::Active(active)), &event);
+    commit_candidate(
+        deps,
+        loan_id,
+        version,
+        event,
+        &updated_loan,
+        detected,
+        skipped(loan_id, OverdueSkipReason::ChangedConcurrently),
+    )
+    .await
 }
@@ -0,0 +1,141 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::application::repository::Versioned;
+use crate::domain::{
+    self,
+    loan::{Loan, LoanEvent, OverdueEscalation},
+    value_objects::*,
+};
+
+use super::batch_runner::{FailedLoanCandidate, Outcome, commit_candidate, run_loan_batches};
+use super::errors::{LoanApplicationError, Result};
+use super::loan_service::ServiceDependencies;
+
+/// 督促を記録した貸出
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SentOverdueNotice {
+    pub loan_id: LoanId,
+    pub member_id: MemberId,
+    pub level: OverdueNoticeLevel,
+}
+
+/// 延滞の督促の結果
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OverdueNoticeReport {
+    /// 判定の基準日時
+    pub as_of: DateTime<Utc>,
+    pub notices: Vec<SentOverdueNotice>,
+    /// 紛失として扱った貸出
+    pub declared_lost: Vec<LoanId>,
+    /// 進める段階がなかった候補の件数（督促済み、読み込み後に返却されたなど）
+    pub skipped: usize,
+    pub failed: Vec<FailedLoanCandidate>,
+}
+
+/// 1件の候補で進めた段階
+enum Advanced {
+    Notice(SentOverdueNotice),
+    DeclaredLost(LoanId),
+}
+
+/// 延滞した貸出の督促を進める（督促のプロセスマネージャー）
+///
+/// 延滞した貸出ごとの督促の進み具合は、貸出のイベント列から復元する：
+/// - LoanBecameOverdue で督促の対象になる
+/// - OverdueNoticeSent で督促の段階が進む
+/// - BookReturned または LoanDeclaredLost で督促を終える
+///
+/// 定期的に実行され、基準日時（`as_of`）の時点で次の段階の時期を迎えた貸出について
+/// OverdueNoticeSent（または LoanDeclaredLost）イベントを記録する。段階と時期は
+/// `domain::loan::escalate_overdue()`に従う。会員への通知は、これらのイベントから
+/// 通知の配信（`dispatch_notifications()`）が送る。
+///
+/// 処理フロー：
+/// 1. Read Modelから1回目の督促の時期を過ぎた延滞中の貸出を取得
+/// 2. 候補をまとめて読み込み、並行して処理する（`run_loan_batches()`）：
+///    - イベントから督促の進み具合を復元し、次の段階を判定
+///    - イベントを保存し、Read Modelを更新
+///
+/// 1件の失敗で全体を止めず、失敗した候補として報告する。
+/// 失敗した候補は次回の実行で改めて処理される。
+///
+/// # エラー
+/// 候補を取得できない場合のみ（ReadModelError）
+#[allow(dead_code)]
+pub async fn advance_overdue_notices(
+    deps: &ServiceDependencies,
+    as_of: DateTime<Utc>,
+) -> Result<OverdueNoticeReport> {
+    // 1. Read Modelから1回目の督促の時期を過ぎた延滞中の貸出を取得
+    let due_before = as_of - Duration::days(OverdueNoticeLevel::First.days_overdue());
+    let candidates = deps
+        .loan_read_model
+        .find_overdue_loans(due_before)
+        .await
+        .map_err(LoanApplicationError::ReadModelError)?;
+
+    // 2. 各候補の督促を次の段階に進める
+    let loan_ids = candidates.iter().map(|l| l.loan_id).collect();
+    let results = run_loan_batches(deps, loan_ids, |loan_id, loan| {
+        advance_overdue_notice(deps, loan_id, loan, as_of)
+    })
+    .await;
+
+    let mut report = OverdueNoticeReport {
+        as_of,
+        notices: Vec::new(),
+        declared_lost: Vec::new(),
+        skipped: results.skipped.len(),
+        failed: results.failed,
+    };
+    for advanced in results.done {
+        match advanced {
+            Advanced::Notice(notice) => report.notices.push(notice),
+            Advanced::DeclaredLost(loan_id) => report.declared_lost.push(loan_id),
+        }
+    }
+
+    Ok(report)
+}
+
+/// 1件の候補の督促を次の段階に進める
+async fn advance_overdue_notice(
+    deps: &ServiceDependencies,
+    loan_id: LoanId,
+    loan: Option<Versioned<Loan>>,
+    as_of: DateTime<Utc>,
+) -> Outcome<Advanced, ()> {
+    let Some(Versioned {
+        aggregate: loan,
+        version,
+    }) = loan
+    else {
+        return Outcome::Skipped(());
+    };
+
+    // Read Modelの反映前に返却された貸出は見送る
+    let Loan::Overdue(overdue) = loan else {
+        return Outcome::Skipped(());
+    };
+    let (advanced, event, updated_loan) = match domain::loan::escalate_overdue(overdue, as_of) {
+        Some(OverdueEscalation::Notice(noticed, event)) => (
+            Advanced::Notice(SentOverdueNotice {
+                loan_id,
+                member_id: event.member_id,
+                level: event.level,
+            }),
+            LoanEvent::from(event),
+            Loan::Overdue(noticed),
+        ),
+        Some(OverdueEscalation::DeclaredLost(lost, event)) => (
+            Advanced::DeclaredLost(loan_id),
+            LoanEvent::from(event),
+            Loan::Lost(lost),
+        ),
+        None => return Outcome::Skipped(()),
+    };
+
+    // 読み込み後に返却された貸出は、次回の実行で改めて判定する
+    commit_candidate(deps, loan_id, version, event, &updated_loan, advanced, ()).await
+}
@@ -0,0 +1,54 @@
+use chrono::{DateTime, Utc};
+
+use crate::domain::{OverrideToken, events::DomainEvent, value_objects::*};
+
+use super::errors::{LoanApplicationError, Result};
+use super::loan_service::ServiceDependencies;
+
+/// 貸出条件の例外を認めて作成された貸出の記録
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoanOverrideRecord {
+    pub loan_id: LoanId,
+    pub book_id: BookId,
+    /// 会員ID（鍵の破棄などで匿名化された貸出では`MemberId::ANONYMISED`）
+    pub member_id: MemberId,
+    pub loaned_at: DateTime<Utc>,
+    /// 貸出を行った職員
+    pub loaned_by: StaffId,
+    /// 承認内容（条件・理由・監督者）
+    pub override_token: OverrideToken,
+}
+
+/// 期間内に貸出条件の例外を認めて作成された貸出を一覧する（監査用）
+///
+/// 貸出日時が`from`以上`to`未満の貸出を、記録された順に返す。
+/// 承認内容は`BookLoaned`イベントから読み取るため、返却済みや
+/// アーカイブ済みの貸出も含まれる。
+pub async fn list_loan_overrides(
+    deps: &ServiceDependencies,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+) -> Result<Vec<LoanOverrideRecord>> {
+    let events = deps
+        .event_store
+        .load_by_type("BookLoaned", from, to)
+        .await
+        .map_err(LoanApplicationError::EventStoreError)?;
+
+    Ok(events
+        .into_iter()
+        .filter_map(|stored| match stored.event {
+            DomainEvent::BookLoaned(e) => {
+                e.override_token.map(|override_token| LoanOverrideRecord {
+                    loan_id: e.loan_id,
+                    book_id: e.book_id,
+                    member_id: e.member_id,
+                    loaned_at: e.loaned_at,
+                    loaned_by: e.loaned_by,
+                    override_token,
+                })
+            }
+            _ => None,
+        })
+        .collect())
+}
@@ -0,0 +1,107 @@
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::domain::{commands::SetReadingHistoryPreference, events::*};
+use crate::ports::EventStoreError;
+
+use super::errors::{LoanApplicationError, Result};
+use super::loan_service::ServiceDependencies;
+
+/// 会員に関するイベント（読書履歴の保持設定、データの写しの提供）を記録する集約の種類
+pub const MEMBER_AGGREGATE_TYPE: &str = "Member";
+
+/// 会員に関するイベントを記録する
+///
+/// 集約IDにはイベントごとに新しいIDを使う。会員IDを集約IDにすると、
+/// 暗号鍵の破棄（クリプトシュレッディング）後もイベントログに平文の会員IDが残り、
+/// 貸出IDとしても衝突するため。
+/// 会員IDはイベント本体に暗号化されて記録され、`EventStore::load_member_events`で検索できる。
+pub(crate) async fn append_member_event(
+    deps: &ServiceDependencies,
+    event: DomainEvent,
+) -> std::result::Result<(), EventStoreError> {
+    deps.event_store
+        .append(Uuid::new_v4(), MEMBER_AGGREGATE_TYPE, vec![event])
+        .await
+}
+
+/// 会員の読書履歴の保持設定を変更する
+///
+/// 設定はReadingHistoryPreferenceChangedイベントとして記録され、
+/// Read Modelに投影される。
+///
+/// ビジネスルール：
+/// - 会員が存在すること
+/// - 保持を選択した会員の返却済み貸出は、保持期間を過ぎても匿名化されない
+/// - 保持を取り消すと、保持期間を過ぎた貸出は次回の匿名化で紐付けが消される
+///
+/// # エラー
+/// - MemberNotFound: 会員が存在しない
+/// - ポート層のエラー
+pub async fn set_reading_history_preference(
+    deps: &ServiceDependencies,
+    cmd: SetReadingHistoryPreference,
+) -> Result<()> {
+    // 1. 会員の存在確認
+    let member_exists = deps
+        .member_service
+        .exists(cmd.member_id)
+        .await
+        .map_err(LoanApplicationError::MemberServiceError)?;
+
+    if !member_exists {
+        return Err(LoanApplicationError::MemberNotFound);
+    }
+
+    // 2. イベントを保存
+    let event = ReadingHistoryPreferenceChanged {
+        member_id: cmd.member_id,
+        keep_history: cmd.keep_history,
+        changed_at: cmd.changed_at,
+    };
+    append_member_event(
+        deps,
+        DomainEvent::ReadingHistoryPreferenceChanged(event.clone()),
+    )
+    .await
+    .map_err(LoanApplicationError::EventStoreError)?;
+
+    // 3. Read Modelを更新
+    deps.loan_read_model
+        .save_history_opt_in(
+            event.member_id,
+            event.keep_history.then_some(event.changed_at),
+        )
+        .await
+        .map_err(LoanApplicationError::ReadModelError)
+}
+
+/// 保持期間を過ぎた返却済み貸出から会員との紐付けを消す（バッチ）
+///
+/// テナントの貸出ポリシーの`history_retention_days`に従う。
+/// 保持期間が無期限の場合は何もしない。
+/// イベントログの会員IDは変更しない（完全な削除は削除請求で行う）。
+///
+/// # 戻り値
+/// 匿名化した貸出の件数
+///
+/// # エラー
+/// ポート層のI/Oエラー（LoanReadModel）
+pub async fn anonymise_loan_history(deps: &ServiceDependencies, now: DateTime<Utc>) -> Result<u64> {
+    let Some(cutoff) = deps.policy.history_cutoff(now) else {
+        return Ok(0);
+    };
+
+    let anonymised = deps
+        .loan_read_model
+        .anonymise_returned_loans(cutoff)
+        .await
+        .map_err(LoanApplicationError::ReadModelError)?;
+
+    tracing::info!(
+        "Anonymised {} returned loans (returned before {})",
+        anonymised,
+        cutoff
+    );
+    Ok(anonymised)
+}
@@ -1 +1,11 @@
+pub mod archive;
+pub mod audit;
+pub mod authorization;
+pub mod backup;
+pub mod command_bus;
+pub mod jobs;
+pub mod legacy_import;
 pub mod loan;
+pub mod notification;
+pub mod privacy;
+pub mod repository;
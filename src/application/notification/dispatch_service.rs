@@ -0,0 +1,95 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::application::loan::ServiceDependencies;
+use crate::ports::{Classified, StoredEvent};
+
+use super::errors::{NotificationDispatchError, Result};
+use super::event_handlers::{NoticeOutcome, handle_notice_event};
+
+/// 会員に通知するイベントの種類
+pub const NOTICE_EVENT_TYPES: [&str; 6] = [
+    "LoanBecameOverdue",
+    "LoanDueSoonReminded",
+    "OverdueNoticeSent",
+    "LoanDeclaredLost",
+    "LoanExtended",
+    "BookReturned",
+];
+
+/// 通知を配信できなかったイベント
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FailedNotice {
+    pub event_id: Uuid,
+    /// 失敗の内容
+    pub error: String,
+    /// 再試行で回復しうる失敗か
+    pub retryable: bool,
+}
+
+/// 通知の配信の結果
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NotificationDispatchReport {
+    /// 配信した通知の件数
+    pub delivered: usize,
+    /// 配信済みだった通知の件数
+    pub already_delivered: usize,
+    /// 配信できなかったイベント（次の配信で改めて処理される）
+    pub failed: Vec<FailedNotice>,
+}
+
+/// 失敗の内容（原因を含める）
+fn failure_message(error: &NotificationDispatchError) -> String {
+    match std::error::Error::source(error) {
+        Some(source) => format!("{}: {}", error, source),
+        None => error.to_string(),
+    }
+}
+
+/// 期間内に保存されたイベントに対して会員への通知を配信する
+///
+/// 発生日時が`from`以上`to`未満の通知対象のイベントを、保存された順に
+/// `handle_notice_event()`で処理する。配信済みの通知は送らないため、
+/// 同じ期間を何度処理してもよい（定期的に重なった期間を処理して取りこぼしを防ぐ）。
+///
+/// 1件の失敗で全体を止めず、失敗したイベントとして報告する。
+///
+/// # エラー
+/// 通知対象のイベントを読み込めない場合のみ（EventStoreError）
+pub async fn dispatch_notifications(
+    deps: &ServiceDependencies,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+) -> Result<NotificationDispatchReport> {
+    let mut events: Vec<StoredEvent> = Vec::new();
+    for event_type in NOTICE_EVENT_TYPES {
+        events.extend(
+            deps.event_store
+                .load_by_type(event_type, from, to)
+                .await
+                .map_err(NotificationDispatchError::EventStoreError)?,
+        );
+    }
+    events.sort_by_key(|stored| stored.sequence_number);
+
+    let mut report = NotificationDispatchReport::default();
+    for stored in &events {
+        match handle_notice_event(deps, stored).await {
+            Ok(NoticeOutcome::Delivered(_)) => report.delivered += 1,
+            Ok(NoticeOutcome::AlreadyDelivered(_)) => report.already_delivered += 1,
+            Ok(NoticeOutcome::NotApplicable) => {}
+            Err(e) => {
+                let error = failure_message(&e);
+                tracing::warn!("Failed to notify for event {}: {}", stored.event_id, error);
+                report.failed.push(FailedNotice {
+                    event_id: stored.event_id,
+                    error,
+                    retryable: e.is_retryable(),
+                });
+            }
+        }
+    }
+
+    Ok(report)
+}
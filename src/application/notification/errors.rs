@@ -0,0 +1,101 @@
+use crate::domain::InvalidNotificationPreferences;
+use crate::ports::{
+    BookServiceError, Classified, DeferredNoticeError, ErrorClass, EventStoreError,
+    MemberServiceError, NotificationError, NotificationLogError, NotificationPreferenceError,
+};
+use thiserror::Error;
+
+/// 会員への通知の配信のエラー
+#[derive(Debug, Error)]
+pub enum NotificationDispatchError {
+    /// 通知のきっかけになったイベントから貸出を特定できない
+    #[error("Loan {0} has no BookLoaned event")]
+    LoanNotFound(uuid::Uuid),
+
+    /// EventStoreのエラー
+    #[error("Event store error")]
+    EventStoreError(#[source] EventStoreError),
+
+    /// BookServiceのエラー
+    #[error("Book service error")]
+    BookServiceError(#[source] BookServiceError),
+
+    /// NotificationServiceのエラー
+    #[error("Notification service error")]
+    NotificationError(#[source] NotificationError),
+
+    /// NotificationLogのエラー
+    #[error("Notification log error")]
+    NotificationLogError(#[source] NotificationLogError),
+}
+
+impl Classified for NotificationDispatchError {
+    fn class(&self) -> ErrorClass {
+        match self {
+            NotificationDispatchError::EventStoreError(e) => e.class(),
+            NotificationDispatchError::BookServiceError(e) => e.class(),
+            NotificationDispatchError::NotificationError(e) => e.class(),
+            NotificationDispatchError::NotificationLogError(e) => e.class(),
+            _ => ErrorClass::Permanent,
+        }
+    }
+}
+
+/// 通知の配信の Result型
+pub type Result<T> = std::result::Result<T, NotificationDispatchError>;
+
+/// 保留中の通知の配信のエラー
+#[derive(Debug, Error)]
+pub enum NotificationRoutingError {
+    /// NotificationPreferenceStoreのエラー
+    #[error("Notification preference store error")]
+    NotificationPreferenceError(#[source] NotificationPreferenceError),
+
+    /// DeferredNoticeQueueのエラー
+    #[error("Deferred notice queue error")]
+    DeferredNoticeError(#[source] DeferredNoticeError),
+
+    /// NotificationGatewayのエラー
+    #[error("Notification gateway error")]
+    NotificationError(#[source] NotificationError),
+}
+
+impl Classified for NotificationRoutingError {
+    fn class(&self) -> ErrorClass {
+        match self {
+            NotificationRoutingError::NotificationPreferenceError(e) => e.class(),
+            NotificationRoutingError::DeferredNoticeError(e) => e.class(),
+            NotificationRoutingError::NotificationError(e) => e.class(),
+        }
+    }
+}
+
+/// 会員の通知設定の取得・変更のエラー
+#[derive(Debug, Error)]
+pub enum NotificationPreferencesError {
+    /// 会員が存在しない
+    #[error("Member {0} not found")]
+    MemberNotFound(uuid::Uuid),
+
+    /// 通知設定が不正
+    #[error("Invalid notification preferences: {0:?}")]
+    InvalidPreferences(InvalidNotificationPreferences),
+
+    /// MemberServiceのエラー
+    #[error("Member service error")]
+    MemberServiceError(#[source] MemberServiceError),
+
+    /// NotificationPreferenceStoreのエラー
+    #[error("Notification preference store error")]
+    NotificationPreferenceError(#[source] NotificationPreferenceError),
+}
+
+impl Classified for NotificationPreferencesError {
+    fn class(&self) -> ErrorClass {
+        match self {
+            NotificationPreferencesError::MemberServiceError(e) => e.class(),
+            NotificationPreferencesError::NotificationPreferenceError(e) => e.class(),
+            _ => ErrorClass::Permanent,
+        }
+    }
+}
@@ -0,0 +1,253 @@
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::application::loan::ServiceDependencies;
+use crate::domain::{Aggregate, events::DomainEvent, loan::Loan, value_objects::*};
+use crate::ports::{DeliveredNotice, NoticeKind, StoredEvent};
+
+use super::errors::{NotificationDispatchError, Result};
+
+/// イベントに対する通知の処理結果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NoticeOutcome {
+    /// 通知を配信した
+    Delivered(NoticeKind),
+    /// 同じ通知を配信済み（イベントの再処理）
+    AlreadyDelivered(NoticeKind),
+    /// 通知の対象ではない（他の種類のイベント、匿名化された会員など）
+    NotApplicable,
+}
+
+/// 通知の重複排除キー
+///
+/// 通知の種類と、きっかけになったイベントの集約ID・バージョンから作る。
+/// イベントを何度処理しても（バックアップから復元した後でも）同じキーになる。
+pub fn notice_dedup_key(kind: NoticeKind, aggregate_id: Uuid, aggregate_version: i32) -> String {
+    format!("{}:{}:{}", kind.as_str(), aggregate_id, aggregate_version)
+}
+
+/// 通知の内容
+enum Notice {
+    Overdue {
+        due_date: DateTime<Utc>,
+    },
+    DueSoonReminder {
+        due_date: DateTime<Utc>,
+    },
+    OverdueEscalated {
+        due_date: DateTime<Utc>,
+        level: OverdueNoticeLevel,
+    },
+    LostDeclaration {
+        declared_at: DateTime<Utc>,
+    },
+    ExtensionConfirmation {
+        new_due_date: DateTime<Utc>,
+    },
+    ReturnConfirmation {
+        was_overdue: bool,
+    },
+}
+
+impl Notice {
+    fn kind(&self) -> NoticeKind {
+        match self {
+            Notice::Overdue { .. } => NoticeKind::Overdue,
+            Notice::DueSoonReminder { .. } => NoticeKind::DueSoonReminder,
+            Notice::OverdueEscalated { .. } => NoticeKind::OverdueNotice,
+            Notice::LostDeclaration { .. } => NoticeKind::LostDeclaration,
+            Notice::ExtensionConfirmation { .. } => NoticeKind::ExtensionConfirmation,
+            Notice::ReturnConfirmation { .. } => NoticeKind::ReturnConfirmation,
+        }
+    }
+}
+
+/// 保存されたイベントに対して会員への通知を配信する
+///
+/// - LoanBecameOverdue: 延滞の通知
+/// - LoanDueSoonReminded: 返却期限のリマインダー
+/// - OverdueNoticeSent: 延滞の督促（段階ごと。1回目の督促は延滞の通知と同じ内容のため、
+///   延滞の通知を配信済みなら送らない）
+/// - LoanDeclaredLost: 紛失の通知
+/// - LoanExtended: 延長の確認（会員と書籍は貸出のBookLoanedイベントから特定する）
+/// - BookReturned: 返却の確認
+///
+/// 通知は少なくとも1回配信される：
+/// 1. 配信記録に重複排除キーがあれば何もしない
+/// 2. 書籍タイトルを取得し、重複排除キーを付けて通知を送る
+/// 3. 配信記録に重複排除キーを記録する
+///
+/// 送信後に記録できなかった通知は、次の処理で同じ重複排除キーで再送される。
+/// 匿名化された会員（削除請求など）には通知しない。
+///
+/// # エラー
+/// - EventStoreError: 延長・督促された貸出のイベントの読み込み失敗
+/// - BookServiceError: 書籍タイトルの取得失敗
+/// - NotificationError: 通知の送信失敗
+/// - NotificationLogError: 配信記録の確認・記録の失敗
+pub async fn handle_notice_event(
+    deps: &ServiceDependencies,
+    stored: &StoredEvent,
+) -> Result<NoticeOutcome> {
+    let (member_id, book_id, notice) = match &stored.event {
+        DomainEvent::LoanBecameOverdue(e) => (
+            e.member_id,
+            e.book_id,
+            Notice::Overdue {
+                due_date: e.due_date,
+            },
+        ),
+        DomainEvent::LoanDueSoonReminded(e) => (
+            e.member_id,
+            e.book_id,
+            Notice::DueSoonReminder {
+                due_date: e.due_date,
+            },
+        ),
+        DomainEvent::OverdueNoticeSent(e) => {
+            if e.level == OverdueNoticeLevel::First
+                && overdue_notice_delivered(deps, e.loan_id).await?
+            {
+                return Ok(NoticeOutcome::AlreadyDelivered(NoticeKind::OverdueNotice));
+            }
+            (
+                e.member_id,
+                e.book_id,
+                Notice::OverdueEscalated {
+                    due_date: e.due_date,
+                    level: e.level,
+                },
+            )
+        }
+        DomainEvent::LoanDeclaredLost(e) => (
+            e.member_id,
+            e.book_id,
+            Notice::LostDeclaration {
+                declared_at: e.declared_at,
+            },
+        ),
+        DomainEvent::LoanExtended(e) => {
+            let (member_id, book_id) = loan_parties(deps, e.loan_id).await?;
+            (
+                member_id,
+                book_id,
+                Notice::ExtensionConfirmation {
+                    new_due_date: e.new_due_date,
+                },
+            )
+        }
+        DomainEvent::BookReturned(e) => (
+            e.member_id,
+            e.book_id,
+            Notice::ReturnConfirmation {
+                was_overdue: e.was_overdue,
+            },
+        ),
+        _ => return Ok(NoticeOutcome::NotApplicable),
+    };
+    if member_id.is_anonymised() {
+        return Ok(NoticeOutcome::NotApplicable);
+    }
+
+    let kind = notice.kind();
+    let dedup_key = notice_dedup_key(kind, stored.aggregate_id, stored.aggregate_version);
+    if deps
+        .notification_log
+        .is_delivered(&dedup_key)
+        .await
+        .map_err(NotificationDispatchError::NotificationLogError)?
+    {
+        return Ok(NoticeOutcome::AlreadyDelivered(kind));
+    }
+
+    let book_title = deps
+        .book_service
+        .get_book_title(book_id)
+        .await
+        .map_err(NotificationDispatchError::BookServiceError)?;
+
+    let notifications = &deps.notification_service;
+    match notice {
+        Notice::Overdue { due_date } => {
+            notifications
+                .send_overdue_notification(&dedup_key, member_id, &book_title, due_date)
+                .await
+        }
+        Notice::DueSoonReminder { due_date } => {
+            notifications
+                .send_due_soon_reminder(&dedup_key, member_id, &book_title, due_date)
+                .await
+        }
+        Notice::OverdueEscalated { due_date, level } => {
+            notifications
+                .send_overdue_notice(&dedup_key, member_id, &book_title, due_date, level)
+                .await
+        }
+        Notice::LostDeclaration { declared_at } => {
+            notifications
+                .send_lost_declaration(&dedup_key, member_id, &book_title, declared_at)
+                .await
+        }
+        Notice::ExtensionConfirmation { new_due_date } => {
+            notifications
+                .send_extension_confirmation(&dedup_key, member_id, &book_title, new_due_date)
+                .await
+        }
+        Notice::ReturnConfirmation { was_overdue } => {
+            notifications
+                .send_return_confirmation(&dedup_key, member_id, &book_title, was_overdue)
+                .await
+        }
+    }
+    .map_err(NotificationDispatchError::NotificationError)?;
+
+    deps.notification_log
+        .record_delivered(&DeliveredNotice {
+            dedup_key,
+            kind,
+            event_id: stored.event_id,
+            delivered_at: Utc::now(),
+        })
+        .await
+        .map_err(NotificationDispatchError::NotificationLogError)?;
+
+    Ok(NoticeOutcome::Delivered(kind))
+}
+
+/// 貸出の延滞の通知（LoanBecameOverdueに対する通知）を配信済みか
+async fn overdue_notice_delivered(deps: &ServiceDependencies, loan_id: LoanId) -> Result<bool> {
+    let events = deps
+        .event_store
+        .load(loan_id.value(), Loan::aggregate_type())
+        .await
+        .map_err(NotificationDispatchError::EventStoreError)?;
+
+    let Some(position) = events
+        .iter()
+        .position(|event| matches!(event, DomainEvent::LoanBecameOverdue(_)))
+    else {
+        return Ok(false);
+    };
+    let dedup_key = notice_dedup_key(NoticeKind::Overdue, loan_id.value(), position as i32 + 1);
+    deps.notification_log
+        .is_delivered(&dedup_key)
+        .await
+        .map_err(NotificationDispatchError::NotificationLogError)
+}
+
+/// 貸出の会員と書籍をBookLoanedイベントから特定する
+async fn loan_parties(deps: &ServiceDependencies, loan_id: LoanId) -> Result<(MemberId, BookId)> {
+    let events = deps
+        .event_store
+        .load(loan_id.value(), Loan::aggregate_type())
+        .await
+        .map_err(NotificationDispatchError::EventStoreError)?;
+
+    events
+        .iter()
+        .find_map(|event| match event {
+            DomainEvent::BookLoaned(e) => Some((e.member_id, e.book_id)),
+            _ => None,
+        })
+        .ok_or(NotificationDispatchError::LoanNotFound(loan_id.value()))
+}
@@ -0,0 +1,23 @@
+mod dispatch_service;
+mod errors;
+mod event_handlers;
+mod preferences;
+mod router;
+mod subscriber;
+
+#[allow(unused_imports)]
+pub use dispatch_service::{
+    FailedNotice, NOTICE_EVENT_TYPES, NotificationDispatchReport, dispatch_notifications,
+};
+#[allow(unused_imports)]
+pub use errors::{
+    NotificationDispatchError, NotificationPreferencesError, NotificationRoutingError, Result,
+};
+#[allow(unused_imports)]
+pub use event_handlers::{NoticeOutcome, handle_notice_event, notice_dedup_key};
+#[allow(unused_imports)]
+pub use preferences::{get_notification_preferences, set_notification_preferences};
+#[allow(unused_imports)]
+pub use router::{DeferredDeliveryReport, FailedDeferredDelivery, NotificationRouter};
+#[allow(unused_imports)]
+pub use subscriber::NotificationSubscriber;
@@ -0,0 +1,67 @@
+use chrono::{DateTime, Utc};
+
+use crate::application::loan::ServiceDependencies;
+use crate::domain::{notification_preferences::NotificationPreferences, value_objects::MemberId};
+
+use super::errors::NotificationPreferencesError;
+
+type Result<T> = std::result::Result<T, NotificationPreferencesError>;
+
+/// 会員が存在することを確認する
+async fn ensure_member_exists(deps: &ServiceDependencies, member_id: MemberId) -> Result<()> {
+    let exists = deps
+        .member_service
+        .exists(member_id)
+        .await
+        .map_err(NotificationPreferencesError::MemberServiceError)?;
+    if exists {
+        Ok(())
+    } else {
+        Err(NotificationPreferencesError::MemberNotFound(
+            member_id.value(),
+        ))
+    }
+}
+
+/// 会員の通知設定を取得する
+///
+/// 設定していない会員には既定の設定（`NotificationPreferences::default()`）を返す。
+///
+/// # エラー
+/// - MemberNotFound: 会員が存在しない
+pub async fn get_notification_preferences(
+    deps: &ServiceDependencies,
+    member_id: MemberId,
+) -> Result<NotificationPreferences> {
+    ensure_member_exists(deps, member_id).await?;
+    let preferences = deps
+        .notification_preferences
+        .get(member_id)
+        .await
+        .map_err(NotificationPreferencesError::NotificationPreferenceError)?;
+    Ok(preferences.unwrap_or_default())
+}
+
+/// 会員の通知設定を変更する（既存の設定は置き換える）
+///
+/// 変更前に保留された通知は、変更後の設定（手段・言語）で送る日時に送られる。
+///
+/// # エラー
+/// - MemberNotFound: 会員が存在しない
+/// - InvalidPreferences: 設定が不正（`NotificationPreferences::validate()`）
+pub async fn set_notification_preferences(
+    deps: &ServiceDependencies,
+    member_id: MemberId,
+    preferences: NotificationPreferences,
+    updated_at: DateTime<Utc>,
+) -> Result<NotificationPreferences> {
+    preferences
+        .validate()
+        .map_err(NotificationPreferencesError::InvalidPreferences)?;
+    ensure_member_exists(deps, member_id).await?;
+    deps.notification_preferences
+        .save(member_id, &preferences, updated_at)
+        .await
+        .map_err(NotificationPreferencesError::NotificationPreferenceError)?;
+    Ok(preferences)
+}
@@ -0,0 +1,309 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use crate::domain::notification_preferences::{NoticeSchedule, NotificationChannel};
+use crate::domain::value_objects::{MemberId, OverdueNoticeLevel};
+use crate::ports::{
+    Classified, DeferredNotice, DeferredNoticeQueue, Notice, NoticeBody, NotificationError,
+    NotificationGateway, NotificationPreferenceStore, NotificationService, OutgoingMessage,
+    notification_service,
+};
+
+use super::errors::NotificationRoutingError;
+
+/// 保留中の通知を送れなかった会員
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FailedDeferredDelivery {
+    pub member_id: MemberId,
+    /// 失敗の内容
+    pub error: String,
+    /// 再試行で回復しうる失敗か（通知は保留されたまま、次回の配信で改めて送る）
+    pub retryable: bool,
+}
+
+/// 保留中の通知の配信の結果
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeferredDeliveryReport {
+    /// 送ったメッセージの件数（ダイジェストも1件）
+    pub messages: usize,
+    /// 送った通知の件数
+    pub notices: usize,
+    /// 送らずに取り除いた通知の件数（通知を受け取らなくなった会員・削除された会員）
+    pub suppressed: usize,
+    pub failed: Vec<FailedDeferredDelivery>,
+}
+
+/// 失敗の内容（原因を含める）
+fn failure_message(error: &NotificationRoutingError) -> String {
+    match std::error::Error::source(error) {
+        Some(source) => format!("{}: {}", error, source),
+        None => error.to_string(),
+    }
+}
+
+/// ストアのエラーを通知サービスのエラーにする（再試行で回復しうるかを保つ）
+fn notification_error<E>(error: E) -> NotificationError
+where
+    E: Classified + std::error::Error + Send + Sync + 'static,
+{
+    if error.is_retryable() {
+        NotificationError::Unavailable(error.into())
+    } else {
+        NotificationError::Internal(error.into())
+    }
+}
+
+/// 会員の通知設定に従って通知を送る`NotificationService`
+///
+/// 通知の配信（`handle_notice_event()`）と配信手段（`NotificationGateway`）の間に置き、
+/// 会員ごとに送る手段・言語・時期を決める：
+/// - 通知を受け取らない会員には送らない
+/// - 日次ダイジェストの会員の通知と、通知を送らない時間帯に発生した通知は、
+///   `DeferredNoticeQueue`に保留し、`deliver_deferred()`でまとめて送る
+/// - それ以外はすぐに1通のメッセージとして送る
+///
+/// 保留した時点で通知は配信済みとして記録されるため、キューが送るまで保持する。
+/// 保留されるのは通知設定のある会員だけで（既定の設定ではすぐ送る）、
+/// 通知設定を削除すると保留中の通知も送られない。
+pub struct NotificationRouter {
+    preferences: Arc<dyn NotificationPreferenceStore>,
+    queue: Arc<dyn DeferredNoticeQueue>,
+    gateway: Arc<dyn NotificationGateway>,
+}
+
+impl NotificationRouter {
+    pub fn new(
+        preferences: Arc<dyn NotificationPreferenceStore>,
+        queue: Arc<dyn DeferredNoticeQueue>,
+        gateway: Arc<dyn NotificationGateway>,
+    ) -> Self {
+        Self {
+            preferences,
+            queue,
+            gateway,
+        }
+    }
+
+    /// `now`に発生した通知を、会員の通知設定に従って送るか保留する
+    pub async fn route(
+        &self,
+        member_id: MemberId,
+        notice: Notice,
+        now: DateTime<Utc>,
+    ) -> notification_service::Result<()> {
+        let preferences = self
+            .preferences
+            .get(member_id)
+            .await
+            .map_err(notification_error)?
+            .unwrap_or_default();
+
+        match preferences.schedule(now) {
+            NoticeSchedule::Suppressed => Ok(()),
+            NoticeSchedule::Immediate => {
+                self.gateway
+                    .deliver(&OutgoingMessage {
+                        member_id,
+                        channel: preferences.channel,
+                        language: preferences.language,
+                        notices: vec![notice],
+                    })
+                    .await
+            }
+            NoticeSchedule::Deferred(deliver_after) => self
+                .queue
+                .enqueue(&DeferredNotice {
+                    member_id,
+                    notice,
+                    queued_at: now,
+                    deliver_after,
+                })
+                .await
+                .map_err(notification_error),
+        }
+    }
+
+    /// 送る日時が`now`以前の保留中の通知を、会員ごとに1通のメッセージにまとめて送る
+    ///
+    /// 送る時点の通知設定（手段・言語）を使う。通知を受け取らなくなった会員と
+    /// 通知設定が削除された会員の通知は送らずに取り除く。
+    /// 送れなかった会員の通知は保留したままにし、次回の配信で改めて送る。
+    ///
+    /// # エラー
+    /// 保留中の通知を読み込めない場合のみ（DeferredNoticeError）
+    pub async fn deliver_deferred(
+        &self,
+        now: DateTime<Utc>,
+    ) -> Result<DeferredDeliveryReport, NotificationRoutingError> {
+        let due = self
+            .queue
+            .due(now)
+            .await
+            .map_err(NotificationRoutingError::DeferredNoticeError)?;
+
+        let mut report = DeferredDeliveryReport::default();
+        for group in due.chunk_by(|a, b| a.member_id == b.member_id) {
+            let member_id = group[0].member_id;
+            match self.deliver_group(member_id, group).await {
+                Ok(Some(sent)) => {
+                    report.messages += 1;
+                    report.notices += sent;
+                }
+                Ok(None) => report.suppressed += group.len(),
+                Err(e) => {
+                    let error = failure_message(&e);
+                    tracing::warn!(
+                        "Failed to deliver deferred notices to member {}: {}",
+                        member_id.value(),
+                        error
+                    );
+                    report.failed.push(FailedDeferredDelivery {
+                        member_id,
+                        error,
+                        retryable: e.is_retryable(),
+                    });
+                }
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// 1人の会員の保留中の通知を送り、キューから取り除く
+    ///
+    /// 送った通知の件数を返す（送らずに取り除いた場合はNone）。
+    async fn deliver_group(
+        &self,
+        member_id: MemberId,
+        group: &[DeferredNotice],
+    ) -> Result<Option<usize>, NotificationRoutingError> {
+        let dedup_keys: Vec<String> = group.iter().map(|n| n.notice.dedup_key.clone()).collect();
+        let preferences = self
+            .preferences
+            .get(member_id)
+            .await
+            .map_err(NotificationRoutingError::NotificationPreferenceError)?;
+
+        let sent = match preferences {
+            Some(preferences) if preferences.channel != NotificationChannel::None => {
+                self.gateway
+                    .deliver(&OutgoingMessage {
+                        member_id,
+                        channel: preferences.channel,
+                        language: preferences.language,
+                        notices: group.iter().map(|n| n.notice.clone()).collect(),
+                    })
+                    .await
+                    .map_err(NotificationRoutingError::NotificationError)?;
+                Some(group.len())
+            }
+            _ => None,
+        };
+
+        self.queue
+            .remove(&dedup_keys)
+            .await
+            .map_err(NotificationRoutingError::DeferredNoticeError)?;
+        Ok(sent)
+    }
+}
+
+#[async_trait]
+impl NotificationService for NotificationRouter {
+    async fn send_overdue_notification(
+        &self,
+        dedup_key: &str,
+        member_id: MemberId,
+        book_title: &str,
+        due_date: DateTime<Utc>,
+    ) -> notification_service::Result<()> {
+        let notice = notice(dedup_key, book_title, NoticeBody::Overdue { due_date });
+        self.route(member_id, notice, Utc::now()).await
+    }
+
+    async fn send_due_soon_reminder(
+        &self,
+        dedup_key: &str,
+        member_id: MemberId,
+        book_title: &str,
+        due_date: DateTime<Utc>,
+    ) -> notification_service::Result<()> {
+        let notice = notice(
+            dedup_key,
+            book_title,
+            NoticeBody::DueSoonReminder { due_date },
+        );
+        self.route(member_id, notice, Utc::now()).await
+    }
+
+    async fn send_overdue_notice(
+        &self,
+        dedup_key: &str,
+        member_id: MemberId,
+        book_title: &str,
+        due_date: DateTime<Utc>,
+        level: OverdueNoticeLevel,
+    ) -> notification_service::Result<()> {
+        let notice = notice(
+            dedup_key,
+            book_title,
+            NoticeBody::OverdueNotice { due_date, level },
+        );
+        self.route(member_id, notice, Utc::now()).await
+    }
+
+    async fn send_lost_declaration(
+        &self,
+        dedup_key: &str,
+        member_id: MemberId,
+        book_title: &str,
+        declared_at: DateTime<Utc>,
+    ) -> notification_service::Result<()> {
+        let notice = notice(
+            dedup_key,
+            book_title,
+            NoticeBody::LostDeclaration { declared_at },
+        );
+        self.route(member_id, notice, Utc::now()).await
+    }
+
+    async fn send_extension_confirmation(
+        &self,
+        dedup_key: &str,
+        member_id: MemberId,
+        book_title: &str,
+        new_due_date: DateTime<Utc>,
+    ) -> notification_service::Result<()> {
+        let notice = notice(
+            dedup_key,
+            book_title,
+            NoticeBody::ExtensionConfirmation { new_due_date },
+        );
+        self.route(member_id, notice, Utc::now()).await
+    }
+
+    async fn send_return_confirmation(
+        &self,
+        dedup_key: &str,
+        member_id: MemberId,
+        book_title: &str,
+        was_overdue: bool,
+    ) -> notification_service::Result<()> {
+        let notice = notice(
+            dedup_key,
+            book_title,
+            NoticeBody::ReturnConfirmation { was_overdue },
+        );
+        self.route(member_id, notice, Utc::now()).await
+    }
+}
+
+fn notice(dedup_key: &str, book_title: &str, body: NoticeBody) -> Notice {
+    Notice {
+        dedup_key: dedup_key.to_string(),
+        book_title: book_title.to_string(),
+        body,
+    }
+}
@@ -0,0 +1,56 @@
+use async_trait::async_trait;
+use std::collections::HashMap;
+
+use crate::application::loan::ServiceDependencies;
+use crate::domain::value_objects::TenantId;
+use crate::ports::{EventHandler, HandlerResult, PublishedEvent};
+
+use super::dispatch_service::NOTICE_EVENT_TYPES;
+use super::event_handlers::handle_notice_event;
+
+/// 発行されたイベントに反応して会員に通知するハンドラー
+///
+/// イベントバスに登録すると、通知の対象のイベントがコミットされた直後に
+/// `handle_notice_event()`で通知を配信する。重複排除キーは
+/// `dispatch_notifications()`と同じため、配信ジョブが同じイベントを
+/// 後から処理しても二度は送らない。停止などでバスから配信されなかった
+/// イベントは、配信ジョブが拾う。
+///
+/// イベントバスはすべてのテナントで共有されるため、テナントごとの依存関係を持つ。
+pub struct NotificationSubscriber {
+    tenants: HashMap<TenantId, ServiceDependencies>,
+}
+
+impl NotificationSubscriber {
+    /// テナントごとの依存関係からハンドラーを作成
+    pub fn new(tenants: impl IntoIterator<Item = ServiceDependencies>) -> Self {
+        Self {
+            tenants: tenants
+                .into_iter()
+                .map(|deps| (deps.tenant_id, deps))
+                .collect(),
+        }
+    }
+}
+
+#[async_trait]
+impl EventHandler for NotificationSubscriber {
+    fn name(&self) -> &str {
+        "notifications"
+    }
+
+    /// 通知の対象のイベントを、発行時に載っているイベントIDで配信する
+    async fn handle(&self, event: &PublishedEvent) -> HandlerResult {
+        let event_type = event.event.event_type();
+        if !NOTICE_EVENT_TYPES.contains(&event_type) {
+            return Ok(());
+        }
+        let deps = self
+            .tenants
+            .get(&event.tenant_id)
+            .ok_or_else(|| format!("Unknown tenant {}", event.tenant_id.value()))?;
+
+        handle_notice_event(deps, &event.to_stored()).await?;
+        Ok(())
+    }
+}
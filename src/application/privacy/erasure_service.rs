@@ -0,0 +1,111 @@
+use crate::application::loan::{ServiceDependencies, build_loan_view};
+use crate::domain::{self, Aggregate, loan::Loan, value_objects::MemberId};
+use crate::ports::{LoanStatus, MemberKeyStore};
+
+use super::errors::{PrivacyError, Result};
+
+/// 削除請求の処理結果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ErasureSummary {
+    /// 会員の鍵を破棄したか（鍵がなかった場合はfalse）
+    pub key_shredded: bool,
+    /// Read Modelで匿名化された貸出の件数
+    pub anonymised_loans: usize,
+}
+
+/// 会員の削除請求を処理する（暗号シュレッディング）
+///
+/// イベントは不変のため削除せず、会員の鍵を破棄して会員識別子を復号不能にする。
+/// その後、会員の貸出をイベントから再投影し、Read Modelからも会員との紐付けを消す。
+/// 貸出日・返却期限・延滞の有無などの統計情報はそのまま残る。
+/// 会員の通知設定と保留中の通知も削除する。
+/// 通知の配信記録は通知の種類ときっかけのイベントだけを持ち、会員IDを含まないため残す。
+///
+/// ビジネスルール：
+/// - 返却されていない貸出がある会員は削除できない
+///
+/// 暗号化の導入前に記録されたイベントの会員識別子は平文のため、
+/// 鍵の破棄では匿名化されない（`anonymised_loans`に含まれない）。
+/// 会員に関するイベント（読書履歴の保持設定、データの写しの提供）は会員IDを集約IDとしないため、
+/// 鍵の破棄後は誰のイベントか分からなくなる。
+/// ただし、この変更の前に会員IDを集約IDとして記録されたイベントは、
+/// ハッシュチェーンを保つため書き換えず、集約IDに会員IDが残る。
+///
+/// # エラー
+/// - MemberHasOpenLoans: 貸出中・延滞中の貸出がある
+/// - ポート層のエラー
+pub async fn erase_member(
+    deps: &ServiceDependencies,
+    key_store: &dyn MemberKeyStore,
+    member_id: MemberId,
+) -> Result<ErasureSummary> {
+    // 1. 会員の貸出を取得し、未返却の貸出がないことを確認
+    let loans = deps
+        .loan_read_model
+        .find_by_member_id(member_id, None)
+        .await
+        .map_err(PrivacyError::ReadModelError)?;
+
+    if loans.iter().any(|l| l.status != LoanStatus::Returned) {
+        return Err(PrivacyError::MemberHasOpenLoans);
+    }
+
+    // 2. 会員の鍵を破棄
+    let key_shredded = key_store
+        .shred(member_id)
+        .await
+        .map_err(PrivacyError::KeyStoreError)?;
+
+    // 3. 各貸出をイベントから再投影（会員は匿名化されて復元される）
+    let loan_ids: Vec<_> = loans.iter().map(|l| l.loan_id.value()).collect();
+    let mut histories = deps
+        .event_store
+        .load_many(&loan_ids, Loan::aggregate_type())
+        .await
+        .map_err(PrivacyError::EventStoreError)?;
+
+    let mut anonymised_loans = 0;
+    for loan_id in loan_ids {
+        let events = histories.remove(&loan_id).unwrap_or_default();
+        let Some(replayed) = domain::loan::replay_events(&events) else {
+            continue;
+        };
+
+        let view = build_loan_view(&replayed);
+        if view.member_id.is_anonymised() {
+            anonymised_loans += 1;
+        }
+        deps.loan_read_model
+            .save(view)
+            .await
+            .map_err(PrivacyError::ReadModelError)?;
+    }
+
+    // 4. 読書履歴の保持設定を取り消す
+    deps.loan_read_model
+        .save_history_opt_in(member_id, None)
+        .await
+        .map_err(PrivacyError::ReadModelError)?;
+
+    // 5. 保留中の通知と通知設定を削除する
+    deps.deferred_notices
+        .remove_for(member_id)
+        .await
+        .map_err(PrivacyError::DeferredNoticeError)?;
+    deps.notification_preferences
+        .delete(member_id)
+        .await
+        .map_err(PrivacyError::NotificationPreferenceError)?;
+
+    tracing::info!(
+        "Erased member {}: key shredded={}, {} loans anonymised",
+        member_id.value(),
+        key_shredded,
+        anonymised_loans
+    );
+
+    Ok(ErasureSummary {
+        key_shredded,
+        anonymised_loans,
+    })
+}
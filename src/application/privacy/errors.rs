@@ -0,0 +1,65 @@
+use crate::ports::{
+    Classified, DeferredNoticeError, ErrorClass, EventStoreError, LoanReadModelError,
+    MemberKeyStoreError, MemberServiceError, NotificationLogError, NotificationPreferenceError,
+};
+use thiserror::Error;
+
+/// 個人情報保護（削除請求など）のエラー
+#[derive(Debug, Error)]
+pub enum PrivacyError {
+    /// 会員が存在しない
+    #[error("Member not found")]
+    MemberNotFound,
+
+    /// 返却されていない貸出がある（資料の所在が分からなくなるため削除できない）
+    #[error("Member has loans that have not been returned")]
+    MemberHasOpenLoans,
+
+    /// EventStoreのエラー
+    #[error("Event store error")]
+    EventStoreError(#[source] EventStoreError),
+
+    /// ReadModelのエラー
+    #[error("Read model error")]
+    ReadModelError(#[source] LoanReadModelError),
+
+    /// MemberServiceのエラー
+    #[error("Member service error")]
+    MemberServiceError(#[source] MemberServiceError),
+
+    /// MemberKeyStoreのエラー
+    #[error("Member key store error")]
+    KeyStoreError(#[source] MemberKeyStoreError),
+
+    /// NotificationPreferenceStoreのエラー
+    #[error("Notification preference store error")]
+    NotificationPreferenceError(#[source] NotificationPreferenceError),
+
+    /// NotificationLogのエラー
+    #[error("Notification log error")]
+    NotificationLogError(#[source] NotificationLogError),
+
+    /// DeferredNoticeQueueのエラー
+    #[error("Deferred notice queue error")]
+    DeferredNoticeError(#[source] DeferredNoticeError),
+}
+
+impl Classified for PrivacyError {
+    fn class(&self) -> ErrorClass {
+        match self {
+            PrivacyError::EventStoreError(e) => e.class(),
+            PrivacyError::ReadModelError(e) => e.class(),
+            PrivacyError::MemberServiceError(e) => e.class(),
+            PrivacyError::KeyStoreError(e) => e.class(),
+            PrivacyError::NotificationPreferenceError(e) => e.class(),
+            PrivacyError::NotificationLogError(e) => e.class(),
+            PrivacyError::DeferredNoticeError(e) => e.class(),
+            PrivacyError::MemberNotFound | PrivacyError::MemberHasOpenLoans => {
+                ErrorClass::Permanent
+            }
+        }
+    }
+}
+
+/// 個人情報保護処理の Result型
+pub type Result<T> = std::result::Result<T, PrivacyError>;
@@ -0,0 +1,309 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt::Write;
+
+use crate::application::loan::{ServiceDependencies, append_member_event};
+use crate::domain::{
+    commands::ExportMemberData,
+    events::{DomainEvent, MemberDataExported},
+    notification_preferences::NotificationPreferences,
+    value_objects::{BookId, LoanId, MemberId, StaffId, TenantId},
+};
+use crate::ports::{DeferredNotice, DeliveredNotice, LoanStatus, LoanView, StoredEvent};
+
+use super::errors::{PrivacyError, Result};
+
+/// 会員データの写しの形式バージョン
+///
+/// 2: 通知設定と通知の記録（`notifications`）を追加
+pub const EXPORT_FORMAT_VERSION: u32 = 2;
+
+/// 会員データの写し（機械可読なアーカイブ）
+///
+/// 図書館が会員について保持しているデータをまとめたもの。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MemberDataArchive {
+    pub format_version: u32,
+    pub tenant_id: TenantId,
+    pub member_id: MemberId,
+    pub exported_at: DateTime<Utc>,
+    /// 写しの作成を承認した職員
+    pub exported_by: StaffId,
+    /// Read Modelに保持している貸出
+    pub loans: Vec<LoanRecord>,
+    /// 読書履歴の保持設定
+    pub reading_history: ReadingHistoryRecord,
+    /// 通知設定と通知の記録
+    pub notifications: NotificationRecord,
+    /// 会員に関する集約のイベント（シーケンス番号順）
+    pub events: Vec<StoredEvent>,
+}
+
+/// 写しに含める貸出（loans_viewの行）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LoanRecord {
+    pub loan_id: LoanId,
+    pub book_id: BookId,
+    pub loaned_at: DateTime<Utc>,
+    pub due_date: DateTime<Utc>,
+    pub returned_at: Option<DateTime<Utc>>,
+    pub extension_count: u8,
+    /// 貸出状態（"active", "overdue", "returned", "lost"）
+    pub status: String,
+}
+
+impl From<LoanView> for LoanRecord {
+    fn from(view: LoanView) -> Self {
+        Self {
+            loan_id: view.loan_id,
+            book_id: view.book_id,
+            loaned_at: view.loaned_at,
+            due_date: view.due_date,
+            returned_at: view.returned_at,
+            extension_count: view.extension_count,
+            status: view.status.as_str().to_string(),
+        }
+    }
+}
+
+/// 写しに含める読書履歴の保持設定
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReadingHistoryRecord {
+    pub keep_history: bool,
+    pub opted_in_at: Option<DateTime<Utc>>,
+}
+
+/// 写しに含める通知設定と通知の記録
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NotificationRecord {
+    /// 通知設定（設定していなければNone）
+    pub preferences: Option<NotificationPreferences>,
+    /// 配信した通知（会員のイベントをきっかけにしたもの、配信日時順）
+    pub delivered: Vec<DeliveredNotice>,
+    /// 保留中の通知（日次ダイジェスト・通知を送らない時間帯）
+    pub pending: Vec<DeferredNotice>,
+}
+
+impl MemberDataArchive {
+    /// 人が読むための要約（会員に渡す書面用）
+    pub fn summary(&self) -> String {
+        let count = |status: LoanStatus| {
+            self.loans
+                .iter()
+                .filter(|l| l.status == status.as_str())
+                .count()
+        };
+        let date = |at: DateTime<Utc>| at.format("%Y-%m-%d").to_string();
+
+        let mut out = String::new();
+        let _ = writeln!(out, "会員データの写し");
+        let _ = writeln!(out, "会員ID: {}", self.member_id.value());
+        let _ = writeln!(out, "作成日時: {}", self.exported_at.to_rfc3339());
+        let _ = writeln!(out, "承認した職員: {}", self.exported_by.value());
+        let _ = writeln!(out);
+        // 紛失として扱った貸出は、ある場合のみ内訳に含める
+        let lost = match count(LoanStatus::Lost) {
+            0 => String::new(),
+            n => format!("、紛失 {}件", n),
+        };
+        let _ = writeln!(
+            out,
+            "貸出: {}件（貸出中 {}件、延滞中 {}件、返却済み {}件{}）",
+            self.loans.len(),
+            count(LoanStatus::Active),
+            count(LoanStatus::Overdue),
+            count(LoanStatus::Returned),
+            lost
+        );
+        for loan in &self.loans {
+            let returned = loan
+                .returned_at
+                .map(|at| format!("返却 {}", date(at)))
+                .unwrap_or_else(|| "未返却".to_string());
+            let _ = writeln!(
+                out,
+                "- {} 貸出 / 返却期限 {} / {} / 延長 {}回（書籍ID {}）",
+                date(loan.loaned_at),
+                date(loan.due_date),
+                returned,
+                loan.extension_count,
+                loan.book_id.value()
+            );
+        }
+        let _ = writeln!(out);
+        let _ = match self.reading_history.opted_in_at {
+            Some(at) => writeln!(out, "読書履歴の保持: 選択済み（{}）", date(at)),
+            None => writeln!(out, "読書履歴の保持: 選択していない"),
+        };
+        let _ = match &self.notifications.preferences {
+            Some(p) => writeln!(
+                out,
+                "通知設定: 手段 {} / 言語 {} / まとめ方 {}",
+                p.channel.as_str(),
+                p.language.as_str(),
+                p.digest.as_str()
+            ),
+            None => writeln!(out, "通知設定: 未設定（既定の設定）"),
+        };
+        let _ = writeln!(
+            out,
+            "通知: 配信済み {}件、保留中 {}件",
+            self.notifications.delivered.len(),
+            self.notifications.pending.len()
+        );
+        let _ = writeln!(out, "記録されているイベント: {}件", self.events.len());
+        out
+    }
+}
+
+/// 会員データの写しを作成する（開示請求への対応）
+///
+/// 会員に関するイベント、loans_viewの行、読書履歴の保持設定、通知設定と通知の記録を集め、
+/// 写しを作成したことをMemberDataExportedイベントとして記録する。
+///
+/// ビジネスルール：
+/// - 会員が存在すること
+/// - 写しの作成は職員が承認する（承認した職員をイベントに記録する）
+///
+/// # エラー
+/// - MemberNotFound: 会員が存在しない
+/// - ポート層のエラー
+pub async fn export_member_data(
+    deps: &ServiceDependencies,
+    cmd: ExportMemberData,
+) -> Result<MemberDataArchive> {
+    // 1. 会員の存在確認
+    let member_exists = deps
+        .member_service
+        .exists(cmd.member_id)
+        .await
+        .map_err(PrivacyError::MemberServiceError)?;
+
+    if !member_exists {
+        return Err(PrivacyError::MemberNotFound);
+    }
+
+    // 2. 会員に関するデータを収集
+    let events = deps
+        .event_store
+        .load_member_events(cmd.member_id)
+        .await
+        .map_err(PrivacyError::EventStoreError)?;
+    let loans = deps
+        .loan_read_model
+        .find_by_member_id(cmd.member_id, None)
+        .await
+        .map_err(PrivacyError::ReadModelError)?;
+    let opted_in_at = deps
+        .loan_read_model
+        .get_history_opt_in(cmd.member_id)
+        .await
+        .map_err(PrivacyError::ReadModelError)?;
+    let preferences = deps
+        .notification_preferences
+        .get(cmd.member_id)
+        .await
+        .map_err(PrivacyError::NotificationPreferenceError)?;
+    let event_ids: Vec<_> = events.iter().map(|e| e.event_id).collect();
+    let delivered = deps
+        .notification_log
+        .find_by_events(&event_ids)
+        .await
+        .map_err(PrivacyError::NotificationLogError)?;
+    let pending = deps
+        .deferred_notices
+        .pending_for(cmd.member_id)
+        .await
+        .map_err(PrivacyError::DeferredNoticeError)?;
+
+    let archive = MemberDataArchive {
+        format_version: EXPORT_FORMAT_VERSION,
+        tenant_id: deps.tenant_id,
+        member_id: cmd.member_id,
+        exported_at: cmd.requested_at,
+        exported_by: cmd.staff_id,
+        loans: loans.into_iter().map(LoanRecord::from).collect(),
+        reading_history: ReadingHistoryRecord {
+            keep_history: opted_in_at.is_some(),
+            opted_in_at,
+        },
+        notifications: NotificationRecord {
+            preferences,
+            delivered,
+            pending,
+        },
+        events,
+    };
+
+    // 3. 写しの作成を記録
+    let event = MemberDataExported {
+        member_id: cmd.member_id,
+        exported_by: cmd.staff_id,
+        exported_at: cmd.requested_at,
+        event_count: archive.events.len(),
+        loan_count: archive.loans.len(),
+    };
+    append_member_event(deps, DomainEvent::MemberDataExported(event))
+        .await
+        .map_err(PrivacyError::EventStoreError)?;
+
+    tracing::info!(
+        "Exported data of member {} ({} events, {} loans) authorised by staff {}",
+        cmd.member_id.value(),
+        archive.events.len(),
+        archive.loans.len(),
+        cmd.staff_id.value()
+    );
+
+    Ok(archive)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_summary_lists_loans_and_preference() {
+        let now = Utc::now();
+        let archive = MemberDataArchive {
+            format_version: EXPORT_FORMAT_VERSION,
+            tenant_id: TenantId::DEFAULT,
+            member_id: MemberId::new(),
+            exported_at: now,
+            exported_by: StaffId::new(),
+            loans: vec![LoanRecord {
+                loan_id: LoanId::new(),
+                book_id: BookId::new(),
+                loaned_at: now,
+                due_date: now + chrono::Duration::days(14),
+                returned_at: None,
+                extension_count: 0,
+                status: LoanStatus::Active.as_str().to_string(),
+            }],
+            reading_history: ReadingHistoryRecord {
+                keep_history: false,
+                opted_in_at: None,
+            },
+            notifications: NotificationRecord {
+                preferences: None,
+                delivered: Vec::new(),
+                pending: Vec::new(),
+            },
+            events: Vec::new(),
+        };
+
+        let summary = archive.summary();
+        assert!(summary.contains("貸出: 1件（貸出中 1件、延滞中 0件、返却済み 0件）"));
+        assert!(summary.contains("未返却"));
+        assert!(summary.contains("読書履歴の保持: 選択していない"));
+        assert!(summary.contains("通知設定: 未設定（既定の設定）"));
+        assert!(summary.contains("通知: 配信済み 0件、保留中 0件"));
+
+        // JSONとして往復できる
+        let json = serde_json::to_string(&archive).unwrap();
+        assert_eq!(
+            serde_json::from_str::<MemberDataArchive>(&json).unwrap(),
+            archive
+        );
+    }
+}
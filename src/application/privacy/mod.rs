@@ -0,0 +1,13 @@
+mod erasure_service;
+mod errors;
+mod export_service;
+
+#[allow(unused_imports)]
+pub use erasure_service::{ErasureSummary, erase_member};
+#[allow(unused_imports)]
+pub use errors::{PrivacyError, Result};
+#[allow(unused_imports)]
+pub use export_service::{
+    EXPORT_FORMAT_VERSION, LoanRecord, MemberDataArchive, NotificationRecord, ReadingHistoryRecord,
+    export_member_data,
+};
@@ -0,0 +1,189 @@
+//! イベントソーシングされた集約のリポジトリ
+//!
+//! 集約の読み込み（イベントの復元）と保存（楽観的排他制御付きのイベント追加）を、
+//! `Aggregate`を実装した任意の集約に提供する。
+
+use crate::domain::{Aggregate, DomainEvent, InvalidTransition, value_objects::AggregateId};
+use crate::ports::{AppendedEvent, EventStore, EventStoreError};
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+/// リポジトリの Result型
+pub type Result<T> = std::result::Result<T, EventStoreError>;
+
+/// 読み込んだ集約とそのバージョン
+///
+/// バージョンは集約に保存済みのイベント数で、保存時の期待バージョンとして使う。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Versioned<A> {
+    pub aggregate: A,
+    pub version: u32,
+}
+
+/// イベントソーシングされた集約のリポジトリ
+///
+/// イベントストアを集約の種類ごとの型付きのインターフェースで包む。
+pub struct EventSourcedRepository<A> {
+    event_store: Arc<dyn EventStore>,
+    _aggregate: PhantomData<fn() -> A>,
+}
+
+impl<A> Clone for EventSourcedRepository<A> {
+    fn clone(&self) -> Self {
+        Self::new(self.event_store.clone())
+    }
+}
+
+impl<A> EventSourcedRepository<A> {
+    pub fn new(event_store: Arc<dyn EventStore>) -> Self {
+        Self {
+            event_store,
+            _aggregate: PhantomData,
+        }
+    }
+}
+
+impl<A: Aggregate> EventSourcedRepository<A> {
+    /// 集約を読み込む
+    ///
+    /// イベントが存在しない場合は`None`を返す。
+    /// 集約のイベント型に変換できないイベントや、不正な状態遷移を含むイベント列は
+    /// `EventStoreError::Corrupted`となる。
+    pub async fn load(&self, id: &A::Id) -> Result<Option<Versioned<A>>> {
+        let events = self
+            .event_store
+            .load(id.value(), A::aggregate_type())
+            .await?;
+        Self::restore(events)
+    }
+
+    /// 保存済みのイベント列から集約とバージョンを復元する
+    ///
+    /// `EventStore::load_many`でまとめて読み込んだイベントの復元に使う。
+    pub fn restore(events: Vec<DomainEvent>) -> Result<Option<Versioned<A>>> {
+        let version = events.len() as u32;
+        let events = events
+            .into_iter()
+            .map(|event| {
+                let event_type = event.event_type();
+                A::Event::try_from(event).map_err(|_| {
+                    EventStoreError::Corrupted(
+                        format!(
+                            "{event_type} is not an event of aggregate {}",
+                            A::aggregate_type()
+                        )
+                        .into(),
+                    )
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let aggregate = A::replay(&events).map_err(|e| EventStoreError::Corrupted(e.into()))?;
+        Ok(aggregate.map(|aggregate| Versioned { aggregate, version }))
+    }
+
+    /// 集約が`expected_version`のときだけイベントを保存する
+    ///
+    /// 新しい集約の期待バージョンは0。
+    /// 読み込み後に他の書き込みがあった場合は`EventStoreError::VersionConflict`となる。
+    /// 保存したイベントのイベントIDとシーケンス番号を、保存した順に返す。
+    pub async fn save_with_expected_version(
+        &self,
+        id: &A::Id,
+        expected_version: u32,
+        events: Vec<A::Event>,
+    ) -> Result<Vec<AppendedEvent>> {
+        self.event_store
+            .append_with_expected_version(
+                id.value(),
+                A::aggregate_type(),
+                expected_version,
+                events.into_iter().map(Into::into).collect(),
+            )
+            .await
+    }
+
+    /// イベント列から集約の状態を復元する純粋関数
+    pub fn replay(events: &[A::Event]) -> std::result::Result<Option<A>, InvalidTransition> {
+        A::replay(events)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{
+        BookLoaned, ReadingHistoryPreferenceChanged, loan::Loan, value_objects::*,
+    };
+    use chrono::{Duration, Utc};
+
+    fn book_loaned(loan_id: LoanId, member_id: MemberId) -> DomainEvent {
+        let now = Utc::now();
+        DomainEvent::BookLoaned(BookLoaned {
+            loan_id,
+            book_id: BookId::new(),
+            member_id,
+            loaned_at: now,
+            due_date: now + Duration::days(14),
+            loaned_by: StaffId::new(),
+            override_token: None,
+        })
+    }
+
+    #[test]
+    fn test_restore_loan_with_version() {
+        let loan_id = LoanId::new();
+        let restored =
+            EventSourcedRepository::<Loan>::restore(vec![book_loaned(loan_id, MemberId::new())])
+                .unwrap()
+                .unwrap();
+
+        assert_eq!(restored.version, 1);
+        assert!(matches!(restored.aggregate, Loan::Active(l) if l.loan_id == loan_id));
+        assert_eq!(
+            EventSourcedRepository::<Loan>::restore(vec![]).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_restore_rejects_events_of_other_aggregates() {
+        // 貸出の履歴に会員のイベントが混入している
+        let member_id = MemberId::new();
+        let events = vec![
+            book_loaned(LoanId::new(), member_id),
+            DomainEvent::ReadingHistoryPreferenceChanged(ReadingHistoryPreferenceChanged {
+                member_id,
+                keep_history: true,
+                changed_at: Utc::now(),
+            }),
+        ];
+
+        let result = EventSourcedRepository::<Loan>::restore(events);
+
+        assert!(matches!(
+            result,
+            Err(EventStoreError::Corrupted(e))
+                if e.to_string() == "ReadingHistoryPreferenceChanged is not an event of aggregate Loan"
+        ));
+    }
+
+    #[test]
+    fn test_restore_rejects_invalid_transitions() {
+        // 同じ貸出に貸出のイベントが2回記録されている
+        let loan_id = LoanId::new();
+        let member_id = MemberId::new();
+        let events = vec![
+            book_loaned(loan_id, member_id),
+            book_loaned(loan_id, member_id),
+        ];
+
+        let result = EventSourcedRepository::<Loan>::restore(events);
+
+        assert!(matches!(
+            result,
+            Err(EventStoreError::Corrupted(e))
+                if e.to_string() == "Invalid state transition: BookLoaned cannot apply to an active loan"
+        ));
+    }
+}
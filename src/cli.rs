@@ -0,0 +1,460 @@
+//! 運用コマンド（サーバー起動以外のサブコマンド）
+//!
+//! `rusty-library-ddd <command> [args...]` の形式で実行する。
+//! 対象テナントは環境変数`TENANT_ID`で指定する（未指定時は既定テナント）。
+
+use rusty_library_ddd::{
+    adapters::in_memory::InMemoryEventBus,
+    adapters::mock::{
+        book_service::BookService as MockBookService,
+        member_service::MemberService as MockMemberService,
+        notification_service::NotificationService as MockNotificationService,
+        staff_service::StaffService as MockStaffService,
+    },
+    adapters::postgres::{
+        EventCodec, PostgresDeferredNoticeQueue, PostgresEventArchive, PostgresEventAudit,
+        PostgresEventStore, PostgresIdempotencyStore, PostgresJobStore, PostgresLoanReadModel,
+        PostgresMemberKeyStore, PostgresNotificationLog, PostgresNotificationPreferenceStore,
+        PostgresTenantDirectory, PostgresUnitOfWork,
+    },
+    application::archive::archive_closed_partitions,
+    application::audit::{AnchorSigner, AnchorStatus, ChainAnchor, anchor_chain, verify_anchor},
+    application::backup::{BackupManifest, export_event_log, import_event_log},
+    application::jobs::Schedule,
+    application::legacy_import::{LegacyImportOptions, import_legacy_loans, write_reject_report},
+    application::loan::{ServiceDependencies, anonymise_loan_history},
+    application::privacy::erase_member,
+    domain::value_objects::{MemberId, StaffId, TenantId},
+    ports::{EventAudit, JobStore, TenantDirectory},
+};
+use sqlx::PgPool;
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+use std::path::Path;
+use std::sync::Arc;
+
+type CliResult = Result<(), Box<dyn std::error::Error + Send + Sync>>;
+
+/// バックアップ本体のファイル名
+const EVENTS_FILE: &str = "events.ndjson";
+/// マニフェストのファイル名
+const MANIFEST_FILE: &str = "manifest.json";
+
+/// サブコマンドを実行する
+///
+/// サブコマンドが指定されていない場合はNoneを返す（サーバーを起動する）。
+pub async fn run(pool: &PgPool, args: &[String]) -> Option<CliResult> {
+    let command = args.first()?;
+    let tenant_id = match tenant_from_env() {
+        Ok(tenant_id) => tenant_id,
+        Err(e) => return Some(Err(e)),
+    };
+
+    let result = match (command.as_str(), &args[1..]) {
+        ("export-events", [dir]) => export_events(pool, tenant_id, Path::new(dir)).await,
+        ("import-events", [dir, rest @ ..]) => {
+            let rebuild = rest.iter().any(|a| a == "--rebuild-read-model");
+            import_events(pool, tenant_id, Path::new(dir), rebuild).await
+        }
+        ("verify-chain", []) => verify_chain(pool, tenant_id, None).await,
+        ("verify-chain", [flag, file]) if flag == "--anchor" => {
+            verify_chain(pool, tenant_id, Some(Path::new(file))).await
+        }
+        ("anchor-chain", [dir]) => anchor(pool, tenant_id, Path::new(dir)).await,
+        ("erase-member", [member_id]) => erase(pool, tenant_id, member_id).await,
+        ("anonymise-history", []) => anonymise_history(pool, tenant_id).await,
+        ("convert-events", [codec]) => convert_events(pool, tenant_id, codec).await,
+        ("validate-events", []) => validate_events(pool, tenant_id).await,
+        ("archive-events", [flag, years]) if flag == "--older-than-years" => {
+            archive_events(pool, years).await
+        }
+        ("import-legacy-loans", [csv, rest @ ..]) => match parse_staff(rest) {
+            Ok(staff_id) => {
+                let dry_run = rest.iter().any(|a| a == "--dry-run");
+                import_legacy(pool, tenant_id, Path::new(csv), staff_id, dry_run).await
+            }
+            Err(e) => Err(e),
+        },
+        ("job-runs", [job]) => job_runs(pool, job).await,
+        _ => Err(usage().into()),
+    };
+
+    Some(result)
+}
+
+fn usage() -> String {
+    [
+        "Usage:",
+        "  rusty-library-ddd                                   start the API server",
+        "  rusty-library-ddd export-events <dir>               export the event log as NDJSON",
+        "  rusty-library-ddd import-events <dir> [--rebuild-read-model]",
+        "                                                      restore an exported event log",
+        "  rusty-library-ddd import-legacy-loans <csv> --staff <uuid> [--dry-run]",
+        "                                                      import loan history from the legacy ILS",
+        "  rusty-library-ddd verify-chain [--anchor <file>]    verify the event hash chain",
+        "  rusty-library-ddd anchor-chain <dir>                export a signed root hash",
+        "  rusty-library-ddd erase-member <uuid>               crypto-shred a member's identity",
+        "  rusty-library-ddd anonymise-history                 unlink members from expired loan history",
+        "  rusty-library-ddd convert-events <json|msgpack>     re-encode stored events with a codec",
+        "  rusty-library-ddd validate-events                   check stored events against their schemas",
+        "  rusty-library-ddd archive-events --older-than-years <n>",
+        "                                                      archive closed yearly event partitions",
+        "  rusty-library-ddd job-runs <job>                    show recent runs of a background job",
+    ]
+    .join("\n")
+}
+
+fn tenant_from_env() -> Result<TenantId, Box<dyn std::error::Error + Send + Sync>> {
+    match std::env::var("TENANT_ID") {
+        Ok(value) => Ok(TenantId::from_uuid(value.parse()?)),
+        Err(_) => Ok(TenantId::DEFAULT),
+    }
+}
+
+/// 新しく書き込むイベントの保存形式（環境変数`EVENT_CODEC`、未指定時はjson）
+pub fn event_codec_from_env() -> Result<EventCodec, Box<dyn std::error::Error + Send + Sync>> {
+    match std::env::var("EVENT_CODEC") {
+        Ok(value) => Ok(value.parse()?),
+        Err(_) => Ok(EventCodec::default()),
+    }
+}
+
+/// バックグラウンドジョブのスケジュール（環境変数`JOB_<ジョブ名>_SCHEDULE`）
+///
+/// 例: `overdue-detection`は`JOB_OVERDUE_DETECTION_SCHEDULE`。
+/// 未指定時は`default`、`off`の場合はNone（このインスタンスでは実行しない）。
+pub fn job_schedule_from_env(
+    job_name: &str,
+    default: &str,
+) -> Result<Option<Schedule>, Box<dyn std::error::Error + Send + Sync>> {
+    let var = format!("JOB_{}_SCHEDULE", job_name.to_uppercase().replace('-', "_"));
+    match std::env::var(var) {
+        Ok(value) if value.trim() == "off" => Ok(None),
+        Ok(value) => Ok(Some(value.parse()?)),
+        Err(_) => Ok(Some(default.parse()?)),
+    }
+}
+
+/// ジョブの実行履歴に記録するインスタンスの識別子
+///
+/// 環境変数`INSTANCE_ID`、なければ`HOSTNAME`（コンテナではPod名など）、どちらもなければランダムなID。
+pub fn instance_id_from_env() -> String {
+    std::env::var("INSTANCE_ID")
+        .or_else(|_| std::env::var("HOSTNAME"))
+        .unwrap_or_else(|_| uuid::Uuid::new_v4().to_string())
+}
+
+/// アンカー署名鍵のシード（32バイト、16進数）の環境変数
+const ANCHOR_SEED_ENV: &str = "CHAIN_ANCHOR_SEED";
+/// アンカー検証用の公開鍵（16進数）の環境変数（未指定時はシードから導出）
+const ANCHOR_PUBLIC_KEY_ENV: &str = "CHAIN_ANCHOR_PUBLIC_KEY";
+
+/// `--staff <uuid>`（移行作業者の職員ID）を取り出す
+fn parse_staff(args: &[String]) -> Result<StaffId, Box<dyn std::error::Error + Send + Sync>> {
+    let value = args
+        .iter()
+        .position(|a| a == "--staff")
+        .and_then(|i| args.get(i + 1))
+        .ok_or_else(usage)?;
+    Ok(StaffId::from_uuid(value.parse()?))
+}
+
+/// イベントログを`<dir>/events.ndjson`と`<dir>/manifest.json`にエクスポート
+async fn export_events(pool: &PgPool, tenant_id: TenantId, dir: &Path) -> CliResult {
+    std::fs::create_dir_all(dir)?;
+    let event_store = PostgresEventStore::for_tenant(pool.clone(), tenant_id);
+
+    let mut out = BufWriter::new(File::create(dir.join(EVENTS_FILE))?);
+    let manifest = export_event_log(&event_store, &mut out, chrono::Utc::now()).await?;
+    manifest.write_to(BufWriter::new(File::create(dir.join(MANIFEST_FILE))?))?;
+
+    tracing::info!(
+        "Exported {} events ({} aggregates) to {}",
+        manifest.event_count,
+        manifest.aggregate_count,
+        dir.display()
+    );
+    Ok(())
+}
+
+/// `export-events`で作成したバックアップを空のイベントストアに復元
+async fn import_events(pool: &PgPool, tenant_id: TenantId, dir: &Path, rebuild: bool) -> CliResult {
+    let event_store =
+        PostgresEventStore::for_tenant(pool.clone(), tenant_id).with_codec(event_codec_from_env()?);
+    let read_model = PostgresLoanReadModel::for_tenant(pool.clone(), tenant_id);
+
+    let manifest = BackupManifest::read_from(File::open(dir.join(MANIFEST_FILE))?)?;
+    let input = BufReader::new(File::open(dir.join(EVENTS_FILE))?);
+    let summary = import_event_log(
+        &event_store,
+        input,
+        &manifest,
+        rebuild.then_some(&read_model as _),
+    )
+    .await?;
+
+    tracing::info!(
+        "Imported {} events ({} aggregates), rebuilt {} loans",
+        summary.event_count,
+        summary.aggregate_count,
+        summary.rebuilt_loans.unwrap_or(0)
+    );
+    Ok(())
+}
+
+/// 会員の削除請求を処理する（鍵を破棄し、貸出を匿名化する）
+async fn erase(pool: &PgPool, tenant_id: TenantId, member_id: &str) -> CliResult {
+    let member_id = MemberId::from_uuid(member_id.parse()?);
+    let deps = postgres_dependencies(pool, tenant_id).await?;
+    let key_store = PostgresMemberKeyStore::for_tenant(pool.clone(), tenant_id);
+
+    let summary = erase_member(&deps, &key_store, member_id).await?;
+    tracing::info!(
+        "Member erased (key shredded: {}, {} loans anonymised)",
+        summary.key_shredded,
+        summary.anonymised_loans
+    );
+    Ok(())
+}
+
+/// 保持期間を過ぎた返却済み貸出から会員との紐付けを消す（定期実行を想定）
+async fn anonymise_history(pool: &PgPool, tenant_id: TenantId) -> CliResult {
+    let deps = postgres_dependencies(pool, tenant_id).await?;
+
+    let anonymised = anonymise_loan_history(&deps, chrono::Utc::now()).await?;
+    match deps.policy.history_retention_days {
+        Some(days) => tracing::info!(
+            "Anonymised {} loans returned more than {} days ago",
+            anonymised,
+            days
+        ),
+        None => tracing::info!("History retention is not configured for this tenant"),
+    }
+    Ok(())
+}
+
+/// テナントの保存済みイベントを指定した形式に変換する
+///
+/// 中断しても再実行すれば続きから変換される。以後の書き込みの形式は`EVENT_CODEC`で指定する。
+async fn convert_events(pool: &PgPool, tenant_id: TenantId, codec: &str) -> CliResult {
+    let codec: EventCodec = codec.parse()?;
+    let event_store = PostgresEventStore::for_tenant(pool.clone(), tenant_id);
+
+    let converted = event_store.convert_codec(codec).await?;
+    tracing::info!("Converted {} events to {}", converted, codec);
+    Ok(())
+}
+
+/// 保存済みのイベントを登録済みのスキーマで検証する
+///
+/// 違反した行を全て出力し、1件でもあれば失敗とする。
+async fn validate_events(pool: &PgPool, tenant_id: TenantId) -> CliResult {
+    let event_store = PostgresEventStore::for_tenant(pool.clone(), tenant_id);
+
+    let scan = event_store.validate_stored().await?;
+    for violation in &scan.violations {
+        tracing::error!(
+            "Invalid {} at sequence {} (event {}, aggregate {}): {}",
+            violation.event_type,
+            violation.sequence_number,
+            violation.event_id,
+            violation.aggregate_id,
+            violation.error
+        );
+    }
+    if !scan.is_valid() {
+        return Err(format!(
+            "{} of {} events do not match their schema",
+            scan.violations.len(),
+            scan.scanned_events
+        )
+        .into());
+    }
+    tracing::info!("All {} events match their schema", scan.scanned_events);
+    Ok(())
+}
+
+/// 古い年のイベントパーティションをアーカイブに移す（定期実行を想定）
+///
+/// パーティションは全テナントで共有されるため、`TENANT_ID`に関係なく全テナントが対象となる。
+async fn archive_events(pool: &PgPool, years: &str) -> CliResult {
+    let archive = PostgresEventArchive::new(pool.clone());
+
+    let report = archive_closed_partitions(&archive, chrono::Utc::now(), years.parse()?).await?;
+    for name in &report.created_partitions {
+        tracing::info!("Created partition {}", name);
+    }
+    for (name, event_count) in &report.archived_partitions {
+        tracing::info!("Archived {} ({} events)", name, event_count);
+    }
+    for (name, loan_count) in &report.skipped_partitions {
+        tracing::warn!("Kept {}: {} loans have not been returned", name, loan_count);
+    }
+    Ok(())
+}
+
+/// バックグラウンドジョブの直近の実行履歴を表示する
+///
+/// 終了していない実行（`Running`）は、実行中か、実行中にインスタンスが停止したもの。
+async fn job_runs(pool: &PgPool, job: &str) -> CliResult {
+    let runs = PostgresJobStore::new(pool.clone())
+        .list_runs(job, 20)
+        .await?;
+    if runs.is_empty() {
+        tracing::info!("Job {} has not run yet", job);
+    }
+    for run in runs {
+        tracing::info!(
+            "{} scheduled {} on {}: {:?} (started {}, finished {}) {}",
+            run.job_name,
+            run.scheduled_for,
+            run.instance_id,
+            run.status,
+            run.started_at,
+            run.finished_at
+                .map(|t| t.to_string())
+                .unwrap_or_else(|| "-".to_string()),
+            run.summary.unwrap_or_default()
+        );
+    }
+    Ok(())
+}
+
+/// 運用コマンド用のサービス依存関係
+///
+/// 貸出ポリシーはテナントの設定を使用する。
+/// 会員・書籍・職員・通知サービスはサーバーと同じくモックを使用する。
+async fn postgres_dependencies(
+    pool: &PgPool,
+    tenant_id: TenantId,
+) -> Result<ServiceDependencies, Box<dyn std::error::Error + Send + Sync>> {
+    let tenant = PostgresTenantDirectory::new(pool.clone())
+        .list_tenants()
+        .await?
+        .into_iter()
+        .find(|t| t.tenant_id == tenant_id)
+        .ok_or_else(|| format!("Unknown tenant {}", tenant_id.value()))?;
+
+    let event_codec = event_codec_from_env()?;
+    Ok(ServiceDependencies {
+        tenant_id,
+        policy: tenant.policy,
+        event_store: Arc::new(
+            PostgresEventStore::for_tenant(pool.clone(), tenant_id).with_codec(event_codec),
+        ),
+        loan_read_model: Arc::new(PostgresLoanReadModel::for_tenant(pool.clone(), tenant_id)),
+        unit_of_work: Some(Arc::new(
+            PostgresUnitOfWork::for_tenant(pool.clone(), tenant_id).with_codec(event_codec),
+        )),
+        member_service: Arc::new(MockMemberService::new()),
+        book_service: Arc::new(MockBookService::new()),
+        staff_service: Arc::new(MockStaffService::new()),
+        notification_service: Arc::new(MockNotificationService::new()),
+        notification_log: Arc::new(PostgresNotificationLog::for_tenant(pool.clone(), tenant_id)),
+        notification_preferences: Arc::new(PostgresNotificationPreferenceStore::for_tenant(
+            pool.clone(),
+            tenant_id,
+        )),
+        deferred_notices: Arc::new(PostgresDeferredNoticeQueue::for_tenant(
+            pool.clone(),
+            tenant_id,
+        )),
+        // CLIの処理に反応するハンドラーはないため、発行されたイベントはどこにも配信されない
+        event_bus: Arc::new(InMemoryEventBus::synchronous()),
+        idempotency_keys: Arc::new(PostgresIdempotencyStore::for_tenant(
+            pool.clone(),
+            tenant_id,
+        )),
+    })
+}
+
+/// 旧システムの貸出履歴CSVを取り込む
+///
+/// 不正な行は`<csv>.rejects.csv`に書き出す。
+/// 会員・書籍の照合は会員・書籍サービス（現在はモック）を使用する。
+async fn import_legacy(
+    pool: &PgPool,
+    tenant_id: TenantId,
+    csv: &Path,
+    staff_id: StaffId,
+    dry_run: bool,
+) -> CliResult {
+    let deps = postgres_dependencies(pool, tenant_id).await?;
+    let options = LegacyImportOptions {
+        imported_by: staff_id,
+        as_of: chrono::Utc::now(),
+        dry_run,
+    };
+
+    let report = import_legacy_loans(&deps, BufReader::new(File::open(csv)?), options).await?;
+
+    if !report.rejected.is_empty() {
+        let path = csv.with_extension("rejects.csv");
+        write_reject_report(&report.rejected, BufWriter::new(File::create(&path)?))?;
+        tracing::warn!(
+            "{} rows rejected, see {}",
+            report.rejected.len(),
+            path.display()
+        );
+    }
+    Ok(())
+}
+
+/// ハッシュチェーンを検証し、最初に壊れた箇所を報告する
+///
+/// `--anchor`を指定した場合は、アンカーの署名とルートハッシュも検証する。
+async fn verify_chain(pool: &PgPool, tenant_id: TenantId, anchor_file: Option<&Path>) -> CliResult {
+    let audit = PostgresEventAudit::for_tenant(pool.clone(), tenant_id);
+
+    if let Some(file) = anchor_file {
+        let anchor = ChainAnchor::read_from(File::open(file)?)?;
+        let public_key = match std::env::var(ANCHOR_PUBLIC_KEY_ENV) {
+            Ok(key) => hex::decode(key.trim())?,
+            Err(_) => AnchorSigner::from_seed_hex(&std::env::var(ANCHOR_SEED_ENV)?)?
+                .public_key()
+                .to_vec(),
+        };
+        let status = verify_anchor(&audit, &anchor, &public_key).await?;
+        if status != AnchorStatus::Valid {
+            return Err(format!("Anchor {} is not valid: {:?}", file.display(), status).into());
+        }
+        tracing::info!("Anchor at sequence {} is valid", anchor.sequence_number);
+    }
+
+    let verification = audit.verify_chain().await?;
+    match verification.first_broken_link {
+        Some(link) => Err(format!(
+            "Hash chain broken at sequence {} (event {}, aggregate {}): {:?}",
+            link.sequence_number, link.event_id, link.aggregate_id, link.reason
+        )
+        .into()),
+        None => {
+            tracing::info!(
+                "Hash chain intact: {} events verified, {} unchained legacy events",
+                verification.verified_events,
+                verification.unchained_events
+            );
+            Ok(())
+        }
+    }
+}
+
+/// チェーンの先頭に署名したアンカーを`<dir>/anchor-<sequence>.json`に書き出す
+///
+/// cron等で定期的に実行し、出力先はデータベースとは別の管理下に置く。
+async fn anchor(pool: &PgPool, tenant_id: TenantId, dir: &Path) -> CliResult {
+    let signer = AnchorSigner::from_seed_hex(&std::env::var(ANCHOR_SEED_ENV)?)?;
+    let audit = PostgresEventAudit::for_tenant(pool.clone(), tenant_id);
+    let anchor = anchor_chain(&audit, &signer, tenant_id, chrono::Utc::now()).await?;
+
+    std::fs::create_dir_all(dir)?;
+    let path = dir.join(format!("anchor-{}.json", anchor.sequence_number));
+    anchor.write_to(BufWriter::new(File::create(&path)?))?;
+
+    tracing::info!(
+        "Anchored root hash {} to {}",
+        anchor.root_hash,
+        path.display()
+    );
+    Ok(())
+}
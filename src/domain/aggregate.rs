@@ -0,0 +1,45 @@
+use super::errors::InvalidTransition;
+use super::events::DomainEvent;
+use super::value_objects::AggregateId;
+
+/// イベントソーシングされる集約
+///
+/// 集約の状態はイベント列のfoldで復元される。
+/// 実装するのは初期状態（`None`）またはある状態にイベントを1つ適用する`try_apply`のみで、
+/// 読み込み・保存は`EventSourcedRepository`が集約の種類によらず共通に行う。
+///
+/// イベントストアには全ての集約のイベントが`DomainEvent`として保存されるため、
+/// 集約のイベント型は`DomainEvent`と相互に変換できる必要がある。
+pub trait Aggregate: Sized + Send + Sync {
+    /// 集約ID
+    type Id: AggregateId;
+
+    /// 集約のイベント
+    type Event: Clone + Send + Sync + Into<DomainEvent> + TryFrom<DomainEvent>;
+
+    /// イベントストアに記録される集約の種類（例: "Loan"）
+    fn aggregate_type() -> &'static str;
+
+    /// イベントを適用して新しい状態を生成する純粋関数
+    ///
+    /// 現在の状態に適用できないイベントは`InvalidTransition`となる。
+    fn try_apply(state: Option<Self>, event: &Self::Event) -> Result<Self, InvalidTransition>;
+
+    /// 直前に生成したイベントを適用して新しい状態を生成する純粋関数
+    ///
+    /// # Panics
+    /// 不正な状態遷移の場合にpanicする（イベントを生成したドメインのバグ）
+    fn apply(state: Option<Self>, event: &Self::Event) -> Self {
+        Self::try_apply(state, event).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// イベント列から現在の状態を復元する純粋関数
+    ///
+    /// イベントが空の場合は`None`を返す。
+    /// 不正な状態遷移を含むイベント列は`InvalidTransition`となる。
+    fn replay(events: &[Self::Event]) -> Result<Option<Self>, InvalidTransition> {
+        events
+            .iter()
+            .try_fold(None, |state, event| Self::try_apply(state, event).map(Some))
+    }
+}
@@ -1,7 +1,7 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
-use super::{BookId, LoanId, MemberId, StaffId};
+use super::{BookId, LoanId, MemberId, OverrideToken, StaffId};
 
 /// コマンド：書籍を貸し出す
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -12,6 +12,17 @@ pub struct LoanBook {
     pub staff_id: StaffId,
 }
 
+/// コマンド：貸出条件の例外を認めて書籍を貸し出す（監督者の承認が必要）
+///
+/// 承認された条件（貸出上限・延滞）は判定せずに貸し出す。
+/// 会員の存在と書籍の貸出可能性は例外にできない。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LoanBookWithOverride {
+    #[serde(flatten)]
+    pub loan: LoanBook,
+    pub override_token: OverrideToken,
+}
+
 /// コマンド：貸出を延長する
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ExtendLoan {
@@ -25,3 +36,19 @@ pub struct ReturnBook {
     pub loan_id: LoanId,
     pub returned_at: DateTime<Utc>,
 }
+
+/// コマンド：会員データの写しを作成する（職員の承認が必要）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExportMemberData {
+    pub member_id: MemberId,
+    pub staff_id: StaffId,
+    pub requested_at: DateTime<Utc>,
+}
+
+/// コマンド：読書履歴の保持設定を変更する
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SetReadingHistoryPreference {
+    pub member_id: MemberId,
+    pub keep_history: bool,
+    pub changed_at: DateTime<Utc>,
+}
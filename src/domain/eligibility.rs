@@ -0,0 +1,204 @@
+#![allow(dead_code)]
+
+use std::fmt;
+
+use super::policy::{CirculationPolicy, EligibilityRule, OverrideToken};
+
+/// 貸出条件の判定に必要な情報（判定時点のスナップショット）
+///
+/// 会員サービス・書籍サービス・Read Modelから集めた値をそのまま持つ。
+/// 判定そのものは`check_eligibility`が純粋関数として行う。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EligibilitySnapshot {
+    /// 会員が存在するか
+    pub member_exists: bool,
+    /// 会員が利用停止中か
+    pub member_suspended: bool,
+    /// 会員に延滞中の貸出があるか
+    pub has_overdue_loans: bool,
+    /// 会員の貸出中の冊数
+    pub active_loan_count: usize,
+    /// 書籍が貸出可能か
+    pub book_available: bool,
+}
+
+/// 満たされていない貸出条件
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EligibilityViolation {
+    /// 会員が存在しない
+    MemberNotFound,
+    /// 会員が利用停止中
+    MemberSuspended,
+    /// 書籍が貸出不可
+    BookNotAvailable,
+    /// 会員に延滞中の貸出がある
+    MemberHasOverdueLoan,
+    /// 貸出中の冊数が上限に達している
+    LoanLimitExceeded {
+        active_loans: usize,
+        max_active_loans: usize,
+    },
+}
+
+impl EligibilityViolation {
+    /// 違反の種類を表すコード（APIのエラーコードと同じ）
+    pub fn code(&self) -> &'static str {
+        match self {
+            EligibilityViolation::MemberNotFound => "MEMBER_NOT_FOUND",
+            EligibilityViolation::MemberSuspended => "MEMBER_SUSPENDED",
+            EligibilityViolation::BookNotAvailable => "BOOK_NOT_AVAILABLE",
+            EligibilityViolation::MemberHasOverdueLoan => "MEMBER_HAS_OVERDUE_LOAN",
+            EligibilityViolation::LoanLimitExceeded { .. } => "LOAN_LIMIT_EXCEEDED",
+        }
+    }
+
+    /// 監督者の承認で例外を認められる条件（認められない違反はNone）
+    pub fn overridable_rule(&self) -> Option<EligibilityRule> {
+        match self {
+            EligibilityViolation::MemberHasOverdueLoan => Some(EligibilityRule::OverdueLoans),
+            EligibilityViolation::LoanLimitExceeded { .. } => Some(EligibilityRule::LoanLimit),
+            _ => None,
+        }
+    }
+
+    /// オーバーライドトークンで例外が認められているか
+    pub fn is_overridden_by(&self, token: &OverrideToken) -> bool {
+        self.overridable_rule()
+            .is_some_and(|rule| token.covers(rule))
+    }
+}
+
+impl fmt::Display for EligibilityViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EligibilityViolation::MemberNotFound => write!(f, "Member not found"),
+            EligibilityViolation::MemberSuspended => write!(f, "Member is suspended"),
+            EligibilityViolation::BookNotAvailable => write!(f, "Book is not available for loan"),
+            EligibilityViolation::MemberHasOverdueLoan => write!(f, "Member has overdue loan"),
+            EligibilityViolation::LoanLimitExceeded {
+                active_loans,
+                max_active_loans,
+            } => write!(
+                f,
+                "Member has {} active loans (limit {})",
+                active_loans, max_active_loans
+            ),
+        }
+    }
+}
+
+/// 貸出条件を判定する（純粋関数）
+///
+/// 最初の違反で止めず、満たされていない条件をすべて返す。
+/// 空であれば貸出可能。
+///
+/// 違反は次の順に並ぶ（貸出時のエラーは先頭の違反になる）:
+/// 1. 会員が存在すること
+/// 2. 会員が利用停止中でないこと
+/// 3. 書籍が貸出可能であること
+/// 4. 会員に延滞中の貸出がないこと
+/// 5. 会員の貸出中の冊数が上限（テナントの貸出ポリシー）未満であること
+pub fn check_eligibility(
+    snapshot: &EligibilitySnapshot,
+    policy: &CirculationPolicy,
+) -> Vec<EligibilityViolation> {
+    let mut violations = Vec::new();
+
+    if !snapshot.member_exists {
+        violations.push(EligibilityViolation::MemberNotFound);
+    }
+    if snapshot.member_suspended {
+        violations.push(EligibilityViolation::MemberSuspended);
+    }
+    if !snapshot.book_available {
+        violations.push(EligibilityViolation::BookNotAvailable);
+    }
+    if snapshot.has_overdue_loans {
+        violations.push(EligibilityViolation::MemberHasOverdueLoan);
+    }
+    if snapshot.active_loan_count >= policy.max_active_loans {
+        violations.push(EligibilityViolation::LoanLimitExceeded {
+            active_loans: snapshot.active_loan_count,
+            max_active_loans: policy.max_active_loans,
+        });
+    }
+
+    violations
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::StaffId;
+
+    fn eligible() -> EligibilitySnapshot {
+        EligibilitySnapshot {
+            member_exists: true,
+            member_suspended: false,
+            has_overdue_loans: false,
+            active_loan_count: 0,
+            book_available: true,
+        }
+    }
+
+    #[test]
+    fn test_eligible_snapshot_has_no_violations() {
+        let policy = CirculationPolicy::default();
+        assert!(check_eligibility(&eligible(), &policy).is_empty());
+
+        let below_limit = EligibilitySnapshot {
+            active_loan_count: policy.max_active_loans - 1,
+            ..eligible()
+        };
+        assert!(check_eligibility(&below_limit, &policy).is_empty());
+    }
+
+    #[test]
+    fn test_check_eligibility_reports_every_violation() {
+        let policy = CirculationPolicy::default();
+        let snapshot = EligibilitySnapshot {
+            member_exists: true,
+            member_suspended: true,
+            has_overdue_loans: true,
+            active_loan_count: 5,
+            book_available: false,
+        };
+
+        assert_eq!(
+            check_eligibility(&snapshot, &policy),
+            vec![
+                EligibilityViolation::MemberSuspended,
+                EligibilityViolation::BookNotAvailable,
+                EligibilityViolation::MemberHasOverdueLoan,
+                EligibilityViolation::LoanLimitExceeded {
+                    active_loans: 5,
+                    max_active_loans: 5,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_only_overdue_and_limit_violations_can_be_overridden() {
+        let token = OverrideToken {
+            rules: vec![EligibilityRule::LoanLimit],
+            reason: "Teacher borrowing class set".to_string(),
+            supervisor_id: StaffId::new(),
+        };
+        let limit = EligibilityViolation::LoanLimitExceeded {
+            active_loans: 5,
+            max_active_loans: 5,
+        };
+
+        assert!(limit.is_overridden_by(&token));
+        assert!(!EligibilityViolation::MemberHasOverdueLoan.is_overridden_by(&token));
+        assert_eq!(
+            EligibilityViolation::MemberSuspended.overridable_rule(),
+            None
+        );
+        assert_eq!(
+            EligibilityViolation::BookNotAvailable.overridable_rule(),
+            None
+        );
+    }
+}
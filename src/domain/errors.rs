@@ -1,12 +1,14 @@
 #![allow(dead_code)]
 
 use super::ExtensionError;
+use thiserror::Error;
+use uuid::Uuid;
 
 /// 貸出のエラー
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum LoanBookError {
-    // 現時点では発生しないが、将来的にアプリケーション層で追加される可能性
-    // 例: MemberNotFound, BookNotAvailable, MemberHasOverdueLoan など
+    /// 貸出条件の例外の承認が不正（条件・理由・承認者の欠落）
+    InvalidOverride(&'static str),
 }
 
 /// 延長のエラー
@@ -34,3 +36,42 @@ pub enum ReturnBookError {
     /// 既に返却済み
     AlreadyReturned,
 }
+
+/// 返却期限のリマインダーのエラー
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RemindDueSoonError {
+    /// 現在の返却期限について知らせ済み
+    AlreadyReminded,
+    /// 返却期限を過ぎている（延滞の通知の対象）
+    PastDue,
+}
+
+/// 通知設定の不備
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InvalidNotificationPreferences {
+    /// 時差が-12:00〜+14:00の範囲にない
+    UtcOffsetOutOfRange,
+    /// 通知を送らない時間帯の開始と終了が同じ
+    EmptyQuietHours,
+}
+
+/// 記録されたイベントを集約の状態に適用できない（イベント列の破損）
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum InvalidTransition {
+    /// 現在の状態では受け付けないイベント
+    #[error("Invalid state transition: {event_type} cannot apply to {state}")]
+    UnexpectedEvent {
+        state: &'static str,
+        event_type: &'static str,
+    },
+    /// 別の集約のイベント
+    #[error("{event_type} of aggregate {found} cannot apply to aggregate {expected}")]
+    AggregateMismatch {
+        event_type: &'static str,
+        expected: Uuid,
+        found: Uuid,
+    },
+    /// 記録された延長回数が上限を超えている
+    #[error("Invalid extension count in persisted event: {0}")]
+    InvalidExtensionCount(u8),
+}
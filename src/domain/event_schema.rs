@@ -0,0 +1,395 @@
+//! ドメインイベントのJSON Schemaとバージョン管理されたレジストリ
+//!
+//! 各イベントのスキーマはイベント型から生成し、登録済みのバージョンは
+//! `schemas/events/<イベント型>.v<バージョン>.json`としてリポジトリで管理する。
+//! イベントの形を変えたときは、既存のファイルを書き換えずに新しいバージョンを追加する
+//! （生成したスキーマが最新の登録バージョンと一致することをテストで確認している）。
+//!
+//! スキーマは保存されるJSON（`{"BookLoaned": {...}}`の外部タグ形式）を、
+//! 会員IDの暗号化を解いた状態で記述する。
+
+use jsonschema::{Draft, JSONSchema};
+use schemars::{JsonSchema, r#gen::SchemaSettings};
+use serde::Serialize;
+use serde_json::{Value, json};
+use std::sync::OnceLock;
+use thiserror::Error;
+use uuid::Uuid;
+
+use super::events::{
+    BookLoaned, BookReturned, DomainEvent, LoanBecameOverdue, LoanDeclaredLost,
+    LoanDueSoonReminded, LoanExtended, MemberDataExported, OverdueNoticeSent,
+    ReadingHistoryPreferenceChanged,
+};
+
+/// スキーマの`$id`の接頭辞
+const SCHEMA_ID_PREFIX: &str = "urn:rusty-library:events";
+
+/// 登録済みのスキーマ（イベント型, バージョン, スキーマ本体）
+///
+/// 同じイベント型のバージョンは昇順に並べる。
+const REGISTERED: &[(&str, u32, &str)] = &[
+    (
+        "BookLoaned",
+        1,
+        include_str!("../../schemas/events/BookLoaned.v1.json"),
+    ),
+    (
+        "BookLoaned",
+        2,
+        include_str!("../../schemas/events/BookLoaned.v2.json"),
+    ),
+    (
+        "LoanExtended",
+        1,
+        include_str!("../../schemas/events/LoanExtended.v1.json"),
+    ),
+    (
+        "BookReturned",
+        1,
+        include_str!("../../schemas/events/BookReturned.v1.json"),
+    ),
+    (
+        "LoanBecameOverdue",
+        1,
+        include_str!("../../schemas/events/LoanBecameOverdue.v1.json"),
+    ),
+    (
+        "LoanDueSoonReminded",
+        1,
+        include_str!("../../schemas/events/LoanDueSoonReminded.v1.json"),
+    ),
+    (
+        "OverdueNoticeSent",
+        1,
+        include_str!("../../schemas/events/OverdueNoticeSent.v1.json"),
+    ),
+    (
+        "LoanDeclaredLost",
+        1,
+        include_str!("../../schemas/events/LoanDeclaredLost.v1.json"),
+    ),
+    (
+        "ReadingHistoryPreferenceChanged",
+        1,
+        include_str!("../../schemas/events/ReadingHistoryPreferenceChanged.v1.json"),
+    ),
+    (
+        "MemberDataExported",
+        1,
+        include_str!("../../schemas/events/MemberDataExported.v1.json"),
+    ),
+];
+
+/// スキーマ検証のエラー
+#[derive(Debug, Error)]
+pub enum EventSchemaError {
+    #[error("No schema is registered for event type {0}")]
+    UnknownEventType(String),
+
+    #[error("Event does not match schema {event_type} v{version}: {}", .errors.join("; "))]
+    Invalid {
+        event_type: String,
+        version: u32,
+        errors: Vec<String>,
+    },
+
+    #[error("Failed to serialize event: {0}")]
+    Serialization(#[from] serde_json::Error),
+}
+
+/// 登録済みのイベントスキーマ
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct EventSchema {
+    pub event_type: &'static str,
+    pub version: u32,
+    pub schema: Value,
+}
+
+/// バージョン管理されたイベントスキーマのレジストリ
+pub struct EventSchemaRegistry {
+    schemas: Vec<EventSchema>,
+    validators: Vec<JSONSchema>,
+}
+
+impl EventSchemaRegistry {
+    /// 登録済みのスキーマを読み込んだレジストリ（初回のみコンパイルする）
+    pub fn global() -> &'static EventSchemaRegistry {
+        static REGISTRY: OnceLock<EventSchemaRegistry> = OnceLock::new();
+        REGISTRY.get_or_init(|| {
+            let schemas = REGISTERED
+                .iter()
+                .map(|(event_type, version, schema)| EventSchema {
+                    event_type,
+                    version: *version,
+                    schema: serde_json::from_str(schema).expect("registered schema is valid JSON"),
+                })
+                .collect();
+            Self::new(schemas)
+        })
+    }
+
+    fn new(schemas: Vec<EventSchema>) -> Self {
+        let validators = schemas
+            .iter()
+            .map(|s| {
+                JSONSchema::options()
+                    .with_draft(Draft::Draft7)
+                    // draft-07には`uuid`形式がないため追加する
+                    .with_format("uuid", |value| Uuid::parse_str(value).is_ok())
+                    .compile(&s.schema)
+                    .expect("registered schema compiles")
+            })
+            .collect();
+        Self {
+            schemas,
+            validators,
+        }
+    }
+
+    /// 全てのスキーマ（イベント型ごとにバージョンの昇順）
+    pub fn schemas(&self) -> &[EventSchema] {
+        &self.schemas
+    }
+
+    /// イベント型の全バージョン
+    pub fn versions(&self, event_type: &str) -> Vec<&EventSchema> {
+        self.schemas
+            .iter()
+            .filter(|s| s.event_type == event_type)
+            .collect()
+    }
+
+    /// イベント型の指定バージョン
+    pub fn get(&self, event_type: &str, version: u32) -> Option<&EventSchema> {
+        self.schemas
+            .iter()
+            .find(|s| s.event_type == event_type && s.version == version)
+    }
+
+    /// イベント型の最新バージョン
+    pub fn latest(&self, event_type: &str) -> Option<&EventSchema> {
+        self.versions(event_type).into_iter().last()
+    }
+
+    /// これから書き込むイベントを最新バージョンのスキーマで検証
+    pub fn validate_event(&self, event: &DomainEvent) -> Result<(), EventSchemaError> {
+        let event_type = event.event_type();
+        let index = *self
+            .indices(event_type)
+            .last()
+            .ok_or_else(|| EventSchemaError::UnknownEventType(event_type.to_string()))?;
+        self.check(index, &serde_json::to_value(event)?)
+    }
+
+    /// 保存済みのイベントデータを検証し、一致したバージョンを返す
+    ///
+    /// 過去のバージョンで書き込まれたイベントも有効とする。
+    /// どのバージョンにも一致しない場合は、最新バージョンでのエラーを返す。
+    pub fn validate(&self, event_type: &str, event_data: &Value) -> Result<u32, EventSchemaError> {
+        let indices = self.indices(event_type);
+        let Some(&latest) = indices.last() else {
+            return Err(EventSchemaError::UnknownEventType(event_type.to_string()));
+        };
+
+        if let Some(&index) = indices
+            .iter()
+            .rev()
+            .find(|&&i| self.validators[i].is_valid(event_data))
+        {
+            return Ok(self.schemas[index].version);
+        }
+        self.check(latest, event_data)?;
+        Ok(self.schemas[latest].version)
+    }
+
+    fn indices(&self, event_type: &str) -> Vec<usize> {
+        (0..self.schemas.len())
+            .filter(|&i| self.schemas[i].event_type == event_type)
+            .collect()
+    }
+
+    fn check(&self, index: usize, value: &Value) -> Result<(), EventSchemaError> {
+        self.validators[index].validate(value).map_err(|errors| {
+            let schema = &self.schemas[index];
+            EventSchemaError::Invalid {
+                event_type: schema.event_type.to_string(),
+                version: schema.version,
+                errors: errors
+                    .map(|e| format!("{}: {}", e.instance_path, e))
+                    .collect(),
+            }
+        })
+    }
+}
+
+/// 現在のイベント型から生成したスキーマ（イベント型ごと）
+///
+/// 新しいバージョンを登録するときは、この出力をファイルに保存する。
+pub fn generate_schemas() -> Vec<(&'static str, Value)> {
+    vec![
+        generate::<BookLoaned>("BookLoaned"),
+        generate::<LoanExtended>("LoanExtended"),
+        generate::<BookReturned>("BookReturned"),
+        generate::<LoanBecameOverdue>("LoanBecameOverdue"),
+        generate::<LoanDueSoonReminded>("LoanDueSoonReminded"),
+        generate::<OverdueNoticeSent>("OverdueNoticeSent"),
+        generate::<LoanDeclaredLost>("LoanDeclaredLost"),
+        generate::<ReadingHistoryPreferenceChanged>("ReadingHistoryPreferenceChanged"),
+        generate::<MemberDataExported>("MemberDataExported"),
+    ]
+}
+
+/// イベント本体のスキーマを外部タグ形式で包む
+fn generate<T: JsonSchema>(event_type: &'static str) -> (&'static str, Value) {
+    let root = SchemaSettings::draft07()
+        .with(|s| s.inline_subschemas = true)
+        .into_generator()
+        .into_root_schema_for::<T>();
+    let mut body = serde_json::to_value(&root.schema).expect("schema serializes");
+    let description = body
+        .as_object_mut()
+        .and_then(|o| {
+            o.remove("title");
+            o.remove("description")
+        })
+        .unwrap_or(Value::Null);
+
+    let mut schema = json!({
+        "$schema": "http://json-schema.org/draft-07/schema#",
+        "title": event_type,
+        "type": "object",
+        "required": [event_type],
+        "properties": { event_type: body },
+        "additionalProperties": false,
+    });
+    if !description.is_null() {
+        schema["description"] = description;
+    }
+    (event_type, schema)
+}
+
+/// スキーマの`$id`（イベント型とバージョンから決まる）
+pub fn schema_id(event_type: &str, version: u32) -> String {
+    format!("{SCHEMA_ID_PREFIX}:{event_type}:v{version}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::value_objects::{BookId, LoanId, MemberId, StaffId};
+    use chrono::Utc;
+
+    fn loaned() -> DomainEvent {
+        let now = Utc::now();
+        DomainEvent::BookLoaned(BookLoaned {
+            loan_id: LoanId::new(),
+            book_id: BookId::new(),
+            member_id: MemberId::new(),
+            loaned_at: now,
+            due_date: now + chrono::Duration::days(14),
+            loaned_by: StaffId::new(),
+            override_token: None,
+        })
+    }
+
+    #[test]
+    fn test_latest_registered_schema_matches_event_types() {
+        let registry = EventSchemaRegistry::global();
+
+        for (event_type, generated) in generate_schemas() {
+            let latest = registry
+                .latest(event_type)
+                .unwrap_or_else(|| panic!("no schema registered for {event_type}"));
+            let mut registered = latest.schema.clone();
+            registered.as_object_mut().unwrap().remove("$id");
+            assert_eq!(
+                registered,
+                generated,
+                "{event_type} changed: register schemas/events/{event_type}.v{}.json with:\n{}",
+                latest.version + 1,
+                serde_json::to_string_pretty(&generated).unwrap()
+            );
+            assert_eq!(
+                latest.schema["$id"],
+                json!(schema_id(event_type, latest.version))
+            );
+        }
+    }
+
+    #[test]
+    fn test_every_event_validates_against_latest_schema() {
+        let registry = EventSchemaRegistry::global();
+        let event = loaned();
+
+        registry.validate_event(&event).unwrap();
+        let value = serde_json::to_value(&event).unwrap();
+        assert_eq!(registry.validate("BookLoaned", &value).unwrap(), 2);
+    }
+
+    #[test]
+    fn test_override_token_requires_book_loaned_v2() {
+        let registry = EventSchemaRegistry::global();
+        let DomainEvent::BookLoaned(plain) = loaned() else {
+            unreachable!()
+        };
+        let overridden = DomainEvent::BookLoaned(BookLoaned {
+            override_token: Some(crate::domain::OverrideToken {
+                rules: vec![crate::domain::EligibilityRule::LoanLimit],
+                reason: "Teacher borrowing class set".to_string(),
+                supervisor_id: StaffId::new(),
+            }),
+            ..plain
+        });
+        let value = serde_json::to_value(&overridden).unwrap();
+
+        // v1で書き込まれた（承認のない）貸出はv1のスキーマにも一致する
+        let v1 = registry.get("BookLoaned", 1).unwrap();
+        let v1 = JSONSchema::options()
+            .with_draft(Draft::Draft7)
+            .compile(&v1.schema)
+            .unwrap();
+        assert!(v1.is_valid(&serde_json::to_value(loaned()).unwrap()));
+        assert!(!v1.is_valid(&value));
+        assert_eq!(registry.validate("BookLoaned", &value).unwrap(), 2);
+    }
+
+    #[test]
+    fn test_malformed_event_data_is_rejected() {
+        let registry = EventSchemaRegistry::global();
+        let mut value = serde_json::to_value(loaned()).unwrap();
+        value["BookLoaned"]["due_date"] = json!("next week");
+        value["BookLoaned"]
+            .as_object_mut()
+            .unwrap()
+            .remove("loaned_by");
+
+        let err = registry.validate("BookLoaned", &value).unwrap_err();
+        match err {
+            EventSchemaError::Invalid {
+                version, errors, ..
+            } => {
+                assert_eq!(version, 2);
+                assert_eq!(errors.len(), 2, "{errors:?}");
+            }
+            other => panic!("unexpected error: {other}"),
+        }
+
+        // 種類の取り違え（タグと`event_type`の不一致）も検出する
+        let value = serde_json::to_value(loaned()).unwrap();
+        assert!(registry.validate("BookReturned", &value).is_err());
+        assert!(matches!(
+            registry.validate("BookLost", &value),
+            Err(EventSchemaError::UnknownEventType(_))
+        ));
+    }
+
+    #[test]
+    fn test_member_id_must_be_uuid() {
+        let registry = EventSchemaRegistry::global();
+        let mut value = serde_json::to_value(loaned()).unwrap();
+        value["BookLoaned"]["member_id"] = json!("not-a-member");
+
+        assert!(registry.validate("BookLoaned", &value).is_err());
+    }
+}
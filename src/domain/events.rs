@@ -1,10 +1,12 @@
 use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use super::{BookId, LoanId, MemberId, StaffId};
+use super::{BookId, LoanId, MemberId, OverdueNoticeLevel, OverrideToken, StaffId};
 
 /// イベント：書籍が貸出された
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[schemars(deny_unknown_fields)]
 pub struct BookLoaned {
     pub loan_id: LoanId,
     pub book_id: BookId,
@@ -12,20 +14,26 @@ pub struct BookLoaned {
     pub loaned_at: DateTime<Utc>,
     pub due_date: DateTime<Utc>,
     pub loaned_by: StaffId,
+    /// 貸出条件の例外を認めて貸し出した場合の承認内容
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub override_token: Option<OverrideToken>,
 }
 
 /// イベント：貸出が延長された
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[schemars(deny_unknown_fields)]
 pub struct LoanExtended {
     pub loan_id: LoanId,
     pub old_due_date: DateTime<Utc>,
     pub new_due_date: DateTime<Utc>,
     pub extended_at: DateTime<Utc>,
+    #[schemars(range(max = 255))]
     pub extension_count: u8,
 }
 
 /// イベント：書籍が返却された
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[schemars(deny_unknown_fields)]
 pub struct BookReturned {
     pub loan_id: LoanId,
     pub book_id: BookId,
@@ -35,7 +43,8 @@ pub struct BookReturned {
 }
 
 /// イベント：貸出が延滞した
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[schemars(deny_unknown_fields)]
 pub struct LoanBecameOverdue {
     pub loan_id: LoanId,
     pub book_id: BookId,
@@ -44,6 +53,74 @@ pub struct LoanBecameOverdue {
     pub detected_at: DateTime<Utc>,
 }
 
+/// イベント：延滞した貸出の督促を送った
+///
+/// 督促の段階（`level`）ごとに1回だけ記録される。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct OverdueNoticeSent {
+    pub loan_id: LoanId,
+    pub book_id: BookId,
+    pub member_id: MemberId,
+    pub due_date: DateTime<Utc>,
+    pub level: OverdueNoticeLevel,
+    pub sent_at: DateTime<Utc>,
+}
+
+/// イベント：延滞した貸出を紛失として扱った
+///
+/// 最終督促の後も返却されなかった貸出は紛失となり、弁償の対象になる。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct LoanDeclaredLost {
+    pub loan_id: LoanId,
+    pub book_id: BookId,
+    pub member_id: MemberId,
+    pub due_date: DateTime<Utc>,
+    pub declared_at: DateTime<Utc>,
+}
+
+/// イベント：返却期限が近いことを会員に知らせた
+///
+/// 返却期限（`due_date`）ごとに1回だけ記録される。
+/// 延長された貸出は、新しい返却期限について改めて記録される。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct LoanDueSoonReminded {
+    pub loan_id: LoanId,
+    pub book_id: BookId,
+    pub member_id: MemberId,
+    pub due_date: DateTime<Utc>,
+    pub reminded_at: DateTime<Utc>,
+}
+
+/// イベント：会員が読書履歴の保持設定を変更した
+///
+/// 既定では返却済みの貸出は保持期間の経過後に会員との紐付けが消される。
+/// `keep_history`がtrueの会員は、読書履歴として紐付けを保持する。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct ReadingHistoryPreferenceChanged {
+    pub member_id: MemberId,
+    pub keep_history: bool,
+    pub changed_at: DateTime<Utc>,
+}
+
+/// イベント：会員データの写しを提供した
+///
+/// 会員からの開示請求に応じ、職員の承認のもとで作成した記録。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct MemberDataExported {
+    pub member_id: MemberId,
+    pub exported_by: StaffId,
+    pub exported_at: DateTime<Utc>,
+    /// 写しに含めたイベントの件数
+    pub event_count: usize,
+    /// 写しに含めた貸出の件数
+    pub loan_count: usize,
+}
+
 /// ドメインイベント統合型
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum DomainEvent {
@@ -51,4 +128,41 @@ pub enum DomainEvent {
     LoanExtended(LoanExtended),
     BookReturned(BookReturned),
     LoanBecameOverdue(LoanBecameOverdue),
+    LoanDueSoonReminded(LoanDueSoonReminded),
+    OverdueNoticeSent(OverdueNoticeSent),
+    LoanDeclaredLost(LoanDeclaredLost),
+    ReadingHistoryPreferenceChanged(ReadingHistoryPreferenceChanged),
+    MemberDataExported(MemberDataExported),
+}
+
+impl DomainEvent {
+    /// イベントの種類（保存時の`event_type`、スキーマレジストリのキー）
+    pub fn event_type(&self) -> &'static str {
+        match self {
+            DomainEvent::BookLoaned(_) => "BookLoaned",
+            DomainEvent::LoanExtended(_) => "LoanExtended",
+            DomainEvent::BookReturned(_) => "BookReturned",
+            DomainEvent::LoanBecameOverdue(_) => "LoanBecameOverdue",
+            DomainEvent::LoanDueSoonReminded(_) => "LoanDueSoonReminded",
+            DomainEvent::OverdueNoticeSent(_) => "OverdueNoticeSent",
+            DomainEvent::LoanDeclaredLost(_) => "LoanDeclaredLost",
+            DomainEvent::ReadingHistoryPreferenceChanged(_) => "ReadingHistoryPreferenceChanged",
+            DomainEvent::MemberDataExported(_) => "MemberDataExported",
+        }
+    }
+
+    /// イベントが発生した日時（保存時の`occurred_at`）
+    pub fn occurred_at(&self) -> DateTime<Utc> {
+        match self {
+            DomainEvent::BookLoaned(e) => e.loaned_at,
+            DomainEvent::LoanExtended(e) => e.extended_at,
+            DomainEvent::BookReturned(e) => e.returned_at,
+            DomainEvent::LoanBecameOverdue(e) => e.detected_at,
+            DomainEvent::LoanDueSoonReminded(e) => e.reminded_at,
+            DomainEvent::OverdueNoticeSent(e) => e.sent_at,
+            DomainEvent::LoanDeclaredLost(e) => e.declared_at,
+            DomainEvent::ReadingHistoryPreferenceChanged(e) => e.changed_at,
+            DomainEvent::MemberDataExported(e) => e.exported_at,
+        }
+    }
 }
@@ -4,20 +4,28 @@ use chrono::{DateTime, Duration, Utc};
 use serde::{Deserialize, Serialize};
 
 use super::{
-    BookId, BookLoaned, BookReturned, DomainEvent, ExtendLoanError, ExtensionCount, LoanBookError,
-    LoanExtended, LoanId, MemberId, ReturnBookError, StaffId,
+    Aggregate, BookId, BookLoaned, BookReturned, CirculationPolicy, DomainEvent, ExtendLoanError,
+    ExtensionCount, InvalidTransition, LoanBecameOverdue, LoanBookError, LoanDeclaredLost,
+    LoanDueSoonReminded, LoanExtended, LoanId, MemberId, OverdueNoticeLevel, OverdueNoticeSent,
+    OverrideToken, RemindDueSoonError, ReturnBookError, StaffId,
 };
 
 /// 貸出期間（日数）
 pub const LOAN_PERIOD_DAYS: i64 = 14;
 
+/// 返却期限のリマインダーを送る期間（返却期限の何日前から）
+pub const DUE_SOON_REMINDER_DAYS: i64 = 3;
+
+/// 延滞した貸出を紛失として扱う、返却期限からの経過日数
+pub const LOST_DECLARATION_DAYS: i64 = 60;
+
 // ============================================================================
 // 型安全な状態パターン
 // ============================================================================
 
 /// Loan集約の共通フィールド
 ///
-/// すべての貸出状態（Active, Overdue, Returned）で共有されるコアデータ。
+/// すべての貸出状態（Active, Overdue, Returned, Lost）で共有されるコアデータ。
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct LoanCore {
     // 識別子
@@ -43,10 +51,14 @@ pub struct LoanCore {
 /// ビジネスルール：
 /// - 返却期限内
 /// - 延長可能（extension_count < 1）
+/// - 返却期限のリマインダーは返却期限ごとに1回まで
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ActiveLoan {
     #[serde(flatten)]
     pub core: LoanCore,
+    /// 返却期限のリマインダーを送った返却期限（延長後の新しい返却期限には改めて送る）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reminded_due_date: Option<DateTime<Utc>>,
 }
 
 impl std::ops::Deref for ActiveLoan {
@@ -62,10 +74,14 @@ impl std::ops::Deref for ActiveLoan {
 /// ビジネスルール：
 /// - 返却期限を過ぎている
 /// - 延長不可
+/// - 督促は段階ごとに1回まで
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct OverdueLoan {
     #[serde(flatten)]
     pub core: LoanCore,
+    /// 送った督促の最も進んだ段階（まだ督促していなければNone）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notice_level: Option<OverdueNoticeLevel>,
 }
 
 impl std::ops::Deref for OverdueLoan {
@@ -96,6 +112,27 @@ impl std::ops::Deref for ReturnedLoan {
     }
 }
 
+/// 紛失状態
+///
+/// ビジネスルール：
+/// - 最終督促の後も返却されず、紛失として弁償の対象になった
+/// - 延長不可
+/// - 見つかって返却された場合は受け付ける
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LostLoan {
+    #[serde(flatten)]
+    pub core: LoanCore,
+    pub declared_lost_at: DateTime<Utc>,
+}
+
+impl std::ops::Deref for LostLoan {
+    type Target = LoanCore;
+
+    fn deref(&self) -> &Self::Target {
+        &self.core
+    }
+}
+
 /// Loan集約の統合型
 ///
 /// 型安全な状態パターン：
@@ -107,6 +144,7 @@ pub enum Loan {
     Active(ActiveLoan),
     Overdue(OverdueLoan),
     Returned(ReturnedLoan),
+    Lost(LostLoan),
 }
 
 // ============================================================================
@@ -126,9 +164,28 @@ pub fn loan_book(
     member_id: MemberId,
     loaned_at: DateTime<Utc>,
     staff_id: StaffId,
+) -> Result<(ActiveLoan, BookLoaned), LoanBookError> {
+    loan_book_with_policy(
+        book_id,
+        member_id,
+        loaned_at,
+        staff_id,
+        &CirculationPolicy::default(),
+    )
+}
+
+/// 純粋関数：貸出ポリシーに従って書籍を貸し出す
+///
+/// 貸出期間を図書館ごとのポリシーから決定する点以外は`loan_book`と同じ。
+pub fn loan_book_with_policy(
+    book_id: BookId,
+    member_id: MemberId,
+    loaned_at: DateTime<Utc>,
+    staff_id: StaffId,
+    policy: &CirculationPolicy,
 ) -> Result<(ActiveLoan, BookLoaned), LoanBookError> {
     let loan_id = LoanId::new();
-    let due_date = loaned_at + Duration::days(LOAN_PERIOD_DAYS);
+    let due_date = loaned_at + policy.loan_period();
 
     let loan = ActiveLoan {
         core: LoanCore {
@@ -142,6 +199,7 @@ pub fn loan_book(
             created_at: loaned_at,
             updated_at: loaned_at,
         },
+        reminded_due_date: None,
     };
 
     let event = BookLoaned {
@@ -151,11 +209,38 @@ pub fn loan_book(
         loaned_at,
         due_date,
         loaned_by: staff_id,
+        override_token: None,
     };
 
     Ok((loan, event))
 }
 
+/// 純粋関数：貸出条件の例外を認めて書籍を貸し出す
+///
+/// 貸出条件の判定はアプリケーション層で行う（例外を認めた条件は判定しない）。
+/// 承認内容は`BookLoaned`に記録され、監査に使われる。
+pub fn loan_book_with_override(
+    book_id: BookId,
+    member_id: MemberId,
+    loaned_at: DateTime<Utc>,
+    staff_id: StaffId,
+    policy: &CirculationPolicy,
+    override_token: OverrideToken,
+) -> Result<(ActiveLoan, BookLoaned), LoanBookError> {
+    override_token
+        .validate()
+        .map_err(LoanBookError::InvalidOverride)?;
+
+    let (loan, event) = loan_book_with_policy(book_id, member_id, loaned_at, staff_id, policy)?;
+    Ok((
+        loan,
+        BookLoaned {
+            override_token: Some(override_token),
+            ..event
+        },
+    ))
+}
+
 /// 純粋関数：貸出を延長する
 ///
 /// ビジネスルール：
@@ -167,6 +252,17 @@ pub fn loan_book(
 pub fn extend_loan(
     loan: ActiveLoan,
     extended_at: DateTime<Utc>,
+) -> Result<(ActiveLoan, LoanExtended), ExtendLoanError> {
+    extend_loan_with_policy(loan, extended_at, &CirculationPolicy::default())
+}
+
+/// 純粋関数：貸出ポリシーに従って貸出を延長する
+///
+/// 延長期間を図書館ごとのポリシーから決定する点以外は`extend_loan`と同じ。
+pub fn extend_loan_with_policy(
+    loan: ActiveLoan,
+    extended_at: DateTime<Utc>,
+    policy: &CirculationPolicy,
 ) -> Result<(ActiveLoan, LoanExtended), ExtendLoanError> {
     // バリデーション：延長可能か（回数制限）
     if !loan.extension_count.can_extend() {
@@ -176,7 +272,7 @@ pub fn extend_loan(
     // 新しい返却期限を計算（必要な値を先に確保してから move）
     let loan_id = loan.loan_id;
     let old_due_date = loan.due_date;
-    let new_due_date = old_due_date + Duration::days(LOAN_PERIOD_DAYS);
+    let new_due_date = old_due_date + policy.loan_period();
     let new_extension_count = loan.extension_count.increment()?;
 
     // 新しいActiveLoanを生成
@@ -187,6 +283,7 @@ pub fn extend_loan(
             updated_at: extended_at,
             ..loan.core
         },
+        reminded_due_date: None,
     };
 
     let event = LoanExtended {
@@ -200,11 +297,45 @@ pub fn extend_loan(
     Ok((new_loan, event))
 }
 
+/// 純粋関数：返却期限が近いことを会員に知らせる
+///
+/// ビジネスルール：
+/// - ActiveLoanのみ受け付ける（型で保証）
+/// - 返却期限ごとに1回まで（延長された貸出は新しい返却期限について改めて知らせる）
+/// - 返却期限を過ぎた貸出には知らせない（延滞の通知の対象）
+///
+/// 副作用なし。新しいActiveLoanとイベントを返す。
+pub fn remind_due_soon(
+    loan: ActiveLoan,
+    reminded_at: DateTime<Utc>,
+) -> Result<(ActiveLoan, LoanDueSoonReminded), RemindDueSoonError> {
+    if loan.reminded_due_date == Some(loan.due_date) {
+        return Err(RemindDueSoonError::AlreadyReminded);
+    }
+    if reminded_at > loan.due_date {
+        return Err(RemindDueSoonError::PastDue);
+    }
+
+    let event = LoanDueSoonReminded {
+        loan_id: loan.loan_id,
+        book_id: loan.book_id,
+        member_id: loan.member_id,
+        due_date: loan.due_date,
+        reminded_at,
+    };
+    let reminded_loan = ActiveLoan {
+        reminded_due_date: Some(loan.due_date),
+        ..loan
+    };
+
+    Ok((reminded_loan, event))
+}
+
 /// 純粋関数：書籍を返却する
 ///
 /// ビジネスルール：
-/// - ActiveまたはOverdueLoanを受け付ける
-/// - 延滞していても返却は受け付ける
+/// - Active, OverdueまたはLostLoanを受け付ける
+/// - 延滞していても返却は受け付ける（紛失として扱った貸出が見つかった場合も）
 /// - 延滞料金なし（公立図書館）
 ///
 /// 副作用なし。ReturnedLoanとイベントを返す。
@@ -262,16 +393,103 @@ pub fn return_book(
 
             Ok((returned_loan, event))
         }
+        Loan::Lost(lost) => {
+            // 先にID類を取り出してから core を move
+            let loan_id = lost.loan_id;
+            let book_id = lost.book_id;
+            let member_id = lost.member_id;
+
+            let returned_loan = ReturnedLoan {
+                core: LoanCore {
+                    updated_at: returned_at,
+                    ..lost.core
+                },
+                returned_at,
+            };
+
+            let event = BookReturned {
+                loan_id,
+                book_id,
+                member_id,
+                returned_at,
+                was_overdue: true,
+            };
+
+            Ok((returned_loan, event))
+        }
         Loan::Returned(_) => Err(ReturnBookError::AlreadyReturned),
     }
 }
 
+/// 延滞した貸出の督促の進め方
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OverdueEscalation {
+    /// 督促を送る
+    Notice(OverdueLoan, OverdueNoticeSent),
+    /// 紛失として扱う
+    DeclaredLost(LostLoan, LoanDeclaredLost),
+}
+
+/// 純粋関数：延滞した貸出の督促を進める
+///
+/// ビジネスルール：
+/// - 返却期限からの経過日数に応じて督促する（1日: 1回目、14日: 2回目、30日: 最終督促）
+/// - 各段階の督促は1回まで。複数の段階の時期を過ぎている場合は最も進んだ段階だけを送る
+/// - 延滞`LOST_DECLARATION_DAYS`日で紛失として扱う（最終督促を送った貸出のみ）
+///
+/// 副作用なし。基準日時（`as_of`）の時点で進める段階がなければNoneを返す。
+pub fn escalate_overdue(loan: OverdueLoan, as_of: DateTime<Utc>) -> Option<OverdueEscalation> {
+    let overdue_for = as_of - loan.due_date;
+
+    if loan.notice_level == Some(OverdueNoticeLevel::Final)
+        && overdue_for >= Duration::days(LOST_DECLARATION_DAYS)
+    {
+        let event = LoanDeclaredLost {
+            loan_id: loan.loan_id,
+            book_id: loan.book_id,
+            member_id: loan.member_id,
+            due_date: loan.due_date,
+            declared_at: as_of,
+        };
+        let lost_loan = LostLoan {
+            core: LoanCore {
+                updated_at: as_of,
+                ..loan.core
+            },
+            declared_lost_at: as_of,
+        };
+        return Some(OverdueEscalation::DeclaredLost(lost_loan, event));
+    }
+
+    let level = OverdueNoticeLevel::ALL
+        .into_iter()
+        .rev()
+        .find(|level| overdue_for >= Duration::days(level.days_overdue()))?;
+    if loan.notice_level.is_some_and(|sent| sent >= level) {
+        return None;
+    }
+
+    let event = OverdueNoticeSent {
+        loan_id: loan.loan_id,
+        book_id: loan.book_id,
+        member_id: loan.member_id,
+        due_date: loan.due_date,
+        level,
+        sent_at: as_of,
+    };
+    let noticed_loan = OverdueLoan {
+        notice_level: Some(level),
+        ..loan
+    };
+    Some(OverdueEscalation::Notice(noticed_loan, event))
+}
+
 /// 純粋関数：延滞判定
 ///
 /// パターンマッチで状態判定を行う。
 pub fn is_overdue(loan: &Loan, now: DateTime<Utc>) -> bool {
     match loan {
-        Loan::Overdue(_) => true,
+        Loan::Overdue(_) | Loan::Lost(_) => true,
         Loan::Active(a) => now > a.due_date,
         Loan::Returned(_) => false,
     }
@@ -292,7 +510,15 @@ pub fn is_overdue(loan: &Loan, now: DateTime<Utc>) -> bool {
 /// # Panics
 /// 不正な状態遷移（例: Returned状態からの延長）の場合にpanicする
 pub fn apply_event(loan: Option<Loan>, event: &DomainEvent) -> Loan {
-    match (loan, event) {
+    try_apply_event(loan, event).unwrap_or_else(|e| panic!("{}", e))
+}
+
+/// イベントを適用して新しい状態を生成する純粋関数（不正な遷移はエラー）
+///
+/// 記録されたイベント列の復元に使用される。破損したイベント列や
+/// 手で編集されたバックアップでも、panicせずに`InvalidTransition`を返す。
+pub fn try_apply_event(loan: Option<Loan>, event: &DomainEvent) -> Result<Loan, InvalidTransition> {
+    let loan = match (loan, event) {
         // BookLoaned: 初期状態（None）からのみ受け入れる
         (None, DomainEvent::BookLoaned(e)) => Loan::Active(ActiveLoan {
             core: LoanCore {
@@ -306,20 +532,17 @@ pub fn apply_event(loan: Option<Loan>, event: &DomainEvent) -> Loan {
                 created_at: e.loaned_at,
                 updated_at: e.loaned_at,
             },
+            reminded_due_date: None,
         }),
-        (Some(_), DomainEvent::BookLoaned(e)) => panic!(
-            "Invalid state transition: BookLoaned({:?}) cannot apply to an existing loan",
-            e.loan_id
-        ),
+        (Some(loan), DomainEvent::BookLoaned(_)) => {
+            return Err(unexpected_event(Some(&loan), event));
+        }
 
         // LoanExtended: Active状態からのみ可能
         (Some(Loan::Active(active)), DomainEvent::LoanExtended(e)) => {
-            assert_eq!(
-                active.loan_id, e.loan_id,
-                "LoanExtended loan_id does not match current loan"
-            );
+            check_loan_id(active.loan_id, e.loan_id, event)?;
             let extension_count = ExtensionCount::try_from(e.extension_count)
-                .expect("Invalid extension_count in persisted event");
+                .map_err(|_| InvalidTransition::InvalidExtensionCount(e.extension_count))?;
 
             Loan::Active(ActiveLoan {
                 core: LoanCore {
@@ -328,15 +551,13 @@ pub fn apply_event(loan: Option<Loan>, event: &DomainEvent) -> Loan {
                     updated_at: e.extended_at,
                     ..active.core
                 },
+                reminded_due_date: None,
             })
         }
 
         // BookReturned: ActiveまたはOverdue状態から可能
         (Some(Loan::Active(active)), DomainEvent::BookReturned(e)) => {
-            assert_eq!(
-                active.loan_id, e.loan_id,
-                "BookReturned loan_id does not match current loan"
-            );
+            check_loan_id(active.loan_id, e.loan_id, event)?;
             Loan::Returned(ReturnedLoan {
                 core: LoanCore {
                     updated_at: e.returned_at,
@@ -346,10 +567,7 @@ pub fn apply_event(loan: Option<Loan>, event: &DomainEvent) -> Loan {
             })
         }
         (Some(Loan::Overdue(overdue)), DomainEvent::BookReturned(e)) => {
-            assert_eq!(
-                overdue.loan_id, e.loan_id,
-                "BookReturned loan_id does not match current loan"
-            );
+            check_loan_id(overdue.loan_id, e.loan_id, event)?;
             Loan::Returned(ReturnedLoan {
                 core: LoanCore {
                     updated_at: e.returned_at,
@@ -361,23 +579,92 @@ pub fn apply_event(loan: Option<Loan>, event: &DomainEvent) -> Loan {
 
         // LoanBecameOverdue: Active状態からのみ可能
         (Some(Loan::Active(active)), DomainEvent::LoanBecameOverdue(e)) => {
-            assert_eq!(
-                active.loan_id, e.loan_id,
-                "LoanBecameOverdue loan_id does not match current loan"
-            );
+            check_loan_id(active.loan_id, e.loan_id, event)?;
             Loan::Overdue(OverdueLoan {
                 core: LoanCore {
                     updated_at: e.detected_at,
                     ..active.core
                 },
+                notice_level: None,
+            })
+        }
+
+        // LoanDueSoonReminded: Active状態からのみ可能（状態は変わらない）
+        (Some(Loan::Active(active)), DomainEvent::LoanDueSoonReminded(e)) => {
+            check_loan_id(active.loan_id, e.loan_id, event)?;
+            Loan::Active(ActiveLoan {
+                reminded_due_date: Some(e.due_date),
+                ..active
+            })
+        }
+
+        // OverdueNoticeSent: Overdue状態からのみ可能（状態は変わらない）
+        (Some(Loan::Overdue(overdue)), DomainEvent::OverdueNoticeSent(e)) => {
+            check_loan_id(overdue.loan_id, e.loan_id, event)?;
+            Loan::Overdue(OverdueLoan {
+                notice_level: Some(e.level),
+                ..overdue
+            })
+        }
+
+        // LoanDeclaredLost: Overdue状態からのみ可能
+        (Some(Loan::Overdue(overdue)), DomainEvent::LoanDeclaredLost(e)) => {
+            check_loan_id(overdue.loan_id, e.loan_id, event)?;
+            Loan::Lost(LostLoan {
+                core: LoanCore {
+                    updated_at: e.declared_at,
+                    ..overdue.core
+                },
+                declared_lost_at: e.declared_at,
+            })
+        }
+
+        // BookReturned: 紛失として扱った貸出が見つかった場合
+        (Some(Loan::Lost(lost)), DomainEvent::BookReturned(e)) => {
+            check_loan_id(lost.loan_id, e.loan_id, event)?;
+            Loan::Returned(ReturnedLoan {
+                core: LoanCore {
+                    updated_at: e.returned_at,
+                    ..lost.core
+                },
+                returned_at: e.returned_at,
             })
         }
 
         // 不正な状態遷移
-        (loan, event) => panic!(
-            "Invalid state transition: loan={:?}, event={:?}",
-            loan, event
-        ),
+        (loan, event) => return Err(unexpected_event(loan.as_ref(), event)),
+    };
+    Ok(loan)
+}
+
+/// 現在の状態では受け付けないイベントのエラー
+fn unexpected_event(loan: Option<&Loan>, event: &DomainEvent) -> InvalidTransition {
+    InvalidTransition::UnexpectedEvent {
+        state: match loan {
+            None => "a new loan",
+            Some(Loan::Active(_)) => "an active loan",
+            Some(Loan::Overdue(_)) => "an overdue loan",
+            Some(Loan::Returned(_)) => "a returned loan",
+            Some(Loan::Lost(_)) => "a lost loan",
+        },
+        event_type: event.event_type(),
+    }
+}
+
+/// イベントが現在の貸出のものであることを確認する
+fn check_loan_id(
+    loan_id: LoanId,
+    event_loan_id: LoanId,
+    event: &DomainEvent,
+) -> Result<(), InvalidTransition> {
+    if loan_id == event_loan_id {
+        Ok(())
+    } else {
+        Err(InvalidTransition::AggregateMismatch {
+            event_type: event.event_type(),
+            expected: loan_id.value(),
+            found: event_loan_id.value(),
+        })
     }
 }
 
@@ -399,10 +686,107 @@ pub fn replay_events(events: &[DomainEvent]) -> Option<Loan> {
         .fold(None, |loan, event| Some(apply_event(loan, event)))
 }
 
+/// 貸出のイベント
+///
+/// `DomainEvent`のうち貸出に関するもの（BookLoaned, LoanExtended, BookReturned,
+/// LoanBecameOverdue, LoanDueSoonReminded, OverdueNoticeSent, LoanDeclaredLost）だけを保持する。
+/// 他の集約のイベントからの変換は失敗し、元のイベントが返される。
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoanEvent(DomainEvent);
+
+impl LoanEvent {
+    pub fn as_domain_event(&self) -> &DomainEvent {
+        &self.0
+    }
+}
+
+impl TryFrom<DomainEvent> for LoanEvent {
+    type Error = DomainEvent;
+
+    fn try_from(event: DomainEvent) -> Result<Self, Self::Error> {
+        match event {
+            DomainEvent::BookLoaned(_)
+            | DomainEvent::LoanExtended(_)
+            | DomainEvent::BookReturned(_)
+            | DomainEvent::LoanBecameOverdue(_)
+            | DomainEvent::LoanDueSoonReminded(_)
+            | DomainEvent::OverdueNoticeSent(_)
+            | DomainEvent::LoanDeclaredLost(_) => Ok(Self(event)),
+            DomainEvent::ReadingHistoryPreferenceChanged(_)
+            | DomainEvent::MemberDataExported(_) => Err(event),
+        }
+    }
+}
+
+impl From<LoanEvent> for DomainEvent {
+    fn from(event: LoanEvent) -> Self {
+        event.0
+    }
+}
+
+impl From<BookLoaned> for LoanEvent {
+    fn from(event: BookLoaned) -> Self {
+        Self(DomainEvent::BookLoaned(event))
+    }
+}
+
+impl From<LoanExtended> for LoanEvent {
+    fn from(event: LoanExtended) -> Self {
+        Self(DomainEvent::LoanExtended(event))
+    }
+}
+
+impl From<BookReturned> for LoanEvent {
+    fn from(event: BookReturned) -> Self {
+        Self(DomainEvent::BookReturned(event))
+    }
+}
+
+impl From<LoanBecameOverdue> for LoanEvent {
+    fn from(event: LoanBecameOverdue) -> Self {
+        Self(DomainEvent::LoanBecameOverdue(event))
+    }
+}
+
+impl From<LoanDueSoonReminded> for LoanEvent {
+    fn from(event: LoanDueSoonReminded) -> Self {
+        Self(DomainEvent::LoanDueSoonReminded(event))
+    }
+}
+
+impl From<OverdueNoticeSent> for LoanEvent {
+    fn from(event: OverdueNoticeSent) -> Self {
+        Self(DomainEvent::OverdueNoticeSent(event))
+    }
+}
+
+impl From<LoanDeclaredLost> for LoanEvent {
+    fn from(event: LoanDeclaredLost) -> Self {
+        Self(DomainEvent::LoanDeclaredLost(event))
+    }
+}
+
+/// Loanはイベントソーシングされる集約
+///
+/// 貸出のイベントは`LoanEvent`で、状態遷移は`apply_event`に従う。
+/// 他の集約のイベントは`LoanEvent`に変換できないため、復元時に`Corrupted`として拒否される。
+impl Aggregate for Loan {
+    type Id = LoanId;
+    type Event = LoanEvent;
+
+    fn aggregate_type() -> &'static str {
+        "Loan"
+    }
+
+    fn try_apply(state: Option<Self>, event: &LoanEvent) -> Result<Self, InvalidTransition> {
+        try_apply_event(state, event.as_domain_event())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::domain::LoanBecameOverdue;
+    use chrono::Duration;
 
     // TDD: apply_event() と replay_events() のテスト
     #[test]
@@ -421,6 +805,7 @@ mod tests {
             loaned_at,
             due_date,
             loaned_by: staff_id,
+            override_token: None,
         });
 
         let loan = apply_event(None, &event);
@@ -561,6 +946,7 @@ mod tests {
                 loaned_at,
                 due_date,
                 loaned_by: staff_id,
+                override_token: None,
             }),
             DomainEvent::LoanExtended(LoanExtended {
                 loan_id,
@@ -619,6 +1005,7 @@ mod tests {
                 created_at: loaned_at,
                 updated_at: loaned_at,
             },
+            reminded_due_date: None,
         };
 
         // Derefでcore.loan_idに直接アクセスできることを確認
@@ -650,6 +1037,7 @@ mod tests {
                 created_at: loaned_at,
                 updated_at: loaned_at,
             },
+            notice_level: None,
         };
 
         // Derefでcore.loan_idに直接アクセスできることを確認
@@ -712,6 +1100,7 @@ mod tests {
                 created_at: loaned_at,
                 updated_at: loaned_at,
             },
+            reminded_due_date: None,
         };
         let loan = Loan::Active(active_loan.clone());
 
@@ -725,6 +1114,7 @@ mod tests {
         // OverdueLoan
         let overdue_loan = OverdueLoan {
             core: active_loan.core.clone(),
+            notice_level: None,
         };
         let loan = Loan::Overdue(overdue_loan);
 
@@ -827,6 +1217,32 @@ mod tests {
         assert_eq!(loan.extension_count.value(), 0);
     }
 
+    #[test]
+    fn test_loan_book_with_policy_uses_policy_loan_period() {
+        let loaned_at = Utc::now();
+        let policy = CirculationPolicy {
+            max_active_loans: 10,
+            loan_period_days: 21,
+            history_retention_days: None,
+        };
+
+        let (loan, event) = loan_book_with_policy(
+            BookId::new(),
+            MemberId::new(),
+            loaned_at,
+            StaffId::new(),
+            &policy,
+        )
+        .unwrap();
+
+        assert_eq!(loan.due_date, loaned_at + Duration::days(21));
+        assert_eq!(event.due_date, loan.due_date);
+
+        let (extended, _) =
+            extend_loan_with_policy(loan, loaned_at + Duration::days(3), &policy).unwrap();
+        assert_eq!(extended.due_date, loaned_at + Duration::days(42));
+    }
+
     // TDD: extend_loan() のテスト
     #[test]
     fn test_extend_loan_success_with_active_loan() {
@@ -909,6 +1325,205 @@ mod tests {
         let _active: ActiveLoan = new_loan;
     }
 
+    // remind_due_soon() のテスト
+    #[test]
+    fn test_remind_due_soon_once_per_due_date() {
+        let loaned_at = Utc::now();
+        let (loan, _) =
+            loan_book(BookId::new(), MemberId::new(), loaned_at, StaffId::new()).unwrap();
+        let reminded_at = loan.due_date - Duration::days(DUE_SOON_REMINDER_DAYS);
+
+        let (reminded, event) = remind_due_soon(loan.clone(), reminded_at).unwrap();
+        assert_eq!(event.loan_id, loan.loan_id);
+        assert_eq!(event.member_id, loan.member_id);
+        assert_eq!(event.due_date, loan.due_date);
+        assert_eq!(reminded.reminded_due_date, Some(loan.due_date));
+
+        // 同じ返却期限には二度知らせない
+        assert_eq!(
+            remind_due_soon(reminded.clone(), reminded_at + Duration::days(1)).unwrap_err(),
+            RemindDueSoonError::AlreadyReminded
+        );
+
+        // 延長後は新しい返却期限について改めて知らせる
+        let (extended, _) = extend_loan(reminded, reminded_at + Duration::days(1)).unwrap();
+        let (_, event) =
+            remind_due_soon(extended.clone(), extended.due_date - Duration::days(2)).unwrap();
+        assert_eq!(event.due_date, extended.due_date);
+    }
+
+    #[test]
+    fn test_remind_due_soon_fails_when_past_due() {
+        let loaned_at = Utc::now();
+        let (loan, _) =
+            loan_book(BookId::new(), MemberId::new(), loaned_at, StaffId::new()).unwrap();
+        let after_due = loan.due_date + Duration::hours(1);
+
+        assert_eq!(
+            remind_due_soon(loan, after_due).unwrap_err(),
+            RemindDueSoonError::PastDue
+        );
+    }
+
+    #[test]
+    fn test_replay_restores_reminded_due_date() {
+        let loaned_at = Utc::now();
+        let (loan, loaned) =
+            loan_book(BookId::new(), MemberId::new(), loaned_at, StaffId::new()).unwrap();
+        let (_, reminded) = remind_due_soon(loan, loaned.due_date - Duration::days(1)).unwrap();
+
+        let events = vec![
+            DomainEvent::BookLoaned(loaned.clone()),
+            DomainEvent::LoanDueSoonReminded(reminded),
+        ];
+        match replay_events(&events) {
+            Some(Loan::Active(active)) => {
+                assert_eq!(active.reminded_due_date, Some(loaned.due_date));
+                // リマインダーは貸出の内容を変えない
+                assert_eq!(active.updated_at, loaned.loaned_at);
+            }
+            other => panic!("Expected Loan::Active, got {:?}", other),
+        }
+    }
+
+    // escalate_overdue() のテスト
+    fn overdue_loan(loaned_at: DateTime<Utc>) -> OverdueLoan {
+        let (loan, _) =
+            loan_book(BookId::new(), MemberId::new(), loaned_at, StaffId::new()).unwrap();
+        OverdueLoan {
+            core: loan.core,
+            notice_level: None,
+        }
+    }
+
+    fn days_overdue(loan: &OverdueLoan, days: i64) -> DateTime<Utc> {
+        loan.due_date + Duration::days(days)
+    }
+
+    #[test]
+    fn test_escalate_overdue_sends_each_level_once() {
+        let loan = overdue_loan(Utc::now());
+
+        // 延滞1日に満たなければ督促しない
+        assert!(escalate_overdue(loan.clone(), days_overdue(&loan, 0)).is_none());
+
+        let mut current = loan.clone();
+        for level in OverdueNoticeLevel::ALL {
+            let as_of = days_overdue(&loan, level.days_overdue());
+            let Some(OverdueEscalation::Notice(noticed, event)) = escalate_overdue(current, as_of)
+            else {
+                panic!("Expected {:?} notice", level);
+            };
+            assert_eq!(event.level, level);
+            assert_eq!(event.loan_id, loan.loan_id);
+            assert_eq!(event.due_date, loan.due_date);
+            assert_eq!(event.sent_at, as_of);
+            assert_eq!(noticed.notice_level, Some(level));
+
+            // 同じ段階の督促は二度送らない
+            assert!(escalate_overdue(noticed.clone(), as_of + Duration::days(1)).is_none());
+            current = noticed;
+        }
+    }
+
+    #[test]
+    fn test_escalate_overdue_catches_up_to_highest_level() {
+        let loan = overdue_loan(Utc::now());
+
+        // 督促が止まっていた場合は最も進んだ段階だけを送る
+        match escalate_overdue(loan.clone(), days_overdue(&loan, 40)) {
+            Some(OverdueEscalation::Notice(noticed, event)) => {
+                assert_eq!(event.level, OverdueNoticeLevel::Final);
+                assert_eq!(noticed.notice_level, Some(OverdueNoticeLevel::Final));
+            }
+            _ => panic!("Expected final notice"),
+        }
+
+        // 最終督促の前に紛失にはしない
+        match escalate_overdue(loan.clone(), days_overdue(&loan, LOST_DECLARATION_DAYS)) {
+            Some(OverdueEscalation::Notice(_, event)) => {
+                assert_eq!(event.level, OverdueNoticeLevel::Final)
+            }
+            _ => panic!("Expected final notice"),
+        }
+    }
+
+    #[test]
+    fn test_escalate_overdue_declares_lost_after_final_notice() {
+        let loan = OverdueLoan {
+            notice_level: Some(OverdueNoticeLevel::Final),
+            ..overdue_loan(Utc::now())
+        };
+
+        assert!(escalate_overdue(loan.clone(), days_overdue(&loan, 59)).is_none());
+
+        let declared_at = days_overdue(&loan, LOST_DECLARATION_DAYS);
+        let Some(OverdueEscalation::DeclaredLost(lost, event)) =
+            escalate_overdue(loan.clone(), declared_at)
+        else {
+            panic!("Expected lost declaration");
+        };
+        assert_eq!(event.loan_id, loan.loan_id);
+        assert_eq!(event.member_id, loan.member_id);
+        assert_eq!(event.declared_at, declared_at);
+        assert_eq!(lost.declared_lost_at, declared_at);
+        assert!(is_overdue(&Loan::Lost(lost), declared_at));
+    }
+
+    #[test]
+    fn test_replay_restores_notice_level_and_returns_lost_loan() {
+        let loaned_at = Utc::now();
+        let (loan, loaned) =
+            loan_book(BookId::new(), MemberId::new(), loaned_at, StaffId::new()).unwrap();
+        let overdue = LoanBecameOverdue {
+            loan_id: loan.loan_id,
+            book_id: loan.book_id,
+            member_id: loan.member_id,
+            due_date: loan.due_date,
+            detected_at: loan.due_date + Duration::hours(1),
+        };
+        let mut events = vec![
+            DomainEvent::BookLoaned(loaned),
+            DomainEvent::LoanBecameOverdue(overdue),
+        ];
+        let mut current = match replay_events(&events) {
+            Some(Loan::Overdue(overdue)) => overdue,
+            other => panic!("Expected Loan::Overdue, got {:?}", other),
+        };
+        for level in OverdueNoticeLevel::ALL {
+            let Some(OverdueEscalation::Notice(noticed, event)) = escalate_overdue(
+                current,
+                loan.due_date + Duration::days(level.days_overdue()),
+            ) else {
+                panic!("Expected {:?} notice", level);
+            };
+            events.push(DomainEvent::OverdueNoticeSent(event));
+            current = noticed;
+        }
+        let declared_at = loan.due_date + Duration::days(LOST_DECLARATION_DAYS);
+        let Some(OverdueEscalation::DeclaredLost(_, declared)) =
+            escalate_overdue(current, declared_at)
+        else {
+            panic!("Expected lost declaration");
+        };
+        events.push(DomainEvent::LoanDeclaredLost(declared));
+
+        // イベントから督促の段階と紛失を復元する
+        let lost = match replay_events(&events) {
+            Some(Loan::Lost(lost)) => lost,
+            other => panic!("Expected Loan::Lost, got {:?}", other),
+        };
+        assert_eq!(lost.declared_lost_at, declared_at);
+
+        // 紛失した貸出も、本が見つかれば返却できる
+        let returned_at = declared_at + Duration::days(3);
+        let (returned, event) = return_book(Loan::Lost(lost), returned_at).unwrap();
+        assert!(event.was_overdue);
+        events.push(DomainEvent::BookReturned(event));
+        assert!(matches!(replay_events(&events), Some(Loan::Returned(_))));
+        assert_eq!(returned.returned_at, returned_at);
+    }
+
     // TDD: return_book() のテスト
     #[test]
     fn test_return_book_success_from_active_loan() {
@@ -946,6 +1561,7 @@ mod tests {
         let (active_loan, _) = loan_book(book_id, member_id, loaned_at, staff_id).unwrap();
         let overdue_loan = OverdueLoan {
             core: active_loan.core,
+            notice_level: None,
         };
         let returned_at = loaned_at + Duration::days(20);
 
@@ -1016,6 +1632,7 @@ mod tests {
         let (active_loan, _) = loan_book(book_id, member_id, loaned_at, staff_id).unwrap();
         let overdue_loan = OverdueLoan {
             core: active_loan.core,
+            notice_level: None,
         };
         let check_time = Utc::now();
 
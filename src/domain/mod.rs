@@ -1,9 +1,21 @@
+pub mod aggregate;
 pub mod commands;
+pub mod eligibility;
 pub mod errors;
+pub mod event_schema;
 pub mod events;
 pub mod loan;
+pub mod notification_preferences;
+pub mod policy;
 pub mod value_objects;
 
+pub use aggregate::Aggregate;
+pub use eligibility::{EligibilitySnapshot, EligibilityViolation, check_eligibility};
 pub use errors::*;
 pub use events::*;
+pub use notification_preferences::{
+    DigestMode, NoticeSchedule, NotificationChannel, NotificationLanguage, NotificationPreferences,
+    QuietHours,
+};
+pub use policy::{CirculationPolicy, EligibilityRule, OverrideToken};
 pub use value_objects::*;
@@ -0,0 +1,336 @@
+#![allow(dead_code)]
+
+use chrono::{DateTime, Duration, FixedOffset, NaiveTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::InvalidNotificationPreferences;
+
+/// 日次ダイジェストを送る時刻（会員の現地時刻）
+pub const DAILY_DIGEST_HOUR: u32 = 8;
+
+/// 既定のUTCからの時差（分）。日本標準時（+09:00）
+pub const DEFAULT_UTC_OFFSET_MINUTES: i32 = 9 * 60;
+
+/// 通知を受け取る手段
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationChannel {
+    /// メール
+    Email,
+    /// アプリ内の通知
+    InApp,
+    /// 通知を受け取らない
+    None,
+}
+
+impl NotificationChannel {
+    /// 文字列表現を取得する
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NotificationChannel::Email => "email",
+            NotificationChannel::InApp => "in_app",
+            NotificationChannel::None => "none",
+        }
+    }
+}
+
+/// 通知の言語
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationLanguage {
+    /// 日本語
+    Ja,
+    /// 英語
+    En,
+}
+
+impl NotificationLanguage {
+    /// 文字列表現を取得する（言語タグ）
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NotificationLanguage::Ja => "ja",
+            NotificationLanguage::En => "en",
+        }
+    }
+}
+
+/// 通知のまとめ方
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DigestMode {
+    /// 通知ごとにすぐ送る
+    Immediate,
+    /// 1日分の通知を毎朝1通にまとめて送る
+    Daily,
+}
+
+impl DigestMode {
+    /// 文字列表現を取得する
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DigestMode::Immediate => "immediate",
+            DigestMode::Daily => "daily",
+        }
+    }
+}
+
+/// 通知を送らない時間帯（会員の現地時刻）
+///
+/// `start`から`end`まで（`end`は含まない）。`start`が`end`より後の場合は日をまたぐ
+/// （例: 22:00〜07:00）。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QuietHours {
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+}
+
+impl QuietHours {
+    /// 現地時刻が通知を送らない時間帯に入っているか
+    pub fn contains(&self, time: NaiveTime) -> bool {
+        if self.start <= self.end {
+            self.start <= time && time < self.end
+        } else {
+            time >= self.start || time < self.end
+        }
+    }
+}
+
+/// 会員の通知設定
+///
+/// 設定のない会員には既定値（メール・日本語・すぐ送る・時間帯の制限なし）が適用される。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NotificationPreferences {
+    pub channel: NotificationChannel,
+    pub language: NotificationLanguage,
+    pub digest: DigestMode,
+    /// 通知を送らない時間帯（Noneは制限なし）
+    pub quiet_hours: Option<QuietHours>,
+    /// 会員の現地時刻のUTCからの時差（分）
+    pub utc_offset_minutes: i32,
+}
+
+impl Default for NotificationPreferences {
+    fn default() -> Self {
+        Self {
+            channel: NotificationChannel::Email,
+            language: NotificationLanguage::Ja,
+            digest: DigestMode::Immediate,
+            quiet_hours: None,
+            utc_offset_minutes: DEFAULT_UTC_OFFSET_MINUTES,
+        }
+    }
+}
+
+/// 通知をいつ送るか
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NoticeSchedule {
+    /// すぐ送る
+    Immediate,
+    /// 指定の日時まで保留し、同じ会員の他の通知とまとめて送る
+    Deferred(DateTime<Utc>),
+    /// 送らない（通知を受け取らない会員）
+    Suppressed,
+}
+
+impl NotificationPreferences {
+    /// 設定を検証する
+    ///
+    /// ビジネスルール：
+    /// - 時差は-12:00〜+14:00
+    /// - 通知を送らない時間帯は開始と終了が異なる（24時間止めるには`channel`を`none`にする）
+    pub fn validate(&self) -> Result<(), InvalidNotificationPreferences> {
+        if !(-12 * 60..=14 * 60).contains(&self.utc_offset_minutes) {
+            return Err(InvalidNotificationPreferences::UtcOffsetOutOfRange);
+        }
+        if self.quiet_hours.is_some_and(|q| q.start == q.end) {
+            return Err(InvalidNotificationPreferences::EmptyQuietHours);
+        }
+        Ok(())
+    }
+
+    /// 会員の現地時刻のタイムゾーン
+    fn offset(&self) -> FixedOffset {
+        FixedOffset::east_opt(self.utc_offset_minutes * 60)
+            .unwrap_or_else(|| FixedOffset::east_opt(0).unwrap())
+    }
+
+    /// 現地時刻の`time`になる、`at`以降で最初の日時
+    fn next_local_time(&self, at: DateTime<Utc>, time: NaiveTime) -> DateTime<Utc> {
+        let local = at.with_timezone(&self.offset());
+        let candidate = self
+            .offset()
+            .from_local_datetime(&local.date_naive().and_time(time))
+            .unwrap()
+            .with_timezone(&Utc);
+        if candidate < at {
+            candidate + Duration::days(1)
+        } else {
+            candidate
+        }
+    }
+
+    /// 日時が通知を送らない時間帯に入っているか
+    pub fn is_quiet_at(&self, at: DateTime<Utc>) -> bool {
+        self.quiet_hours
+            .is_some_and(|q| q.contains(at.with_timezone(&self.offset()).time()))
+    }
+
+    /// 純粋関数：`now`に発生した通知をいつ送るか
+    ///
+    /// ビジネスルール：
+    /// - 通知を受け取らない会員には送らない
+    /// - 日次ダイジェストの会員には、次の`DAILY_DIGEST_HOUR`時（現地時刻）にまとめて送る
+    /// - 送る日時が通知を送らない時間帯に入る場合は、時間帯の終わりまで保留する
+    pub fn schedule(&self, now: DateTime<Utc>) -> NoticeSchedule {
+        if self.channel == NotificationChannel::None {
+            return NoticeSchedule::Suppressed;
+        }
+
+        let send_at = match self.digest {
+            DigestMode::Immediate => now,
+            DigestMode::Daily => self.next_local_time(
+                now,
+                NaiveTime::from_hms_opt(DAILY_DIGEST_HOUR, 0, 0).unwrap(),
+            ),
+        };
+        let send_at = match self.quiet_hours {
+            Some(quiet) if self.is_quiet_at(send_at) => self.next_local_time(send_at, quiet.end),
+            _ => send_at,
+        };
+
+        if self.digest == DigestMode::Immediate && send_at == now {
+            NoticeSchedule::Immediate
+        } else {
+            NoticeSchedule::Deferred(send_at)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn time(hour: u32, minute: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(hour, minute, 0).unwrap()
+    }
+
+    /// 日本標準時の2025-01-15の時刻
+    fn jst(hour: u32, minute: u32) -> DateTime<Utc> {
+        FixedOffset::east_opt(DEFAULT_UTC_OFFSET_MINUTES * 60)
+            .unwrap()
+            .with_ymd_and_hms(2025, 1, 15, hour, minute, 0)
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    fn overnight_quiet_hours() -> NotificationPreferences {
+        NotificationPreferences {
+            quiet_hours: Some(QuietHours {
+                start: time(22, 0),
+                end: time(7, 0),
+            }),
+            ..NotificationPreferences::default()
+        }
+    }
+
+    #[test]
+    fn test_default_preferences_send_immediately() {
+        let preferences = NotificationPreferences::default();
+        assert_eq!(preferences.schedule(jst(3, 0)), NoticeSchedule::Immediate);
+    }
+
+    #[test]
+    fn test_quiet_hours_defer_until_they_end() {
+        let preferences = overnight_quiet_hours();
+
+        assert_eq!(preferences.schedule(jst(21, 59)), NoticeSchedule::Immediate);
+        // 日をまたいで翌朝7時まで保留する
+        assert_eq!(
+            preferences.schedule(jst(23, 30)),
+            NoticeSchedule::Deferred(jst(7, 0) + Duration::days(1))
+        );
+        assert_eq!(
+            preferences.schedule(jst(6, 0)),
+            NoticeSchedule::Deferred(jst(7, 0))
+        );
+        assert_eq!(preferences.schedule(jst(7, 0)), NoticeSchedule::Immediate);
+    }
+
+    #[test]
+    fn test_daily_digest_is_sent_next_morning() {
+        let preferences = NotificationPreferences {
+            digest: DigestMode::Daily,
+            ..NotificationPreferences::default()
+        };
+
+        assert_eq!(
+            preferences.schedule(jst(6, 0)),
+            NoticeSchedule::Deferred(jst(8, 0))
+        );
+        assert_eq!(
+            preferences.schedule(jst(8, 30)),
+            NoticeSchedule::Deferred(jst(8, 0) + Duration::days(1))
+        );
+
+        // ダイジェストの時刻が通知を送らない時間帯に入る場合は、時間帯の終わりに送る
+        let late_riser = NotificationPreferences {
+            digest: DigestMode::Daily,
+            quiet_hours: Some(QuietHours {
+                start: time(22, 0),
+                end: time(9, 30),
+            }),
+            ..NotificationPreferences::default()
+        };
+        assert_eq!(
+            late_riser.schedule(jst(12, 0)),
+            NoticeSchedule::Deferred(jst(9, 30) + Duration::days(1))
+        );
+    }
+
+    #[test]
+    fn test_quiet_hours_use_member_local_time() {
+        // UTC+0の会員の22:00〜07:00は、日本時間の7:00〜16:00
+        let preferences = NotificationPreferences {
+            utc_offset_minutes: 0,
+            ..overnight_quiet_hours()
+        };
+        assert!(preferences.is_quiet_at(jst(8, 0)));
+        assert!(!preferences.is_quiet_at(jst(17, 0)));
+    }
+
+    #[test]
+    fn test_members_without_channel_are_not_notified() {
+        let preferences = NotificationPreferences {
+            channel: NotificationChannel::None,
+            digest: DigestMode::Daily,
+            ..NotificationPreferences::default()
+        };
+        assert_eq!(preferences.schedule(jst(12, 0)), NoticeSchedule::Suppressed);
+    }
+
+    #[test]
+    fn test_validate_rejects_empty_quiet_hours_and_bad_offsets() {
+        let empty = NotificationPreferences {
+            quiet_hours: Some(QuietHours {
+                start: time(22, 0),
+                end: time(22, 0),
+            }),
+            ..NotificationPreferences::default()
+        };
+        assert_eq!(
+            empty.validate(),
+            Err(InvalidNotificationPreferences::EmptyQuietHours)
+        );
+
+        let offset = NotificationPreferences {
+            utc_offset_minutes: 15 * 60,
+            ..NotificationPreferences::default()
+        };
+        assert_eq!(
+            offset.validate(),
+            Err(InvalidNotificationPreferences::UtcOffsetOutOfRange)
+        );
+
+        assert!(overnight_quiet_hours().validate().is_ok());
+    }
+}
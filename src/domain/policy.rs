@@ -0,0 +1,155 @@
+#![allow(dead_code)]
+
+use chrono::{DateTime, Duration, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::StaffId;
+use super::loan::LOAN_PERIOD_DAYS;
+
+/// 会員1人あたりの既定の最大貸出冊数
+pub const DEFAULT_MAX_ACTIVE_LOANS: usize = 5;
+
+/// 貸出ポリシー（図書館ごとに設定可能な貸出ルール）
+///
+/// 複数の図書館が1つのデプロイメントを共有する場合、
+/// 館ごとに貸出上限や貸出期間が異なることがある。
+/// 既定値は単一館運用時のビジネスルール（5冊・14日間、履歴は無期限に保持）。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CirculationPolicy {
+    /// 会員1人あたりの最大貸出冊数
+    pub max_active_loans: usize,
+    /// 貸出期間・延長期間（日数）
+    pub loan_period_days: i64,
+    /// 返却済みの貸出と会員の紐付けを保持する日数（Noneは無期限）
+    ///
+    /// 読書履歴の保持を選択した会員の貸出には適用されない。
+    #[serde(default)]
+    pub history_retention_days: Option<i64>,
+}
+
+impl CirculationPolicy {
+    /// 貸出期間
+    pub fn loan_period(&self) -> Duration {
+        Duration::days(self.loan_period_days)
+    }
+
+    /// 会員との紐付けを保持する返却日時の下限
+    ///
+    /// これより前に返却された貸出は、会員が履歴の保持を選択していない限り
+    /// 会員の貸出履歴に含めない。保持期間が無期限の場合はNone。
+    pub fn history_cutoff(&self, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        self.history_retention_days
+            .map(|days| now - Duration::days(days))
+    }
+}
+
+impl Default for CirculationPolicy {
+    fn default() -> Self {
+        Self {
+            max_active_loans: DEFAULT_MAX_ACTIVE_LOANS,
+            loan_period_days: LOAN_PERIOD_DAYS,
+            history_retention_days: None,
+        }
+    }
+}
+
+/// 監督者の承認で例外を認められる貸出条件
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum EligibilityRule {
+    /// 貸出上限冊数（`CirculationPolicy::max_active_loans`）
+    LoanLimit,
+    /// 延滞中の貸出がある会員には貸し出さない
+    OverdueLoans,
+}
+
+/// 貸出条件の例外の承認（オーバーライドトークン）
+///
+/// どの条件を、誰の承認で、どういう理由で外したかを記録する。
+/// 例: 教員への6冊目の貸出、延滞中の会員への貸出。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct OverrideToken {
+    /// 例外を認める貸出条件
+    pub rules: Vec<EligibilityRule>,
+    /// 例外を認めた理由
+    pub reason: String,
+    /// 承認した監督者
+    pub supervisor_id: StaffId,
+}
+
+impl OverrideToken {
+    /// 承認が記録として成立しているか検証する
+    ///
+    /// 条件と理由のない承認は監査に使えないため受け付けない。
+    pub fn validate(&self) -> Result<(), &'static str> {
+        if self.rules.is_empty() {
+            return Err("override must name at least one rule");
+        }
+        if self.reason.trim().is_empty() {
+            return Err("override must give a reason");
+        }
+        if self.supervisor_id.value().is_nil() {
+            return Err("override must name the approving supervisor");
+        }
+        Ok(())
+    }
+
+    /// 条件の例外が認められているか
+    pub fn covers(&self, rule: EligibilityRule) -> bool {
+        self.rules.contains(&rule)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_policy_matches_single_library_rules() {
+        let policy = CirculationPolicy::default();
+        assert_eq!(policy.max_active_loans, 5);
+        assert_eq!(policy.loan_period(), Duration::days(14));
+        assert_eq!(policy.history_cutoff(Utc::now()), None);
+    }
+
+    #[test]
+    fn test_history_cutoff_uses_retention_days() {
+        let now = Utc::now();
+        let policy = CirculationPolicy {
+            history_retention_days: Some(30),
+            ..CirculationPolicy::default()
+        };
+        assert_eq!(policy.history_cutoff(now), Some(now - Duration::days(30)));
+    }
+
+    #[test]
+    fn test_override_token_requires_rules_reason_and_supervisor() {
+        let token = OverrideToken {
+            rules: vec![EligibilityRule::LoanLimit],
+            reason: "Teacher borrowing class set".to_string(),
+            supervisor_id: StaffId::new(),
+        };
+        assert!(token.validate().is_ok());
+        assert!(token.covers(EligibilityRule::LoanLimit));
+        assert!(!token.covers(EligibilityRule::OverdueLoans));
+
+        for invalid in [
+            OverrideToken {
+                rules: vec![],
+                ..token.clone()
+            },
+            OverrideToken {
+                reason: "  ".to_string(),
+                ..token.clone()
+            },
+            OverrideToken {
+                supervisor_id: StaffId::from_uuid(uuid::Uuid::nil()),
+                ..token.clone()
+            },
+        ] {
+            assert!(invalid.validate().is_err());
+        }
+    }
+}
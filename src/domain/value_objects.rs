@@ -1,5 +1,6 @@
 #![allow(dead_code)]
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
@@ -16,7 +17,7 @@ pub trait AggregateId: Send + Sync {
 }
 
 /// 貸出ID - 貸出管理コンテキストの集約ID
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
 pub struct LoanId(Uuid);
 
 impl LoanId {
@@ -50,7 +51,7 @@ impl AggregateId for LoanId {
 }
 
 /// 書籍ID - カタログ管理コンテキストへの参照
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
 pub struct BookId(Uuid);
 
 impl BookId {
@@ -74,14 +75,24 @@ impl Default for BookId {
 }
 
 /// 会員ID - 会員管理コンテキストへの参照
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+///
+/// 削除請求により会員を特定できなくなった貸出は`ANONYMISED`（nil UUID）を持つ。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
 pub struct MemberId(Uuid);
 
 impl MemberId {
+    /// 匿名化された会員（会員の特定ができない貸出）
+    pub const ANONYMISED: MemberId = MemberId(Uuid::nil());
+
     pub fn new() -> Self {
         Self(Uuid::new_v4())
     }
 
+    /// 匿名化された会員か
+    pub fn is_anonymised(&self) -> bool {
+        *self == Self::ANONYMISED
+    }
+
     pub fn from_uuid(uuid: Uuid) -> Self {
         Self(uuid)
     }
@@ -98,7 +109,7 @@ impl Default for MemberId {
 }
 
 /// 職員ID - 職員管理コンテキストへの参照
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
 pub struct StaffId(Uuid);
 
 impl StaffId {
@@ -121,6 +132,36 @@ impl Default for StaffId {
     }
 }
 
+/// テナントID - 同一デプロイメントを共有する図書館（自治体）の識別子
+///
+/// 図書館ごとのデータ分離に使用される。
+/// 単一館運用との互換性のため、既定テナント（nil UUID）を持つ。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct TenantId(Uuid);
+
+impl TenantId {
+    /// 既定テナント（単一館運用・マイグレーション前のデータ）
+    pub const DEFAULT: TenantId = TenantId(Uuid::nil());
+
+    pub fn new() -> Self {
+        Self(Uuid::new_v4())
+    }
+
+    pub fn from_uuid(uuid: Uuid) -> Self {
+        Self(uuid)
+    }
+
+    pub fn value(&self) -> Uuid {
+        self.0
+    }
+}
+
+impl Default for TenantId {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
 /// 延長回数エラー
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ExtensionError {
@@ -180,6 +221,50 @@ impl TryFrom<u8> for ExtensionCount {
     }
 }
 
+/// 延滞の督促の段階
+///
+/// 返却期限からの経過日数（`days_overdue()`）に達すると、その段階の督促を送る。
+/// 最終督促の後も返却されない貸出は紛失として扱われる。
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, JsonSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum OverdueNoticeLevel {
+    /// 1回目の督促
+    First,
+    /// 2回目の督促
+    Second,
+    /// 最終督促
+    Final,
+}
+
+impl OverdueNoticeLevel {
+    /// すべての段階（督促する順）
+    pub const ALL: [OverdueNoticeLevel; 3] = [
+        OverdueNoticeLevel::First,
+        OverdueNoticeLevel::Second,
+        OverdueNoticeLevel::Final,
+    ];
+
+    /// この段階の督促を送る、返却期限からの経過日数
+    pub fn days_overdue(&self) -> i64 {
+        match self {
+            OverdueNoticeLevel::First => 1,
+            OverdueNoticeLevel::Second => 14,
+            OverdueNoticeLevel::Final => 30,
+        }
+    }
+
+    /// 文字列表現を取得する
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OverdueNoticeLevel::First => "first",
+            OverdueNoticeLevel::Second => "second",
+            OverdueNoticeLevel::Final => "final",
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -249,6 +334,12 @@ mod tests {
         assert_ne!(id1, id2);
     }
 
+    #[test]
+    fn test_member_id_anonymised() {
+        assert!(MemberId::ANONYMISED.is_anonymised());
+        assert!(!MemberId::new().is_anonymised());
+    }
+
     #[test]
     fn test_staff_id_creation() {
         let id1 = StaffId::new();
@@ -256,6 +347,13 @@ mod tests {
         assert_ne!(id1, id2);
     }
 
+    #[test]
+    fn test_tenant_id_default_is_nil() {
+        assert_eq!(TenantId::default(), TenantId::DEFAULT);
+        assert_eq!(TenantId::DEFAULT.value(), Uuid::nil());
+        assert_ne!(TenantId::new(), TenantId::DEFAULT);
+    }
+
     // TDD: ExtensionCount TryFrom のテスト
     #[test]
     fn test_extension_count_try_from_valid() {
@@ -1,18 +1,51 @@
+mod cli;
+
 use rusty_library_ddd::{
+    adapters::in_memory::InMemoryEventBus,
     adapters::mock::{
         book_service::BookService as MockBookService,
         member_service::MemberService as MockMemberService,
+        notification_gateway::NotificationGateway as MockNotificationGateway,
+        staff_service::StaffService as MockStaffService,
     },
     adapters::postgres::{
+        deferred_notices::DeferredNoticeQueue as PostgresDeferredNoticeQueue,
         event_store::EventStore as PostgresEventStore,
+        idempotency_store::IdempotencyStore as PostgresIdempotencyStore,
+        job_store::JobStore as PostgresJobStore,
         loan_read_model::LoanReadModel as PostgresLoanReadModel,
+        notification_log::NotificationLog as PostgresNotificationLog,
+        notification_preferences::NotificationPreferenceStore as PostgresNotificationPreferenceStore,
+        tenant::TenantDirectory as PostgresTenantDirectory,
+        unit_of_work::UnitOfWork as PostgresUnitOfWork,
+    },
+    api::{handlers::AppState, router::create_router, tenant::TenantRegistry},
+    application::jobs::{
+        DUE_SOON_REMINDER_JOB, DueSoonReminderJob, HISTORY_RETENTION_JOB, HistoryRetentionJob,
+        NOTIFICATION_DIGEST_JOB, NOTIFICATION_DISPATCH_JOB, NotificationDigestJob,
+        NotificationDispatchJob, OVERDUE_DETECTION_JOB, OVERDUE_NOTICE_JOB, OverdueDetectionJob,
+        OverdueNoticeJob, Scheduler,
     },
-    api::{handlers::AppState, router::create_router},
     application::loan::ServiceDependencies,
+    application::notification::{NotificationRouter, NotificationSubscriber},
+    ports::{EventBus, TenantDirectory},
 };
 use std::sync::Arc;
+use tokio::signal::unix::{SignalKind, signal};
+use tokio::sync::watch;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
+/// イベントバスで並行して配信するレーンの数
+const EVENT_BUS_LANES: usize = 4;
+
+/// データベース接続プールの大きさ
+///
+/// プールはリクエストとジョブの処理が共有する。ジョブの実行権（JobLease）は
+/// プールとは別の専用の接続で保持するため、1インスタンスが使う接続は
+/// 最大で「プールの大きさ + 登録したジョブの数（現在6）」になる。
+/// インスタンス数を増やす場合はPostgreSQLの`max_connections`に収まるようにする。
+const DB_POOL_SIZE: u32 = 5;
+
 #[tokio::main]
 async fn main() {
     // トレーシングの初期化
@@ -33,27 +66,151 @@ async fn main() {
 
     // データベース接続プールの初期化
     let pool = sqlx::postgres::PgPoolOptions::new()
-        .max_connections(5)
+        .max_connections(DB_POOL_SIZE)
         .connect(&database_url)
         .await
         .expect("Failed to connect to database");
 
-    // アダプターの初期化
-    let event_store = Arc::new(PostgresEventStore::new(pool.clone()));
-    let loan_read_model = Arc::new(PostgresLoanReadModel::new(pool.clone()));
+    // サブコマンドが指定された場合は実行して終了
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if let Some(result) = cli::run(&pool, &args).await {
+        if let Err(e) = result {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    // 会員・書籍・職員サービスと通知の配信手段（全テナント共通のモック）
     let member_service = Arc::new(MockMemberService::new());
     let book_service = Arc::new(MockBookService::new());
+    let staff_service = Arc::new(MockStaffService::new());
+    let notification_gateway = Arc::new(MockNotificationGateway::new());
+
+    // コンテキスト間でイベントを配信するイベントバス（全テナント共通）
+    let event_bus = Arc::new(InMemoryEventBus::new(EVENT_BUS_LANES));
+
+    // テナント一覧の読み込み
+    let tenants = PostgresTenantDirectory::new(pool.clone())
+        .list_tenants()
+        .await
+        .expect("Failed to load tenants");
+
+    // 新しいイベントの保存形式
+    let event_codec = cli::event_codec_from_env().expect("Invalid EVENT_CODEC");
 
-    // サービス依存関係の作成
-    let service_deps = ServiceDependencies {
-        event_store,
-        loan_read_model,
-        member_service,
-        book_service,
-    };
+    // テナントごとにスコープされたサービス依存関係を作成
+    let mut registry = TenantRegistry::new();
+    let mut tenant_dependencies = Vec::new();
+    let mut notification_routers = Vec::new();
+    for tenant in tenants {
+        tracing::info!("Registering tenant {} ({})", tenant.name, tenant.subdomain);
+
+        // 会員の通知設定に従って、通知を送るか保留する
+        let notification_preferences = Arc::new(PostgresNotificationPreferenceStore::for_tenant(
+            pool.clone(),
+            tenant.tenant_id,
+        ));
+        let deferred_notices = Arc::new(PostgresDeferredNoticeQueue::for_tenant(
+            pool.clone(),
+            tenant.tenant_id,
+        ));
+        let notification_router = Arc::new(NotificationRouter::new(
+            notification_preferences.clone(),
+            deferred_notices.clone(),
+            notification_gateway.clone(),
+        ));
+        notification_routers.push((tenant.tenant_id, notification_router.clone()));
+
+        let service_deps = ServiceDependencies {
+            tenant_id: tenant.tenant_id,
+            policy: tenant.policy,
+            event_store: Arc::new(
+                PostgresEventStore::for_tenant(pool.clone(), tenant.tenant_id)
+                    .with_codec(event_codec),
+            ),
+            loan_read_model: Arc::new(PostgresLoanReadModel::for_tenant(
+                pool.clone(),
+                tenant.tenant_id,
+            )),
+            // イベントとRead Modelは同じデータベースにあるため、同一トランザクションで更新する
+            unit_of_work: Some(Arc::new(
+                PostgresUnitOfWork::for_tenant(pool.clone(), tenant.tenant_id)
+                    .with_codec(event_codec),
+            )),
+            member_service: member_service.clone(),
+            book_service: book_service.clone(),
+            staff_service: staff_service.clone(),
+            notification_service: notification_router,
+            notification_log: Arc::new(PostgresNotificationLog::for_tenant(
+                pool.clone(),
+                tenant.tenant_id,
+            )),
+            notification_preferences,
+            deferred_notices,
+            event_bus: event_bus.clone(),
+            idempotency_keys: Arc::new(PostgresIdempotencyStore::for_tenant(
+                pool.clone(),
+                tenant.tenant_id,
+            )),
+        };
+        tenant_dependencies.push(service_deps.clone());
+        registry.register(tenant.subdomain, service_deps);
+    }
+
+    // コミットされたイベントに反応して会員に通知する（取りこぼしは通知の配信ジョブが拾う）
+    event_bus.subscribe(Arc::new(NotificationSubscriber::new(
+        tenant_dependencies.clone(),
+    )));
+
+    // バックグラウンドジョブ（複数インスタンスで動かしても各回は1つのインスタンスだけが実行する）
+    let mut scheduler = Scheduler::new(
+        Arc::new(PostgresJobStore::new(pool.clone())),
+        cli::instance_id_from_env(),
+    );
+    if let Some(schedule) = cli::job_schedule_from_env(OVERDUE_DETECTION_JOB, "*/15 * * * *")
+        .expect("Invalid overdue detection schedule")
+    {
+        let job = OverdueDetectionJob::new(tenant_dependencies.clone());
+        scheduler = scheduler.with_job(Arc::new(job), schedule);
+    }
+    if let Some(schedule) = cli::job_schedule_from_env(HISTORY_RETENTION_JOB, "0 3 * * *")
+        .expect("Invalid history retention schedule")
+    {
+        let job = HistoryRetentionJob::new(tenant_dependencies.clone());
+        scheduler = scheduler.with_job(Arc::new(job), schedule);
+    }
+    if let Some(schedule) = cli::job_schedule_from_env(DUE_SOON_REMINDER_JOB, "0 0 * * *")
+        .expect("Invalid due-soon reminder schedule")
+    {
+        let job = DueSoonReminderJob::new(tenant_dependencies.clone());
+        scheduler = scheduler.with_job(Arc::new(job), schedule);
+    }
+    if let Some(schedule) = cli::job_schedule_from_env(OVERDUE_NOTICE_JOB, "0 1 * * *")
+        .expect("Invalid overdue notice schedule")
+    {
+        let job = OverdueNoticeJob::new(tenant_dependencies.clone());
+        scheduler = scheduler.with_job(Arc::new(job), schedule);
+    }
+    if let Some(schedule) = cli::job_schedule_from_env(NOTIFICATION_DISPATCH_JOB, "*/5 * * * *")
+        .expect("Invalid notification dispatch schedule")
+    {
+        let job = NotificationDispatchJob::new(tenant_dependencies);
+        scheduler = scheduler.with_job(Arc::new(job), schedule);
+    }
+    if let Some(schedule) = cli::job_schedule_from_env(NOTIFICATION_DIGEST_JOB, "*/15 * * * *")
+        .expect("Invalid notification digest schedule")
+    {
+        let job = NotificationDigestJob::new(notification_routers);
+        scheduler = scheduler.with_job(Arc::new(job), schedule);
+    }
+
+    // SIGTERM / Ctrl+Cで停止を指示する
+    let (shutdown_tx, shutdown_rx) = watch::channel(false);
+    let jobs = tokio::spawn(async move { scheduler.run(shutdown_rx).await });
 
     // アプリケーション状態の作成
-    let app_state = Arc::new(AppState { service_deps });
+    let app_state = Arc::new(AppState::new(registry));
 
     // ルーターの作成
     let app = create_router(app_state);
@@ -67,8 +224,27 @@ async fn main() {
 
     tracing::info!("Server listening on {}", addr);
 
-    // サーバー起動
+    // サーバー起動（停止の指示で新しい接続の受け付けをやめ、処理中のリクエストを終えてから戻る）
     axum::serve(listener, app)
+        .with_graceful_shutdown(async move {
+            shutdown_signal().await;
+            tracing::info!("Shutting down...");
+            let _ = shutdown_tx.send(true);
+        })
         .await
         .expect("Failed to start server");
+
+    // 実行中のジョブと、発行済みのイベントの配信が終わるのを待つ
+    jobs.await.expect("Background jobs panicked");
+    event_bus.flush().await;
+    tracing::info!("Shutdown complete");
+}
+
+/// SIGTERM（コンテナの停止など）またはCtrl+Cを待つ
+async fn shutdown_signal() {
+    let mut terminate = signal(SignalKind::terminate()).expect("Failed to install SIGTERM handler");
+    tokio::select! {
+        _ = terminate.recv() => {}
+        _ = tokio::signal::ctrl_c() => {}
+    }
 }
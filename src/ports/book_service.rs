@@ -1,8 +1,31 @@
 use crate::domain::value_objects::BookId;
+use crate::ports::errors::{BoxError, Classified, ErrorClass};
 use async_trait::async_trait;
+use thiserror::Error;
 
 #[allow(dead_code)]
-pub type Result<T> = std::result::Result<T, Box<dyn std::error::Error + Send + Sync>>;
+pub type Result<T> = std::result::Result<T, BookServiceError>;
+
+/// 書籍サービスのエラー
+#[derive(Debug, Error)]
+pub enum BookServiceError {
+    /// 書籍サービスに接続できない（接続断・タイムアウトなど）
+    #[error("Book service is unavailable: {0}")]
+    Unavailable(#[source] BoxError),
+
+    /// 予期しない障害（不正な応答・バグなど）
+    #[error("Book service failure: {0}")]
+    Internal(#[source] BoxError),
+}
+
+impl Classified for BookServiceError {
+    fn class(&self) -> ErrorClass {
+        match self {
+            BookServiceError::Unavailable(_) => ErrorClass::Transient,
+            _ => ErrorClass::Permanent,
+        }
+    }
+}
 
 /// 書籍サービスポート
 ///
@@ -20,4 +43,9 @@ pub trait BookService: Send + Sync {
     ///
     /// 通知メッセージでわかりやすい表示をするために使用される。
     async fn get_book_title(&self, book_id: BookId) -> Result<String>;
+
+    /// 資料バーコードから書籍を検索する
+    ///
+    /// 旧システムからの移行時に、バーコードを書籍IDに変換するために使用される。
+    async fn find_by_barcode(&self, barcode: &str) -> Result<Option<BookId>>;
 }
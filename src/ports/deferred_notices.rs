@@ -0,0 +1,66 @@
+use crate::domain::value_objects::MemberId;
+use crate::ports::errors::{BoxError, Classified, ErrorClass};
+use crate::ports::notification_gateway::Notice;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+#[allow(dead_code)]
+pub type Result<T> = std::result::Result<T, DeferredNoticeError>;
+
+/// 保留中の通知のキューのエラー
+#[derive(Debug, Error)]
+pub enum DeferredNoticeError {
+    /// キューに接続できない（接続断・タイムアウトなど）
+    #[error("Deferred notice queue is unavailable: {0}")]
+    Unavailable(#[source] BoxError),
+
+    /// 予期しない障害（不正なデータ・バグなど）
+    #[error("Deferred notice queue failure: {0}")]
+    Internal(#[source] BoxError),
+}
+
+impl Classified for DeferredNoticeError {
+    fn class(&self) -> ErrorClass {
+        match self {
+            DeferredNoticeError::Unavailable(_) => ErrorClass::Transient,
+            _ => ErrorClass::Permanent,
+        }
+    }
+}
+
+/// 送るのを保留した通知
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeferredNotice {
+    pub member_id: MemberId,
+    pub notice: Notice,
+    pub queued_at: DateTime<Utc>,
+    /// この日時以降に送る
+    pub deliver_after: DateTime<Utc>,
+}
+
+/// 保留中の通知のキューポート
+///
+/// 日次ダイジェストにまとめる通知と、通知を送らない時間帯に発生した通知を、
+/// 送る日時まで保持する。
+#[allow(dead_code)]
+#[async_trait]
+pub trait DeferredNoticeQueue: Send + Sync {
+    /// 通知を保留する
+    ///
+    /// 同じ重複排除キーの通知が既に保留されている場合は何もしない。
+    async fn enqueue(&self, notice: &DeferredNotice) -> Result<()>;
+
+    /// 送る日時が`until`以前の通知（会員ごと、保留した順）
+    async fn due(&self, until: DateTime<Utc>) -> Result<Vec<DeferredNotice>>;
+
+    /// 送った通知をキューから取り除く
+    async fn remove(&self, dedup_keys: &[String]) -> Result<()>;
+
+    /// 会員の保留中の通知（保留した順。会員データの写し用）
+    async fn pending_for(&self, member_id: MemberId) -> Result<Vec<DeferredNotice>>;
+
+    /// 会員の保留中の通知をすべて取り除く（削除請求）
+    async fn remove_for(&self, member_id: MemberId) -> Result<()>;
+}
@@ -0,0 +1,28 @@
+//! ポートのエラーの分類
+//!
+//! 各ポートは自身のエラー型を持ち、障害を一時的なもの（再試行で回復しうる）と
+//! 恒久的なもの（データの破損やバグなど、再試行しても回復しない）に分類する。
+//! アプリケーション層とAPI層はこの分類で再試行の可否やHTTPステータスを決める。
+
+/// アダプター固有の障害の原因
+pub type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+/// 障害の分類
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorClass {
+    /// 接続断・タイムアウト・同時更新の競合など、再試行で回復しうる障害
+    Transient,
+    /// データの破損・バグなど、再試行しても回復しない障害
+    Permanent,
+}
+
+/// 分類されたエラー
+pub trait Classified {
+    /// 障害の分類
+    fn class(&self) -> ErrorClass;
+
+    /// 再試行で回復しうるか
+    fn is_retryable(&self) -> bool {
+        self.class() == ErrorClass::Transient
+    }
+}
@@ -0,0 +1,83 @@
+use crate::ports::errors::{BoxError, Classified, ErrorClass};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+#[allow(dead_code)]
+pub type Result<T> = std::result::Result<T, EventArchiveError>;
+
+/// イベントアーカイブのエラー
+#[derive(Debug, Error)]
+pub enum EventArchiveError {
+    /// 指定したパーティションがアーカイブされていない年単位のパーティションではない
+    #[error("{0} is not a live yearly event partition")]
+    NotALivePartition(String),
+
+    /// データベースに接続できない、または同時に実行された処理と競合した
+    #[error("Event archive is unavailable: {0}")]
+    Unavailable(#[source] BoxError),
+
+    /// 予期しない障害（不正なデータ・バグなど）
+    #[error("Event archive failure: {0}")]
+    Internal(#[source] BoxError),
+}
+
+impl Classified for EventArchiveError {
+    fn class(&self) -> ErrorClass {
+        match self {
+            EventArchiveError::Unavailable(_) => ErrorClass::Transient,
+            _ => ErrorClass::Permanent,
+        }
+    }
+}
+
+/// イベントログの年単位のパーティション
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EventPartition {
+    /// パーティション名（例: "events_y2020"）
+    pub name: String,
+    /// パーティションの年（UTC）
+    pub year: i32,
+    /// 範囲の開始（この日時を含む）
+    pub from: DateTime<Utc>,
+    /// 範囲の終了（この日時を含まない）
+    pub to: DateTime<Utc>,
+    /// アーカイブ済みか
+    pub archived: bool,
+}
+
+/// パーティションのアーカイブの結果
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PartitionArchival {
+    /// アーカイブした（移したイベント数）
+    Archived { event_count: u64 },
+    /// 返却されていない貸出を含むためアーカイブしなかった（該当する貸出の件数）
+    OpenLoans { loan_count: u64 },
+}
+
+/// イベントアーカイブポート
+///
+/// イベントログは発生日時で年単位にパーティション化されている。
+/// 古いパーティションをアーカイブに移すことで、日常の読み書きが参照するインデックスを小さく保つ。
+/// アーカイブ済みのイベントもEventStoreの`load`等からは引き続き参照できる。
+///
+/// パーティションは全テナントで共有されるため、テナント単位ではなくデプロイメント全体を対象とする。
+#[allow(dead_code)]
+#[async_trait]
+pub trait EventArchive: Send + Sync {
+    /// 指定した年（両端を含む）のパーティションを作成する
+    ///
+    /// 既定パーティションに溜まった年のイベントも、その年のパーティションへ移す。
+    /// 作成したパーティションの名前を返す。
+    async fn ensure_partitions(&self, from_year: i32, through_year: i32) -> Result<Vec<String>>;
+
+    /// 年単位のパーティションを年の昇順で取得する（アーカイブ済みを含む）
+    async fn partitions(&self) -> Result<Vec<EventPartition>>;
+
+    /// パーティションをアーカイブに移す
+    ///
+    /// パーティション内に1件でもイベントがある貸出がすべて返却済みの場合のみ移す。
+    /// 貸出以外の集約（会員など）は終了状態を持たないため、アーカイブを妨げない。
+    async fn archive_partition(&self, name: &str) -> Result<PartitionArchival>;
+}
@@ -0,0 +1,109 @@
+use crate::ports::errors::{BoxError, Classified, ErrorClass};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use uuid::Uuid;
+
+#[allow(dead_code)]
+pub type Result<T> = std::result::Result<T, EventAuditError>;
+
+/// イベント監査のエラー
+///
+/// 改ざんによってチェーンが壊れていることはエラーではなく、
+/// `ChainVerification::first_broken_link`で報告する。
+#[derive(Debug, Error)]
+pub enum EventAuditError {
+    /// イベントストアに接続できない（接続断・タイムアウトなど）
+    #[error("Event audit is unavailable: {0}")]
+    Unavailable(#[source] BoxError),
+
+    /// 保存されたハッシュを読み込めない
+    #[error("Stored chain data is corrupted: {0}")]
+    Corrupted(#[source] BoxError),
+
+    /// 予期しない障害（バグなど）
+    #[error("Event audit failure: {0}")]
+    Internal(#[source] BoxError),
+}
+
+impl Classified for EventAuditError {
+    fn class(&self) -> ErrorClass {
+        match self {
+            EventAuditError::Unavailable(_) => ErrorClass::Transient,
+            _ => ErrorClass::Permanent,
+        }
+    }
+}
+
+/// ハッシュチェーンのハッシュ値（SHA-256）
+pub type ChainHash = [u8; 32];
+
+/// チェーンの先頭（最新のハッシュ化済みイベント）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChainHead {
+    /// 先頭イベントのシーケンス番号
+    pub sequence_number: i64,
+    /// 先頭イベントのハッシュ（ルートハッシュ）
+    pub event_hash: ChainHash,
+    /// チェーンに含まれるイベント数
+    pub event_count: i64,
+}
+
+/// チェーンが壊れている理由
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BrokenLinkReason {
+    /// イベントの内容からハッシュを再計算すると一致しない（イベントが書き換えられた）
+    HashMismatch,
+    /// 直前のイベント（テナント全体）のハッシュと一致しない（イベントが削除・挿入された）
+    GlobalLinkMismatch,
+    /// 集約内の直前のイベントのハッシュと一致しない
+    AggregateLinkMismatch,
+    /// チェーン開始後のイベントにハッシュがない
+    MissingHash,
+}
+
+/// チェーンの壊れている箇所
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BrokenLink {
+    pub sequence_number: i64,
+    pub event_id: Uuid,
+    pub aggregate_id: Uuid,
+    pub reason: BrokenLinkReason,
+}
+
+/// チェーン検証の結果
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChainVerification {
+    /// 検証したハッシュ化済みイベントの件数
+    pub verified_events: usize,
+    /// チェーン導入前のハッシュを持たないイベントの件数
+    pub unchained_events: usize,
+    /// 最初に見つかった壊れた箇所（Noneならチェーンは完全）
+    pub first_broken_link: Option<BrokenLink>,
+}
+
+impl ChainVerification {
+    /// チェーンが完全か
+    pub fn is_intact(&self) -> bool {
+        self.first_broken_link.is_none()
+    }
+}
+
+/// イベント監査ポート
+///
+/// イベントストアのハッシュチェーンを検証し、アンカリング用のルートハッシュを提供する。
+/// チェーンはテナント単位で、テナント全体の順序と集約内の順序の両方で連結される。
+#[allow(dead_code)]
+#[async_trait]
+pub trait EventAudit: Send + Sync {
+    /// イベントを挿入順に走査し、最初に壊れた箇所を報告する
+    async fn verify_chain(&self) -> Result<ChainVerification>;
+
+    /// チェーンの先頭を取得する（ハッシュ化済みイベントがなければNone）
+    async fn chain_head(&self) -> Result<Option<ChainHead>>;
+
+    /// 指定したシーケンス番号のイベントのハッシュを取得する
+    ///
+    /// アンカーに記録されたルートハッシュが現在も残っているかの確認に使用される。
+    async fn hash_at(&self, sequence_number: i64) -> Result<Option<ChainHash>>;
+}
@@ -0,0 +1,100 @@
+use crate::domain::{events::DomainEvent, value_objects::TenantId};
+use crate::ports::errors::BoxError;
+use crate::ports::event_store::{AppendedEvent, StoredEvent};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// イベントハンドラーの Result型
+pub type HandlerResult = std::result::Result<(), BoxError>;
+
+/// イベントストアへの追加がコミットされたイベント
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PublishedEvent {
+    /// イベントを保存したテナント
+    pub tenant_id: TenantId,
+    pub aggregate_id: Uuid,
+    /// 集約の種類（例: "Loan"）
+    pub aggregate_type: String,
+    /// 集約内のバージョン（1から連番）
+    pub aggregate_version: u32,
+    /// イベントの一意識別子
+    pub event_id: Uuid,
+    /// ストア全体での挿入順序
+    pub sequence_number: i64,
+    /// イベントがストアに記録された日時
+    pub recorded_at: DateTime<Utc>,
+    pub event: DomainEvent,
+}
+
+impl PublishedEvent {
+    /// 追加されたイベントから発行するイベントを作る
+    pub fn new(
+        tenant_id: TenantId,
+        aggregate_id: Uuid,
+        aggregate_type: &str,
+        aggregate_version: u32,
+        appended: AppendedEvent,
+        event: DomainEvent,
+    ) -> Self {
+        Self {
+            tenant_id,
+            aggregate_id,
+            aggregate_type: aggregate_type.to_string(),
+            aggregate_version,
+            event_id: appended.event_id,
+            sequence_number: appended.sequence_number,
+            recorded_at: appended.recorded_at,
+            event,
+        }
+    }
+
+    /// 保存されたイベントとしての表現（ストアから読み直さずに使える）
+    pub fn to_stored(&self) -> StoredEvent {
+        StoredEvent {
+            event_id: self.event_id,
+            aggregate_id: self.aggregate_id,
+            aggregate_type: self.aggregate_type.clone(),
+            aggregate_version: self.aggregate_version as i32,
+            sequence_number: self.sequence_number,
+            occurred_at: self.event.occurred_at(),
+            recorded_at: self.recorded_at,
+            event: self.event.clone(),
+        }
+    }
+}
+
+/// イベントバスに登録するハンドラー
+///
+/// 他のコンテキスト（予約管理など）は、アプリケーションサービスを直接呼ばず、
+/// ハンドラーでイベントに反応して協調する。
+#[async_trait]
+pub trait EventHandler: Send + Sync {
+    /// ハンドラーの名前（ログに使う）
+    fn name(&self) -> &str;
+
+    /// イベントを処理する
+    ///
+    /// 失敗してもイベントの保存は取り消されず、他のハンドラーにも影響しない。
+    /// 再処理が必要なハンドラーは、失敗を自身で記録すること。
+    async fn handle(&self, event: &PublishedEvent) -> HandlerResult;
+}
+
+/// イベントバスポート
+///
+/// コミットされたドメインイベントを、登録されたハンドラーに配信する。
+/// - 同じ集約のイベントは発行された順に、1件ずつ配信される
+/// - ハンドラーの失敗（パニックを含む）は、他のハンドラーや後続のイベントの配信を妨げない
+/// - 配信はプロセス内で行われ、永続化されない（停止時に配信されていないイベントは失われる）
+#[allow(dead_code)]
+#[async_trait]
+pub trait EventBus: Send + Sync {
+    /// ハンドラーを登録する（以降に発行されたイベントから配信される）
+    fn subscribe(&self, handler: Arc<dyn EventHandler>);
+
+    /// コミットされたイベントを発行する
+    ///
+    /// 配信の失敗は発行元に返さない（イベントは既に保存されているため）。
+    async fn publish(&self, events: Vec<PublishedEvent>);
+}
@@ -1,10 +1,103 @@
-use crate::domain::events::DomainEvent;
+use crate::domain::{events::DomainEvent, value_objects::MemberId};
+use crate::ports::errors::{BoxError, Classified, ErrorClass};
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use futures::stream::BoxStream;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use thiserror::Error;
 use uuid::Uuid;
 
 #[allow(dead_code)]
-pub type Result<T> = std::result::Result<T, Box<dyn std::error::Error + Send + Sync>>;
+pub type Result<T> = std::result::Result<T, EventStoreError>;
+
+/// イベントストアのエラー
+#[derive(Debug, Error)]
+pub enum EventStoreError {
+    /// イベントストアに接続できない（接続断・タイムアウトなど）
+    #[error("Event store is unavailable: {0}")]
+    Unavailable(#[source] BoxError),
+
+    /// 同時に行われた書き込みとの競合
+    #[error("Concurrent write to the event store: {0}")]
+    Conflict(#[source] BoxError),
+
+    /// 集約のバージョンが期待したものと異なる（他の書き込みが先に行われた）
+    #[error("Aggregate {aggregate_id} is at version {actual}, expected {expected}")]
+    VersionConflict {
+        aggregate_id: Uuid,
+        expected: u32,
+        actual: u32,
+    },
+
+    /// 集約IDが既に他の種類の集約のイベントに使われている
+    #[error("Aggregate {aggregate_id} is a {actual}, not a {expected}")]
+    AggregateTypeMismatch {
+        aggregate_id: Uuid,
+        expected: String,
+        actual: String,
+    },
+
+    /// 取り込もうとした集約が既に存在する
+    #[error("Aggregate {0} already exists in the event store")]
+    AggregateExists(Uuid),
+
+    /// 保存済みのイベントを読み取れない（データの破損など）
+    #[error("Stored event is invalid: {0}")]
+    Corrupted(#[source] BoxError),
+
+    /// 予期しない障害（バグなど）
+    #[error("Event store failure: {0}")]
+    Internal(#[source] BoxError),
+}
+
+impl Classified for EventStoreError {
+    fn class(&self) -> ErrorClass {
+        match self {
+            EventStoreError::Unavailable(_)
+            | EventStoreError::Conflict(_)
+            | EventStoreError::VersionConflict { .. } => ErrorClass::Transient,
+            _ => ErrorClass::Permanent,
+        }
+    }
+}
+
+/// 永続化されたイベント（イベントストアのメタデータ付き）
+///
+/// バックアップやバックエンド間の移行で、イベントを
+/// 集約ID・バージョン・シーケンス番号ごと保存・復元するために使用される。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StoredEvent {
+    /// イベントの一意識別子
+    pub event_id: Uuid,
+    /// 集約ID
+    pub aggregate_id: Uuid,
+    /// 集約の種類（例: "Loan"）
+    pub aggregate_type: String,
+    /// 集約内のバージョン（1から連番）
+    pub aggregate_version: i32,
+    /// ストア全体での挿入順序
+    pub sequence_number: i64,
+    /// イベントが発生した日時
+    pub occurred_at: DateTime<Utc>,
+    /// イベントがストアに記録された日時
+    pub recorded_at: DateTime<Utc>,
+    /// ドメインイベント本体
+    pub event: DomainEvent,
+}
+
+/// 追加されたイベントのストア上の識別情報
+///
+/// `EventStore::append_with_expected_version`が、追加したイベントの順に返す。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AppendedEvent {
+    /// イベントの一意識別子
+    pub event_id: Uuid,
+    /// ストア全体での挿入順序
+    pub sequence_number: i64,
+    /// イベントがストアに記録された日時
+    pub recorded_at: DateTime<Utc>,
+}
 
 /// イベントストアポート
 ///
@@ -17,6 +110,7 @@ pub trait EventStore: Send + Sync {
     ///
     /// イベントは追記専用ログに保存され、変更・削除不可。
     /// イベントの順序は保持される。
+    /// 集約IDが既に他の種類の集約に使われている場合は`EventStoreError::AggregateTypeMismatch`となる。
     async fn append(
         &self,
         aggregate_id: Uuid,
@@ -24,15 +118,74 @@ pub trait EventStore: Send + Sync {
         events: Vec<DomainEvent>,
     ) -> Result<()>;
 
+    /// 集約が期待したバージョンのときだけイベントを追加する（楽観的排他制御）
+    ///
+    /// バージョンは集約に保存済みのイベント数（新しい集約は0）。
+    /// 読み込んでから保存するまでに他の書き込みがあった場合は
+    /// `EventStoreError::VersionConflict`となり、イベントは追加されない。
+    /// 追加したイベントのイベントIDとシーケンス番号を、追加した順に返す。
+    async fn append_with_expected_version(
+        &self,
+        aggregate_id: Uuid,
+        aggregate_type: &str,
+        expected_version: u32,
+        events: Vec<DomainEvent>,
+    ) -> Result<Vec<AppendedEvent>>;
+
     /// 集約のすべてのイベントを読み込む
     ///
     /// 追加された順序でイベントを返す。
     /// replay_events による集約状態の復元に使用される。
-    async fn load(&self, aggregate_id: Uuid) -> Result<Vec<DomainEvent>>;
+    /// `aggregate_type`の集約として記録されたイベントだけを返す
+    /// （他の種類の集約のIDを渡した場合は空になる）。
+    async fn load(&self, aggregate_id: Uuid, aggregate_type: &str) -> Result<Vec<DomainEvent>>;
+
+    /// 複数の集約のイベントをまとめて読み込む
+    ///
+    /// 集約IDごとに、追加された順序でイベントを返す。
+    /// 延滞検知など多数の集約を扱うバッチ処理で、集約ごとの`load`の
+    /// 繰り返し（N+1クエリ）を避けるために使用される。
+    /// `aggregate_type`の集約として記録されていない集約は結果に含まれない。
+    async fn load_many(
+        &self,
+        aggregate_ids: &[Uuid],
+        aggregate_type: &str,
+    ) -> Result<HashMap<Uuid, Vec<DomainEvent>>>;
+
+    /// 会員に関するすべての集約のイベントをメタデータ付きで読み込む
+    ///
+    /// 会員IDを含むイベントを持つ集約（貸出など）の全イベントを、
+    /// シーケンス番号順に返す。会員データの開示請求に使用される。
+    /// 鍵が破棄された会員のイベントは会員を特定できないため含まれない。
+    async fn load_member_events(&self, member_id: MemberId) -> Result<Vec<StoredEvent>>;
+
+    /// 指定した種類のイベントを発生日時の範囲でメタデータ付きで読み込む
+    ///
+    /// 発生日時が`from`以上`to`未満のイベントを、シーケンス番号順に返す。
+    /// アーカイブ済みのイベントも含む。貸出条件の例外の監査レポートなどに使用される。
+    async fn load_by_type(
+        &self,
+        event_type: &str,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<StoredEvent>>;
 
     /// すべての集約のイベントをストリーム配信する
     ///
     /// 延滞検知などのバッチ操作に使用される。
     /// イベントは挿入順にストリーム配信される。
     fn stream_all(&self) -> BoxStream<'_, Result<DomainEvent>>;
+
+    /// すべてのイベントをメタデータ付きでストリーム配信する
+    ///
+    /// バックアップのエクスポートに使用される。
+    /// イベントはシーケンス番号順にストリーム配信される。
+    fn stream_stored(&self) -> BoxStream<'_, Result<StoredEvent>>;
+
+    /// メタデータ付きのイベントをそのまま取り込む
+    ///
+    /// バックアップからの復元に使用される。
+    /// イベントID・バージョン・シーケンス番号・記録日時を保持したまま保存する。
+    /// 既存の集約と衝突する場合はエラーとなる。
+    async fn import(&self, events: Vec<StoredEvent>) -> Result<()>;
 }
@@ -0,0 +1,72 @@
+use crate::domain::value_objects::LoanId;
+use crate::ports::errors::{BoxError, Classified, ErrorClass};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use thiserror::Error;
+
+#[allow(dead_code)]
+pub type Result<T> = std::result::Result<T, IdempotencyStoreError>;
+
+/// 冪等キーストアのエラー
+#[derive(Debug, Error)]
+pub enum IdempotencyStoreError {
+    /// 冪等キーストアに接続できない（接続断・タイムアウトなど）
+    #[error("Idempotency key store is unavailable: {0}")]
+    Unavailable(#[source] BoxError),
+
+    /// 予期しない障害（不正なデータ・バグなど）
+    #[error("Idempotency key store failure: {0}")]
+    Internal(#[source] BoxError),
+}
+
+impl Classified for IdempotencyStoreError {
+    fn class(&self) -> ErrorClass {
+        match self {
+            IdempotencyStoreError::Unavailable(_) => ErrorClass::Transient,
+            _ => ErrorClass::Permanent,
+        }
+    }
+}
+
+/// 冪等キーを確保した結果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IdempotencyClaim {
+    /// キーを確保した（コマンドを実行してよい）
+    Claimed,
+    /// キーは他のコマンドが使っている
+    Taken {
+        /// キーを使ったコマンドの内容（`LoanCommand::fingerprint`）
+        fingerprint: String,
+        /// コマンドが作成または更新した貸出（実行中の場合はNone）
+        loan_id: Option<LoanId>,
+    },
+}
+
+/// 冪等キーストアポート
+///
+/// 再送されたコマンドを一度だけ実行するため、冪等キーとコマンドの結果を記録する。
+/// インスタンスをまたいで、また再起動後も再送を判定できるように永続化する。
+/// テナントごとにスコープされる（同じキーでもテナントが異なれば別のキー）。
+#[allow(dead_code)]
+#[async_trait]
+pub trait IdempotencyStore: Send + Sync {
+    /// 冪等キーを確保する
+    ///
+    /// `expired_before`より前に完了した記録と、`abandoned_before`より前に確保されたまま
+    /// 完了していない記録（実行中に停止したインスタンスのもの）は破棄してから確保する。
+    async fn claim(
+        &self,
+        key: &str,
+        fingerprint: &str,
+        claimed_at: DateTime<Utc>,
+        expired_before: DateTime<Utc>,
+        abandoned_before: DateTime<Utc>,
+    ) -> Result<IdempotencyClaim>;
+
+    /// 確保したキーにコマンドの結果を記録する
+    async fn complete(&self, key: &str, loan_id: LoanId, completed_at: DateTime<Utc>)
+    -> Result<()>;
+
+    /// 確保したキーを手放す（コマンドが失敗した場合。同じキーで再試行できる）
+    async fn release(&self, key: &str) -> Result<()>;
+}
@@ -0,0 +1,94 @@
+use crate::ports::errors::{BoxError, Classified, ErrorClass};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use uuid::Uuid;
+
+#[allow(dead_code)]
+pub type Result<T> = std::result::Result<T, JobStoreError>;
+
+/// ジョブストアのエラー
+#[derive(Debug, Error)]
+pub enum JobStoreError {
+    /// ジョブストアに接続できない（接続断・タイムアウトなど）
+    #[error("Job store is unavailable: {0}")]
+    Unavailable(#[source] BoxError),
+
+    /// 予期しない障害（不正なデータ・バグなど）
+    #[error("Job store failure: {0}")]
+    Internal(#[source] BoxError),
+}
+
+impl Classified for JobStoreError {
+    fn class(&self) -> ErrorClass {
+        match self {
+            JobStoreError::Unavailable(_) => ErrorClass::Transient,
+            _ => ErrorClass::Permanent,
+        }
+    }
+}
+
+/// ジョブの実行結果
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobRunStatus {
+    /// 実行中（または実行中にインスタンスが停止した）
+    Running,
+    /// 成功
+    Succeeded,
+    /// 失敗
+    Failed,
+}
+
+/// ジョブの実行履歴
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JobRun {
+    pub run_id: Uuid,
+    /// ジョブ名（例: "overdue-detection"）
+    pub job_name: String,
+    /// スケジュール上の実行時刻（ジョブと実行時刻の組で1回だけ実行される）
+    pub scheduled_for: DateTime<Utc>,
+    /// 実行したインスタンス
+    pub instance_id: String,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+    pub status: JobRunStatus,
+    /// 処理結果の要約、または失敗の理由
+    pub summary: Option<String>,
+}
+
+/// ジョブの実行権
+///
+/// 保持している間、他のインスタンスは同じジョブの実行権を取得できない。
+/// 実行が終わったら`release`で手放す。インスタンスが停止した場合も手放される。
+#[async_trait]
+pub trait JobLease: Send {
+    /// 実行権を手放す
+    async fn release(self: Box<Self>) -> Result<()>;
+}
+
+/// ジョブストアポート
+///
+/// 複数のインスタンス（レプリカ）で動くバックグラウンドジョブの排他制御と実行履歴を提供する。
+/// ジョブはテナントをまたいで実行されるため、テナントにはスコープされない。
+#[allow(dead_code)]
+#[async_trait]
+pub trait JobStore: Send + Sync {
+    /// ジョブの実行権を取得する
+    ///
+    /// 他のインスタンスが実行権を持っている場合は待たずにNoneを返す。
+    async fn try_acquire(&self, job_name: &str) -> Result<Option<Box<dyn JobLease>>>;
+
+    /// 実行の開始を記録する
+    ///
+    /// 同じジョブの同じ実行時刻が既に記録されている場合（他のインスタンスが実行済み）は
+    /// 何もせずfalseを返す。
+    async fn record_started(&self, run: &JobRun) -> Result<bool>;
+
+    /// 実行の終了（状態・終了時刻・要約）を記録する
+    async fn record_finished(&self, run: &JobRun) -> Result<()>;
+
+    /// ジョブの実行履歴を新しい順に取得する
+    async fn list_runs(&self, job_name: &str, limit: usize) -> Result<Vec<JobRun>>;
+}
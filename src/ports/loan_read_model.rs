@@ -1,9 +1,42 @@
 use crate::domain::value_objects::{BookId, LoanId, MemberId};
+use crate::ports::errors::{BoxError, Classified, ErrorClass};
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
+use thiserror::Error;
 
 #[allow(dead_code)]
-pub type Result<T> = std::result::Result<T, Box<dyn std::error::Error + Send + Sync>>;
+pub type Result<T> = std::result::Result<T, LoanReadModelError>;
+
+/// Read Modelのエラー
+#[derive(Debug, Error)]
+pub enum LoanReadModelError {
+    /// Read Modelに接続できない（接続断・タイムアウトなど）
+    #[error("Read model is unavailable: {0}")]
+    Unavailable(#[source] BoxError),
+
+    /// 同時に行われた書き込みとの競合
+    #[error("Concurrent write to the read model: {0}")]
+    Conflict(#[source] BoxError),
+
+    /// 保存済みのビューを読み取れない（データの破損など）
+    #[error("Stored loan view is invalid: {0}")]
+    Corrupted(#[source] BoxError),
+
+    /// 予期しない障害（バグなど）
+    #[error("Read model failure: {0}")]
+    Internal(#[source] BoxError),
+}
+
+impl Classified for LoanReadModelError {
+    fn class(&self) -> ErrorClass {
+        match self {
+            LoanReadModelError::Unavailable(_) | LoanReadModelError::Conflict(_) => {
+                ErrorClass::Transient
+            }
+            _ => ErrorClass::Permanent,
+        }
+    }
+}
 
 /// 貸出ステータス（Read Model用）
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -14,6 +47,8 @@ pub enum LoanStatus {
     Overdue,
     /// 返却済み
     Returned,
+    /// 紛失（最終督促の後も返却されず、弁償の対象）
+    Lost,
 }
 
 impl LoanStatus {
@@ -24,6 +59,7 @@ impl LoanStatus {
             LoanStatus::Active => "active",
             LoanStatus::Overdue => "overdue",
             LoanStatus::Returned => "returned",
+            LoanStatus::Lost => "lost",
         }
     }
 }
@@ -36,6 +72,7 @@ impl std::str::FromStr for LoanStatus {
             "active" => Ok(LoanStatus::Active),
             "overdue" => Ok(LoanStatus::Overdue),
             "returned" => Ok(LoanStatus::Returned),
+            "lost" => Ok(LoanStatus::Lost),
             _ => Err(format!("Invalid loan status: {}", s)),
         }
     }
@@ -84,11 +121,56 @@ pub trait LoanReadModel: Send + Sync {
     /// バッチジョブでの延滞検知に使用される。
     async fn find_overdue_candidates(&self, cutoff_date: DateTime<Utc>) -> Result<Vec<LoanView>>;
 
+    /// 返却期限が近い貸出を検索する
+    ///
+    /// `from` < due_date <= `until` かつ status が "active" の貸出を返却期限の順に返す。
+    /// 返却期限のリマインダーのバッチジョブで使用される。
+    async fn find_due_soon_candidates(
+        &self,
+        from: DateTime<Utc>,
+        until: DateTime<Utc>,
+    ) -> Result<Vec<LoanView>>;
+
+    /// 延滞中の貸出を検索する
+    ///
+    /// due_date <= `due_before` かつ status が "overdue" の貸出を返却期限の順に返す。
+    /// 延滞の督促のバッチジョブで使用される。
+    async fn find_overdue_loans(&self, due_before: DateTime<Utc>) -> Result<Vec<LoanView>>;
+
     /// IDで貸出を取得する
     async fn get_by_id(&self, loan_id: LoanId) -> Result<Option<LoanView>>;
 
     /// 会員の全貸出を検索する
     ///
     /// 会員の貸出履歴表示に使用される。
-    async fn find_by_member_id(&self, member_id: MemberId) -> Result<Vec<LoanView>>;
+    /// `history_cutoff`を指定した場合、それより前に返却された貸出は
+    /// 会員が読書履歴の保持を選択していない限り含めない
+    /// （保持期間の匿名化ジョブが未実行でも期限切れの履歴を返さないため）。
+    async fn find_by_member_id(
+        &self,
+        member_id: MemberId,
+        history_cutoff: Option<DateTime<Utc>>,
+    ) -> Result<Vec<LoanView>>;
+
+    /// 会員の読書履歴の保持設定を保存する
+    ///
+    /// `opted_in_at`がSomeの場合は保持を選択した日時を記録し、
+    /// Noneの場合は選択を取り消す。
+    async fn save_history_opt_in(
+        &self,
+        member_id: MemberId,
+        opted_in_at: Option<DateTime<Utc>>,
+    ) -> Result<()>;
+
+    /// 会員が読書履歴の保持を選択した日時を取得する（未選択の場合はNone）
+    async fn get_history_opt_in(&self, member_id: MemberId) -> Result<Option<DateTime<Utc>>>;
+
+    /// 指定日時より前に返却された貸出から会員との紐付けを消す
+    ///
+    /// 読書履歴の保持を選択した会員の貸出は対象外。
+    /// 会員IDは`MemberId::ANONYMISED`に置き換えられる。
+    ///
+    /// # 戻り値
+    /// 匿名化した貸出の件数
+    async fn anonymise_returned_loans(&self, returned_before: DateTime<Utc>) -> Result<u64>;
 }
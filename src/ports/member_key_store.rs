@@ -0,0 +1,43 @@
+use crate::domain::value_objects::MemberId;
+use crate::ports::errors::{BoxError, Classified, ErrorClass};
+use async_trait::async_trait;
+use thiserror::Error;
+
+#[allow(dead_code)]
+pub type Result<T> = std::result::Result<T, MemberKeyStoreError>;
+
+/// 会員鍵ストアのエラー
+#[derive(Debug, Error)]
+pub enum MemberKeyStoreError {
+    /// 鍵ストアに接続できない、または同時に実行された処理と競合した
+    #[error("Member key store is unavailable: {0}")]
+    Unavailable(#[source] BoxError),
+
+    /// 予期しない障害（バグなど）
+    #[error("Member key store failure: {0}")]
+    Internal(#[source] BoxError),
+}
+
+impl Classified for MemberKeyStoreError {
+    fn class(&self) -> ErrorClass {
+        match self {
+            MemberKeyStoreError::Unavailable(_) => ErrorClass::Transient,
+            _ => ErrorClass::Permanent,
+        }
+    }
+}
+
+/// 会員鍵ストアポート
+///
+/// イベント中の会員識別子は会員ごとの鍵で暗号化して保存される。
+/// 鍵を破棄する（暗号シュレッディング）と、イベントを変更せずに
+/// その会員を特定できなくなる。
+#[allow(dead_code)]
+#[async_trait]
+pub trait MemberKeyStore: Send + Sync {
+    /// 会員の鍵を破棄する
+    ///
+    /// 以後、その会員のイベントは匿名化された会員（`MemberId::ANONYMISED`）として読み込まれる。
+    /// 鍵が存在しなかった場合はfalseを返す。
+    async fn shred(&self, member_id: MemberId) -> Result<bool>;
+}
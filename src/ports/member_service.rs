@@ -1,8 +1,31 @@
 use crate::domain::value_objects::MemberId;
+use crate::ports::errors::{BoxError, Classified, ErrorClass};
 use async_trait::async_trait;
+use thiserror::Error;
 
 #[allow(dead_code)]
-pub type Result<T> = std::result::Result<T, Box<dyn std::error::Error + Send + Sync>>;
+pub type Result<T> = std::result::Result<T, MemberServiceError>;
+
+/// 会員サービスのエラー
+#[derive(Debug, Error)]
+pub enum MemberServiceError {
+    /// 会員サービスに接続できない（接続断・タイムアウトなど）
+    #[error("Member service is unavailable: {0}")]
+    Unavailable(#[source] BoxError),
+
+    /// 予期しない障害（不正な応答・バグなど）
+    #[error("Member service failure: {0}")]
+    Internal(#[source] BoxError),
+}
+
+impl Classified for MemberServiceError {
+    fn class(&self) -> ErrorClass {
+        match self {
+            MemberServiceError::Unavailable(_) => ErrorClass::Transient,
+            _ => ErrorClass::Permanent,
+        }
+    }
+}
 
 /// 会員サービスポート
 ///
@@ -20,4 +43,14 @@ pub trait MemberService: Send + Sync {
     ///
     /// ビジネスルール: 延滞中の会員には貸出不可。
     async fn has_overdue_loans(&self, member_id: MemberId) -> Result<bool>;
+
+    /// 会員が利用停止中か確認する
+    ///
+    /// ビジネスルール: 利用停止中の会員には貸出不可（監督者の承認でも例外にできない）。
+    async fn is_suspended(&self, member_id: MemberId) -> Result<bool>;
+
+    /// 利用者カード番号から会員を検索する
+    ///
+    /// 旧システムからの移行時に、カード番号を会員IDに変換するために使用される。
+    async fn find_by_card_number(&self, card_number: &str) -> Result<Option<MemberId>>;
 }
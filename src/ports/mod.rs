@@ -1,13 +1,43 @@
 pub mod book_service;
+pub mod deferred_notices;
+pub mod errors;
+pub mod event_archive;
+pub mod event_audit;
+pub mod event_bus;
 pub mod event_store;
+pub mod idempotency_store;
+pub mod job_store;
 pub mod loan_read_model;
+pub mod member_key_store;
 pub mod member_service;
+pub mod notification_gateway;
+pub mod notification_log;
+pub mod notification_preferences;
 pub mod notification_service;
+pub mod staff_service;
+pub mod tenant_directory;
+pub mod unit_of_work;
 
 // 明示的に型を再エクスポート（Result型の衝突を避けるため、グロブインポートを使わない）
-pub use book_service::BookService;
-pub use event_store::EventStore;
-pub use loan_read_model::{LoanReadModel, LoanStatus, LoanView};
-pub use member_service::MemberService;
-#[allow(unused_imports)] // 将来のAPI層で使用予定
-pub use notification_service::NotificationService;
+pub use book_service::{BookService, BookServiceError};
+pub use deferred_notices::{DeferredNotice, DeferredNoticeError, DeferredNoticeQueue};
+pub use errors::{Classified, ErrorClass};
+pub use event_archive::{EventArchive, EventArchiveError, EventPartition, PartitionArchival};
+pub use event_audit::{
+    BrokenLink, BrokenLinkReason, ChainHash, ChainHead, ChainVerification, EventAudit,
+    EventAuditError,
+};
+pub use event_bus::{EventBus, EventHandler, HandlerResult, PublishedEvent};
+pub use event_store::{AppendedEvent, EventStore, EventStoreError, StoredEvent};
+pub use idempotency_store::{IdempotencyClaim, IdempotencyStore, IdempotencyStoreError};
+pub use job_store::{JobLease, JobRun, JobRunStatus, JobStore, JobStoreError};
+pub use loan_read_model::{LoanReadModel, LoanReadModelError, LoanStatus, LoanView};
+pub use member_key_store::{MemberKeyStore, MemberKeyStoreError};
+pub use member_service::{MemberService, MemberServiceError};
+pub use notification_gateway::{Notice, NoticeBody, NotificationGateway, OutgoingMessage};
+pub use notification_log::{DeliveredNotice, NoticeKind, NotificationLog, NotificationLogError};
+pub use notification_preferences::{NotificationPreferenceError, NotificationPreferenceStore};
+pub use notification_service::{NotificationError, NotificationService};
+pub use staff_service::{StaffRole, StaffService, StaffServiceError};
+pub use tenant_directory::{TenantConfig, TenantDirectory, TenantDirectoryError};
+pub use unit_of_work::{UnitOfWork, UnitOfWorkError};
@@ -0,0 +1,82 @@
+use crate::domain::notification_preferences::{NotificationChannel, NotificationLanguage};
+use crate::domain::value_objects::{MemberId, OverdueNoticeLevel};
+use crate::ports::notification_log::NoticeKind;
+use crate::ports::notification_service::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// 通知の内容
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum NoticeBody {
+    /// 延滞の通知
+    Overdue { due_date: DateTime<Utc> },
+    /// 返却期限のリマインダー
+    DueSoonReminder { due_date: DateTime<Utc> },
+    /// 延滞の督促
+    OverdueNotice {
+        due_date: DateTime<Utc>,
+        level: OverdueNoticeLevel,
+    },
+    /// 紛失の通知
+    LostDeclaration { declared_at: DateTime<Utc> },
+    /// 延長の確認
+    ExtensionConfirmation { new_due_date: DateTime<Utc> },
+    /// 返却の確認
+    ReturnConfirmation { was_overdue: bool },
+}
+
+impl NoticeBody {
+    /// 通知の種類
+    pub fn kind(&self) -> NoticeKind {
+        match self {
+            NoticeBody::Overdue { .. } => NoticeKind::Overdue,
+            NoticeBody::DueSoonReminder { .. } => NoticeKind::DueSoonReminder,
+            NoticeBody::OverdueNotice { .. } => NoticeKind::OverdueNotice,
+            NoticeBody::LostDeclaration { .. } => NoticeKind::LostDeclaration,
+            NoticeBody::ExtensionConfirmation { .. } => NoticeKind::ExtensionConfirmation,
+            NoticeBody::ReturnConfirmation { .. } => NoticeKind::ReturnConfirmation,
+        }
+    }
+}
+
+/// 会員への1件の通知
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Notice {
+    /// 重複排除キー（`NotificationService`に渡されたもの）
+    pub dedup_key: String,
+    pub book_title: String,
+    pub body: NoticeBody,
+}
+
+/// 会員に送る1通のメッセージ
+///
+/// 通知が2件以上のメッセージはダイジェスト（まとめて送る通知）になる。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OutgoingMessage {
+    pub member_id: MemberId,
+    /// 送る手段（`None`にはならない）
+    pub channel: NotificationChannel,
+    pub language: NotificationLanguage,
+    /// 通知（発生した順）
+    pub notices: Vec<Notice>,
+}
+
+impl OutgoingMessage {
+    /// ダイジェストか
+    pub fn is_digest(&self) -> bool {
+        self.notices.len() > 1
+    }
+}
+
+/// 通知の配信手段ポート
+///
+/// 組み立てたメッセージを会員の選んだ手段（メール・アプリ内の通知）で、会員の言語で送る。
+/// メッセージは少なくとも1回送られるため、配信手段は通知の`dedup_key`で重複を除けるようにする。
+#[allow(dead_code)]
+#[async_trait]
+pub trait NotificationGateway: Send + Sync {
+    /// メッセージを送る
+    async fn deliver(&self, message: &OutgoingMessage) -> Result<()>;
+}
@@ -0,0 +1,95 @@
+use crate::ports::errors::{BoxError, Classified, ErrorClass};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use uuid::Uuid;
+
+#[allow(dead_code)]
+pub type Result<T> = std::result::Result<T, NotificationLogError>;
+
+/// 通知の配信記録のエラー
+#[derive(Debug, Error)]
+pub enum NotificationLogError {
+    /// 配信記録に接続できない（接続断・タイムアウトなど）
+    #[error("Notification log is unavailable: {0}")]
+    Unavailable(#[source] BoxError),
+
+    /// 予期しない障害（不正なデータ・バグなど）
+    #[error("Notification log failure: {0}")]
+    Internal(#[source] BoxError),
+}
+
+impl Classified for NotificationLogError {
+    fn class(&self) -> ErrorClass {
+        match self {
+            NotificationLogError::Unavailable(_) => ErrorClass::Transient,
+            _ => ErrorClass::Permanent,
+        }
+    }
+}
+
+/// 会員への通知の種類
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NoticeKind {
+    /// 延滞の通知（LoanBecameOverdue）
+    Overdue,
+    /// 返却期限のリマインダー（LoanDueSoonReminded）
+    DueSoonReminder,
+    /// 延滞の督促（OverdueNoticeSent）
+    OverdueNotice,
+    /// 紛失の通知（LoanDeclaredLost）
+    LostDeclaration,
+    /// 延長の確認（LoanExtended）
+    ExtensionConfirmation,
+    /// 返却の確認（BookReturned）
+    ReturnConfirmation,
+}
+
+impl NoticeKind {
+    /// 配信記録と重複排除キーに使う名前
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NoticeKind::Overdue => "overdue",
+            NoticeKind::DueSoonReminder => "due_soon_reminder",
+            NoticeKind::OverdueNotice => "overdue_notice",
+            NoticeKind::LostDeclaration => "lost_declaration",
+            NoticeKind::ExtensionConfirmation => "extension_confirmation",
+            NoticeKind::ReturnConfirmation => "return_confirmation",
+        }
+    }
+}
+
+/// 配信した通知の記録
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeliveredNotice {
+    /// 重複排除キー（同じ通知は常に同じキーになる）
+    pub dedup_key: String,
+    pub kind: NoticeKind,
+    /// 通知のきっかけになったイベント
+    pub event_id: Uuid,
+    pub delivered_at: DateTime<Utc>,
+}
+
+/// 通知の配信記録ポート
+///
+/// 配信した通知を重複排除キーで記録し、イベントを再処理しても
+/// 同じ通知を二度送らないようにする。
+/// 会員IDなどの個人情報は記録しない。
+#[allow(dead_code)]
+#[async_trait]
+pub trait NotificationLog: Send + Sync {
+    /// 重複排除キーの通知を配信済みか
+    async fn is_delivered(&self, dedup_key: &str) -> Result<bool>;
+
+    /// 通知を配信済みとして記録する
+    ///
+    /// 同じキーが既に記録されている場合は何もしない。
+    async fn record_delivered(&self, notice: &DeliveredNotice) -> Result<()>;
+
+    /// イベントをきっかけに配信した通知（配信日時順）
+    ///
+    /// 配信記録は会員IDを持たないため、会員データの写しには会員のイベントから辿って含める。
+    async fn find_by_events(&self, event_ids: &[Uuid]) -> Result<Vec<DeliveredNotice>>;
+}
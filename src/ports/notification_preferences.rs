@@ -0,0 +1,53 @@
+use crate::domain::{notification_preferences::NotificationPreferences, value_objects::MemberId};
+use crate::ports::errors::{BoxError, Classified, ErrorClass};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use thiserror::Error;
+
+#[allow(dead_code)]
+pub type Result<T> = std::result::Result<T, NotificationPreferenceError>;
+
+/// 通知設定ストアのエラー
+#[derive(Debug, Error)]
+pub enum NotificationPreferenceError {
+    /// 通知設定ストアに接続できない（接続断・タイムアウトなど）
+    #[error("Notification preference store is unavailable: {0}")]
+    Unavailable(#[source] BoxError),
+
+    /// 予期しない障害（不正なデータ・バグなど）
+    #[error("Notification preference store failure: {0}")]
+    Internal(#[source] BoxError),
+}
+
+impl Classified for NotificationPreferenceError {
+    fn class(&self) -> ErrorClass {
+        match self {
+            NotificationPreferenceError::Unavailable(_) => ErrorClass::Transient,
+            _ => ErrorClass::Permanent,
+        }
+    }
+}
+
+/// 会員の通知設定ストアポート
+///
+/// 会員ごとの通知の受け取り方（手段・言語・通知を送らない時間帯・まとめ方）を保持する。
+/// 設定のない会員には`NotificationPreferences::default()`が適用される。
+#[allow(dead_code)]
+#[async_trait]
+pub trait NotificationPreferenceStore: Send + Sync {
+    /// 会員の通知設定を取得する（設定していなければNone）
+    async fn get(&self, member_id: MemberId) -> Result<Option<NotificationPreferences>>;
+
+    /// 会員の通知設定を保存する（既存の設定は置き換える）
+    async fn save(
+        &self,
+        member_id: MemberId,
+        preferences: &NotificationPreferences,
+        updated_at: DateTime<Utc>,
+    ) -> Result<()>;
+
+    /// 会員の通知設定を削除する（削除請求）
+    ///
+    /// 会員の保留中の通知も送られなくなる。
+    async fn delete(&self, member_id: MemberId) -> Result<()>;
+}
@@ -1,14 +1,45 @@
-use crate::domain::value_objects::MemberId;
+use crate::domain::value_objects::{MemberId, OverdueNoticeLevel};
+use crate::ports::errors::{BoxError, Classified, ErrorClass};
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
+use thiserror::Error;
 
 #[allow(dead_code)]
-pub type Result<T> = std::result::Result<T, Box<dyn std::error::Error + Send + Sync>>;
+pub type Result<T> = std::result::Result<T, NotificationError>;
+
+/// 通知サービスのエラー
+#[derive(Debug, Error)]
+pub enum NotificationError {
+    /// 通知の配信手段に接続できない（接続断・タイムアウトなど）
+    #[error("Notification service is unavailable: {0}")]
+    Unavailable(#[source] BoxError),
+
+    /// 通知が受け付けられなかった（宛先が不正など）
+    #[error("Notification was rejected: {0}")]
+    Rejected(String),
+
+    /// 予期しない障害（バグなど）
+    #[error("Notification service failure: {0}")]
+    Internal(#[source] BoxError),
+}
+
+impl Classified for NotificationError {
+    fn class(&self) -> ErrorClass {
+        match self {
+            NotificationError::Unavailable(_) => ErrorClass::Transient,
+            _ => ErrorClass::Permanent,
+        }
+    }
+}
 
 /// 通知サービスポート
 ///
 /// 会員への通知配信メカニズムを抽象化する。
 /// 実装はメール、SMS、プッシュ通知などが考えられる。
+///
+/// 通知は少なくとも1回配信される（送信後に記録できなかった通知は再送される）。
+/// `dedup_key`は通知ごとに一意で、同じ通知の再送では同じ値になるため、
+/// 配信手段が重複を除けるように宛先側に渡す（メールのMessage-IDなど）。
 #[allow(dead_code)]
 #[async_trait]
 pub trait NotificationService: Send + Sync {
@@ -17,16 +48,52 @@ pub trait NotificationService: Send + Sync {
     /// LoanBecameOverdueイベント処理時に呼ばれる。
     async fn send_overdue_notification(
         &self,
+        dedup_key: &str,
+        member_id: MemberId,
+        book_title: &str,
+        due_date: DateTime<Utc>,
+    ) -> Result<()>;
+
+    /// 返却期限が近いことを会員に知らせる
+    ///
+    /// LoanDueSoonRemindedイベント処理時に呼ばれる。
+    async fn send_due_soon_reminder(
+        &self,
+        dedup_key: &str,
         member_id: MemberId,
         book_title: &str,
         due_date: DateTime<Utc>,
     ) -> Result<()>;
 
+    /// 延滞の督促を会員に送信する
+    ///
+    /// OverdueNoticeSentイベント処理時に呼ばれる。段階が進むほど強い文面にする。
+    async fn send_overdue_notice(
+        &self,
+        dedup_key: &str,
+        member_id: MemberId,
+        book_title: &str,
+        due_date: DateTime<Utc>,
+        level: OverdueNoticeLevel,
+    ) -> Result<()>;
+
+    /// 貸出を紛失として扱ったことを会員に知らせる
+    ///
+    /// LoanDeclaredLostイベント処理時に呼ばれる。
+    async fn send_lost_declaration(
+        &self,
+        dedup_key: &str,
+        member_id: MemberId,
+        book_title: &str,
+        declared_at: DateTime<Utc>,
+    ) -> Result<()>;
+
     /// 延長確認通知を会員に送信する
     ///
     /// LoanExtendedイベント処理時に呼ばれる。
     async fn send_extension_confirmation(
         &self,
+        dedup_key: &str,
         member_id: MemberId,
         book_title: &str,
         new_due_date: DateTime<Utc>,
@@ -37,6 +104,7 @@ pub trait NotificationService: Send + Sync {
     /// BookReturnedイベント処理時に呼ばれる。
     async fn send_return_confirmation(
         &self,
+        dedup_key: &str,
         member_id: MemberId,
         book_title: &str,
         was_overdue: bool,
@@ -0,0 +1,57 @@
+use crate::domain::value_objects::StaffId;
+use crate::ports::errors::{BoxError, Classified, ErrorClass};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+#[allow(dead_code)]
+pub type Result<T> = std::result::Result<T, StaffServiceError>;
+
+/// 職員サービスのエラー
+#[derive(Debug, Error)]
+pub enum StaffServiceError {
+    /// 職員サービスに接続できない（接続断・タイムアウトなど）
+    #[error("Staff service is unavailable: {0}")]
+    Unavailable(#[source] BoxError),
+
+    /// 予期しない障害（不正な応答・バグなど）
+    #[error("Staff service failure: {0}")]
+    Internal(#[source] BoxError),
+}
+
+impl Classified for StaffServiceError {
+    fn class(&self) -> ErrorClass {
+        match self {
+            StaffServiceError::Unavailable(_) => ErrorClass::Transient,
+            _ => ErrorClass::Permanent,
+        }
+    }
+}
+
+/// 職員の役割
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StaffRole {
+    /// カウンター担当
+    CounterClerk,
+    /// 監督者（訂正・例外の承認ができる）
+    Supervisor,
+    /// システム管理者
+    Administrator,
+    /// セルフサービス端末
+    Kiosk,
+}
+
+/// 職員サービスポート
+///
+/// 貸出コンテキストと職員管理の境界を維持する。
+/// 貸出コンテキストはStaffIDと役割のみを知り、職員の詳細は知らない。
+#[allow(dead_code)]
+#[async_trait]
+pub trait StaffService: Send + Sync {
+    /// 職員の役割を取得する
+    ///
+    /// 職員が存在しない（または無効化されている）場合は`None`を返す。
+    /// コマンドの認可に使用される。
+    async fn roles(&self, staff_id: StaffId) -> Result<Option<Vec<StaffRole>>>;
+}
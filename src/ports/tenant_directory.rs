@@ -0,0 +1,55 @@
+use crate::domain::{CirculationPolicy, value_objects::TenantId};
+use crate::ports::errors::{BoxError, Classified, ErrorClass};
+use async_trait::async_trait;
+use thiserror::Error;
+
+#[allow(dead_code)]
+pub type Result<T> = std::result::Result<T, TenantDirectoryError>;
+
+/// テナントディレクトリのエラー
+#[derive(Debug, Error)]
+pub enum TenantDirectoryError {
+    /// テナントディレクトリに接続できない（接続断・タイムアウトなど）
+    #[error("Tenant directory is unavailable: {0}")]
+    Unavailable(#[source] BoxError),
+
+    /// 登録されている設定が不正（貸出ポリシーの値が範囲外など）
+    #[error("Tenant configuration is corrupted: {0}")]
+    Corrupted(#[source] BoxError),
+
+    /// 予期しない障害（バグなど）
+    #[error("Tenant directory failure: {0}")]
+    Internal(#[source] BoxError),
+}
+
+impl Classified for TenantDirectoryError {
+    fn class(&self) -> ErrorClass {
+        match self {
+            TenantDirectoryError::Unavailable(_) => ErrorClass::Transient,
+            _ => ErrorClass::Permanent,
+        }
+    }
+}
+
+/// テナント（図書館）の設定
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TenantConfig {
+    pub tenant_id: TenantId,
+    /// 表示名（例: "○○市立図書館"）
+    pub name: String,
+    /// テナント解決に使用するサブドメイン（例: "minato" → minato.library.example.jp）
+    pub subdomain: String,
+    /// 館ごとの貸出ポリシー
+    pub policy: CirculationPolicy,
+}
+
+/// テナントディレクトリポート
+///
+/// デプロイメントを共有する図書館の一覧と、館ごとの貸出ポリシーを提供する。
+/// 起動時にテナントごとの依存関係を構築するために使用される。
+#[allow(dead_code)]
+#[async_trait]
+pub trait TenantDirectory: Send + Sync {
+    /// 登録されているすべてのテナントを取得する
+    async fn list_tenants(&self) -> Result<Vec<TenantConfig>>;
+}
@@ -0,0 +1,59 @@
+use crate::domain::events::DomainEvent;
+use async_trait::async_trait;
+use thiserror::Error;
+use uuid::Uuid;
+
+use super::errors::{Classified, ErrorClass};
+use super::event_store::{AppendedEvent, EventStoreError};
+use super::loan_read_model::{LoanReadModelError, LoanView};
+
+#[allow(dead_code)]
+pub type Result<T> = std::result::Result<T, UnitOfWorkError>;
+
+/// ユニットオブワークのエラー
+///
+/// どちらで失敗した場合も、イベントとRead Modelのいずれも更新されていない。
+#[derive(Debug, Error)]
+pub enum UnitOfWorkError {
+    #[error(transparent)]
+    EventStore(#[from] EventStoreError),
+
+    #[error(transparent)]
+    ReadModel(#[from] LoanReadModelError),
+}
+
+impl Classified for UnitOfWorkError {
+    fn class(&self) -> ErrorClass {
+        match self {
+            UnitOfWorkError::EventStore(e) => e.class(),
+            UnitOfWorkError::ReadModel(e) => e.class(),
+        }
+    }
+}
+
+/// ユニットオブワークポート
+///
+/// 集約のイベントの追加とRead Modelの更新を1つの単位として実行する。
+/// 実装は両方をコミットするか、どちらもコミットしないかのいずれかを保証する。
+///
+/// イベントストアとRead Modelが同じトランザクションを共有できない構成では
+/// 実装を用意せず、アプリケーション層がイベントの追加とRead Modelの更新を
+/// 個別に行う（結果整合性）。
+#[allow(dead_code)]
+#[async_trait]
+pub trait UnitOfWork: Send + Sync {
+    /// イベントを追加し、貸出ビューを保存する
+    ///
+    /// どちらかが失敗した場合はどちらも反映されない。
+    /// 集約が`expected_version`でなければ`EventStoreError::VersionConflict`となる
+    /// （`EventStore::append_with_expected_version`を参照）。
+    /// 追加したイベントのイベントIDとシーケンス番号を、追加した順に返す。
+    async fn commit(
+        &self,
+        aggregate_id: Uuid,
+        aggregate_type: &str,
+        expected_version: u32,
+        events: Vec<DomainEvent>,
+        loan_view: LoanView,
+    ) -> Result<Vec<AppendedEvent>>;
+}
@@ -0,0 +1,320 @@
+mod common;
+
+use async_trait::async_trait;
+use chrono::Utc;
+use rusty_library_ddd::adapters::in_memory::InMemoryEventBus;
+use rusty_library_ddd::adapters::mock::{
+    BookService, DeferredNoticeQueue, MemberService, NotificationLog, NotificationPreferenceStore,
+    NotificationService, StaffService,
+};
+use rusty_library_ddd::adapters::postgres::{
+    PostgresEventStore, PostgresIdempotencyStore, PostgresLoanReadModel, PostgresUnitOfWork,
+};
+use rusty_library_ddd::application::command_bus::{
+    CommandBus, CommandEnvelope, CommandMetrics, IdempotencyMiddleware, Middleware, Next,
+    RetryMiddleware,
+};
+use rusty_library_ddd::application::loan::{LoanApplicationError, ServiceDependencies};
+use rusty_library_ddd::domain::CirculationPolicy;
+use rusty_library_ddd::domain::commands::{ExtendLoan, LoanBook, ReturnBook};
+use rusty_library_ddd::domain::value_objects::{BookId, LoanId, MemberId, StaffId};
+use rusty_library_ddd::ports::{EventStoreError, IdempotencyClaim, StaffRole};
+use sqlx::PgPool;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::time::Duration;
+
+/// 会員・貸出可能な書籍・カウンター担当を登録したテナントの依存関係
+async fn setup(pool: &PgPool) -> (ServiceDependencies, LoanBook, Arc<StaffService>) {
+    let tenant_id = common::insert_tenant(pool).await;
+    let member_service = Arc::new(MemberService::new());
+    let book_service = Arc::new(BookService::new());
+    let staff_service = Arc::new(StaffService::new());
+    let member_id = MemberId::new();
+    let book_id = BookId::new();
+    let staff_id = StaffId::new();
+    member_service.add_member(member_id);
+    book_service.add_available_book(book_id);
+    staff_service.add_staff(staff_id, [StaffRole::CounterClerk]);
+
+    let deps = ServiceDependencies {
+        tenant_id,
+        policy: CirculationPolicy::default(),
+        event_store: Arc::new(PostgresEventStore::for_tenant(pool.clone(), tenant_id)),
+        loan_read_model: Arc::new(PostgresLoanReadModel::for_tenant(pool.clone(), tenant_id)),
+        unit_of_work: Some(Arc::new(PostgresUnitOfWork::for_tenant(
+            pool.clone(),
+            tenant_id,
+        ))),
+        member_service,
+        book_service,
+        staff_service: staff_service.clone(),
+        notification_service: Arc::new(NotificationService::new()),
+        notification_log: Arc::new(NotificationLog::new()),
+        notification_preferences: Arc::new(NotificationPreferenceStore::new()),
+        deferred_notices: Arc::new(DeferredNoticeQueue::new()),
+        event_bus: Arc::new(InMemoryEventBus::synchronous()),
+        idempotency_keys: Arc::new(PostgresIdempotencyStore::for_tenant(
+            pool.clone(),
+            tenant_id,
+        )),
+    };
+    let cmd = LoanBook {
+        book_id,
+        member_id,
+        loaned_at: Utc::now(),
+        staff_id,
+    };
+    (deps, cmd, staff_service)
+}
+
+/// 最初の数回だけ、コマンドを実行せずに書き込みの競合を返すミドルウェア
+struct FailWithConflict {
+    remaining: AtomicU32,
+    calls: Arc<AtomicU32>,
+}
+
+#[async_trait]
+impl Middleware for FailWithConflict {
+    async fn handle(
+        &self,
+        deps: &ServiceDependencies,
+        envelope: CommandEnvelope,
+        next: Next<'_>,
+    ) -> Result<LoanId, LoanApplicationError> {
+        self.calls.fetch_add(1, Ordering::SeqCst);
+        if self
+            .remaining
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1))
+            .is_ok()
+        {
+            return Err(LoanApplicationError::EventStoreError(
+                EventStoreError::VersionConflict {
+                    aggregate_id: LoanId::new().value(),
+                    expected: 0,
+                    actual: 1,
+                },
+            ));
+        }
+        next.run(deps, envelope).await
+    }
+}
+
+#[tokio::test]
+async fn test_standard_bus_runs_commands_once_per_idempotency_key() {
+    let pool = common::create_test_pool().await;
+    let (deps, cmd, _) = setup(&pool).await;
+    let metrics = Arc::new(CommandMetrics::default());
+    let bus = CommandBus::standard(metrics.clone());
+
+    // 同じキーでの再送は、最初の結果を返し、貸出を二重に作成しない
+    let envelope = CommandEnvelope::new(cmd.clone()).with_idempotency_key("checkout-1");
+    let loan_id = bus.dispatch(&deps, envelope.clone()).await.unwrap();
+    let resent = bus.dispatch(&deps, envelope).await.unwrap();
+    assert_eq!(resent, loan_id);
+    assert_eq!(
+        deps.event_store
+            .load(loan_id.value(), "Loan")
+            .await
+            .unwrap()
+            .len(),
+        1
+    );
+
+    // 同じキーを別のコマンドに使うことはできない
+    let extend = CommandEnvelope::new(ExtendLoan {
+        loan_id,
+        extended_at: Utc::now(),
+    })
+    .with_actor(cmd.staff_id)
+    .with_idempotency_key("checkout-1");
+    assert!(matches!(
+        bus.dispatch(&deps, extend).await,
+        Err(LoanApplicationError::IdempotencyKeyReused)
+    ));
+
+    // 実行前の検証で拒否されたコマンドも失敗として集計される
+    let mut future = cmd.clone();
+    future.loaned_at = Utc::now() + chrono::Duration::days(1);
+    assert!(matches!(
+        bus.dispatch(&deps, CommandEnvelope::new(future)).await,
+        Err(LoanApplicationError::InvalidCommand(_))
+    ));
+
+    let stats = metrics.snapshot()["LoanBook"];
+    assert_eq!((stats.succeeded, stats.failed), (2, 1));
+    assert_eq!(metrics.snapshot()["ExtendLoan"].failed, 1);
+}
+
+#[tokio::test]
+async fn test_write_conflicts_are_retried_by_the_pipeline() {
+    let pool = common::create_test_pool().await;
+    let (deps, cmd, _) = setup(&pool).await;
+    let calls = Arc::new(AtomicU32::new(0));
+
+    let bus = CommandBus::new()
+        .with(RetryMiddleware::new(3, Duration::from_millis(1)))
+        .with(FailWithConflict {
+            remaining: AtomicU32::new(2),
+            calls: calls.clone(),
+        });
+    let loan_id = bus
+        .dispatch(&deps, CommandEnvelope::new(cmd.clone()))
+        .await
+        .unwrap();
+    assert_eq!(calls.load(Ordering::SeqCst), 3);
+    assert!(
+        deps.loan_read_model
+            .get_by_id(loan_id)
+            .await
+            .unwrap()
+            .is_some()
+    );
+
+    // 上限を超えた競合はそのまま返す
+    let calls = Arc::new(AtomicU32::new(0));
+    let bus = CommandBus::new()
+        .with(RetryMiddleware::new(2, Duration::from_millis(1)))
+        .with(FailWithConflict {
+            remaining: AtomicU32::new(5),
+            calls: calls.clone(),
+        });
+    let result = bus.dispatch(&deps, CommandEnvelope::new(cmd)).await;
+    assert!(matches!(
+        result,
+        Err(LoanApplicationError::EventStoreError(
+            EventStoreError::VersionConflict { .. }
+        ))
+    ));
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn test_failed_commands_can_be_retried_with_the_same_key() {
+    let pool = common::create_test_pool().await;
+    let (deps, cmd, _) = setup(&pool).await;
+
+    // 冪等性の内側で失敗したコマンドは記録されない
+    let bus = CommandBus::new()
+        .with(IdempotencyMiddleware::default())
+        .with(FailWithConflict {
+            remaining: AtomicU32::new(1),
+            calls: Arc::new(AtomicU32::new(0)),
+        });
+    let envelope = CommandEnvelope::new(cmd).with_idempotency_key("checkout-2");
+    assert!(bus.dispatch(&deps, envelope.clone()).await.is_err());
+
+    let loan_id = bus.dispatch(&deps, envelope.clone()).await.unwrap();
+    assert_eq!(bus.dispatch(&deps, envelope).await.unwrap(), loan_id);
+}
+
+#[tokio::test]
+async fn test_idempotency_keys_are_shared_between_instances() {
+    let pool = common::create_test_pool().await;
+    let (deps, cmd, _) = setup(&pool).await;
+    let envelope = CommandEnvelope::new(cmd.clone()).with_idempotency_key("checkout-3");
+
+    // 別のインスタンス（再起動後を含む）のバスへの再送も、最初の結果を返す
+    let first = CommandBus::standard(Arc::new(CommandMetrics::default()));
+    let loan_id = first.dispatch(&deps, envelope.clone()).await.unwrap();
+    let second = CommandBus::standard(Arc::new(CommandMetrics::default()));
+    assert_eq!(second.dispatch(&deps, envelope).await.unwrap(), loan_id);
+    assert_eq!(
+        deps.event_store
+            .load(loan_id.value(), "Loan")
+            .await
+            .unwrap()
+            .len(),
+        1
+    );
+
+    // 別のインスタンスで実行中のキーは拒否する（完了すれば再送で結果が返る）
+    let now = Utc::now();
+    let fingerprint = format!("ExtendLoan:{}", loan_id.value());
+    let claim = deps
+        .idempotency_keys
+        .claim("extend-1", &fingerprint, now, now, now)
+        .await
+        .unwrap();
+    assert_eq!(claim, IdempotencyClaim::Claimed);
+    let extend = CommandEnvelope::new(ExtendLoan {
+        loan_id,
+        extended_at: Utc::now(),
+    })
+    .with_actor(cmd.staff_id)
+    .with_idempotency_key("extend-1");
+    assert!(matches!(
+        second.dispatch(&deps, extend.clone()).await,
+        Err(LoanApplicationError::IdempotencyKeyInProgress)
+    ));
+
+    // 実行中のまま放置されたキーは破棄され、再送を実行する
+    let bus = CommandBus::new().with(IdempotencyMiddleware::new(
+        Duration::from_secs(60),
+        Duration::ZERO,
+    ));
+    assert_eq!(bus.dispatch(&deps, extend).await.unwrap(), loan_id);
+}
+
+#[tokio::test]
+async fn test_standard_bus_authorizes_commands_by_staff_role() {
+    let pool = common::create_test_pool().await;
+    let (deps, cmd, staff_service) = setup(&pool).await;
+    let bus = CommandBus::standard(Arc::new(CommandMetrics::default()));
+    let clerk = cmd.staff_id;
+    let loan_id = bus
+        .dispatch(&deps, CommandEnvelope::new(cmd))
+        .await
+        .unwrap();
+
+    // 実行者が不明なコマンド、登録されていない職員のコマンドは拒否される
+    let extend = ExtendLoan {
+        loan_id,
+        extended_at: Utc::now(),
+    };
+    for envelope in [
+        CommandEnvelope::new(extend.clone()),
+        CommandEnvelope::new(extend.clone()).with_actor(StaffId::new()),
+    ] {
+        assert!(matches!(
+            bus.dispatch(&deps, envelope).await,
+            Err(LoanApplicationError::Forbidden(_))
+        ));
+    }
+
+    // 他の職員の名前で貸し出すことはできない
+    let other_loan = LoanBook {
+        book_id: BookId::new(),
+        member_id: MemberId::new(),
+        loaned_at: Utc::now(),
+        staff_id: StaffId::new(),
+    };
+    assert!(matches!(
+        bus.dispatch(&deps, CommandEnvelope::new(other_loan).with_actor(clerk))
+            .await,
+        Err(LoanApplicationError::Forbidden(_))
+    ));
+
+    // 数日前の返却を記録する（訂正）には監督者が必要
+    let backdated = ReturnBook {
+        loan_id,
+        returned_at: Utc::now() - chrono::Duration::days(2),
+    };
+    assert!(matches!(
+        bus.dispatch(
+            &deps,
+            CommandEnvelope::new(backdated.clone()).with_actor(clerk)
+        )
+        .await,
+        Err(LoanApplicationError::Forbidden(_))
+    ));
+
+    let supervisor = StaffId::new();
+    staff_service.add_staff(supervisor, [StaffRole::Supervisor]);
+    bus.dispatch(
+        &deps,
+        CommandEnvelope::new(backdated).with_actor(supervisor),
+    )
+    .await
+    .unwrap();
+}
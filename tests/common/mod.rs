@@ -1,3 +1,4 @@
+use rusty_library_ddd::domain::value_objects::TenantId;
 use sqlx::PgPool;
 
 /// テスト用データベースプールを作成し、マイグレーションを実行
@@ -22,3 +23,26 @@ pub async fn create_test_pool() -> PgPool {
 
     pool
 }
+
+/// テスト用のテナントのサブドメイン（テナントIDから一意に決まる）
+#[allow(dead_code)]
+pub fn tenant_subdomain(tenant_id: TenantId) -> String {
+    format!("lib-{}", tenant_id.value().simple())
+}
+
+/// テスト用のテナントを登録
+///
+/// イベントストア・リードモデル・会員の鍵などはテナント単位で分離されるため、
+/// テストごとに新しいテナントを使えば、他のテストのデータと干渉しない。
+#[allow(dead_code)]
+pub async fn insert_tenant(pool: &PgPool) -> TenantId {
+    let tenant_id = TenantId::new();
+    sqlx::query("INSERT INTO tenants (tenant_id, name, subdomain) VALUES ($1, $2, $3)")
+        .bind(tenant_id.value())
+        .bind("Test Library")
+        .bind(tenant_subdomain(tenant_id))
+        .execute(pool)
+        .await
+        .expect("Failed to insert tenant");
+    tenant_id
+}
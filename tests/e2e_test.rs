@@ -1,12 +1,24 @@
 use axum::body::Body;
-use axum::http::{Request, StatusCode};
-use rusty_library_ddd::adapters::mock::{BookService, MemberService};
-use rusty_library_ddd::adapters::postgres::{PostgresEventStore, PostgresLoanReadModel};
-use rusty_library_ddd::api::handlers::AppState;
+use axum::http::{Request, StatusCode, header};
+use rusty_library_ddd::adapters::in_memory::InMemoryEventBus;
+use rusty_library_ddd::adapters::mock::{
+    BookService, DeferredNoticeQueue, IdempotencyStore, MemberService, NotificationLog,
+    NotificationPreferenceStore, NotificationService, StaffService,
+};
+use rusty_library_ddd::adapters::postgres::{
+    PostgresEventStore, PostgresLoanReadModel, PostgresUnitOfWork,
+};
+use rusty_library_ddd::api::handlers::{APPROVER_HEADER, AppState, MEMBER_HEADER, STAFF_HEADER};
 use rusty_library_ddd::api::router::create_router;
+use rusty_library_ddd::api::tenant::{TENANT_HEADER, TenantRegistry};
 use rusty_library_ddd::api::types::*;
-use rusty_library_ddd::application::loan::ServiceDependencies;
+use rusty_library_ddd::application::loan::{ServiceDependencies, loan_book};
+use rusty_library_ddd::domain::commands::LoanBook;
 use rusty_library_ddd::domain::value_objects::*;
+use rusty_library_ddd::domain::{
+    CirculationPolicy, DigestMode, EligibilityRule, NotificationChannel, NotificationLanguage,
+};
+use rusty_library_ddd::ports::{LoanStatus, StaffRole};
 use serde_json::json;
 use serial_test::serial;
 use sqlx::PgPool;
@@ -38,17 +50,51 @@ async fn setup_e2e_app(
     let loan_read_model = Arc::new(PostgresLoanReadModel::new(pool.clone()));
 
     let service_deps = ServiceDependencies {
+        tenant_id: TenantId::DEFAULT,
+        policy: CirculationPolicy::default(),
         event_store,
         loan_read_model,
+        unit_of_work: Some(Arc::new(PostgresUnitOfWork::new(pool.clone()))),
         member_service,
         book_service,
+        staff_service: staff_service(),
+        notification_service: Arc::new(NotificationService::new()),
+        notification_log: Arc::new(NotificationLog::new()),
+        notification_preferences: Arc::new(NotificationPreferenceStore::new()),
+        deferred_notices: Arc::new(DeferredNoticeQueue::new()),
+        event_bus: Arc::new(InMemoryEventBus::synchronous()),
+        idempotency_keys: Arc::new(IdempotencyStore::new()),
     };
 
-    let app_state = Arc::new(AppState { service_deps });
+    let app_state = Arc::new(AppState::single_tenant(service_deps));
 
     create_router(app_state)
 }
 
+/// E2Eテストでコマンドを実行するカウンター担当
+fn counter_clerk() -> StaffId {
+    StaffId::from_uuid(uuid::Uuid::from_u128(0xc1e4c))
+}
+
+/// E2Eテストで貸出条件の例外を承認する監督者
+fn supervisor() -> StaffId {
+    StaffId::from_uuid(uuid::Uuid::from_u128(0x5e4e))
+}
+
+/// E2Eテストで一括処理を実行する管理者
+fn administrator() -> StaffId {
+    StaffId::from_uuid(uuid::Uuid::from_u128(0xad41))
+}
+
+/// カウンター担当・監督者・管理者を登録した職員サービス
+fn staff_service() -> Arc<StaffService> {
+    let staff_service = Arc::new(StaffService::new());
+    staff_service.add_staff(counter_clerk(), [StaffRole::CounterClerk]);
+    staff_service.add_staff(supervisor(), [StaffRole::Supervisor]);
+    staff_service.add_staff(administrator(), [StaffRole::Administrator]);
+    staff_service
+}
+
 /// データベースのクリーンアップ
 ///
 /// テストの独立性を保つため、各テスト前にすべてのデータを削除します。
@@ -62,6 +108,11 @@ async fn cleanup_database(pool: &PgPool) {
         .execute(pool)
         .await
         .expect("Failed to truncate events");
+
+    sqlx::query("TRUNCATE TABLE reading_history_opt_ins")
+        .execute(pool)
+        .await
+        .expect("Failed to truncate reading_history_opt_ins");
 }
 
 /// テスト用のメンバーと本をセットアップ
@@ -99,7 +150,7 @@ async fn test_e2e_full_loan_flow() {
     let loan_request = json!({
         "book_id": book_id.value(),
         "member_id": member_id.value(),
-        "staff_id": StaffId::new().value(),
+        "staff_id": counter_clerk().value(),
     });
 
     let response = app
@@ -144,7 +195,7 @@ async fn test_e2e_full_loan_flow() {
     let loan_view: LoanResponse = serde_json::from_slice(&body).unwrap();
     assert_eq!(loan_view.loan_id, loan_id);
     assert_eq!(loan_view.book_id, book_id.value());
-    assert_eq!(loan_view.member_id, member_id.value());
+    assert_eq!(loan_view.member_id, Some(member_id.value()));
     assert_eq!(loan_view.status, "active");
     assert_eq!(loan_view.extension_count, 0);
 
@@ -155,6 +206,7 @@ async fn test_e2e_full_loan_flow() {
             Request::builder()
                 .method("POST")
                 .uri(format!("/loans/{}/extend", loan_id))
+                .header(STAFF_HEADER, counter_clerk().value().to_string())
                 .header("content-type", "application/json")
                 .body(Body::empty())
                 .unwrap(),
@@ -196,6 +248,7 @@ async fn test_e2e_full_loan_flow() {
             Request::builder()
                 .method("POST")
                 .uri(format!("/loans/{}/return", loan_id))
+                .header(STAFF_HEADER, counter_clerk().value().to_string())
                 .header("content-type", "application/json")
                 .body(Body::empty())
                 .unwrap(),
@@ -236,6 +289,65 @@ async fn test_e2e_full_loan_flow() {
 // E2Eテスト: エラーケース
 // ============================================================================
 
+#[tokio::test]
+#[serial]
+async fn test_e2e_idempotent_loan_creation() {
+    let pool = common::create_test_pool().await;
+    let member_service = Arc::new(MemberService::new());
+    let book_service = Arc::new(BookService::new());
+    let (member_id, book_id) = setup_test_entities(&member_service, &book_service);
+    let app = setup_e2e_app(&pool, member_service, book_service).await;
+
+    let loan_request = json!({
+        "book_id": book_id.value(),
+        "member_id": member_id.value(),
+        "staff_id": counter_clerk().value(),
+    });
+    let post = |key: &str| {
+        Request::builder()
+            .method("POST")
+            .uri("/loans")
+            .header("content-type", "application/json")
+            .header("idempotency-key", key)
+            .body(Body::from(serde_json::to_string(&loan_request).unwrap()))
+            .unwrap()
+    };
+
+    // 同じキーでの再送は同じ貸出を返し、貸出は1件だけ作成される
+    let mut loan_ids = Vec::new();
+    for _ in 0..2 {
+        let response = app.clone().oneshot(post("counter-1-0001")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let created: LoanCreatedResponse = serde_json::from_slice(&body).unwrap();
+        loan_ids.push(created.loan_id);
+    }
+    assert_eq!(loan_ids[0], loan_ids[1]);
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("GET")
+                .uri(format!("/loans?member_id={}", member_id.value()))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let loans: Vec<LoanResponse> = serde_json::from_slice(&body).unwrap();
+    assert_eq!(loans.len(), 1);
+
+    // 不正なキーは実行前に拒否される
+    let response = app.oneshot(post("not a key")).await.unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
 #[tokio::test]
 #[serial]
 async fn test_e2e_loan_member_not_found() {
@@ -254,7 +366,7 @@ async fn test_e2e_loan_member_not_found() {
     let loan_request = json!({
         "book_id": book_id.value(),
         "member_id": member_id.value(),
-        "staff_id": StaffId::new().value(),
+        "staff_id": counter_clerk().value(),
     });
 
     // Act
@@ -298,7 +410,7 @@ async fn test_e2e_loan_book_not_available() {
     let loan_request = json!({
         "book_id": book_id.value(),
         "member_id": member_id.value(),
-        "staff_id": StaffId::new().value(),
+        "staff_id": counter_clerk().value(),
     });
 
     // Act
@@ -342,6 +454,7 @@ async fn test_e2e_extend_loan_not_found() {
             Request::builder()
                 .method("POST")
                 .uri(format!("/loans/{}/extend", non_existent_loan_id.value()))
+                .header(STAFF_HEADER, counter_clerk().value().to_string())
                 .header("content-type", "application/json")
                 .body(Body::empty())
                 .unwrap(),
@@ -371,6 +484,7 @@ async fn test_e2e_return_loan_not_found() {
             Request::builder()
                 .method("POST")
                 .uri(format!("/loans/{}/return", non_existent_loan_id.value()))
+                .header(STAFF_HEADER, counter_clerk().value().to_string())
                 .header("content-type", "application/json")
                 .body(Body::empty())
                 .unwrap(),
@@ -382,212 +496,1270 @@ async fn test_e2e_return_loan_not_found() {
     assert_eq!(response.status(), StatusCode::NOT_FOUND);
 }
 
-// ============================================================================
-// E2Eテスト: クエリエンドポイント
-// ============================================================================
-
 #[tokio::test]
 #[serial]
-async fn test_e2e_list_loans_by_member() {
-    // Arrange: 複数の貸出を作成
+async fn test_e2e_member_id_is_not_a_loan() {
+    // Arrange: 会員IDを集約IDとするイベントを記録する
     let pool = common::create_test_pool().await;
-
     let member_service = Arc::new(MemberService::new());
     let book_service = Arc::new(BookService::new());
     let member_id = MemberId::new();
     member_service.add_member(member_id);
+    let app = setup_e2e_app(&pool, member_service, book_service).await;
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("PUT")
+                .uri(format!("/members/{}/reading-history", member_id.value()))
+                .header(MEMBER_HEADER, member_id.value().to_string())
+                .header("content-type", "application/json")
+                .body(Body::from(json!({ "keep_history": true }).to_string()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
 
-    let app = setup_e2e_app(&pool, member_service.clone(), book_service.clone()).await;
-
-    // 3冊借りる
-    let mut loan_ids = Vec::new();
-    for _ in 0..3 {
-        let book_id = BookId::new();
-        book_service.add_available_book(book_id);
-
-        let loan_request = json!({
-            "book_id": book_id.value(),
-            "member_id": member_id.value(),
-            "staff_id": StaffId::new().value(),
-        });
-
+    // Act & Assert: 会員IDを貸出IDとして延長・返却しても貸出は見つからない
+    for action in ["extend", "return"] {
         let response = app
             .clone()
             .oneshot(
                 Request::builder()
                     .method("POST")
-                    .uri("/loans")
+                    .uri(format!("/loans/{}/{}", member_id.value(), action))
+                    .header(STAFF_HEADER, counter_clerk().value().to_string())
                     .header("content-type", "application/json")
-                    .body(Body::from(serde_json::to_string(&loan_request).unwrap()))
+                    .body(Body::empty())
                     .unwrap(),
             )
             .await
             .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND, "{action}");
+    }
+}
+
+// ============================================================================
+// E2Eテスト: クエリエンドポイント
+// ============================================================================
+
+#[tokio::test]
+#[serial]
+async fn test_e2e_commands_require_authorized_staff() {
+    // Arrange
+    let pool = common::create_test_pool().await;
+    let member_service = Arc::new(MemberService::new());
+    let book_service = Arc::new(BookService::new());
+    let app = setup_e2e_app(&pool, member_service, book_service).await;
+    let loan_id = LoanId::new();
+
+    // Act & Assert: 実行者が不明、または登録されていない職員の延長・返却は拒否される
+    for (action, staff) in [
+        ("extend", None),
+        ("return", None),
+        ("return", Some(StaffId::new().value().to_string())),
+    ] {
+        let mut request =
+            Request::builder()
+                .method("POST")
+                .uri(format!("/loans/{}/{}", loan_id.value(), action));
+        if let Some(staff) = staff {
+            request = request.header(STAFF_HEADER, staff);
+        }
+        let response = app
+            .clone()
+            .oneshot(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
 
         let body = axum::body::to_bytes(response.into_body(), usize::MAX)
             .await
             .unwrap();
-        let loan_response: LoanCreatedResponse = serde_json::from_slice(&body).unwrap();
-        loan_ids.push(loan_response.loan_id);
+        let error: ErrorResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(error.error, "FORBIDDEN");
     }
 
-    // Act: 会員IDで貸出を検索
+    // 不正な形式のヘッダーは400
     let response = app
-        .clone()
         .oneshot(
             Request::builder()
-                .method("GET")
-                .uri(format!("/loans?member_id={}", member_id.value()))
+                .method("POST")
+                .uri(format!("/loans/{}/extend", loan_id.value()))
+                .header(STAFF_HEADER, "front-desk")
                 .body(Body::empty())
                 .unwrap(),
         )
         .await
         .unwrap();
-
-    // Assert
-    assert_eq!(response.status(), StatusCode::OK);
-
-    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
-        .await
-        .unwrap();
-    let loans: Vec<LoanResponse> = serde_json::from_slice(&body).unwrap();
-    assert_eq!(loans.len(), 3);
-
-    // すべての貸出が正しい会員IDを持つことを確認
-    for loan in loans {
-        assert_eq!(loan.member_id, member_id.value());
-        assert!(loan_ids.contains(&loan.loan_id));
-    }
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
 }
 
 #[tokio::test]
 #[serial]
-async fn test_e2e_list_loans_by_status() {
-    // Arrange: Active と Returned の貸出を作成
+async fn test_e2e_backdated_records_require_a_supervisor() {
+    // Arrange
     let pool = common::create_test_pool().await;
-
     let member_service = Arc::new(MemberService::new());
     let book_service = Arc::new(BookService::new());
     let (member_id, book_id) = setup_test_entities(&member_service, &book_service);
-
-    let app = setup_e2e_app(&pool, member_service.clone(), book_service.clone()).await;
-
-    // 1冊目: Active
-    let loan_request = json!({
-        "book_id": book_id.value(),
-        "member_id": member_id.value(),
-        "staff_id": StaffId::new().value(),
-    });
-
-    let response = app
-        .clone()
-        .oneshot(
+    let app = setup_e2e_app(&pool, member_service, book_service).await;
+    let loaned_at = chrono::Utc::now() - chrono::Duration::days(5);
+    let create_loan = |staff_id: StaffId| {
+        let request = json!({
+            "book_id": book_id.value(),
+            "member_id": member_id.value(),
+            "staff_id": staff_id.value(),
+            "loaned_at": loaned_at,
+        });
+        app.clone().oneshot(
             Request::builder()
                 .method("POST")
                 .uri("/loans")
+                .header(STAFF_HEADER, staff_id.value().to_string())
                 .header("content-type", "application/json")
-                .body(Body::from(serde_json::to_string(&loan_request).unwrap()))
+                .body(Body::from(request.to_string()))
                 .unwrap(),
         )
-        .await
-        .unwrap();
+    };
 
+    // Act & Assert: 記録漏れの貸出を遡って記録するには監督者が必要
+    let response = create_loan(counter_clerk()).await.unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    let response = create_loan(supervisor()).await.unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
     let body = axum::body::to_bytes(response.into_body(), usize::MAX)
         .await
         .unwrap();
-    let active_loan: LoanCreatedResponse = serde_json::from_slice(&body).unwrap();
-
-    // 2冊目: Returned
-    let book_id2 = BookId::new();
-    book_service.add_available_book(book_id2);
-    let loan_request2 = json!({
-        "book_id": book_id2.value(),
-        "member_id": member_id.value(),
-        "staff_id": StaffId::new().value(),
-    });
+    let loan: LoanCreatedResponse = serde_json::from_slice(&body).unwrap();
+    assert_eq!(loan.loaned_at.timestamp(), loaned_at.timestamp());
 
-    let response = app
-        .clone()
-        .oneshot(
+    let return_book = |staff_id: StaffId, returned_at: chrono::DateTime<chrono::Utc>| {
+        app.clone().oneshot(
             Request::builder()
                 .method("POST")
-                .uri("/loans")
-                .header("content-type", "application/json")
-                .body(Body::from(serde_json::to_string(&loan_request2).unwrap()))
+                .uri(format!(
+                    "/loans/{}/return?returned_at={}",
+                    loan.loan_id,
+                    returned_at.to_rfc3339_opts(chrono::SecondsFormat::Secs, true)
+                ))
+                .header(STAFF_HEADER, staff_id.value().to_string())
+                .body(Body::empty())
                 .unwrap(),
         )
+    };
+
+    // 未来の日時では記録できない
+    let response = return_book(
+        supervisor(),
+        chrono::Utc::now() + chrono::Duration::hours(1),
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    // 2日前の返却の記録（訂正）は、カウンター担当には認められない
+    let returned_at = chrono::Utc::now() - chrono::Duration::days(2);
+    let response = return_book(counter_clerk(), returned_at).await.unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    let response = return_book(supervisor(), returned_at).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
         .await
         .unwrap();
+    let returned: BookReturnedResponse = serde_json::from_slice(&body).unwrap();
+    assert_eq!(returned.returned_at.timestamp(), returned_at.timestamp());
+}
+
+#[tokio::test]
+#[serial]
+async fn test_e2e_supervisor_override_is_recorded_and_reported() {
+    // Arrange: 延滞中の会員
+    let pool = common::create_test_pool().await;
+    let member_service = Arc::new(MemberService::new());
+    let book_service = Arc::new(BookService::new());
+    let (member_id, book_id) = setup_test_entities(&member_service, &book_service);
+    member_service.mark_overdue(member_id);
+    let app = setup_e2e_app(&pool, member_service, book_service).await;
+
+    let create_loan = |overridden: bool, approver: Option<StaffId>| {
+        let mut request = json!({
+            "book_id": book_id.value(),
+            "member_id": member_id.value(),
+            "staff_id": counter_clerk().value(),
+        });
+        if overridden {
+            request["override"] = json!({
+                "rules": ["overdue_loans"],
+                "reason": "Exam preparation, agreed with branch manager",
+            });
+        }
+        let mut builder = Request::builder()
+            .method("POST")
+            .uri("/loans")
+            .header(STAFF_HEADER, counter_clerk().value().to_string())
+            .header("content-type", "application/json");
+        if let Some(approver) = approver {
+            builder = builder.header(APPROVER_HEADER, approver.value().to_string());
+        }
+        app.clone()
+            .oneshot(builder.body(Body::from(request.to_string())).unwrap())
+    };
+
+    // Act & Assert: 承認がなければ延滞で拒否され、カウンター担当は承認できない
+    let response = create_loan(false, None).await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    let response = create_loan(true, Some(counter_clerk())).await.unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    // 承認者はヘッダーで示す必要がある（本文の承認者は受け付けない）
+    let response = create_loan(true, None).await.unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
 
+    // 監督者の承認があれば貸し出せる
+    let response = create_loan(true, Some(supervisor())).await.unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
     let body = axum::body::to_bytes(response.into_body(), usize::MAX)
         .await
         .unwrap();
-    let returned_loan: LoanCreatedResponse = serde_json::from_slice(&body).unwrap();
+    let loan: LoanCreatedResponse = serde_json::from_slice(&body).unwrap();
 
-    // 2冊目を返却
-    app.clone()
+    // 例外のレポートはカウンター担当には閲覧できない
+    let response = app
+        .clone()
         .oneshot(
             Request::builder()
-                .method("POST")
-                .uri(format!("/loans/{}/return", returned_loan.loan_id))
-                .header("content-type", "application/json")
+                .method("GET")
+                .uri("/reports/loan-overrides")
+                .header(STAFF_HEADER, counter_clerk().value().to_string())
                 .body(Body::empty())
                 .unwrap(),
         )
         .await
         .unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
 
-    // Act: Active の貸出のみ取得
+    // 例外のレポートに承認内容が載る
     let response = app
-        .clone()
         .oneshot(
             Request::builder()
                 .method("GET")
-                .uri(format!(
-                    "/loans?member_id={}&status=active",
-                    member_id.value()
-                ))
+                .uri("/reports/loan-overrides")
+                .header(STAFF_HEADER, supervisor().value().to_string())
                 .body(Body::empty())
                 .unwrap(),
         )
         .await
         .unwrap();
-
-    // Assert
     assert_eq!(response.status(), StatusCode::OK);
-
     let body = axum::body::to_bytes(response.into_body(), usize::MAX)
         .await
         .unwrap();
-    let loans: Vec<LoanResponse> = serde_json::from_slice(&body).unwrap();
-    assert_eq!(loans.len(), 1);
-    assert_eq!(loans[0].loan_id, active_loan.loan_id);
-    assert_eq!(loans[0].status, "active");
+    let overrides: Vec<LoanOverrideResponse> = serde_json::from_slice(&body).unwrap();
+    assert_eq!(overrides.len(), 1);
+    assert_eq!(overrides[0].loan_id, loan.loan_id);
+    assert_eq!(overrides[0].member_id, Some(member_id.value()));
+    assert_eq!(overrides[0].loaned_by, counter_clerk().value());
+    assert_eq!(overrides[0].supervisor_id, supervisor().value());
+    assert_eq!(overrides[0].rules, vec![EligibilityRule::OverdueLoans]);
+    assert_eq!(
+        overrides[0].reason,
+        "Exam preparation, agreed with branch manager"
+    );
 }
 
 #[tokio::test]
 #[serial]
-async fn test_e2e_get_loan_not_found() {
-    // Arrange
+async fn test_e2e_loan_eligibility_lists_every_failed_rule() {
+    // Arrange: 延滞中の会員と、貸出できない書籍
     let pool = common::create_test_pool().await;
     let member_service = Arc::new(MemberService::new());
     let book_service = Arc::new(BookService::new());
+    let (member_id, book_id) = setup_test_entities(&member_service, &book_service);
+    member_service.mark_overdue(member_id);
+    let unavailable_book = BookId::new();
     let app = setup_e2e_app(&pool, member_service, book_service).await;
 
-    let non_existent_loan_id = LoanId::new();
-
-    // Act
-    let response = app
-        .oneshot(
+    let check = |book_id: Option<BookId>| {
+        let uri = match book_id {
+            Some(book_id) => format!(
+                "/members/{}/eligibility?book_id={}",
+                member_id.value(),
+                book_id.value()
+            ),
+            None => format!("/members/{}/eligibility", member_id.value()),
+        };
+        app.clone().oneshot(
             Request::builder()
                 .method("GET")
-                .uri(format!("/loans/{}", non_existent_loan_id.value()))
+                .uri(uri)
                 .body(Body::empty())
                 .unwrap(),
         )
+    };
+
+    // Act & Assert: 満たされていない条件がすべて返る
+    let response = check(Some(unavailable_book)).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
         .await
         .unwrap();
+    let eligibility: EligibilityResponse = serde_json::from_slice(&body).unwrap();
+    assert!(!eligibility.eligible);
+    let codes: Vec<&str> = eligibility
+        .violations
+        .iter()
+        .map(|v| v.code.as_str())
+        .collect();
+    assert_eq!(codes, vec!["BOOK_NOT_AVAILABLE", "MEMBER_HAS_OVERDUE_LOAN"]);
+    assert_eq!(eligibility.violations[0].overridable_rule, None);
+    assert_eq!(
+        eligibility.violations[1].overridable_rule,
+        Some(EligibilityRule::OverdueLoans)
+    );
+
+    // 貸出可能な書籍でも延滞は残る
+    let response = check(Some(book_id)).await.unwrap();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let eligibility: EligibilityResponse = serde_json::from_slice(&body).unwrap();
+    assert_eq!(eligibility.violations.len(), 1);
 
-    // Assert
-    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    // book_idは必須
+    let response = check(None).await.unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    // 確認だけでは何も保存されない
+    let (events,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM events")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(events, 0);
+}
+
+#[tokio::test]
+#[serial]
+async fn test_e2e_admin_overdue_detection() {
+    // Arrange: 返却期限を過ぎた貸出（30日前に貸出）
+    let pool = common::create_test_pool().await;
+    let member_service = Arc::new(MemberService::new());
+    let book_service = Arc::new(BookService::new());
+    let (member_id, book_id) = setup_test_entities(&member_service, &book_service);
+    let app = setup_e2e_app(&pool, member_service.clone(), book_service.clone()).await;
+
+    let deps = ServiceDependencies {
+        tenant_id: TenantId::DEFAULT,
+        policy: CirculationPolicy::default(),
+        event_store: Arc::new(PostgresEventStore::new(pool.clone())),
+        loan_read_model: Arc::new(PostgresLoanReadModel::new(pool.clone())),
+        unit_of_work: Some(Arc::new(PostgresUnitOfWork::new(pool.clone()))),
+        member_service,
+        book_service,
+        staff_service: staff_service(),
+        notification_service: Arc::new(NotificationService::new()),
+        notification_log: Arc::new(NotificationLog::new()),
+        notification_preferences: Arc::new(NotificationPreferenceStore::new()),
+        deferred_notices: Arc::new(DeferredNoticeQueue::new()),
+        event_bus: Arc::new(InMemoryEventBus::synchronous()),
+        idempotency_keys: Arc::new(IdempotencyStore::new()),
+    };
+    let loan_id = loan_book(
+        &deps,
+        LoanBook {
+            book_id,
+            member_id,
+            loaned_at: chrono::Utc::now() - chrono::Duration::days(30),
+            staff_id: counter_clerk(),
+        },
+    )
+    .await
+    .unwrap();
+
+    let run = |staff: Option<StaffId>, as_of: Option<chrono::DateTime<chrono::Utc>>| {
+        let uri = match as_of {
+            Some(as_of) => format!(
+                "/admin/overdue-detection?as_of={}",
+                as_of.format("%Y-%m-%dT%H:%M:%SZ")
+            ),
+            None => "/admin/overdue-detection".to_string(),
+        };
+        let mut request = Request::builder().method("POST").uri(uri);
+        if let Some(staff) = staff {
+            request = request.header(STAFF_HEADER, staff.value().to_string());
+        }
+        app.clone().oneshot(request.body(Body::empty()).unwrap())
+    };
+
+    // Act & Assert: 管理者以外は実行できない
+    let response = run(None, None).await.unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    let response = run(Some(supervisor()), None).await.unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    // 未来の基準日時は指定できない
+    let tomorrow = chrono::Utc::now() + chrono::Duration::days(1);
+    let response = run(Some(administrator()), Some(tomorrow)).await.unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    // 返却期限より前の基準日時では候補にならない
+    let before_due = chrono::Utc::now() - chrono::Duration::days(20);
+    let response = run(Some(administrator()), Some(before_due)).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let report: OverdueDetectionResponse = serde_json::from_slice(&body).unwrap();
+    assert_eq!(report.candidates, 0);
+
+    // 現在を基準にすると延滞になる
+    let response = run(Some(administrator()), None).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let report: OverdueDetectionResponse = serde_json::from_slice(&body).unwrap();
+    assert_eq!(report.candidates, 1);
+    assert_eq!(report.detected.len(), 1);
+    assert_eq!(report.detected[0].loan_id, loan_id.value());
+    assert_eq!(report.detected[0].member_id, member_id.value());
+    assert!(report.skipped.is_empty());
+    assert!(report.failed.is_empty());
+
+    let view = deps
+        .loan_read_model
+        .get_by_id(loan_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(view.status, LoanStatus::Overdue);
+
+    // 延滞になった貸出は次の実行の候補にならない
+    let response = run(Some(administrator()), None).await.unwrap();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let report: OverdueDetectionResponse = serde_json::from_slice(&body).unwrap();
+    assert_eq!(report.candidates, 0);
+}
+
+#[tokio::test]
+#[serial]
+async fn test_e2e_list_loans_by_member() {
+    // Arrange: 複数の貸出を作成
+    let pool = common::create_test_pool().await;
+
+    let member_service = Arc::new(MemberService::new());
+    let book_service = Arc::new(BookService::new());
+    let member_id = MemberId::new();
+    member_service.add_member(member_id);
+
+    let app = setup_e2e_app(&pool, member_service.clone(), book_service.clone()).await;
+
+    // 3冊借りる
+    let mut loan_ids = Vec::new();
+    for _ in 0..3 {
+        let book_id = BookId::new();
+        book_service.add_available_book(book_id);
+
+        let loan_request = json!({
+            "book_id": book_id.value(),
+            "member_id": member_id.value(),
+            "staff_id": counter_clerk().value(),
+        });
+
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/loans")
+                    .header("content-type", "application/json")
+                    .body(Body::from(serde_json::to_string(&loan_request).unwrap()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let loan_response: LoanCreatedResponse = serde_json::from_slice(&body).unwrap();
+        loan_ids.push(loan_response.loan_id);
+    }
+
+    // Act: 会員IDで貸出を検索
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("GET")
+                .uri(format!("/loans?member_id={}", member_id.value()))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let loans: Vec<LoanResponse> = serde_json::from_slice(&body).unwrap();
+    assert_eq!(loans.len(), 3);
+
+    // すべての貸出が正しい会員IDを持つことを確認
+    for loan in loans {
+        assert_eq!(loan.member_id, Some(member_id.value()));
+        assert!(loan_ids.contains(&loan.loan_id));
+    }
+}
+
+#[tokio::test]
+#[serial]
+async fn test_e2e_list_loans_by_status() {
+    // Arrange: Active と Returned の貸出を作成
+    let pool = common::create_test_pool().await;
+
+    let member_service = Arc::new(MemberService::new());
+    let book_service = Arc::new(BookService::new());
+    let (member_id, book_id) = setup_test_entities(&member_service, &book_service);
+
+    let app = setup_e2e_app(&pool, member_service.clone(), book_service.clone()).await;
+
+    // 1冊目: Active
+    let loan_request = json!({
+        "book_id": book_id.value(),
+        "member_id": member_id.value(),
+        "staff_id": counter_clerk().value(),
+    });
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/loans")
+                .header("content-type", "application/json")
+                .body(Body::from(serde_json::to_string(&loan_request).unwrap()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let active_loan: LoanCreatedResponse = serde_json::from_slice(&body).unwrap();
+
+    // 2冊目: Returned
+    let book_id2 = BookId::new();
+    book_service.add_available_book(book_id2);
+    let loan_request2 = json!({
+        "book_id": book_id2.value(),
+        "member_id": member_id.value(),
+        "staff_id": counter_clerk().value(),
+    });
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/loans")
+                .header("content-type", "application/json")
+                .body(Body::from(serde_json::to_string(&loan_request2).unwrap()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let returned_loan: LoanCreatedResponse = serde_json::from_slice(&body).unwrap();
+
+    // 2冊目を返却
+    app.clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri(format!("/loans/{}/return", returned_loan.loan_id))
+                .header(STAFF_HEADER, counter_clerk().value().to_string())
+                .header("content-type", "application/json")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    // Act: Active の貸出のみ取得
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("GET")
+                .uri(format!(
+                    "/loans?member_id={}&status=active",
+                    member_id.value()
+                ))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let loans: Vec<LoanResponse> = serde_json::from_slice(&body).unwrap();
+    assert_eq!(loans.len(), 1);
+    assert_eq!(loans[0].loan_id, active_loan.loan_id);
+    assert_eq!(loans[0].status, "active");
+}
+
+#[tokio::test]
+#[serial]
+async fn test_e2e_get_loan_not_found() {
+    // Arrange
+    let pool = common::create_test_pool().await;
+    let member_service = Arc::new(MemberService::new());
+    let book_service = Arc::new(BookService::new());
+    let app = setup_e2e_app(&pool, member_service, book_service).await;
+
+    let non_existent_loan_id = LoanId::new();
+
+    // Act
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("GET")
+                .uri(format!("/loans/{}", non_existent_loan_id.value()))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+// ============================================================================
+// E2Eテスト: 読書履歴の保持設定
+// ============================================================================
+
+#[tokio::test]
+#[serial]
+async fn test_e2e_reading_history_preference() {
+    // Arrange
+    let pool = common::create_test_pool().await;
+    let member_service = Arc::new(MemberService::new());
+    let book_service = Arc::new(BookService::new());
+    let member_id = MemberId::new();
+    member_service.add_member(member_id);
+    let app = setup_e2e_app(&pool, member_service, book_service).await;
+    let uri = format!("/members/{}/reading-history", member_id.value());
+
+    // 既定では保持を選択していない
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri(&uri)
+                .header(MEMBER_HEADER, member_id.value().to_string())
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let preference: ReadingHistoryPreferenceResponse = serde_json::from_slice(&body).unwrap();
+    assert!(!preference.keep_history);
+    assert_eq!(preference.opted_in_at, None);
+
+    // Act: 保持を選択（PUT）
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("PUT")
+                .uri(&uri)
+                .header(MEMBER_HEADER, member_id.value().to_string())
+                .header("content-type", "application/json")
+                .body(Body::from(json!({ "keep_history": true }).to_string()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let preference: ReadingHistoryPreferenceResponse = serde_json::from_slice(&body).unwrap();
+    assert_eq!(preference.member_id, member_id.value());
+    assert!(preference.keep_history);
+    assert!(preference.opted_in_at.is_some());
+
+    // 会員本人でも、会員の設定を扱える職員でもなければ拒否
+    let other_member = MemberId::new();
+    for (header, value) in [
+        (MEMBER_HEADER, other_member.value()),
+        (STAFF_HEADER, StaffId::new().value()),
+    ] {
+        for method in ["GET", "PUT"] {
+            let response = app
+                .clone()
+                .oneshot(
+                    Request::builder()
+                        .method(method)
+                        .uri(&uri)
+                        .header(header, value.to_string())
+                        .header("content-type", "application/json")
+                        .body(Body::from(json!({ "keep_history": false }).to_string()))
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+            assert_eq!(
+                response.status(),
+                StatusCode::FORBIDDEN,
+                "{header} {method}"
+            );
+        }
+    }
+
+    // カウンター担当は会員に代わって変更できる
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("PUT")
+                .uri(&uri)
+                .header(STAFF_HEADER, counter_clerk().value().to_string())
+                .header("content-type", "application/json")
+                .body(Body::from(json!({ "keep_history": false }).to_string()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // 存在しない会員
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("PUT")
+                .uri(format!(
+                    "/members/{}/reading-history",
+                    MemberId::new().value()
+                ))
+                .header(STAFF_HEADER, counter_clerk().value().to_string())
+                .header("content-type", "application/json")
+                .body(Body::from(json!({ "keep_history": true }).to_string()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+#[tokio::test]
+#[serial]
+async fn test_e2e_notification_preferences() {
+    // Arrange
+    let pool = common::create_test_pool().await;
+    let member_service = Arc::new(MemberService::new());
+    let book_service = Arc::new(BookService::new());
+    let member_id = MemberId::new();
+    member_service.add_member(member_id);
+    let app = setup_e2e_app(&pool, member_service, book_service).await;
+    let uri = format!("/members/{}/notification-preferences", member_id.value());
+    let get = |uri: &str, member_id: MemberId| {
+        Request::builder()
+            .uri(uri)
+            .header(MEMBER_HEADER, member_id.value().to_string())
+            .body(Body::empty())
+            .unwrap()
+    };
+    let put = |body: serde_json::Value| {
+        Request::builder()
+            .method("PUT")
+            .uri(&uri)
+            .header(MEMBER_HEADER, member_id.value().to_string())
+            .header("content-type", "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap()
+    };
+
+    // 既定ではメールですぐ送る
+    let response = app.clone().oneshot(get(&uri, member_id)).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let preferences: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(preferences["channel"], "email");
+    assert_eq!(preferences["language"], "ja");
+    assert_eq!(preferences["digest"], "immediate");
+    assert_eq!(preferences["quiet_hours"], serde_json::Value::Null);
+    assert_eq!(preferences["utc_offset_minutes"], 540);
+
+    // Act: アプリ内の通知で、英語の日次ダイジェストにする（PUT）
+    let response = app
+        .clone()
+        .oneshot(put(json!({
+            "channel": "in_app",
+            "language": "en",
+            "digest": "daily",
+            "quiet_hours": { "start": "22:00", "end": "07:00" }
+        })))
+        .await
+        .unwrap();
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::OK);
+    let response = app.clone().oneshot(get(&uri, member_id)).await.unwrap();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let preferences: NotificationPreferencesResponse = serde_json::from_slice(&body).unwrap();
+    assert_eq!(preferences.member_id, member_id.value());
+    assert_eq!(preferences.channel, NotificationChannel::InApp);
+    assert_eq!(preferences.language, NotificationLanguage::En);
+    assert_eq!(preferences.digest, DigestMode::Daily);
+    let quiet_hours = preferences.quiet_hours.unwrap();
+    assert_eq!(quiet_hours.start.to_string(), "22:00:00");
+    assert_eq!(quiet_hours.end.to_string(), "07:00:00");
+
+    // 開始と終了が同じ時間帯は拒否
+    let response = app
+        .clone()
+        .oneshot(put(json!({
+            "quiet_hours": { "start": "22:00", "end": "22:00" }
+        })))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    // 他の会員や権限のない職員は参照も変更もできない
+    let response = app
+        .clone()
+        .oneshot(get(&uri, MemberId::new()))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("PUT")
+                .uri(&uri)
+                .header(STAFF_HEADER, StaffId::new().value().to_string())
+                .header("content-type", "application/json")
+                .body(Body::from(json!({ "channel": "none" }).to_string()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    // カウンター担当は会員に代わって参照できる
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri(&uri)
+                .header(STAFF_HEADER, counter_clerk().value().to_string())
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // 存在しない会員
+    let other_member = MemberId::new();
+    let response = app
+        .oneshot(get(
+            &format!("/members/{}/notification-preferences", other_member.value()),
+            other_member,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+#[serial]
+async fn test_e2e_export_member_data() {
+    // Arrange
+    let pool = common::create_test_pool().await;
+    let member_service = Arc::new(MemberService::new());
+    let book_service = Arc::new(BookService::new());
+    let member_id = MemberId::new();
+    member_service.add_member(member_id);
+    let app = setup_e2e_app(&pool, member_service, book_service).await;
+    let uri = format!("/members/{}/export", member_id.value());
+
+    // 実行者が不明、登録されていない、または権限がない場合は拒否
+    for staff_id in [None, Some(StaffId::new()), Some(counter_clerk())] {
+        let mut request = Request::builder().uri(&uri);
+        if let Some(staff_id) = staff_id {
+            request = request.header(STAFF_HEADER, staff_id.value().to_string());
+        }
+        let response = app
+            .clone()
+            .oneshot(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN, "{staff_id:?}");
+    }
+
+    // Act: 機械可読なアーカイブ（監督者が作成する）
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri(&uri)
+                .header(STAFF_HEADER, supervisor().value().to_string())
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let archive: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(archive["member_id"], json!(member_id.value()));
+    assert_eq!(archive["exported_by"], json!(supervisor().value()));
+
+    // 人が読む要約（前回の写しの作成が記録されている）
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri(format!("{}?format=summary", uri))
+                .header(STAFF_HEADER, administrator().value().to_string())
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let summary = String::from_utf8(body.to_vec()).unwrap();
+    assert!(summary.contains("記録されているイベント: 1件"));
+}
+
+// ============================================================================
+// E2Eテスト: マルチテナント
+// ============================================================================
+
+/// テスト用のテナントを登録し、そのテナントにスコープされた依存関係を作成
+async fn register_test_tenant(
+    pool: &PgPool,
+    registry: &mut TenantRegistry,
+    member_service: Arc<MemberService>,
+    book_service: Arc<BookService>,
+) -> TenantId {
+    let tenant_id = common::insert_tenant(pool).await;
+
+    registry.register(
+        common::tenant_subdomain(tenant_id),
+        ServiceDependencies {
+            tenant_id,
+            policy: CirculationPolicy::default(),
+            event_store: Arc::new(PostgresEventStore::for_tenant(pool.clone(), tenant_id)),
+            loan_read_model: Arc::new(PostgresLoanReadModel::for_tenant(pool.clone(), tenant_id)),
+            unit_of_work: Some(Arc::new(PostgresUnitOfWork::for_tenant(
+                pool.clone(),
+                tenant_id,
+            ))),
+            member_service,
+            book_service,
+            staff_service: staff_service(),
+            notification_service: Arc::new(NotificationService::new()),
+            notification_log: Arc::new(NotificationLog::new()),
+            notification_preferences: Arc::new(NotificationPreferenceStore::new()),
+            deferred_notices: Arc::new(DeferredNoticeQueue::new()),
+            event_bus: Arc::new(InMemoryEventBus::synchronous()),
+            idempotency_keys: Arc::new(IdempotencyStore::new()),
+        },
+    );
+
+    tenant_id
+}
+
+#[tokio::test]
+#[serial]
+async fn test_e2e_tenant_isolation() {
+    // Arrange: 既定テナントと別テナントの2館を登録
+    let pool = common::create_test_pool().await;
+    cleanup_database(&pool).await;
+
+    let member_service = Arc::new(MemberService::new());
+    let book_service = Arc::new(BookService::new());
+    let (member_id, book_id) = setup_test_entities(&member_service, &book_service);
+
+    let mut registry = TenantRegistry::new();
+    registry.register(
+        "default",
+        ServiceDependencies {
+            tenant_id: TenantId::DEFAULT,
+            policy: CirculationPolicy::default(),
+            event_store: Arc::new(PostgresEventStore::new(pool.clone())),
+            loan_read_model: Arc::new(PostgresLoanReadModel::new(pool.clone())),
+            unit_of_work: Some(Arc::new(PostgresUnitOfWork::new(pool.clone()))),
+            member_service: member_service.clone(),
+            book_service: book_service.clone(),
+            staff_service: staff_service(),
+            notification_service: Arc::new(NotificationService::new()),
+            notification_log: Arc::new(NotificationLog::new()),
+            notification_preferences: Arc::new(NotificationPreferenceStore::new()),
+            deferred_notices: Arc::new(DeferredNoticeQueue::new()),
+            event_bus: Arc::new(InMemoryEventBus::synchronous()),
+            idempotency_keys: Arc::new(IdempotencyStore::new()),
+        },
+    );
+    let other_tenant = register_test_tenant(
+        &pool,
+        &mut registry,
+        member_service.clone(),
+        book_service.clone(),
+    )
+    .await;
+
+    let app = create_router(Arc::new(AppState::new(registry)));
+
+    // Act: 別テナントで貸出を作成
+    let loan_request = json!({
+        "book_id": book_id.value(),
+        "member_id": member_id.value(),
+        "staff_id": counter_clerk().value(),
+    });
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/loans")
+                .header("content-type", "application/json")
+                .header(TENANT_HEADER, other_tenant.value().to_string())
+                .body(Body::from(serde_json::to_string(&loan_request).unwrap()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let loan: LoanCreatedResponse = serde_json::from_slice(&body).unwrap();
+
+    // Assert: 同じテナントからは取得できる
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("GET")
+                .uri(format!("/loans/{}", loan.loan_id))
+                .header(TENANT_HEADER, other_tenant.value().to_string())
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // Assert: 既定テナントからは見えない
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("GET")
+                .uri(format!("/loans/{}", loan.loan_id))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    // Assert: 既定テナントからは延長もできない
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri(format!("/loans/{}/extend", loan.loan_id))
+                .header(STAFF_HEADER, counter_clerk().value().to_string())
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    // Assert: サブドメインと一致しないX-Tenant-Idは拒否される
+    let other_host = format!(
+        "{}.library.example.jp",
+        common::tenant_subdomain(other_tenant)
+    );
+    for (tenant, expected) in [
+        (other_tenant, StatusCode::OK),
+        (TenantId::DEFAULT, StatusCode::BAD_REQUEST),
+    ] {
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method("GET")
+                    .uri(format!("/loans/{}", loan.loan_id))
+                    .header(header::HOST, &other_host)
+                    .header(TENANT_HEADER, tenant.value().to_string())
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), expected);
+    }
+}
+
+#[tokio::test]
+#[serial]
+async fn test_e2e_unknown_tenant() {
+    // Arrange
+    let pool = common::create_test_pool().await;
+    let member_service = Arc::new(MemberService::new());
+    let book_service = Arc::new(BookService::new());
+    let app = setup_e2e_app(&pool, member_service, book_service).await;
+
+    // Act: 登録されていないテナントを指定
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("GET")
+                .uri(format!("/loans/{}", LoanId::new().value()))
+                .header(TENANT_HEADER, TenantId::new().value().to_string())
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let error: ErrorResponse = serde_json::from_slice(&body).unwrap();
+    assert_eq!(error.error, "UNKNOWN_TENANT");
+
+    // Act: UUIDではないテナントIDを指定
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("GET")
+                .uri(format!("/loans/{}", LoanId::new().value()))
+                .header(TENANT_HEADER, "not-a-uuid")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+#[serial]
+async fn test_e2e_event_schemas() {
+    // Arrange
+    let pool = common::create_test_pool().await;
+    let member_service = Arc::new(MemberService::new());
+    let book_service = Arc::new(BookService::new());
+    let app = setup_e2e_app(&pool, member_service, book_service).await;
+
+    // Act: 登録済みスキーマの一覧を取得（テナントの指定は不要）
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("GET")
+                .uri("/schemas/events")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let schemas: Vec<EventSchemaSummary> = serde_json::from_slice(&body).unwrap();
+    let loaned = schemas
+        .iter()
+        .find(|s| s.event_type == "BookLoaned" && s.latest)
+        .expect("BookLoaned schema is listed");
+
+    // Act: 一覧のリンクからスキーマ本体を取得
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("GET")
+                .uri(&loaned.href)
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers()["content-type"],
+        "application/schema+json"
+    );
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let schema: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(schema["$id"], json!(loaned.id));
+    assert_eq!(schema["required"], json!(["BookLoaned"]));
+
+    // Act & Assert: 未登録のイベント型・バージョン
+    for uri in ["/schemas/events/BookLost", "/schemas/events/BookLoaned/999"] {
+        let response = app
+            .clone()
+            .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND, "{uri}");
+    }
 }
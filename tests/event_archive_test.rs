@@ -0,0 +1,231 @@
+mod common;
+
+use chrono::{DateTime, TimeZone, Utc};
+use rusty_library_ddd::adapters::postgres::{
+    PostgresEventArchive, PostgresEventAudit, PostgresEventStore,
+};
+use rusty_library_ddd::domain::events::{BookLoaned, BookReturned, DomainEvent};
+use rusty_library_ddd::domain::value_objects::{BookId, LoanId, MemberId, StaffId};
+use rusty_library_ddd::ports::{
+    EventArchive, EventAudit, EventStore, EventStoreError, PartitionArchival,
+};
+use sqlx::PgPool;
+
+/// 未返却の貸出を置く年
+const OPEN_YEAR: i32 = 1998;
+/// 返却済みの貸出だけを置く年
+const CLOSED_YEAR: i32 = 1999;
+
+/// 前回の実行で作られたテスト用の年のパーティションと既定パーティションの行を消す
+async fn drop_test_years(pool: &PgPool) {
+    for year in [OPEN_YEAR, CLOSED_YEAR] {
+        sqlx::query(&format!(
+            "DROP TABLE IF EXISTS events_y{year}, event_archive.events_y{year}"
+        ))
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+    sqlx::query("DELETE FROM events_default WHERE occurred_at < '2000-01-01T00:00:00Z'")
+        .execute(pool)
+        .await
+        .unwrap();
+}
+
+/// 指定した日時の貸出を追加し、`returned`なら3日後に返却する
+async fn seed_loan(
+    event_store: &dyn EventStore,
+    loaned_at: DateTime<Utc>,
+    returned: bool,
+) -> LoanId {
+    let loan_id = LoanId::new();
+    let book_id = BookId::new();
+    let member_id = MemberId::new();
+
+    let mut events = vec![DomainEvent::BookLoaned(BookLoaned {
+        loan_id,
+        book_id,
+        member_id,
+        loaned_at,
+        due_date: loaned_at + chrono::Duration::days(14),
+        loaned_by: StaffId::new(),
+        override_token: None,
+    })];
+    if returned {
+        events.push(DomainEvent::BookReturned(BookReturned {
+            loan_id,
+            book_id,
+            member_id,
+            returned_at: loaned_at + chrono::Duration::days(3),
+            was_overdue: false,
+        }));
+    }
+    event_store
+        .append(loan_id.value(), "Loan", events)
+        .await
+        .unwrap();
+
+    loan_id
+}
+
+#[tokio::test]
+async fn test_archive_moves_closed_partition_and_load_still_finds_it() {
+    let pool = common::create_test_pool().await;
+    drop_test_years(&pool).await;
+
+    let tenant_id = common::insert_tenant(&pool).await;
+    let event_store = PostgresEventStore::for_tenant(pool.clone(), tenant_id);
+    let audit = PostgresEventAudit::for_tenant(pool.clone(), tenant_id);
+    let archive = PostgresEventArchive::new(pool.clone());
+
+    // パーティションがない年のイベントは既定パーティションに入る
+    let open_loan = seed_loan(
+        &event_store,
+        Utc.with_ymd_and_hms(OPEN_YEAR, 6, 1, 10, 0, 0).unwrap(),
+        false,
+    )
+    .await;
+    let closed_loan = seed_loan(
+        &event_store,
+        Utc.with_ymd_and_hms(CLOSED_YEAR, 6, 1, 10, 0, 0).unwrap(),
+        true,
+    )
+    .await;
+
+    // 年のパーティションを作成すると、既定パーティションのイベントが移される
+    let created = archive
+        .ensure_partitions(OPEN_YEAR, CLOSED_YEAR)
+        .await
+        .unwrap();
+    assert_eq!(
+        created,
+        vec![
+            format!("events_y{OPEN_YEAR}"),
+            format!("events_y{CLOSED_YEAR}")
+        ]
+    );
+    let in_default: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM events_default WHERE occurred_at < '2000-01-01T00:00:00Z'",
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(in_default, 0);
+
+    // 未返却の貸出を含む年はアーカイブしない
+    let outcome = archive
+        .archive_partition(&format!("events_y{OPEN_YEAR}"))
+        .await
+        .unwrap();
+    assert_eq!(outcome, PartitionArchival::OpenLoans { loan_count: 1 });
+
+    let outcome = archive
+        .archive_partition(&format!("events_y{CLOSED_YEAR}"))
+        .await
+        .unwrap();
+    assert_eq!(outcome, PartitionArchival::Archived { event_count: 2 });
+
+    let partitions = archive.partitions().await.unwrap();
+    let archived: Vec<_> = partitions
+        .iter()
+        .filter(|p| p.year == OPEN_YEAR || p.year == CLOSED_YEAR)
+        .map(|p| (p.year, p.archived))
+        .collect();
+    assert_eq!(archived, vec![(OPEN_YEAR, false), (CLOSED_YEAR, true)]);
+
+    // アーカイブ済みのパーティションは二重にアーカイブできない
+    assert!(
+        archive
+            .archive_partition(&format!("events_y{CLOSED_YEAR}"))
+            .await
+            .is_err()
+    );
+
+    // アーカイブ済みの集約も読み込める
+    let events = event_store.load(closed_loan.value(), "Loan").await.unwrap();
+    assert_eq!(events.len(), 2);
+    assert!(matches!(events[1], DomainEvent::BookReturned(_)));
+    assert_eq!(
+        event_store
+            .load(open_loan.value(), "Loan")
+            .await
+            .unwrap()
+            .len(),
+        1
+    );
+    let histories = event_store
+        .load_many(&[closed_loan.value(), open_loan.value()], "Loan")
+        .await
+        .unwrap();
+    assert_eq!(histories[&closed_loan.value()], events);
+    assert_eq!(histories[&open_loan.value()].len(), 1);
+
+    // アーカイブ後もチェーンはつながり、新しいイベントはその先に連結される
+    seed_loan(&event_store, Utc::now(), true).await;
+    let verification = audit.verify_chain().await.unwrap();
+    assert!(verification.is_intact());
+    assert_eq!(verification.verified_events, 5);
+
+    drop_test_years(&pool).await;
+}
+
+#[tokio::test]
+async fn test_concurrent_appends_cannot_share_an_aggregate_version() {
+    let pool = common::create_test_pool().await;
+    let tenant_id = common::insert_tenant(&pool).await;
+    let event_store = PostgresEventStore::for_tenant(pool.clone(), tenant_id);
+
+    // 同じ新しい貸出を8件同時に作成する
+    let loan_id = LoanId::new();
+    let loaned_at = Utc::now();
+    let loaned = DomainEvent::BookLoaned(BookLoaned {
+        loan_id,
+        book_id: BookId::new(),
+        member_id: MemberId::new(),
+        loaned_at,
+        due_date: loaned_at + chrono::Duration::days(14),
+        loaned_by: StaffId::new(),
+        override_token: None,
+    });
+    let results = futures::future::join_all((0..8).map(|_| {
+        event_store.append_with_expected_version(loan_id.value(), "Loan", 0, vec![loaned.clone()])
+    }))
+    .await;
+
+    // 1件だけが保存され、残りはバージョンの競合となる
+    assert_eq!(results.iter().filter(|r| r.is_ok()).count(), 1);
+    assert!(
+        results
+            .iter()
+            .filter_map(|r| r.as_ref().err())
+            .all(|e| matches!(e, EventStoreError::VersionConflict { .. }))
+    );
+    let versions: Vec<i32> =
+        sqlx::query_scalar("SELECT aggregate_version FROM events WHERE aggregate_id = $1")
+            .bind(loan_id.value())
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+    assert_eq!(versions, vec![1]);
+
+    // チェーンロックを取らずに同じバージョンを書き込もうとしても、一意索引が拒否する
+    let duplicate = sqlx::query(
+        r#"
+        INSERT INTO events (
+            tenant_id, aggregate_id, aggregate_version, aggregate_type,
+            event_type, event_data, occurred_at
+        )
+        SELECT tenant_id, aggregate_id, aggregate_version, aggregate_type,
+            event_type, event_data, occurred_at
+        FROM events
+        WHERE aggregate_id = $1
+        "#,
+    )
+    .bind(loan_id.value())
+    .execute(&pool)
+    .await;
+    assert!(matches!(
+        duplicate,
+        Err(sqlx::Error::Database(e)) if e.code().as_deref() == Some("23505")
+    ));
+}
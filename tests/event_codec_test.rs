@@ -0,0 +1,193 @@
+mod common;
+
+use chrono::Utc;
+use rusty_library_ddd::adapters::postgres::{EventCodec, PostgresEventAudit, PostgresEventStore};
+use rusty_library_ddd::domain::events::{BookLoaned, BookReturned, DomainEvent};
+use rusty_library_ddd::domain::value_objects::{BookId, LoanId, MemberId, StaffId};
+use rusty_library_ddd::ports::{EventAudit, EventStore};
+use sqlx::PgPool;
+
+/// 貸出のイベントの保存形式（バージョン順）
+async fn codecs_of(pool: &PgPool, loan_id: LoanId) -> Vec<(String, bool)> {
+    sqlx::query_as(
+        r#"
+        SELECT event_codec, event_data IS NULL
+        FROM events
+        WHERE aggregate_id = $1
+        ORDER BY aggregate_version
+        "#,
+    )
+    .bind(loan_id.value())
+    .fetch_all(pool)
+    .await
+    .unwrap()
+}
+
+#[tokio::test]
+async fn test_mixed_codecs_load_verify_and_convert() {
+    let pool = common::create_test_pool().await;
+    let tenant_id = common::insert_tenant(&pool).await;
+    let json_store = PostgresEventStore::for_tenant(pool.clone(), tenant_id);
+    let msgpack_store =
+        PostgresEventStore::for_tenant(pool.clone(), tenant_id).with_codec(EventCodec::MessagePack);
+    let audit = PostgresEventAudit::for_tenant(pool.clone(), tenant_id);
+
+    let now = Utc::now();
+    let loan_id = LoanId::new();
+    let book_id = BookId::new();
+    let member_id = MemberId::new();
+
+    // 貸出はJSON、返却はMessagePackで書き込む
+    json_store
+        .append(
+            loan_id.value(),
+            "Loan",
+            vec![DomainEvent::BookLoaned(BookLoaned {
+                loan_id,
+                book_id,
+                member_id,
+                loaned_at: now,
+                due_date: now + chrono::Duration::days(14),
+                loaned_by: StaffId::new(),
+                override_token: None,
+            })],
+        )
+        .await
+        .unwrap();
+    msgpack_store
+        .append(
+            loan_id.value(),
+            "Loan",
+            vec![DomainEvent::BookReturned(BookReturned {
+                loan_id,
+                book_id,
+                member_id,
+                returned_at: now + chrono::Duration::days(3),
+                was_overdue: false,
+            })],
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(
+        codecs_of(&pool, loan_id).await,
+        vec![("json".to_string(), false), ("msgpack".to_string(), true)]
+    );
+
+    // 形式が混在していても読み込め、チェーンも検証できる
+    let events = json_store.load(loan_id.value(), "Loan").await.unwrap();
+    assert_eq!(events.len(), 2);
+    assert!(matches!(&events[1], DomainEvent::BookReturned(e) if e.member_id == member_id));
+    assert!(audit.verify_chain().await.unwrap().is_intact());
+
+    // バイナリ形式の行も会員のイベントとして見つかる
+    let member_events = json_store.load_member_events(member_id).await.unwrap();
+    assert_eq!(member_events.len(), 2);
+
+    // 変換しても値は変わらないため、チェーンは壊れない
+    assert_eq!(
+        json_store
+            .convert_codec(EventCodec::MessagePack)
+            .await
+            .unwrap(),
+        1
+    );
+    assert_eq!(
+        codecs_of(&pool, loan_id).await,
+        vec![("msgpack".to_string(), true), ("msgpack".to_string(), true)]
+    );
+    assert!(audit.verify_chain().await.unwrap().is_intact());
+    assert_eq!(
+        json_store.load(loan_id.value(), "Loan").await.unwrap(),
+        events
+    );
+
+    assert_eq!(json_store.convert_codec(EventCodec::Json).await.unwrap(), 2);
+    assert_eq!(
+        codecs_of(&pool, loan_id).await,
+        vec![("json".to_string(), false), ("json".to_string(), false)]
+    );
+    assert!(audit.verify_chain().await.unwrap().is_intact());
+}
+
+#[tokio::test]
+async fn test_member_events_are_found_through_member_refs() {
+    let pool = common::create_test_pool().await;
+    let tenant_id = common::insert_tenant(&pool).await;
+    let event_store =
+        PostgresEventStore::for_tenant(pool.clone(), tenant_id).with_codec(EventCodec::MessagePack);
+
+    let now = Utc::now();
+    let member_id = MemberId::new();
+    let loaned = |loan_id: LoanId, member_id: MemberId| {
+        DomainEvent::BookLoaned(BookLoaned {
+            loan_id,
+            book_id: BookId::new(),
+            member_id,
+            loaned_at: now,
+            due_date: now + chrono::Duration::days(14),
+            loaned_by: StaffId::new(),
+            override_token: None,
+        })
+    };
+    let own_loan = LoanId::new();
+    let legacy_loan = LoanId::new();
+    let other_loan = LoanId::new();
+    for (loan_id, owner) in [
+        (own_loan, member_id),
+        (legacy_loan, member_id),
+        (other_loan, MemberId::new()),
+    ] {
+        event_store
+            .append(loan_id.value(), "Loan", vec![loaned(loan_id, owner)])
+            .await
+            .unwrap();
+    }
+
+    // バイナリの行にも会員の参照（鍵ID）が記録され、会員IDそのものは記録されない
+    let refs: Vec<Option<uuid::Uuid>> = sqlx::query_scalar(
+        "SELECT member_ref FROM events WHERE aggregate_id = ANY($1) ORDER BY sequence_number",
+    )
+    .bind(vec![own_loan.value(), legacy_loan.value()])
+    .fetch_all(&pool)
+    .await
+    .unwrap();
+    assert_eq!(refs.len(), 2);
+    assert!(refs[0].is_some() && refs[0] == refs[1]);
+    assert_ne!(refs[0], Some(member_id.value()));
+
+    // 列の追加前に書き込まれたバイナリの行は、復号して照合される
+    sqlx::query(
+        "UPDATE events SET member_ref = NULL, member_ref_pending = TRUE WHERE aggregate_id = $1",
+    )
+    .bind(legacy_loan.value())
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let mut found: Vec<uuid::Uuid> = event_store
+        .load_member_events(member_id)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|e| e.aggregate_id)
+        .collect();
+    found.sort_unstable();
+    let mut expected = vec![own_loan.value(), legacy_loan.value()];
+    expected.sort_unstable();
+    assert_eq!(found, expected);
+
+    // 変換し直すと会員の参照が埋まる
+    assert_eq!(
+        event_store.convert_codec(EventCodec::Json).await.unwrap(),
+        3
+    );
+    let pending: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM events WHERE tenant_id = $1 AND member_ref_pending",
+    )
+    .bind(tenant_id.value())
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(pending, 0);
+}
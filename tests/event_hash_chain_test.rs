@@ -0,0 +1,221 @@
+mod common;
+
+use chrono::Utc;
+use rusty_library_ddd::adapters::postgres::{PostgresEventAudit, PostgresEventStore};
+use rusty_library_ddd::application::audit::{
+    AnchorSigner, AnchorStatus, anchor_chain, verify_anchor,
+};
+use rusty_library_ddd::domain::events::{BookLoaned, BookReturned, DomainEvent};
+use rusty_library_ddd::domain::value_objects::{BookId, LoanId, MemberId, StaffId};
+use rusty_library_ddd::ports::{BrokenLinkReason, EventAudit, EventStore};
+use sqlx::PgPool;
+
+const SEED: &str = "2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a";
+
+/// 貸出と返却のイベントを追加し、貸出IDを返す
+async fn seed_loan(event_store: &dyn EventStore) -> LoanId {
+    let now = Utc::now();
+    let loan_id = LoanId::new();
+    let book_id = BookId::new();
+    let member_id = MemberId::new();
+
+    event_store
+        .append(
+            loan_id.value(),
+            "Loan",
+            vec![DomainEvent::BookLoaned(BookLoaned {
+                loan_id,
+                book_id,
+                member_id,
+                loaned_at: now,
+                due_date: now + chrono::Duration::days(14),
+                loaned_by: StaffId::new(),
+                override_token: None,
+            })],
+        )
+        .await
+        .unwrap();
+    event_store
+        .append(
+            loan_id.value(),
+            "Loan",
+            vec![DomainEvent::BookReturned(BookReturned {
+                loan_id,
+                book_id,
+                member_id,
+                returned_at: now + chrono::Duration::days(3),
+                was_overdue: false,
+            })],
+        )
+        .await
+        .unwrap();
+
+    loan_id
+}
+
+/// 集約の指定バージョンのシーケンス番号
+async fn sequence_of(pool: &PgPool, loan_id: LoanId, version: i32) -> i64 {
+    sqlx::query_scalar(
+        "SELECT sequence_number FROM events WHERE aggregate_id = $1 AND aggregate_version = $2",
+    )
+    .bind(loan_id.value())
+    .bind(version)
+    .fetch_one(pool)
+    .await
+    .unwrap()
+}
+
+#[tokio::test]
+async fn test_hash_chain_detects_edited_and_deleted_events() {
+    let pool = common::create_test_pool().await;
+    let tenant_id = common::insert_tenant(&pool).await;
+    let event_store = PostgresEventStore::for_tenant(pool.clone(), tenant_id);
+    let audit = PostgresEventAudit::for_tenant(pool.clone(), tenant_id);
+
+    let first = seed_loan(&event_store).await;
+    let second = seed_loan(&event_store).await;
+
+    // 改ざん前はチェーンが完全
+    let verification = audit.verify_chain().await.unwrap();
+    assert!(verification.is_intact());
+    assert_eq!(verification.verified_events, 4);
+
+    // 返却を延滞扱いに書き換える
+    sqlx::query(
+        r#"
+        UPDATE events
+        SET event_data = jsonb_set(event_data, '{BookReturned,was_overdue}', 'true')
+        WHERE aggregate_id = $1 AND aggregate_version = 2
+        "#,
+    )
+    .bind(first.value())
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let broken = audit
+        .verify_chain()
+        .await
+        .unwrap()
+        .first_broken_link
+        .unwrap();
+    assert_eq!(broken.aggregate_id, first.value());
+    assert_eq!(broken.sequence_number, sequence_of(&pool, first, 2).await);
+    assert_eq!(broken.reason, BrokenLinkReason::HashMismatch);
+
+    // 書き換えた行を削除すると、次のイベントのリンクが切れる
+    sqlx::query("DELETE FROM events WHERE aggregate_id = $1 AND aggregate_version = 2")
+        .bind(first.value())
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let broken = audit
+        .verify_chain()
+        .await
+        .unwrap()
+        .first_broken_link
+        .unwrap();
+    assert_eq!(broken.aggregate_id, second.value());
+    assert_eq!(broken.reason, BrokenLinkReason::GlobalLinkMismatch);
+}
+
+#[tokio::test]
+async fn test_hash_chain_detects_edited_occurrence_times() {
+    let pool = common::create_test_pool().await;
+    let tenant_id = common::insert_tenant(&pool).await;
+    let event_store = PostgresEventStore::for_tenant(pool.clone(), tenant_id);
+    let audit = PostgresEventAudit::for_tenant(pool.clone(), tenant_id);
+
+    let loan_id = seed_loan(&event_store).await;
+    assert!(audit.verify_chain().await.unwrap().is_intact());
+
+    // 貸出の発生日時を1秒早める（内容とリンクは変えない）
+    sqlx::query(
+        r#"
+        UPDATE events
+        SET occurred_at = occurred_at - interval '1 second'
+        WHERE aggregate_id = $1 AND aggregate_version = 1
+        "#,
+    )
+    .bind(loan_id.value())
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let broken = audit
+        .verify_chain()
+        .await
+        .unwrap()
+        .first_broken_link
+        .unwrap();
+    assert_eq!(broken.sequence_number, sequence_of(&pool, loan_id, 1).await);
+    assert_eq!(broken.reason, BrokenLinkReason::HashMismatch);
+}
+
+#[tokio::test]
+async fn test_hash_chain_detects_events_copied_from_another_tenant() {
+    let pool = common::create_test_pool().await;
+    let tenant_id = common::insert_tenant(&pool).await;
+    let other_tenant = common::insert_tenant(&pool).await;
+    let event_store = PostgresEventStore::for_tenant(pool.clone(), tenant_id);
+
+    let loan_id = seed_loan(&event_store).await;
+
+    // 別のテナントのチェーンの先頭として、ハッシュごと行を付け替える
+    sqlx::query("UPDATE events SET tenant_id = $2 WHERE aggregate_id = $1")
+        .bind(loan_id.value())
+        .bind(other_tenant.value())
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let broken = PostgresEventAudit::for_tenant(pool.clone(), other_tenant)
+        .verify_chain()
+        .await
+        .unwrap()
+        .first_broken_link
+        .unwrap();
+    assert_eq!(broken.aggregate_id, loan_id.value());
+    assert_eq!(broken.reason, BrokenLinkReason::HashMismatch);
+}
+
+#[tokio::test]
+async fn test_anchor_detects_recomputed_chain() {
+    let pool = common::create_test_pool().await;
+    let tenant_id = common::insert_tenant(&pool).await;
+    let event_store = PostgresEventStore::for_tenant(pool.clone(), tenant_id);
+    let audit = PostgresEventAudit::for_tenant(pool.clone(), tenant_id);
+    let signer = AnchorSigner::from_seed_hex(SEED).unwrap();
+
+    let loan_id = seed_loan(&event_store).await;
+    let anchor = anchor_chain(&audit, &signer, tenant_id, Utc::now())
+        .await
+        .unwrap();
+    assert_eq!(anchor.event_count, 2);
+    assert_eq!(anchor.sequence_number, sequence_of(&pool, loan_id, 2).await);
+
+    // アンカー後の追記はアンカーの有効性に影響しない
+    seed_loan(&event_store).await;
+    assert_eq!(
+        verify_anchor(&audit, &anchor, signer.public_key())
+            .await
+            .unwrap(),
+        AnchorStatus::Valid
+    );
+
+    // チェーンを再計算して書き換えた場合（ここではハッシュのみ差し替え）
+    sqlx::query(
+        "UPDATE events SET event_hash = sha256(event_hash) WHERE aggregate_id = $1 AND aggregate_version = 2",
+    )
+    .bind(loan_id.value())
+    .execute(&pool)
+    .await
+    .unwrap();
+    assert_eq!(
+        verify_anchor(&audit, &anchor, signer.public_key())
+            .await
+            .unwrap(),
+        AnchorStatus::RootMismatch
+    );
+}
@@ -0,0 +1,319 @@
+mod common;
+
+use chrono::Utc;
+use futures::StreamExt;
+use rusty_library_ddd::adapters::postgres::{PostgresEventStore, PostgresLoanReadModel};
+use rusty_library_ddd::application::backup::{
+    BackupError, export_event_log, import_event_log, rebuild_loan_read_model,
+};
+use rusty_library_ddd::domain::events::{BookLoaned, BookReturned, DomainEvent, LoanExtended};
+use rusty_library_ddd::domain::value_objects::{BookId, LoanId, MemberId, StaffId, TenantId};
+use rusty_library_ddd::ports::{EventStore, LoanReadModel, LoanStatus, StoredEvent};
+use sqlx::PgPool;
+
+/// 2件の貸出（返却済み・延長中）のイベントを追加
+async fn seed_loans(event_store: &dyn EventStore) -> (LoanId, LoanId) {
+    let now = Utc::now();
+    let returned_loan = LoanId::new();
+    let extended_loan = LoanId::new();
+    let book_id = BookId::new();
+    let member_id = MemberId::new();
+
+    event_store
+        .append(
+            returned_loan.value(),
+            "Loan",
+            vec![DomainEvent::BookLoaned(BookLoaned {
+                loan_id: returned_loan,
+                book_id,
+                member_id,
+                loaned_at: now,
+                due_date: now + chrono::Duration::days(14),
+                loaned_by: StaffId::new(),
+                override_token: None,
+            })],
+        )
+        .await
+        .unwrap();
+
+    event_store
+        .append(
+            extended_loan.value(),
+            "Loan",
+            vec![
+                DomainEvent::BookLoaned(BookLoaned {
+                    loan_id: extended_loan,
+                    book_id: BookId::new(),
+                    member_id,
+                    loaned_at: now,
+                    due_date: now + chrono::Duration::days(14),
+                    loaned_by: StaffId::new(),
+                    override_token: None,
+                }),
+                DomainEvent::LoanExtended(LoanExtended {
+                    loan_id: extended_loan,
+                    old_due_date: now + chrono::Duration::days(14),
+                    new_due_date: now + chrono::Duration::days(28),
+                    extended_at: now + chrono::Duration::days(3),
+                    extension_count: 1,
+                }),
+            ],
+        )
+        .await
+        .unwrap();
+
+    event_store
+        .append(
+            returned_loan.value(),
+            "Loan",
+            vec![DomainEvent::BookReturned(BookReturned {
+                loan_id: returned_loan,
+                book_id,
+                member_id,
+                returned_at: now + chrono::Duration::days(5),
+                was_overdue: false,
+            })],
+        )
+        .await
+        .unwrap();
+
+    (returned_loan, extended_loan)
+}
+
+/// ストアのすべてのイベントをメタデータ付きで取得
+async fn collect_stored(event_store: &dyn EventStore) -> Vec<StoredEvent> {
+    let mut stream = event_store.stream_stored();
+    let mut events = Vec::new();
+    while let Some(stored) = stream.next().await {
+        events.push(stored.expect("Failed to stream event"));
+    }
+    events
+}
+
+/// テナントのデータを削除（障害による消失を再現）
+async fn wipe_tenant(pool: &PgPool, tenant_id: TenantId) {
+    for table in ["events", "loans_view"] {
+        sqlx::query(&format!("DELETE FROM {} WHERE tenant_id = $1", table))
+            .bind(tenant_id.value())
+            .execute(pool)
+            .await
+            .expect("Failed to wipe tenant");
+    }
+}
+
+#[tokio::test]
+async fn test_export_and_import_round_trip() {
+    let pool = common::create_test_pool().await;
+    let tenant_id = common::insert_tenant(&pool).await;
+
+    let event_store = PostgresEventStore::for_tenant(pool.clone(), tenant_id);
+    let read_model = PostgresLoanReadModel::for_tenant(pool.clone(), tenant_id);
+    let (returned_loan, extended_loan) = seed_loans(&event_store).await;
+    let original = collect_stored(&event_store).await;
+
+    // Export
+    let mut ndjson = Vec::new();
+    let manifest = export_event_log(&event_store, &mut ndjson, Utc::now())
+        .await
+        .expect("Failed to export");
+
+    assert_eq!(manifest.event_count, 4);
+    assert_eq!(manifest.aggregate_count, 2);
+    assert_eq!(
+        manifest.first_sequence_number,
+        Some(original[0].sequence_number)
+    );
+    assert_eq!(
+        String::from_utf8(ndjson.clone()).unwrap().lines().count(),
+        4
+    );
+
+    // Lose the data, then restore it into the now empty store
+    wipe_tenant(&pool, tenant_id).await;
+
+    let summary = import_event_log(
+        &event_store,
+        ndjson.as_slice(),
+        &manifest,
+        Some(&read_model),
+    )
+    .await
+    .expect("Failed to import");
+
+    assert_eq!(summary.event_count, 4);
+    assert_eq!(summary.aggregate_count, 2);
+    assert_eq!(summary.rebuilt_loans, Some(2));
+
+    // Events are identical, including ids, versions, sequence numbers and timestamps
+    assert_eq!(collect_stored(&event_store).await, original);
+
+    // Read model was rebuilt
+    let returned = read_model.get_by_id(returned_loan).await.unwrap().unwrap();
+    assert_eq!(returned.status, LoanStatus::Returned);
+    let extended = read_model.get_by_id(extended_loan).await.unwrap().unwrap();
+    assert_eq!(extended.status, LoanStatus::Active);
+    assert_eq!(extended.extension_count, 1);
+
+    // Appends after import continue the aggregate's version and global ordering
+    event_store
+        .append(
+            extended_loan.value(),
+            "Loan",
+            vec![DomainEvent::BookReturned(BookReturned {
+                loan_id: extended_loan,
+                book_id: extended.book_id,
+                member_id: extended.member_id,
+                returned_at: Utc::now(),
+                was_overdue: false,
+            })],
+        )
+        .await
+        .expect("Failed to append after import");
+    let after = collect_stored(&event_store).await;
+    assert_eq!(after.len(), 5);
+    assert_eq!(after[4].aggregate_version, 3);
+    assert!(after[4].sequence_number > original[3].sequence_number);
+
+    // Importing again is rejected because the store is no longer empty
+    let result = import_event_log(&event_store, ndjson.as_slice(), &manifest, None).await;
+    assert!(matches!(result, Err(BackupError::StoreNotEmpty)));
+}
+
+#[tokio::test]
+async fn test_import_rejects_tampered_backup() {
+    let pool = common::create_test_pool().await;
+    let source_tenant = common::insert_tenant(&pool).await;
+    let target_tenant = common::insert_tenant(&pool).await;
+
+    let source = PostgresEventStore::for_tenant(pool.clone(), source_tenant);
+    seed_loans(&source).await;
+
+    let mut ndjson = Vec::new();
+    let manifest = export_event_log(&source, &mut ndjson, Utc::now())
+        .await
+        .unwrap();
+
+    // Drop the last event (a BookReturned) from the backup
+    let text = String::from_utf8(ndjson).unwrap();
+    let truncated: String = text
+        .lines()
+        .take(3)
+        .map(|line| format!("{}\n", line))
+        .collect();
+
+    let target = PostgresEventStore::for_tenant(pool.clone(), target_tenant);
+    let result = import_event_log(&target, truncated.as_bytes(), &manifest, None).await;
+    assert!(matches!(result, Err(BackupError::ChecksumMismatch { .. })));
+
+    // Nothing was written
+    assert!(collect_stored(&target).await.is_empty());
+}
+
+#[tokio::test]
+async fn test_import_restores_an_aggregate_spanning_a_large_backup() {
+    // Arrange: 最初の貸出の返却が1000件以上のイベントの後に記録されたテナント
+    let pool = common::create_test_pool().await;
+    let tenant_id = common::insert_tenant(&pool).await;
+    let event_store = PostgresEventStore::for_tenant(pool.clone(), tenant_id);
+    let read_model = PostgresLoanReadModel::for_tenant(pool.clone(), tenant_id);
+
+    let now = Utc::now();
+    let loaned = |loan_id: LoanId| {
+        DomainEvent::BookLoaned(BookLoaned {
+            loan_id,
+            book_id: BookId::new(),
+            member_id: MemberId::new(),
+            loaned_at: now,
+            due_date: now + chrono::Duration::days(14),
+            loaned_by: StaffId::new(),
+            override_token: None,
+        })
+    };
+    let first_loan = LoanId::new();
+    let first = loaned(first_loan);
+    event_store
+        .append(first_loan.value(), "Loan", vec![first.clone()])
+        .await
+        .unwrap();
+    for _ in 0..1000 {
+        let loan_id = LoanId::new();
+        event_store
+            .append(loan_id.value(), "Loan", vec![loaned(loan_id)])
+            .await
+            .unwrap();
+    }
+    let DomainEvent::BookLoaned(first) = first else {
+        unreachable!();
+    };
+    event_store
+        .append(
+            first_loan.value(),
+            "Loan",
+            vec![DomainEvent::BookReturned(BookReturned {
+                loan_id: first_loan,
+                book_id: first.book_id,
+                member_id: first.member_id,
+                returned_at: now + chrono::Duration::days(5),
+                was_overdue: false,
+            })],
+        )
+        .await
+        .unwrap();
+    let original = collect_stored(&event_store).await;
+
+    let mut ndjson = Vec::new();
+    let manifest = export_event_log(&event_store, &mut ndjson, Utc::now())
+        .await
+        .unwrap();
+    wipe_tenant(&pool, tenant_id).await;
+
+    // Act
+    let summary = import_event_log(
+        &event_store,
+        ndjson.as_slice(),
+        &manifest,
+        Some(&read_model),
+    )
+    .await
+    .expect("Failed to import");
+
+    // Assert: 全件が復元され、最初の貸出は返却済みとして再構築される
+    assert_eq!(summary.event_count, 1002);
+    assert_eq!(summary.rebuilt_loans, Some(1001));
+    assert_eq!(collect_stored(&event_store).await, original);
+    let returned = read_model.get_by_id(first_loan).await.unwrap().unwrap();
+    assert_eq!(returned.status, LoanStatus::Returned);
+}
+
+#[tokio::test]
+async fn test_rebuild_reports_an_aggregate_with_an_invalid_transition() {
+    // Arrange: 同じ貸出に貸出のイベントが2回記録されている（破損したバックアップ）
+    let pool = common::create_test_pool().await;
+    let tenant_id = common::insert_tenant(&pool).await;
+    let event_store = PostgresEventStore::for_tenant(pool.clone(), tenant_id);
+    let read_model = PostgresLoanReadModel::for_tenant(pool.clone(), tenant_id);
+    let loan_id = LoanId::new();
+    let now = Utc::now();
+    let loaned = DomainEvent::BookLoaned(BookLoaned {
+        loan_id,
+        book_id: BookId::new(),
+        member_id: MemberId::new(),
+        loaned_at: now,
+        due_date: now + chrono::Duration::days(14),
+        loaned_by: StaffId::new(),
+        override_token: None,
+    });
+    event_store
+        .append(loan_id.value(), "Loan", vec![loaned.clone(), loaned])
+        .await
+        .unwrap();
+
+    // Act
+    let result = rebuild_loan_read_model(&event_store, &read_model).await;
+
+    // Assert: panicせず、再生できない集約を報告する
+    assert!(matches!(
+        result,
+        Err(BackupError::InvalidAggregate { aggregate_id, .. }) if aggregate_id == loan_id.value()
+    ));
+}
@@ -0,0 +1,125 @@
+mod common;
+
+use chrono::Utc;
+use rusty_library_ddd::adapters::postgres::{EventCodec, PostgresEventStore};
+use rusty_library_ddd::domain::events::{BookLoaned, BookReturned, DomainEvent};
+use rusty_library_ddd::domain::value_objects::{BookId, LoanId, MemberId, StaffId, TenantId};
+use rusty_library_ddd::ports::EventStore;
+use serde_json::{Value, json};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// アダプターを経由せずにイベントの行を書き込む（直接のSQLによる書き込みを再現）
+async fn insert_raw(
+    pool: &PgPool,
+    tenant_id: TenantId,
+    event_type: &str,
+    event_data: Option<Value>,
+    payload: Option<Vec<u8>>,
+) -> Uuid {
+    let aggregate_id = Uuid::new_v4();
+    let codec = if payload.is_some() { "msgpack" } else { "json" };
+    sqlx::query(
+        r#"
+        INSERT INTO events (
+            tenant_id, aggregate_id, aggregate_version, aggregate_type, event_type,
+            event_data, occurred_at, event_codec, event_payload
+        )
+        VALUES ($1, $2, 1, 'Loan', $3, $4, now(), $5, $6)
+        "#,
+    )
+    .bind(tenant_id.value())
+    .bind(aggregate_id)
+    .bind(event_type)
+    .bind(event_data)
+    .bind(codec)
+    .bind(payload)
+    .execute(pool)
+    .await
+    .expect("Failed to insert raw event");
+    aggregate_id
+}
+
+#[tokio::test]
+async fn test_validate_stored_reports_malformed_rows() {
+    let pool = common::create_test_pool().await;
+    let tenant_id = common::insert_tenant(&pool).await;
+    let event_store =
+        PostgresEventStore::for_tenant(pool.clone(), tenant_id).with_codec(EventCodec::MessagePack);
+
+    let now = Utc::now();
+    let loan_id = LoanId::new();
+    let book_id = BookId::new();
+    let member_id = MemberId::new();
+    event_store
+        .append(
+            loan_id.value(),
+            "Loan",
+            vec![
+                DomainEvent::BookLoaned(BookLoaned {
+                    loan_id,
+                    book_id,
+                    member_id,
+                    loaned_at: now,
+                    due_date: now + chrono::Duration::days(14),
+                    loaned_by: StaffId::new(),
+                    override_token: None,
+                }),
+                DomainEvent::BookReturned(BookReturned {
+                    loan_id,
+                    book_id,
+                    member_id,
+                    returned_at: now + chrono::Duration::days(3),
+                    was_overdue: false,
+                }),
+            ],
+        )
+        .await
+        .unwrap();
+
+    // 暗号化された会員IDを含む正しいイベントは違反にならない
+    let scan = event_store.validate_stored().await.unwrap();
+    assert_eq!(scan.scanned_events, 2);
+    assert!(scan.is_valid(), "{:?}", scan.violations);
+
+    // 必須項目の欠けたイベント、種類と中身の食い違い、デコードできない行
+    let missing_field = insert_raw(
+        &pool,
+        tenant_id,
+        "BookReturned",
+        Some(json!({ "BookReturned": { "loan_id": Uuid::new_v4() } })),
+        None,
+    )
+    .await;
+    let mismatched_type = insert_raw(
+        &pool,
+        tenant_id,
+        "LoanExtended",
+        Some(json!({ "BookReturned": {
+            "loan_id": Uuid::new_v4(),
+            "book_id": Uuid::new_v4(),
+            "member_id": Uuid::new_v4(),
+            "returned_at": now,
+            "was_overdue": false,
+        }})),
+        None,
+    )
+    .await;
+    let undecodable = insert_raw(&pool, tenant_id, "BookLoaned", None, Some(vec![0xc1])).await;
+
+    let scan = event_store.validate_stored().await.unwrap();
+    assert_eq!(scan.scanned_events, 5);
+    let invalid: Vec<Uuid> = scan.violations.iter().map(|v| v.aggregate_id).collect();
+    assert_eq!(invalid, vec![missing_field, mismatched_type, undecodable]);
+    assert!(scan.violations[0].error.contains("BookReturned v1"));
+
+    // 正しいイベントの読み込みには影響しない
+    assert_eq!(
+        event_store
+            .load(loan_id.value(), "Loan")
+            .await
+            .unwrap()
+            .len(),
+        2
+    );
+}
@@ -0,0 +1,195 @@
+use async_trait::async_trait;
+use chrono::{DateTime, TimeZone, Utc};
+use rusty_library_ddd::adapters::postgres::PostgresJobStore;
+use rusty_library_ddd::application::jobs::{Job, JobError, Result, Schedule, Scheduler};
+use rusty_library_ddd::ports::{JobRunStatus, JobStore};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+use tokio::sync::watch;
+
+mod common;
+
+/// 実行回数を数えるジョブ（実行に少し時間がかかる）
+struct CountingJob {
+    name: String,
+    runs: AtomicUsize,
+    fail: bool,
+}
+
+impl CountingJob {
+    /// テストごとに別の名前を付け、実行履歴と実行権が他のテストと混ざらないようにする
+    fn new(fail: bool) -> Arc<Self> {
+        Arc::new(Self {
+            name: format!("test-job-{}", uuid::Uuid::new_v4()),
+            runs: AtomicUsize::new(0),
+            fail,
+        })
+    }
+}
+
+#[async_trait]
+impl Job for CountingJob {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn run(&self, _scheduled_for: DateTime<Utc>) -> Result<String> {
+        self.runs.fetch_add(1, Ordering::SeqCst);
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        if self.fail {
+            return Err(JobError::Failed("read model is unavailable".to_string()));
+        }
+        Ok("Processed 3 loans".to_string())
+    }
+}
+
+fn slot() -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2025, 1, 15, 3, 0, 0).unwrap()
+}
+
+#[tokio::test]
+async fn test_lease_excludes_other_instances() {
+    let pool = common::create_test_pool().await;
+    let store = PostgresJobStore::new(pool.clone());
+    let job = format!("test-job-{}", uuid::Uuid::new_v4());
+
+    let lease = store.try_acquire(&job).await.unwrap().expect("lease");
+    assert!(store.try_acquire(&job).await.unwrap().is_none());
+
+    // 別のジョブの実行権は独立している
+    let other = store.try_acquire("another-job").await.unwrap();
+    assert!(other.is_some());
+    other.unwrap().release().await.unwrap();
+
+    lease.release().await.unwrap();
+    let lease = store.try_acquire(&job).await.unwrap();
+    assert!(lease.is_some());
+    lease.unwrap().release().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_lease_does_not_hold_a_pooled_connection() {
+    // Arrange: 接続が1つだけのプール
+    let pool = common::create_test_pool().await;
+    let small_pool = sqlx::postgres::PgPoolOptions::new()
+        .max_connections(1)
+        .acquire_timeout(Duration::from_secs(2))
+        .connect_with((*pool.connect_options()).clone())
+        .await
+        .unwrap();
+    let store = PostgresJobStore::new(small_pool.clone());
+    let job = format!("test-job-{}", uuid::Uuid::new_v4());
+
+    // Act: 実行権を保持している間もプールの接続を使える
+    let lease = store.try_acquire(&job).await.unwrap().expect("lease");
+    let one: i32 = sqlx::query_scalar("SELECT 1")
+        .fetch_one(&small_pool)
+        .await
+        .unwrap();
+    assert_eq!(one, 1);
+    assert!(store.try_acquire(&job).await.unwrap().is_none());
+
+    // Assert: 解放すると再び取得できる
+    lease.release().await.unwrap();
+    let lease = store.try_acquire(&job).await.unwrap();
+    assert!(lease.is_some());
+    lease.unwrap().release().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_each_scheduled_run_executes_once_across_instances() {
+    // Arrange: 同じジョブストアを使う2つのインスタンス
+    let pool = common::create_test_pool().await;
+    let store: Arc<dyn JobStore> = Arc::new(PostgresJobStore::new(pool.clone()));
+    let replica_a = Scheduler::new(store.clone(), "replica-a");
+    let replica_b = Scheduler::new(store.clone(), "replica-b");
+    let job = CountingJob::new(false);
+
+    // Act: 同じ回を同時に実行する
+    let (a, b) = tokio::join!(
+        replica_a.run_once(job.as_ref(), slot()),
+        replica_b.run_once(job.as_ref(), slot())
+    );
+
+    // Assert: 実行したのは1つだけ
+    let runs: Vec<_> = [a.unwrap(), b.unwrap()].into_iter().flatten().collect();
+    assert_eq!(runs.len(), 1);
+    assert_eq!(job.runs.load(Ordering::SeqCst), 1);
+
+    // 実行後に遅れて起動したインスタンスも同じ回は実行しない
+    assert!(
+        replica_b
+            .run_once(job.as_ref(), slot())
+            .await
+            .unwrap()
+            .is_none()
+    );
+    assert_eq!(job.runs.load(Ordering::SeqCst), 1);
+
+    // 実行履歴が記録される
+    let history = store.list_runs(&job.name, 10).await.unwrap();
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0].run_id, runs[0].run_id);
+    assert_eq!(history[0].scheduled_for, slot());
+    assert_eq!(history[0].instance_id, runs[0].instance_id);
+    assert_eq!(history[0].status, JobRunStatus::Succeeded);
+    assert_eq!(history[0].summary.as_deref(), Some("Processed 3 loans"));
+    assert!(history[0].finished_at.is_some());
+
+    // 次の回は実行される
+    let next = slot() + chrono::Duration::minutes(15);
+    assert!(
+        replica_b
+            .run_once(job.as_ref(), next)
+            .await
+            .unwrap()
+            .is_some()
+    );
+    assert_eq!(store.list_runs(&job.name, 10).await.unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn test_failed_runs_are_recorded() {
+    let pool = common::create_test_pool().await;
+    let store: Arc<dyn JobStore> = Arc::new(PostgresJobStore::new(pool.clone()));
+    let scheduler = Scheduler::new(store.clone(), "replica-a");
+    let job = CountingJob::new(true);
+
+    let run = scheduler
+        .run_once(job.as_ref(), slot())
+        .await
+        .unwrap()
+        .expect("run");
+
+    assert_eq!(run.status, JobRunStatus::Failed);
+    let history = store.list_runs(&job.name, 10).await.unwrap();
+    assert_eq!(history[0].status, JobRunStatus::Failed);
+    assert_eq!(
+        history[0].summary.as_deref(),
+        Some("Job failed: read model is unavailable")
+    );
+
+    // 失敗しても実行権は手放される
+    let lease = store.try_acquire(&job.name).await.unwrap();
+    assert!(lease.is_some());
+    lease.unwrap().release().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_scheduler_stops_on_shutdown() {
+    let pool = common::create_test_pool().await;
+    let store: Arc<dyn JobStore> = Arc::new(PostgresJobStore::new(pool.clone()));
+    let every_minute: Schedule = "* * * * *".parse().unwrap();
+    let scheduler =
+        Scheduler::new(store, "replica-a").with_job(CountingJob::new(false), every_minute);
+
+    let (shutdown_tx, shutdown_rx) = watch::channel(false);
+    let running = tokio::spawn(async move { scheduler.run(shutdown_rx).await });
+
+    shutdown_tx.send(true).unwrap();
+    tokio::time::timeout(Duration::from_secs(5), running)
+        .await
+        .expect("scheduler should stop without waiting for the next run")
+        .unwrap();
+}
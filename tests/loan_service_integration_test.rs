@@ -1,13 +1,27 @@
 use chrono::Utc;
-use rusty_library_ddd::adapters::mock::{BookService, MemberService};
+use rusty_library_ddd::adapters::in_memory::InMemoryEventBus;
+use rusty_library_ddd::adapters::mock::{
+    BookService, DeferredNoticeQueue, IdempotencyStore, MemberService, NotificationLog,
+    NotificationPreferenceStore, NotificationService, StaffService,
+};
+use rusty_library_ddd::application::legacy_import::{
+    LegacyImportOptions, RejectReason, import_legacy_loans,
+};
 use rusty_library_ddd::application::loan::{
-    ServiceDependencies, detect_overdue_loans, extend_loan, loan_book, return_book,
+    LoanApplicationError, OverdueSkipReason, ServiceDependencies, advance_overdue_notices,
+    anonymise_loan_history, check_loan_eligibility, detect_overdue_loans, extend_loan, loan_book,
+    loan_book_with_override, remind_due_soon_loans, return_book, set_reading_history_preference,
 };
+use rusty_library_ddd::application::repository::EventSourcedRepository;
 use rusty_library_ddd::domain::commands::*;
 use rusty_library_ddd::domain::events::DomainEvent;
+use rusty_library_ddd::domain::loan::{Loan, LoanEvent};
 use rusty_library_ddd::domain::value_objects::*;
+use rusty_library_ddd::domain::{
+    CirculationPolicy, EligibilityRule, EligibilityViolation, OverrideToken,
+};
 use rusty_library_ddd::ports::*;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::{Arc, Mutex};
 use uuid::Uuid;
 
@@ -16,16 +30,25 @@ use uuid::Uuid;
 // ============================================================================
 
 /// インメモリEventStore実装
+///
+/// イベントは集約の種類とIDの組ごとに保持する。
 struct InMemoryEventStore {
-    events: Mutex<HashMap<Uuid, Vec<DomainEvent>>>,
+    events: Mutex<HashMap<(String, Uuid), Vec<DomainEvent>>>,
+    unavailable: Mutex<HashSet<Uuid>>,
 }
 
 impl InMemoryEventStore {
     fn new() -> Self {
         Self {
             events: Mutex::new(HashMap::new()),
+            unavailable: Mutex::new(HashSet::new()),
         }
     }
+
+    /// 集約への書き込みを失敗させる（障害の再現用）
+    fn make_unavailable(&self, aggregate_id: Uuid) {
+        self.unavailable.lock().unwrap().insert(aggregate_id);
+    }
 }
 
 #[async_trait::async_trait]
@@ -33,33 +56,117 @@ impl EventStore for InMemoryEventStore {
     async fn append(
         &self,
         aggregate_id: Uuid,
-        _aggregate_type: &str,
+        aggregate_type: &str,
         events: Vec<DomainEvent>,
     ) -> event_store::Result<()> {
         let mut store = self.events.lock().unwrap();
-        store.entry(aggregate_id).or_default().extend(events);
+        store
+            .entry((aggregate_type.to_string(), aggregate_id))
+            .or_default()
+            .extend(events);
         Ok(())
     }
 
-    async fn load(&self, aggregate_id: Uuid) -> event_store::Result<Vec<DomainEvent>> {
+    async fn append_with_expected_version(
+        &self,
+        aggregate_id: Uuid,
+        aggregate_type: &str,
+        expected_version: u32,
+        events: Vec<DomainEvent>,
+    ) -> event_store::Result<Vec<AppendedEvent>> {
+        if self.unavailable.lock().unwrap().contains(&aggregate_id) {
+            return Err(EventStoreError::Unavailable("connection reset".into()));
+        }
+        let mut store = self.events.lock().unwrap();
+        let stored = store
+            .entry((aggregate_type.to_string(), aggregate_id))
+            .or_default();
+        if stored.len() as u32 != expected_version {
+            return Err(EventStoreError::VersionConflict {
+                aggregate_id,
+                expected: expected_version,
+                actual: stored.len() as u32,
+            });
+        }
+        let appended = (0..events.len())
+            .map(|i| AppendedEvent {
+                event_id: Uuid::new_v4(),
+                sequence_number: (stored.len() + i + 1) as i64,
+                recorded_at: Utc::now(),
+            })
+            .collect();
+        stored.extend(events);
+        Ok(appended)
+    }
+
+    async fn load(
+        &self,
+        aggregate_id: Uuid,
+        aggregate_type: &str,
+    ) -> event_store::Result<Vec<DomainEvent>> {
+        let store = self.events.lock().unwrap();
+        Ok(store
+            .get(&(aggregate_type.to_string(), aggregate_id))
+            .cloned()
+            .unwrap_or_default())
+    }
+
+    async fn load_many(
+        &self,
+        aggregate_ids: &[Uuid],
+        aggregate_type: &str,
+    ) -> event_store::Result<HashMap<Uuid, Vec<DomainEvent>>> {
         let store = self.events.lock().unwrap();
-        Ok(store.get(&aggregate_id).cloned().unwrap_or_default())
+        Ok(aggregate_ids
+            .iter()
+            .filter_map(|id| {
+                store
+                    .get(&(aggregate_type.to_string(), *id))
+                    .map(|events| (*id, events.clone()))
+            })
+            .collect())
+    }
+
+    async fn load_member_events(
+        &self,
+        _member_id: MemberId,
+    ) -> event_store::Result<Vec<StoredEvent>> {
+        unimplemented!("load_member_events not needed for these tests")
+    }
+
+    async fn load_by_type(
+        &self,
+        _event_type: &str,
+        _from: chrono::DateTime<Utc>,
+        _to: chrono::DateTime<Utc>,
+    ) -> event_store::Result<Vec<StoredEvent>> {
+        unimplemented!("load_by_type not needed for these tests")
     }
 
     fn stream_all(&self) -> futures::stream::BoxStream<'_, event_store::Result<DomainEvent>> {
         unimplemented!("stream_all not needed for these tests")
     }
+
+    fn stream_stored(&self) -> futures::stream::BoxStream<'_, event_store::Result<StoredEvent>> {
+        unimplemented!("stream_stored not needed for these tests")
+    }
+
+    async fn import(&self, _events: Vec<StoredEvent>) -> event_store::Result<()> {
+        unimplemented!("import not needed for these tests")
+    }
 }
 
 /// インメモリLoanReadModel実装
 struct InMemoryLoanReadModel {
     loans: Mutex<HashMap<LoanId, LoanView>>,
+    opt_ins: Mutex<HashMap<MemberId, chrono::DateTime<Utc>>>,
 }
 
 impl InMemoryLoanReadModel {
     fn new() -> Self {
         Self {
             loans: Mutex::new(HashMap::new()),
+            opt_ins: Mutex::new(HashMap::new()),
         }
     }
 }
@@ -96,6 +203,33 @@ impl LoanReadModel for InMemoryLoanReadModel {
             .collect())
     }
 
+    async fn find_due_soon_candidates(
+        &self,
+        from: chrono::DateTime<Utc>,
+        until: chrono::DateTime<Utc>,
+    ) -> loan_read_model::Result<Vec<LoanView>> {
+        let loans = self.loans.lock().unwrap();
+        Ok(loans
+            .values()
+            .filter(|l| {
+                matches!(l.status, LoanStatus::Active) && l.due_date > from && l.due_date <= until
+            })
+            .cloned()
+            .collect())
+    }
+
+    async fn find_overdue_loans(
+        &self,
+        due_before: chrono::DateTime<Utc>,
+    ) -> loan_read_model::Result<Vec<LoanView>> {
+        let loans = self.loans.lock().unwrap();
+        Ok(loans
+            .values()
+            .filter(|l| matches!(l.status, LoanStatus::Overdue) && l.due_date <= due_before)
+            .cloned()
+            .collect())
+    }
+
     async fn get_by_id(&self, loan_id: LoanId) -> loan_read_model::Result<Option<LoanView>> {
         let loans = self.loans.lock().unwrap();
         Ok(loans.get(&loan_id).cloned())
@@ -104,14 +238,58 @@ impl LoanReadModel for InMemoryLoanReadModel {
     async fn find_by_member_id(
         &self,
         member_id: MemberId,
+        history_cutoff: Option<chrono::DateTime<Utc>>,
     ) -> loan_read_model::Result<Vec<LoanView>> {
+        let keeps_history = self.opt_ins.lock().unwrap().contains_key(&member_id);
         let loans = self.loans.lock().unwrap();
         Ok(loans
             .values()
             .filter(|l| l.member_id == member_id)
+            .filter(|l| match (history_cutoff, l.returned_at) {
+                (Some(cutoff), Some(returned_at)) => keeps_history || returned_at >= cutoff,
+                _ => true,
+            })
             .cloned()
             .collect())
     }
+
+    async fn save_history_opt_in(
+        &self,
+        member_id: MemberId,
+        opted_in_at: Option<chrono::DateTime<Utc>>,
+    ) -> loan_read_model::Result<()> {
+        let mut opt_ins = self.opt_ins.lock().unwrap();
+        match opted_in_at {
+            Some(opted_in_at) => opt_ins.insert(member_id, opted_in_at),
+            None => opt_ins.remove(&member_id),
+        };
+        Ok(())
+    }
+
+    async fn get_history_opt_in(
+        &self,
+        member_id: MemberId,
+    ) -> loan_read_model::Result<Option<chrono::DateTime<Utc>>> {
+        Ok(self.opt_ins.lock().unwrap().get(&member_id).copied())
+    }
+
+    async fn anonymise_returned_loans(
+        &self,
+        returned_before: chrono::DateTime<Utc>,
+    ) -> loan_read_model::Result<u64> {
+        let opt_ins = self.opt_ins.lock().unwrap();
+        let mut loans = self.loans.lock().unwrap();
+        let mut anonymised = 0;
+        for loan in loans.values_mut() {
+            let expired = loan.returned_at.is_some_and(|r| r < returned_before);
+            if expired && !loan.member_id.is_anonymised() && !opt_ins.contains_key(&loan.member_id)
+            {
+                loan.member_id = MemberId::ANONYMISED;
+                anonymised += 1;
+            }
+        }
+        Ok(anonymised)
+    }
 }
 
 // ============================================================================
@@ -135,10 +313,21 @@ async fn test_loan_book_success() {
     book_service.add_available_book(book_id);
 
     let deps = ServiceDependencies {
+        tenant_id: TenantId::DEFAULT,
+
+        policy: CirculationPolicy::default(),
         event_store: event_store.clone(),
         loan_read_model: loan_read_model.clone(),
+        unit_of_work: None,
         member_service,
         book_service,
+        staff_service: Arc::new(StaffService::new()),
+        notification_service: Arc::new(NotificationService::new()),
+        notification_log: Arc::new(NotificationLog::new()),
+        notification_preferences: Arc::new(NotificationPreferenceStore::new()),
+        deferred_notices: Arc::new(DeferredNoticeQueue::new()),
+        event_bus: Arc::new(InMemoryEventBus::synchronous()),
+        idempotency_keys: Arc::new(IdempotencyStore::new()),
     };
 
     // Act: 貸出実行（純粋な関数呼び出し）
@@ -156,7 +345,7 @@ async fn test_loan_book_success() {
     let loan_id = result.unwrap();
 
     // イベントが保存されたことを確認
-    let events = event_store.load(loan_id.value()).await.unwrap();
+    let events = event_store.load(loan_id.value(), "Loan").await.unwrap();
     assert_eq!(events.len(), 1);
     assert!(matches!(events[0], DomainEvent::BookLoaned(_)));
 
@@ -182,10 +371,21 @@ async fn test_loan_book_member_not_found() {
     book_service.add_available_book(book_id);
 
     let deps = ServiceDependencies {
+        tenant_id: TenantId::DEFAULT,
+
+        policy: CirculationPolicy::default(),
         event_store,
         loan_read_model,
+        unit_of_work: None,
         member_service,
         book_service,
+        staff_service: Arc::new(StaffService::new()),
+        notification_service: Arc::new(NotificationService::new()),
+        notification_log: Arc::new(NotificationLog::new()),
+        notification_preferences: Arc::new(NotificationPreferenceStore::new()),
+        deferred_notices: Arc::new(DeferredNoticeQueue::new()),
+        event_bus: Arc::new(InMemoryEventBus::synchronous()),
+        idempotency_keys: Arc::new(IdempotencyStore::new()),
     };
 
     // Act
@@ -244,10 +444,21 @@ async fn test_loan_book_limit_exceeded() {
     book_service.add_available_book(new_book_id);
 
     let deps = ServiceDependencies {
+        tenant_id: TenantId::DEFAULT,
+
+        policy: CirculationPolicy::default(),
         event_store,
         loan_read_model,
+        unit_of_work: None,
         member_service,
         book_service,
+        staff_service: Arc::new(StaffService::new()),
+        notification_service: Arc::new(NotificationService::new()),
+        notification_log: Arc::new(NotificationLog::new()),
+        notification_preferences: Arc::new(NotificationPreferenceStore::new()),
+        deferred_notices: Arc::new(DeferredNoticeQueue::new()),
+        event_bus: Arc::new(InMemoryEventBus::synchronous()),
+        idempotency_keys: Arc::new(IdempotencyStore::new()),
     };
 
     // Act
@@ -268,6 +479,212 @@ async fn test_loan_book_limit_exceeded() {
     ));
 }
 
+#[tokio::test]
+async fn test_loan_book_respects_tenant_policy_limit() {
+    // Arrange: 貸出上限2冊のテナント
+    let event_store = Arc::new(InMemoryEventStore::new());
+    let loan_read_model = Arc::new(InMemoryLoanReadModel::new());
+    let member_service = Arc::new(MemberService::new());
+    let book_service = Arc::new(BookService::new());
+
+    let member_id = MemberId::new();
+    member_service.add_member(member_id);
+
+    let deps = ServiceDependencies {
+        tenant_id: TenantId::new(),
+        policy: CirculationPolicy {
+            max_active_loans: 2,
+            loan_period_days: 21,
+            history_retention_days: None,
+        },
+        event_store,
+        loan_read_model: loan_read_model.clone(),
+        unit_of_work: None,
+        member_service,
+        book_service: book_service.clone(),
+        staff_service: Arc::new(StaffService::new()),
+        notification_service: Arc::new(NotificationService::new()),
+        notification_log: Arc::new(NotificationLog::new()),
+        notification_preferences: Arc::new(NotificationPreferenceStore::new()),
+        deferred_notices: Arc::new(DeferredNoticeQueue::new()),
+        event_bus: Arc::new(InMemoryEventBus::synchronous()),
+        idempotency_keys: Arc::new(IdempotencyStore::new()),
+    };
+
+    let loaned_at = Utc::now();
+    let mut results = Vec::new();
+    for _ in 0..3 {
+        let book_id = BookId::new();
+        book_service.add_available_book(book_id);
+        let cmd = LoanBook {
+            book_id,
+            member_id,
+            loaned_at,
+            staff_id: StaffId::new(),
+        };
+        results.push(loan_book(&deps, cmd).await);
+    }
+
+    // Assert: 2冊までは成功し、3冊目は上限超過
+    let first_loan_id = *results[0].as_ref().unwrap();
+    assert!(results[1].is_ok());
+    assert!(matches!(
+        results[2],
+        Err(rusty_library_ddd::application::loan::LoanApplicationError::LoanLimitExceeded)
+    ));
+
+    // 貸出期間もテナントのポリシーに従う
+    let loan_view = loan_read_model
+        .get_by_id(first_loan_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(loan_view.due_date, loaned_at + chrono::Duration::days(21));
+}
+
+#[tokio::test]
+async fn test_loan_book_with_override_skips_only_overridden_rules() {
+    // Arrange: 貸出上限1冊のテナントで、既に1冊借りていて延滞中の会員
+    let event_store = Arc::new(InMemoryEventStore::new());
+    let member_service = Arc::new(MemberService::new());
+    let book_service = Arc::new(BookService::new());
+
+    let member_id = MemberId::new();
+    member_service.add_member(member_id);
+
+    let deps = ServiceDependencies {
+        tenant_id: TenantId::new(),
+        policy: CirculationPolicy {
+            max_active_loans: 1,
+            ..CirculationPolicy::default()
+        },
+        event_store: event_store.clone(),
+        loan_read_model: Arc::new(InMemoryLoanReadModel::new()),
+        unit_of_work: None,
+        member_service: member_service.clone(),
+        book_service: book_service.clone(),
+        staff_service: Arc::new(StaffService::new()),
+        notification_service: Arc::new(NotificationService::new()),
+        notification_log: Arc::new(NotificationLog::new()),
+        notification_preferences: Arc::new(NotificationPreferenceStore::new()),
+        deferred_notices: Arc::new(DeferredNoticeQueue::new()),
+        event_bus: Arc::new(InMemoryEventBus::synchronous()),
+        idempotency_keys: Arc::new(IdempotencyStore::new()),
+    };
+    let new_loan = || {
+        let book_id = BookId::new();
+        book_service.add_available_book(book_id);
+        LoanBook {
+            book_id,
+            member_id,
+            loaned_at: Utc::now(),
+            staff_id: StaffId::new(),
+        }
+    };
+    loan_book(&deps, new_loan()).await.unwrap();
+    member_service.mark_overdue(member_id);
+
+    let token = |rules: Vec<EligibilityRule>| OverrideToken {
+        rules,
+        reason: "Teacher borrowing class set".to_string(),
+        supervisor_id: StaffId::new(),
+    };
+
+    // Act & Assert: 例外を認めていない条件は従来どおり判定される
+    let result = loan_book_with_override(
+        &deps,
+        LoanBookWithOverride {
+            loan: new_loan(),
+            override_token: token(vec![EligibilityRule::LoanLimit]),
+        },
+    )
+    .await;
+    assert!(matches!(
+        result,
+        Err(LoanApplicationError::MemberHasOverdueLoan)
+    ));
+
+    // 両方の条件の例外を認めれば貸し出せ、承認内容がイベントに記録される
+    let override_token = token(vec![
+        EligibilityRule::LoanLimit,
+        EligibilityRule::OverdueLoans,
+    ]);
+    let loan_id = loan_book_with_override(
+        &deps,
+        LoanBookWithOverride {
+            loan: new_loan(),
+            override_token: override_token.clone(),
+        },
+    )
+    .await
+    .unwrap();
+
+    let events = event_store.load(loan_id.value(), "Loan").await.unwrap();
+    let DomainEvent::BookLoaned(loaned) = &events[0] else {
+        panic!("expected BookLoaned, got {:?}", events[0]);
+    };
+    assert_eq!(loaned.override_token, Some(override_token));
+}
+
+#[tokio::test]
+async fn test_check_loan_eligibility_reports_every_violation() {
+    // Arrange: 利用停止中で延滞中の会員と、貸出できない書籍
+    let event_store = Arc::new(InMemoryEventStore::new());
+    let member_service = Arc::new(MemberService::new());
+
+    let member_id = MemberId::new();
+    member_service.add_member(member_id);
+    member_service.mark_overdue(member_id);
+    member_service.mark_suspended(member_id);
+    let book_id = BookId::new();
+
+    let deps = ServiceDependencies {
+        tenant_id: TenantId::new(),
+        policy: CirculationPolicy::default(),
+        event_store: event_store.clone(),
+        loan_read_model: Arc::new(InMemoryLoanReadModel::new()),
+        unit_of_work: None,
+        member_service,
+        book_service: Arc::new(BookService::new()),
+        staff_service: Arc::new(StaffService::new()),
+        notification_service: Arc::new(NotificationService::new()),
+        notification_log: Arc::new(NotificationLog::new()),
+        notification_preferences: Arc::new(NotificationPreferenceStore::new()),
+        deferred_notices: Arc::new(DeferredNoticeQueue::new()),
+        event_bus: Arc::new(InMemoryEventBus::synchronous()),
+        idempotency_keys: Arc::new(IdempotencyStore::new()),
+    };
+
+    // Act: 貸出可否の確認
+    let violations = check_loan_eligibility(&deps, member_id, book_id)
+        .await
+        .unwrap();
+
+    // Assert: 満たされていない条件がすべて返る
+    assert_eq!(
+        violations,
+        vec![
+            EligibilityViolation::MemberSuspended,
+            EligibilityViolation::BookNotAvailable,
+            EligibilityViolation::MemberHasOverdueLoan,
+        ]
+    );
+
+    // 貸出は同じ判定の先頭の条件で拒否され、何も保存されない
+    let result = loan_book(
+        &deps,
+        LoanBook {
+            book_id,
+            member_id,
+            loaned_at: Utc::now(),
+            staff_id: StaffId::new(),
+        },
+    )
+    .await;
+    assert!(matches!(result, Err(LoanApplicationError::MemberSuspended)));
+    assert!(event_store.events.lock().unwrap().is_empty());
+}
+
 #[tokio::test]
 async fn test_extend_loan_success() {
     // Arrange: 貸出を事前に作成
@@ -284,10 +701,21 @@ async fn test_extend_loan_success() {
     book_service.add_available_book(book_id);
 
     let deps = ServiceDependencies {
+        tenant_id: TenantId::DEFAULT,
+
+        policy: CirculationPolicy::default(),
         event_store: event_store.clone(),
         loan_read_model: loan_read_model.clone(),
+        unit_of_work: None,
         member_service,
         book_service,
+        staff_service: Arc::new(StaffService::new()),
+        notification_service: Arc::new(NotificationService::new()),
+        notification_log: Arc::new(NotificationLog::new()),
+        notification_preferences: Arc::new(NotificationPreferenceStore::new()),
+        deferred_notices: Arc::new(DeferredNoticeQueue::new()),
+        event_bus: Arc::new(InMemoryEventBus::synchronous()),
+        idempotency_keys: Arc::new(IdempotencyStore::new()),
     };
 
     // 貸出作成
@@ -311,11 +739,97 @@ async fn test_extend_loan_success() {
     assert!(result.is_ok());
 
     // イベントが追加されたことを確認
-    let events = event_store.load(loan_id.value()).await.unwrap();
+    let events = event_store.load(loan_id.value(), "Loan").await.unwrap();
     assert_eq!(events.len(), 2); // BookLoaned + LoanExtended
     assert!(matches!(events[1], DomainEvent::LoanExtended(_)));
 }
 
+#[tokio::test]
+async fn test_loan_repository_rejects_stale_version() {
+    let event_store = Arc::new(InMemoryEventStore::new());
+    let loan_read_model = Arc::new(InMemoryLoanReadModel::new());
+    let member_service = Arc::new(MemberService::new());
+    let book_service = Arc::new(BookService::new());
+
+    let member_id = MemberId::new();
+    let book_id = BookId::new();
+    member_service.add_member(member_id);
+    book_service.add_available_book(book_id);
+
+    let deps = ServiceDependencies {
+        tenant_id: TenantId::DEFAULT,
+
+        policy: CirculationPolicy::default(),
+        event_store: event_store.clone(),
+        loan_read_model: loan_read_model.clone(),
+        unit_of_work: None,
+        member_service,
+        book_service,
+        staff_service: Arc::new(StaffService::new()),
+        notification_service: Arc::new(NotificationService::new()),
+        notification_log: Arc::new(NotificationLog::new()),
+        notification_preferences: Arc::new(NotificationPreferenceStore::new()),
+        deferred_notices: Arc::new(DeferredNoticeQueue::new()),
+        event_bus: Arc::new(InMemoryEventBus::synchronous()),
+        idempotency_keys: Arc::new(IdempotencyStore::new()),
+    };
+
+    let loan_id = loan_book(
+        &deps,
+        LoanBook {
+            book_id,
+            member_id,
+            loaned_at: Utc::now(),
+            staff_id: StaffId::new(),
+        },
+    )
+    .await
+    .unwrap();
+
+    // 集約の読み込み（バージョン付き）
+    let repository = EventSourcedRepository::<Loan>::new(event_store.clone());
+    let loaded = repository.load(&loan_id).await.unwrap().unwrap();
+    assert_eq!(loaded.version, 1);
+    assert!(matches!(loaded.aggregate, Loan::Active(_)));
+
+    // 読み込み後に別の操作で延長された
+    extend_loan(
+        &deps,
+        ExtendLoan {
+            loan_id,
+            extended_at: Utc::now(),
+        },
+    )
+    .await
+    .unwrap();
+
+    // 古いバージョンでの保存は拒否される
+    let Loan::Active(active) = loaded.aggregate else {
+        unreachable!()
+    };
+    let (_, event) =
+        rusty_library_ddd::domain::loan::return_book(Loan::Active(active), Utc::now()).unwrap();
+    let result = repository
+        .save_with_expected_version(&loan_id, loaded.version, vec![LoanEvent::from(event)])
+        .await;
+    assert!(matches!(
+        result,
+        Err(EventStoreError::VersionConflict {
+            expected: 1,
+            actual: 2,
+            ..
+        })
+    ));
+    assert_eq!(
+        event_store
+            .load(loan_id.value(), "Loan")
+            .await
+            .unwrap()
+            .len(),
+        2
+    );
+}
+
 #[tokio::test]
 async fn test_return_book_success() {
     // Arrange: 貸出を事前に作成
@@ -332,10 +846,21 @@ async fn test_return_book_success() {
     book_service.add_available_book(book_id);
 
     let deps = ServiceDependencies {
+        tenant_id: TenantId::DEFAULT,
+
+        policy: CirculationPolicy::default(),
         event_store: event_store.clone(),
         loan_read_model: loan_read_model.clone(),
+        unit_of_work: None,
         member_service,
         book_service,
+        staff_service: Arc::new(StaffService::new()),
+        notification_service: Arc::new(NotificationService::new()),
+        notification_log: Arc::new(NotificationLog::new()),
+        notification_preferences: Arc::new(NotificationPreferenceStore::new()),
+        deferred_notices: Arc::new(DeferredNoticeQueue::new()),
+        event_bus: Arc::new(InMemoryEventBus::synchronous()),
+        idempotency_keys: Arc::new(IdempotencyStore::new()),
     };
 
     // 貸出作成
@@ -359,7 +884,7 @@ async fn test_return_book_success() {
     assert!(result.is_ok());
 
     // イベントが追加されたことを確認
-    let events = event_store.load(loan_id.value()).await.unwrap();
+    let events = event_store.load(loan_id.value(), "Loan").await.unwrap();
     assert_eq!(events.len(), 2); // BookLoaned + BookReturned
     assert!(matches!(events[1], DomainEvent::BookReturned(_)));
 
@@ -385,10 +910,21 @@ async fn test_detect_overdue_loans() {
     book_service.add_available_book(book_id);
 
     let deps = ServiceDependencies {
+        tenant_id: TenantId::DEFAULT,
+
+        policy: CirculationPolicy::default(),
         event_store: event_store.clone(),
         loan_read_model: loan_read_model.clone(),
+        unit_of_work: None,
         member_service,
         book_service,
+        staff_service: Arc::new(StaffService::new()),
+        notification_service: Arc::new(NotificationService::new()),
+        notification_log: Arc::new(NotificationLog::new()),
+        notification_preferences: Arc::new(NotificationPreferenceStore::new()),
+        deferred_notices: Arc::new(DeferredNoticeQueue::new()),
+        event_bus: Arc::new(InMemoryEventBus::synchronous()),
+        idempotency_keys: Arc::new(IdempotencyStore::new()),
     };
 
     // 過去の日付で貸出作成（延滞させる）
@@ -402,14 +938,21 @@ async fn test_detect_overdue_loans() {
     let loan_id = loan_book(&deps, loan_cmd).await.unwrap();
 
     // Act: 延滞検出バッチ実行（純粋な関数呼び出し）
-    let result = detect_overdue_loans(&deps).await;
+    let as_of = Utc::now();
+    let result = detect_overdue_loans(&deps, as_of).await;
 
     // Assert: 1件検出されたことを確認
     assert!(result.is_ok());
-    assert_eq!(result.unwrap(), 1);
+    let report = result.unwrap();
+    assert_eq!(report.as_of, as_of);
+    assert_eq!(report.detected.len(), 1);
+    assert_eq!(report.detected[0].loan_id, loan_id);
+    assert_eq!(report.detected[0].member_id, member_id);
+    assert!(report.skipped.is_empty());
+    assert!(report.failed.is_empty());
 
     // LoanBecameOverdueイベントが追加されたことを確認
-    let events = event_store.load(loan_id.value()).await.unwrap();
+    let events = event_store.load(loan_id.value(), "Loan").await.unwrap();
     assert_eq!(events.len(), 2); // BookLoaned + LoanBecameOverdue
     assert!(matches!(events[1], DomainEvent::LoanBecameOverdue(_)));
 
@@ -418,3 +961,628 @@ async fn test_detect_overdue_loans() {
     assert!(loan_view.is_some());
     assert_eq!(loan_view.unwrap().status, LoanStatus::Overdue);
 }
+
+#[tokio::test]
+async fn test_detect_overdue_loans_isolates_failures() {
+    // Arrange: 延滞した貸出3件（1件は書き込みに失敗、1件はRead Modelにだけ残っている）
+    let event_store = Arc::new(InMemoryEventStore::new());
+    let loan_read_model = Arc::new(InMemoryLoanReadModel::new());
+    let member_service = Arc::new(MemberService::new());
+    let book_service = Arc::new(BookService::new());
+
+    let member_id = MemberId::new();
+    member_service.add_member(member_id);
+
+    let deps = ServiceDependencies {
+        tenant_id: TenantId::DEFAULT,
+
+        policy: CirculationPolicy::default(),
+        event_store: event_store.clone(),
+        loan_read_model: loan_read_model.clone(),
+        unit_of_work: None,
+        member_service,
+        book_service: book_service.clone(),
+        staff_service: Arc::new(StaffService::new()),
+        notification_service: Arc::new(NotificationService::new()),
+        notification_log: Arc::new(NotificationLog::new()),
+        notification_preferences: Arc::new(NotificationPreferenceStore::new()),
+        deferred_notices: Arc::new(DeferredNoticeQueue::new()),
+        event_bus: Arc::new(InMemoryEventBus::synchronous()),
+        idempotency_keys: Arc::new(IdempotencyStore::new()),
+    };
+
+    let loaned_at = Utc::now() - chrono::Duration::days(30);
+    let mut loan_ids = Vec::new();
+    for _ in 0..2 {
+        let book_id = BookId::new();
+        book_service.add_available_book(book_id);
+        let loan_cmd = LoanBook {
+            book_id,
+            member_id,
+            loaned_at,
+            staff_id: StaffId::new(),
+        };
+        loan_ids.push(loan_book(&deps, loan_cmd).await.unwrap());
+    }
+    let (detected_id, failing_id) = (loan_ids[0], loan_ids[1]);
+    event_store.make_unavailable(failing_id.value());
+
+    let mut orphan = loan_read_model
+        .get_by_id(detected_id)
+        .await
+        .unwrap()
+        .unwrap();
+    orphan.loan_id = LoanId::new();
+    loan_read_model.save(orphan.clone()).await.unwrap();
+
+    // Act
+    let as_of = Utc::now();
+    let report = detect_overdue_loans(&deps, as_of).await.unwrap();
+
+    // Assert: 1件の失敗で他の候補の処理は止まらない
+    assert_eq!(report.candidates(), 3);
+    assert_eq!(report.detected.len(), 1);
+    assert_eq!(report.detected[0].loan_id, detected_id);
+
+    assert_eq!(report.skipped.len(), 1);
+    assert_eq!(report.skipped[0].loan_id, orphan.loan_id);
+    assert_eq!(report.skipped[0].reason, OverdueSkipReason::NoEvents);
+
+    assert_eq!(report.failed.len(), 1);
+    assert_eq!(report.failed[0].loan_id, failing_id);
+    assert!(report.failed[0].retryable);
+    assert!(report.failed[0].error.contains("connection reset"));
+
+    // 失敗した貸出は延滞になっていない（次回の検出で改めて処理される）
+    let failed_view = loan_read_model
+        .get_by_id(failing_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(failed_view.status, LoanStatus::Active);
+
+    // 延滞になった貸出は次回の検出の候補にならない
+    let report = detect_overdue_loans(&deps, as_of).await.unwrap();
+    assert!(report.detected.is_empty());
+    assert_eq!(report.failed.len(), 1);
+}
+
+/// 受け取ったイベントを記録するハンドラー（返却には失敗する）
+struct ReturnWatcher {
+    seen: Mutex<Vec<(LoanId, u32, &'static str)>>,
+}
+
+#[async_trait::async_trait]
+impl EventHandler for ReturnWatcher {
+    fn name(&self) -> &str {
+        "return-watcher"
+    }
+
+    async fn handle(&self, event: &PublishedEvent) -> HandlerResult {
+        self.seen.lock().unwrap().push((
+            LoanId::from_uuid(event.aggregate_id),
+            event.aggregate_version,
+            event.event.event_type(),
+        ));
+        match event.event {
+            DomainEvent::BookReturned(_) => Err("reservation context is unavailable".into()),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_remind_due_soon_loans_once_per_due_date() {
+    // Arrange: 2日後が返却期限の貸出と、14日後が返却期限の貸出
+    let event_store = Arc::new(InMemoryEventStore::new());
+    let loan_read_model = Arc::new(InMemoryLoanReadModel::new());
+    let member_service = Arc::new(MemberService::new());
+    let book_service = Arc::new(BookService::new());
+
+    let member_id = MemberId::new();
+    member_service.add_member(member_id);
+
+    let deps = ServiceDependencies {
+        tenant_id: TenantId::DEFAULT,
+
+        policy: CirculationPolicy::default(),
+        event_store: event_store.clone(),
+        loan_read_model: loan_read_model.clone(),
+        unit_of_work: None,
+        member_service,
+        book_service: book_service.clone(),
+        staff_service: Arc::new(StaffService::new()),
+        notification_service: Arc::new(NotificationService::new()),
+        notification_log: Arc::new(NotificationLog::new()),
+        notification_preferences: Arc::new(NotificationPreferenceStore::new()),
+        deferred_notices: Arc::new(DeferredNoticeQueue::new()),
+        event_bus: Arc::new(InMemoryEventBus::synchronous()),
+        idempotency_keys: Arc::new(IdempotencyStore::new()),
+    };
+
+    let now = Utc::now();
+    let mut loan_ids = Vec::new();
+    for loaned_at in [now - chrono::Duration::days(12), now] {
+        let book_id = BookId::new();
+        book_service.add_available_book(book_id);
+        let loan_cmd = LoanBook {
+            book_id,
+            member_id,
+            loaned_at,
+            staff_id: StaffId::new(),
+        };
+        loan_ids.push(loan_book(&deps, loan_cmd).await.unwrap());
+    }
+    let within = chrono::Duration::days(3);
+
+    // Act & Assert: 返却期限まで3日以内の貸出だけに知らせる
+    let report = remind_due_soon_loans(&deps, now, within).await.unwrap();
+    assert_eq!(report.reminded.len(), 1);
+    assert_eq!(report.reminded[0].loan_id, loan_ids[0]);
+    assert_eq!(report.reminded[0].member_id, member_id);
+    assert!(report.failed.is_empty());
+
+    let events = event_store.load(loan_ids[0].value(), "Loan").await.unwrap();
+    assert!(matches!(
+        events.last(),
+        Some(DomainEvent::LoanDueSoonReminded(e)) if e.due_date == report.reminded[0].due_date
+    ));
+
+    // 同じ返却期限には二度知らせない
+    let report = remind_due_soon_loans(&deps, now + chrono::Duration::hours(1), within)
+        .await
+        .unwrap();
+    assert!(report.reminded.is_empty());
+    assert_eq!(report.skipped, 1);
+
+    // 延長された貸出には新しい返却期限について改めて知らせる
+    extend_loan(
+        &deps,
+        ExtendLoan {
+            loan_id: loan_ids[0],
+            extended_at: now,
+        },
+    )
+    .await
+    .unwrap();
+    let new_due_date = loan_read_model
+        .get_by_id(loan_ids[0])
+        .await
+        .unwrap()
+        .unwrap()
+        .due_date;
+    let before_new_due = new_due_date - chrono::Duration::days(2);
+    let report = remind_due_soon_loans(&deps, before_new_due, within)
+        .await
+        .unwrap();
+    assert!(
+        report
+            .reminded
+            .iter()
+            .any(|r| r.loan_id == loan_ids[0] && r.due_date == new_due_date)
+    );
+}
+
+#[tokio::test]
+async fn test_advance_overdue_notices_until_declared_lost() {
+    // Arrange: 返却期限を16日過ぎて延滞になった貸出
+    let event_store = Arc::new(InMemoryEventStore::new());
+    let loan_read_model = Arc::new(InMemoryLoanReadModel::new());
+    let member_service = Arc::new(MemberService::new());
+    let book_service = Arc::new(BookService::new());
+
+    let member_id = MemberId::new();
+    member_service.add_member(member_id);
+    let book_id = BookId::new();
+    book_service.add_available_book(book_id);
+
+    let deps = ServiceDependencies {
+        tenant_id: TenantId::DEFAULT,
+
+        policy: CirculationPolicy::default(),
+        event_store: event_store.clone(),
+        loan_read_model: loan_read_model.clone(),
+        unit_of_work: None,
+        member_service,
+        book_service,
+        staff_service: Arc::new(StaffService::new()),
+        notification_service: Arc::new(NotificationService::new()),
+        notification_log: Arc::new(NotificationLog::new()),
+        notification_preferences: Arc::new(NotificationPreferenceStore::new()),
+        deferred_notices: Arc::new(DeferredNoticeQueue::new()),
+        event_bus: Arc::new(InMemoryEventBus::synchronous()),
+        idempotency_keys: Arc::new(IdempotencyStore::new()),
+    };
+
+    let now = Utc::now();
+    let loan_cmd = LoanBook {
+        book_id,
+        member_id,
+        loaned_at: now - chrono::Duration::days(30),
+        staff_id: StaffId::new(),
+    };
+    let loan_id = loan_book(&deps, loan_cmd).await.unwrap();
+    detect_overdue_loans(&deps, now).await.unwrap();
+    let due_date = loan_read_model
+        .get_by_id(loan_id)
+        .await
+        .unwrap()
+        .unwrap()
+        .due_date;
+
+    // Act & Assert: 督促が止まっていた場合は最も進んだ段階だけを送る
+    let report = advance_overdue_notices(&deps, now).await.unwrap();
+    assert_eq!(report.notices.len(), 1);
+    assert_eq!(report.notices[0].loan_id, loan_id);
+    assert_eq!(report.notices[0].member_id, member_id);
+    assert_eq!(report.notices[0].level, OverdueNoticeLevel::Second);
+    assert!(report.failed.is_empty());
+
+    // 同じ段階の督促は二度送らない
+    let report = advance_overdue_notices(&deps, now + chrono::Duration::hours(1))
+        .await
+        .unwrap();
+    assert!(report.notices.is_empty());
+    assert_eq!(report.skipped, 1);
+
+    // 延滞30日で最終督促、延滞60日で紛失
+    let report = advance_overdue_notices(&deps, due_date + chrono::Duration::days(30))
+        .await
+        .unwrap();
+    assert_eq!(report.notices[0].level, OverdueNoticeLevel::Final);
+
+    let declared_at = due_date + chrono::Duration::days(60);
+    let report = advance_overdue_notices(&deps, declared_at).await.unwrap();
+    assert!(report.notices.is_empty());
+    assert_eq!(report.declared_lost, vec![loan_id]);
+
+    let view = loan_read_model.get_by_id(loan_id).await.unwrap().unwrap();
+    assert_eq!(view.status, LoanStatus::Lost);
+    let levels: Vec<_> = event_store
+        .load(loan_id.value(), "Loan")
+        .await
+        .unwrap()
+        .into_iter()
+        .filter_map(|event| match event {
+            DomainEvent::OverdueNoticeSent(e) => Some(e.level),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        levels,
+        vec![OverdueNoticeLevel::Second, OverdueNoticeLevel::Final]
+    );
+
+    // Read Modelの反映が遅れていても、紛失した貸出は延滞検出で紛失として見送る
+    let mut stale = view.clone();
+    stale.status = LoanStatus::Active;
+    loan_read_model.save(stale).await.unwrap();
+    let report = detect_overdue_loans(&deps, declared_at).await.unwrap();
+    assert_eq!(report.skipped.len(), 1);
+    assert_eq!(report.skipped[0].reason, OverdueSkipReason::Lost);
+    loan_read_model.save(view).await.unwrap();
+
+    // 紛失した貸出は督促の対象から外れ、見つかれば返却できる
+    let report = advance_overdue_notices(&deps, declared_at + chrono::Duration::days(1))
+        .await
+        .unwrap();
+    assert!(report.notices.is_empty() && report.declared_lost.is_empty());
+
+    return_book(
+        &deps,
+        ReturnBook {
+            loan_id,
+            returned_at: declared_at + chrono::Duration::days(2),
+        },
+    )
+    .await
+    .unwrap();
+    let view = loan_read_model.get_by_id(loan_id).await.unwrap().unwrap();
+    assert_eq!(view.status, LoanStatus::Returned);
+}
+
+#[tokio::test]
+async fn test_committed_events_are_published() {
+    // Arrange: 発行の直後に結果を確認できる同期モードのイベントバス
+    let event_bus = Arc::new(InMemoryEventBus::synchronous());
+    let watcher = Arc::new(ReturnWatcher {
+        seen: Mutex::new(Vec::new()),
+    });
+    event_bus.subscribe(watcher.clone());
+
+    let member_service = Arc::new(MemberService::new());
+    let book_service = Arc::new(BookService::new());
+    let member_id = MemberId::new();
+    let book_id = BookId::new();
+    member_service.add_member(member_id);
+    book_service.add_available_book(book_id);
+
+    let deps = ServiceDependencies {
+        tenant_id: TenantId::DEFAULT,
+
+        policy: CirculationPolicy::default(),
+        event_store: Arc::new(InMemoryEventStore::new()),
+        loan_read_model: Arc::new(InMemoryLoanReadModel::new()),
+        unit_of_work: None,
+        member_service,
+        book_service,
+        staff_service: Arc::new(StaffService::new()),
+        notification_service: Arc::new(NotificationService::new()),
+        notification_log: Arc::new(NotificationLog::new()),
+        notification_preferences: Arc::new(NotificationPreferenceStore::new()),
+        deferred_notices: Arc::new(DeferredNoticeQueue::new()),
+        event_bus,
+        idempotency_keys: Arc::new(IdempotencyStore::new()),
+    };
+
+    // Act
+    let loan_id = loan_book(
+        &deps,
+        LoanBook {
+            book_id,
+            member_id,
+            loaned_at: Utc::now(),
+            staff_id: StaffId::new(),
+        },
+    )
+    .await
+    .unwrap();
+    let result = return_book(
+        &deps,
+        ReturnBook {
+            loan_id,
+            returned_at: Utc::now(),
+        },
+    )
+    .await;
+
+    // Assert: コミットされたイベントがバージョン付きで配信され、
+    // ハンドラーの失敗はコマンドの結果に影響しない
+    assert!(result.is_ok());
+    assert_eq!(
+        *watcher.seen.lock().unwrap(),
+        vec![(loan_id, 1, "BookLoaned"), (loan_id, 2, "BookReturned")]
+    );
+
+    // 保存されなかったイベントは発行されない
+    let result = return_book(
+        &deps,
+        ReturnBook {
+            loan_id,
+            returned_at: Utc::now(),
+        },
+    )
+    .await;
+    assert!(result.is_err());
+    assert_eq!(watcher.seen.lock().unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn test_import_legacy_loans() {
+    // Arrange: カード番号・バーコードを登録
+    let event_store = Arc::new(InMemoryEventStore::new());
+    let loan_read_model = Arc::new(InMemoryLoanReadModel::new());
+    let member_service = Arc::new(MemberService::new());
+    let book_service = Arc::new(BookService::new());
+
+    let member_id = MemberId::new();
+    let book_id = BookId::new();
+    member_service.add_card_number("M-0001", member_id);
+    book_service.add_barcode("B-0001", book_id);
+
+    let deps = ServiceDependencies {
+        tenant_id: TenantId::DEFAULT,
+        policy: CirculationPolicy::default(),
+        event_store: event_store.clone(),
+        loan_read_model: loan_read_model.clone(),
+        unit_of_work: None,
+        member_service,
+        book_service,
+        staff_service: Arc::new(StaffService::new()),
+        notification_service: Arc::new(NotificationService::new()),
+        notification_log: Arc::new(NotificationLog::new()),
+        notification_preferences: Arc::new(NotificationPreferenceStore::new()),
+        deferred_notices: Arc::new(DeferredNoticeQueue::new()),
+        event_bus: Arc::new(InMemoryEventBus::synchronous()),
+        idempotency_keys: Arc::new(IdempotencyStore::new()),
+    };
+
+    let csv = "\
+item_barcode,member_card_number,loan_date,due_date,return_date,renewals
+B-0001,M-0001,2024-04-01,2024-04-29,2024-05-10,1
+B-0001,M-0001,2024-05-20,2024-06-03,,0
+B-0001,M-9999,2024-04-01,2024-04-15,,0
+B-0001,M-0001,2024-04-01,2024-04-15,,3
+";
+    let options = LegacyImportOptions {
+        imported_by: StaffId::new(),
+        as_of: Utc::now(),
+        dry_run: true,
+    };
+
+    // Act: dry-runでは何も書き込まない
+    let report = import_legacy_loans(&deps, csv.as_bytes(), options)
+        .await
+        .unwrap();
+    assert_eq!(report.total_rows, 4);
+    assert_eq!(report.imported_loans, 2);
+    assert!(event_store.events.lock().unwrap().is_empty());
+
+    // Act: 本実行
+    let report = import_legacy_loans(
+        &deps,
+        csv.as_bytes(),
+        LegacyImportOptions {
+            dry_run: false,
+            ..options
+        },
+    )
+    .await
+    .unwrap();
+
+    // Assert: 2件取り込み（延長・延滞・返却済み、未返却の延滞中）、2件リジェクト
+    assert_eq!(report.imported_loans, 2);
+    assert_eq!(report.event_count, 4 + 2);
+    assert_eq!(report.rejected.len(), 2);
+    assert_eq!(report.rejected[0].line, 4);
+    assert_eq!(
+        report.rejected[0].reason,
+        RejectReason::UnknownMember("M-9999".to_string())
+    );
+    assert_eq!(
+        report.rejected[1].reason,
+        RejectReason::RenewalLimitExceeded(3)
+    );
+
+    let loans = loan_read_model
+        .find_by_member_id(member_id, None)
+        .await
+        .unwrap();
+    assert_eq!(loans.len(), 2);
+    let returned = loans
+        .iter()
+        .find(|l| l.status == LoanStatus::Returned)
+        .unwrap();
+    assert_eq!(returned.extension_count, 1);
+    assert_eq!(returned.loaned_at.to_rfc3339(), "2024-04-01T00:00:00+00:00");
+    assert!(loans.iter().any(|l| l.status == LoanStatus::Overdue));
+
+    // 元の日時のイベントが保存されている
+    let events = event_store
+        .load(returned.loan_id.value(), "Loan")
+        .await
+        .unwrap();
+    assert_eq!(events.len(), 4);
+    assert!(matches!(events[3], DomainEvent::BookReturned(_)));
+}
+
+#[tokio::test]
+async fn test_reading_history_retention_respects_opt_in() {
+    // Arrange: 保持期間30日の館で、60日前に返却された貸出を持つ会員2人
+    let event_store = Arc::new(InMemoryEventStore::new());
+    let loan_read_model = Arc::new(InMemoryLoanReadModel::new());
+    let member_service = Arc::new(MemberService::new());
+    let book_service = Arc::new(BookService::new());
+
+    let member_id = MemberId::new();
+    let opted_in_member = MemberId::new();
+    member_service.add_member(member_id);
+    member_service.add_member(opted_in_member);
+
+    let deps = ServiceDependencies {
+        tenant_id: TenantId::DEFAULT,
+        policy: CirculationPolicy {
+            history_retention_days: Some(30),
+            ..CirculationPolicy::default()
+        },
+        event_store: event_store.clone(),
+        loan_read_model: loan_read_model.clone(),
+        unit_of_work: None,
+        member_service,
+        book_service: book_service.clone(),
+        staff_service: Arc::new(StaffService::new()),
+        notification_service: Arc::new(NotificationService::new()),
+        notification_log: Arc::new(NotificationLog::new()),
+        notification_preferences: Arc::new(NotificationPreferenceStore::new()),
+        deferred_notices: Arc::new(DeferredNoticeQueue::new()),
+        event_bus: Arc::new(InMemoryEventBus::synchronous()),
+        idempotency_keys: Arc::new(IdempotencyStore::new()),
+    };
+
+    let loaned_at = Utc::now() - chrono::Duration::days(70);
+    let mut loan_ids = Vec::new();
+    for member in [member_id, opted_in_member] {
+        let book_id = BookId::new();
+        book_service.add_available_book(book_id);
+        let loan_id = loan_book(
+            &deps,
+            LoanBook {
+                book_id,
+                member_id: member,
+                loaned_at,
+                staff_id: StaffId::new(),
+            },
+        )
+        .await
+        .unwrap();
+        return_book(
+            &deps,
+            ReturnBook {
+                loan_id,
+                returned_at: loaned_at + chrono::Duration::days(10),
+            },
+        )
+        .await
+        .unwrap();
+        loan_ids.push(loan_id);
+    }
+
+    // 保持の選択はイベントとして記録される
+    let cmd = SetReadingHistoryPreference {
+        member_id: opted_in_member,
+        keep_history: true,
+        changed_at: Utc::now(),
+    };
+    set_reading_history_preference(&deps, cmd.clone())
+        .await
+        .unwrap();
+    // 会員IDは集約IDとして使われない
+    assert!(
+        event_store
+            .load(opted_in_member.value(), "Member")
+            .await
+            .unwrap()
+            .is_empty()
+    );
+    let events: Vec<_> = event_store
+        .events
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|((aggregate_type, _), _)| aggregate_type == "Member")
+        .flat_map(|(_, events)| events.clone())
+        .collect();
+    assert!(matches!(
+        events.as_slice(),
+        [DomainEvent::ReadingHistoryPreferenceChanged(e)]
+            if e.member_id == opted_in_member && e.keep_history
+    ));
+
+    // 存在しない会員は設定できない
+    let result = set_reading_history_preference(
+        &deps,
+        SetReadingHistoryPreference {
+            member_id: MemberId::new(),
+            ..cmd
+        },
+    )
+    .await;
+    assert!(matches!(result, Err(LoanApplicationError::MemberNotFound)));
+
+    // Act: 匿名化バッチ実行
+    let anonymised = anonymise_loan_history(&deps, Utc::now()).await.unwrap();
+
+    // Assert: 保持を選択していない会員の貸出のみ匿名化される
+    assert_eq!(anonymised, 1);
+    let loan_view = loan_read_model
+        .get_by_id(loan_ids[0])
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(loan_view.member_id.is_anonymised());
+    assert!(
+        loan_read_model
+            .find_by_member_id(member_id, None)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+    assert_eq!(
+        loan_read_model
+            .find_by_member_id(opted_in_member, None)
+            .await
+            .unwrap()
+            .len(),
+        1
+    );
+}
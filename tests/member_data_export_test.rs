@@ -0,0 +1,223 @@
+mod common;
+
+use chrono::Utc;
+use rusty_library_ddd::adapters::in_memory::InMemoryEventBus;
+use rusty_library_ddd::adapters::mock::{
+    BookService, DeferredNoticeQueue, IdempotencyStore, MemberService, NotificationLog,
+    NotificationPreferenceStore, NotificationService, StaffService,
+};
+use rusty_library_ddd::adapters::postgres::{PostgresEventStore, PostgresLoanReadModel};
+use rusty_library_ddd::application::loan::{
+    ServiceDependencies, extend_loan, loan_book, return_book, set_reading_history_preference,
+};
+use rusty_library_ddd::application::privacy::{PrivacyError, export_member_data};
+use rusty_library_ddd::domain::CirculationPolicy;
+use rusty_library_ddd::domain::commands::{
+    ExportMemberData, ExtendLoan, LoanBook, ReturnBook, SetReadingHistoryPreference,
+};
+use rusty_library_ddd::domain::events::DomainEvent;
+use rusty_library_ddd::domain::notification_preferences::NotificationPreferences;
+use rusty_library_ddd::domain::value_objects::{BookId, LoanId, MemberId, StaffId};
+use rusty_library_ddd::ports::deferred_notices::DeferredNotice;
+use rusty_library_ddd::ports::notification_gateway::{Notice, NoticeBody};
+use rusty_library_ddd::ports::notification_log::{DeliveredNotice, NoticeKind};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// 会員に書籍を貸し出し、貸出IDを返す
+async fn loan_to(
+    deps: &ServiceDependencies,
+    book_service: &BookService,
+    member_id: MemberId,
+) -> LoanId {
+    let book_id = BookId::new();
+    book_service.add_available_book(book_id);
+    loan_book(
+        deps,
+        LoanBook {
+            book_id,
+            member_id,
+            loaned_at: Utc::now(),
+            staff_id: StaffId::new(),
+        },
+    )
+    .await
+    .unwrap()
+}
+
+#[tokio::test]
+async fn test_export_member_data_gathers_member_records() {
+    let pool = common::create_test_pool().await;
+    let tenant_id = common::insert_tenant(&pool).await;
+    let member_service = Arc::new(MemberService::new());
+    let book_service = Arc::new(BookService::new());
+    let deps = ServiceDependencies {
+        tenant_id,
+        policy: CirculationPolicy::default(),
+        event_store: Arc::new(PostgresEventStore::for_tenant(pool.clone(), tenant_id)),
+        loan_read_model: Arc::new(PostgresLoanReadModel::for_tenant(pool.clone(), tenant_id)),
+        unit_of_work: None,
+        member_service: member_service.clone(),
+        book_service: book_service.clone(),
+        staff_service: Arc::new(StaffService::new()),
+        notification_service: Arc::new(NotificationService::new()),
+        notification_log: Arc::new(NotificationLog::new()),
+        notification_preferences: Arc::new(NotificationPreferenceStore::new()),
+        deferred_notices: Arc::new(DeferredNoticeQueue::new()),
+        event_bus: Arc::new(InMemoryEventBus::synchronous()),
+        idempotency_keys: Arc::new(IdempotencyStore::new()),
+    };
+
+    let member_id = MemberId::new();
+    let other_member = MemberId::new();
+    member_service.add_member(member_id);
+    member_service.add_member(other_member);
+
+    // 延長・返却した貸出と貸出中の貸出、他の会員の貸出
+    let returned = loan_to(&deps, &book_service, member_id).await;
+    extend_loan(
+        &deps,
+        ExtendLoan {
+            loan_id: returned,
+            extended_at: Utc::now(),
+        },
+    )
+    .await
+    .unwrap();
+    return_book(
+        &deps,
+        ReturnBook {
+            loan_id: returned,
+            returned_at: Utc::now(),
+        },
+    )
+    .await
+    .unwrap();
+    let active = loan_to(&deps, &book_service, member_id).await;
+    let others = loan_to(&deps, &book_service, other_member).await;
+    set_reading_history_preference(
+        &deps,
+        SetReadingHistoryPreference {
+            member_id,
+            keep_history: true,
+            changed_at: Utc::now(),
+        },
+    )
+    .await
+    .unwrap();
+
+    let staff_id = StaffId::new();
+    let cmd = ExportMemberData {
+        member_id,
+        staff_id,
+        requested_at: Utc::now(),
+    };
+    let archive = export_member_data(&deps, cmd.clone()).await.unwrap();
+
+    // 会員の貸出の全イベント（会員IDを含まない延長も含む）と保持設定
+    assert_eq!(archive.events.len(), 3 + 1 + 1);
+    assert!(
+        archive
+            .events
+            .iter()
+            .all(|e| e.aggregate_id != others.value())
+    );
+    assert!(
+        archive
+            .events
+            .iter()
+            .any(|e| matches!(e.event, DomainEvent::LoanExtended(_)))
+    );
+    assert!(
+        archive
+            .events
+            .windows(2)
+            .all(|w| w[0].sequence_number < w[1].sequence_number)
+    );
+
+    let mut loan_ids: Vec<_> = archive.loans.iter().map(|l| l.loan_id).collect();
+    loan_ids.sort_by_key(|id| id.value());
+    let mut expected = vec![returned, active];
+    expected.sort_by_key(|id| id.value());
+    assert_eq!(loan_ids, expected);
+    assert!(archive.reading_history.keep_history);
+    assert!(archive.summary().contains("貸出: 2件"));
+    assert!(archive.notifications.preferences.is_none());
+    assert!(archive.notifications.delivered.is_empty());
+    assert!(archive.summary().contains("通知設定: 未設定（既定の設定）"));
+
+    // 通知設定と通知の記録（他の会員の通知は含まない）
+    deps.notification_preferences
+        .save(member_id, &NotificationPreferences::default(), Utc::now())
+        .await
+        .unwrap();
+    let delivered = DeliveredNotice {
+        dedup_key: format!("return_confirmation:{}", archive.events[0].event_id),
+        kind: NoticeKind::ReturnConfirmation,
+        event_id: archive.events[0].event_id,
+        delivered_at: Utc::now(),
+    };
+    deps.notification_log
+        .record_delivered(&delivered)
+        .await
+        .unwrap();
+    deps.notification_log
+        .record_delivered(&DeliveredNotice {
+            dedup_key: "return_confirmation:other".to_string(),
+            event_id: Uuid::new_v4(),
+            ..delivered.clone()
+        })
+        .await
+        .unwrap();
+    for (owner, dedup_key) in [(member_id, "pending:own"), (other_member, "pending:other")] {
+        deps.deferred_notices
+            .enqueue(&DeferredNotice {
+                member_id: owner,
+                notice: Notice {
+                    dedup_key: dedup_key.to_string(),
+                    book_title: "Export Test Book".to_string(),
+                    body: NoticeBody::ReturnConfirmation { was_overdue: false },
+                },
+                queued_at: Utc::now(),
+                deliver_after: Utc::now(),
+            })
+            .await
+            .unwrap();
+    }
+
+    // 写しの作成はイベントとして記録され、次回の写しに含まれる
+    let archive = export_member_data(&deps, cmd.clone()).await.unwrap();
+    let exported: Vec<_> = archive
+        .events
+        .iter()
+        .filter_map(|e| match &e.event {
+            DomainEvent::MemberDataExported(e) => Some(e),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(exported.len(), 1);
+    assert_eq!(exported[0].exported_by, staff_id);
+    assert_eq!(exported[0].loan_count, 2);
+    assert_eq!(
+        archive.notifications.preferences,
+        Some(NotificationPreferences::default())
+    );
+    assert_eq!(archive.notifications.delivered, vec![delivered]);
+    assert_eq!(archive.notifications.pending.len(), 1);
+    assert_eq!(
+        archive.notifications.pending[0].notice.dedup_key,
+        "pending:own"
+    );
+    assert!(archive.summary().contains("通知: 配信済み 1件、保留中 1件"));
+
+    // 存在しない会員
+    let result = export_member_data(
+        &deps,
+        ExportMemberData {
+            member_id: MemberId::new(),
+            ..cmd
+        },
+    )
+    .await;
+    assert!(matches!(result, Err(PrivacyError::MemberNotFound)));
+}
@@ -0,0 +1,210 @@
+mod common;
+
+use chrono::Utc;
+use rusty_library_ddd::adapters::in_memory::InMemoryEventBus;
+use rusty_library_ddd::adapters::mock::{
+    BookService, DeferredNoticeQueue, IdempotencyStore, MemberService, NotificationLog,
+    NotificationPreferenceStore, NotificationService, StaffService,
+};
+use rusty_library_ddd::adapters::postgres::{
+    PostgresEventAudit, PostgresEventStore, PostgresLoanReadModel, PostgresMemberKeyStore,
+};
+use rusty_library_ddd::application::loan::{
+    ServiceDependencies, loan_book, return_book, set_reading_history_preference,
+};
+use rusty_library_ddd::application::privacy::{PrivacyError, erase_member};
+use rusty_library_ddd::domain::commands::{LoanBook, ReturnBook, SetReadingHistoryPreference};
+use rusty_library_ddd::domain::events::DomainEvent;
+use rusty_library_ddd::domain::value_objects::{BookId, LoanId, MemberId, StaffId, TenantId};
+use rusty_library_ddd::domain::{CirculationPolicy, NotificationPreferences};
+use rusty_library_ddd::ports::EventAudit;
+use rusty_library_ddd::ports::deferred_notices::DeferredNotice;
+use rusty_library_ddd::ports::notification_gateway::{Notice, NoticeBody};
+use sqlx::PgPool;
+use std::sync::Arc;
+
+fn postgres_dependencies(
+    pool: &PgPool,
+    tenant_id: TenantId,
+    member_service: Arc<MemberService>,
+    book_service: Arc<BookService>,
+) -> ServiceDependencies {
+    ServiceDependencies {
+        tenant_id,
+        policy: CirculationPolicy::default(),
+        event_store: Arc::new(PostgresEventStore::for_tenant(pool.clone(), tenant_id)),
+        loan_read_model: Arc::new(PostgresLoanReadModel::for_tenant(pool.clone(), tenant_id)),
+        unit_of_work: None,
+        member_service,
+        book_service,
+        staff_service: Arc::new(StaffService::new()),
+        notification_service: Arc::new(NotificationService::new()),
+        notification_log: Arc::new(NotificationLog::new()),
+        notification_preferences: Arc::new(NotificationPreferenceStore::new()),
+        deferred_notices: Arc::new(DeferredNoticeQueue::new()),
+        event_bus: Arc::new(InMemoryEventBus::synchronous()),
+        idempotency_keys: Arc::new(IdempotencyStore::new()),
+    }
+}
+
+/// 会員に書籍を貸し出し、貸出IDを返す
+async fn loan_to(
+    deps: &ServiceDependencies,
+    book_service: &BookService,
+    member_id: MemberId,
+) -> LoanId {
+    let book_id = BookId::new();
+    book_service.add_available_book(book_id);
+    loan_book(
+        deps,
+        LoanBook {
+            book_id,
+            member_id,
+            loaned_at: Utc::now(),
+            staff_id: StaffId::new(),
+        },
+    )
+    .await
+    .unwrap()
+}
+
+fn member_of(event: &DomainEvent) -> Option<MemberId> {
+    match event {
+        DomainEvent::BookLoaned(e) => Some(e.member_id),
+        DomainEvent::BookReturned(e) => Some(e.member_id),
+        DomainEvent::LoanBecameOverdue(e) => Some(e.member_id),
+        DomainEvent::LoanDueSoonReminded(e) => Some(e.member_id),
+        DomainEvent::OverdueNoticeSent(e) => Some(e.member_id),
+        DomainEvent::LoanDeclaredLost(e) => Some(e.member_id),
+        DomainEvent::ReadingHistoryPreferenceChanged(e) => Some(e.member_id),
+        DomainEvent::MemberDataExported(e) => Some(e.member_id),
+        DomainEvent::LoanExtended(_) => None,
+    }
+}
+
+#[tokio::test]
+async fn test_erase_member_shreds_identity_and_keeps_chain_intact() {
+    let pool = common::create_test_pool().await;
+    let tenant_id = common::insert_tenant(&pool).await;
+    let member_service = Arc::new(MemberService::new());
+    let book_service = Arc::new(BookService::new());
+    let deps = postgres_dependencies(
+        &pool,
+        tenant_id,
+        member_service.clone(),
+        book_service.clone(),
+    );
+    let key_store = PostgresMemberKeyStore::for_tenant(pool.clone(), tenant_id);
+    let audit = PostgresEventAudit::for_tenant(pool.clone(), tenant_id);
+
+    let member_id = MemberId::new();
+    member_service.add_member(member_id);
+    let loan_id = loan_to(&deps, &book_service, member_id).await;
+    deps.notification_preferences
+        .save(member_id, &NotificationPreferences::default(), Utc::now())
+        .await
+        .unwrap();
+    deps.deferred_notices
+        .enqueue(&DeferredNotice {
+            member_id,
+            notice: Notice {
+                dedup_key: "pending:erasure".to_string(),
+                book_title: "Erasure Test Book".to_string(),
+                body: NoticeBody::ReturnConfirmation { was_overdue: false },
+            },
+            queued_at: Utc::now(),
+            deliver_after: Utc::now(),
+        })
+        .await
+        .unwrap();
+    set_reading_history_preference(
+        &deps,
+        SetReadingHistoryPreference {
+            member_id,
+            keep_history: true,
+            changed_at: Utc::now(),
+        },
+    )
+    .await
+    .unwrap();
+
+    // 保存されたイベントに会員IDは平文で残らない（集約IDとしても使われない）
+    let raw: Vec<String> = sqlx::query_scalar(
+        "SELECT aggregate_id::text || event_data::text FROM events WHERE tenant_id = $1",
+    )
+    .bind(tenant_id.value())
+    .fetch_all(&pool)
+    .await
+    .unwrap();
+    assert_eq!(raw.len(), 2);
+    assert!(
+        raw.iter()
+            .all(|data| !data.contains(&member_id.value().to_string()))
+    );
+
+    // 貸出中は削除できない
+    let result = erase_member(&deps, &key_store, member_id).await;
+    assert!(matches!(result, Err(PrivacyError::MemberHasOpenLoans)));
+
+    return_book(
+        &deps,
+        ReturnBook {
+            loan_id,
+            returned_at: Utc::now(),
+        },
+    )
+    .await
+    .unwrap();
+
+    let summary = erase_member(&deps, &key_store, member_id).await.unwrap();
+    assert!(summary.key_shredded);
+    assert_eq!(summary.anonymised_loans, 1);
+    assert_eq!(
+        deps.notification_preferences.get(member_id).await.unwrap(),
+        None
+    );
+    assert!(
+        deps.deferred_notices
+            .pending_for(member_id)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+
+    // イベントは残るが、会員は匿名化されて復元される
+    let events = deps
+        .event_store
+        .load(loan_id.value(), "Loan")
+        .await
+        .unwrap();
+    assert_eq!(events.len(), 2);
+    assert!(
+        events
+            .iter()
+            .filter_map(member_of)
+            .all(|m| m.is_anonymised())
+    );
+
+    // Read Modelからも会員との紐付けが消える
+    assert!(
+        deps.loan_read_model
+            .find_by_member_id(member_id, None)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+    let view = deps
+        .loan_read_model
+        .get_by_id(loan_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(view.member_id.is_anonymised());
+
+    // イベントを書き換えていないため、ハッシュチェーンは完全なまま
+    assert!(audit.verify_chain().await.unwrap().is_intact());
+
+    // 2回目は破棄する鍵がない
+    let summary = erase_member(&deps, &key_store, member_id).await.unwrap();
+    assert!(!summary.key_shredded);
+}
@@ -0,0 +1,694 @@
+mod common;
+
+use chrono::{DateTime, Duration, NaiveTime, TimeZone, Utc};
+use rusty_library_ddd::adapters::in_memory::InMemoryEventBus;
+use rusty_library_ddd::adapters::mock::{
+    BookService, MemberService, NotificationGateway, NotificationService, StaffService,
+};
+use rusty_library_ddd::adapters::postgres::{
+    PostgresDeferredNoticeQueue, PostgresEventStore, PostgresIdempotencyStore,
+    PostgresLoanReadModel, PostgresNotificationLog, PostgresNotificationPreferenceStore,
+};
+use rusty_library_ddd::application::loan::{
+    ServiceDependencies, advance_overdue_notices, detect_overdue_loans, extend_loan, loan_book,
+    remind_due_soon_loans, return_book,
+};
+use rusty_library_ddd::application::notification::{
+    NotificationRouter, NotificationSubscriber, dispatch_notifications, notice_dedup_key,
+};
+use rusty_library_ddd::domain::commands::{ExtendLoan, LoanBook, ReturnBook};
+use rusty_library_ddd::domain::value_objects::{BookId, LoanId, MemberId, StaffId, TenantId};
+use rusty_library_ddd::domain::{
+    CirculationPolicy, DigestMode, NotificationChannel, NotificationLanguage,
+    NotificationPreferences, QuietHours,
+};
+use rusty_library_ddd::ports::{
+    EventBus, Notice, NoticeBody, NoticeKind, NotificationPreferenceStore,
+};
+use sqlx::PgPool;
+use std::sync::Arc;
+
+fn postgres_dependencies(
+    pool: &PgPool,
+    tenant_id: TenantId,
+    member_service: Arc<MemberService>,
+    book_service: Arc<BookService>,
+    notification_service: Arc<dyn rusty_library_ddd::ports::NotificationService>,
+) -> ServiceDependencies {
+    ServiceDependencies {
+        tenant_id,
+        policy: CirculationPolicy::default(),
+        event_store: Arc::new(PostgresEventStore::for_tenant(pool.clone(), tenant_id)),
+        loan_read_model: Arc::new(PostgresLoanReadModel::for_tenant(pool.clone(), tenant_id)),
+        unit_of_work: None,
+        member_service,
+        book_service,
+        staff_service: Arc::new(StaffService::new()),
+        notification_service,
+        notification_log: Arc::new(PostgresNotificationLog::for_tenant(pool.clone(), tenant_id)),
+        notification_preferences: Arc::new(PostgresNotificationPreferenceStore::for_tenant(
+            pool.clone(),
+            tenant_id,
+        )),
+        deferred_notices: Arc::new(PostgresDeferredNoticeQueue::for_tenant(
+            pool.clone(),
+            tenant_id,
+        )),
+        event_bus: Arc::new(InMemoryEventBus::synchronous()),
+        idempotency_keys: Arc::new(PostgresIdempotencyStore::for_tenant(
+            pool.clone(),
+            tenant_id,
+        )),
+    }
+}
+
+/// 延滞して返却された貸出と、延長された貸出を作成する
+///
+/// 通知の対象は延滞・返却・延長の3件。
+async fn circulate(
+    deps: &ServiceDependencies,
+    book_service: &BookService,
+    member_id: MemberId,
+) -> (LoanId, LoanId) {
+    let loan = |loaned_at| {
+        let book_id = BookId::new();
+        book_service.add_available_book(book_id);
+        loan_book(
+            deps,
+            LoanBook {
+                book_id,
+                member_id,
+                loaned_at,
+                staff_id: StaffId::new(),
+            },
+        )
+    };
+
+    let returned = loan(Utc::now() - Duration::days(30)).await.unwrap();
+    let report = detect_overdue_loans(deps, Utc::now()).await.unwrap();
+    assert_eq!(report.detected.len(), 1);
+    return_book(
+        deps,
+        ReturnBook {
+            loan_id: returned,
+            returned_at: Utc::now(),
+        },
+    )
+    .await
+    .unwrap();
+
+    let extended = loan(Utc::now()).await.unwrap();
+    extend_loan(
+        deps,
+        ExtendLoan {
+            loan_id: extended,
+            extended_at: Utc::now(),
+        },
+    )
+    .await
+    .unwrap();
+
+    (returned, extended)
+}
+
+#[tokio::test]
+async fn test_each_notice_is_delivered_once() {
+    // Arrange
+    let pool = common::create_test_pool().await;
+    let tenant_id = common::insert_tenant(&pool).await;
+    let member_service = Arc::new(MemberService::new());
+    let book_service = Arc::new(BookService::new());
+    let notifications = Arc::new(NotificationService::new());
+    let deps = postgres_dependencies(
+        &pool,
+        tenant_id,
+        member_service.clone(),
+        book_service.clone(),
+        notifications.clone(),
+    );
+    let member_id = MemberId::new();
+    member_service.add_member(member_id);
+    let (returned, extended) = circulate(&deps, &book_service, member_id).await;
+
+    let from = Utc::now() - Duration::days(1);
+    let to = Utc::now() + Duration::minutes(1);
+
+    // Act
+    let report = dispatch_notifications(&deps, from, to).await.unwrap();
+
+    // Assert: 延滞・返却・延長の通知が1件ずつ、保存された順に配信される
+    assert_eq!(report.delivered, 3);
+    assert_eq!(report.already_delivered, 0);
+    assert!(report.failed.is_empty());
+
+    let sent = notifications.sent();
+    let keys: Vec<&str> = sent.iter().map(|n| n.dedup_key.as_str()).collect();
+    assert_eq!(
+        keys,
+        vec![
+            notice_dedup_key(NoticeKind::Overdue, returned.value(), 2),
+            notice_dedup_key(NoticeKind::ReturnConfirmation, returned.value(), 3),
+            notice_dedup_key(NoticeKind::ExtensionConfirmation, extended.value(), 2),
+        ]
+    );
+    assert!(sent.iter().all(|n| n.member_id == member_id));
+    assert!(sent.iter().all(|n| n.book_title == "Mock Book Title"));
+    assert_eq!(sent[1].body, "returned late");
+
+    // 同じ期間を再処理しても二度は送らない（別のインスタンスの配信記録でも同じ）
+    let restarted = postgres_dependencies(
+        &pool,
+        tenant_id,
+        member_service,
+        book_service,
+        notifications.clone(),
+    );
+    let report = dispatch_notifications(&restarted, from, to).await.unwrap();
+    assert_eq!(report.delivered, 0);
+    assert_eq!(report.already_delivered, 3);
+    assert_eq!(notifications.sent().len(), 3);
+
+    // 配信記録に会員IDは残らない
+    let recorded: Vec<String> = sqlx::query_scalar(
+        "SELECT dedup_key FROM notification_deliveries WHERE tenant_id = $1 ORDER BY dedup_key",
+    )
+    .bind(tenant_id.value())
+    .fetch_all(&pool)
+    .await
+    .unwrap();
+    assert_eq!(recorded.len(), 3);
+    assert!(
+        recorded
+            .iter()
+            .all(|key| !key.contains(&member_id.value().to_string()))
+    );
+}
+
+#[tokio::test]
+async fn test_notices_are_delivered_when_events_are_published() {
+    // Arrange: 通知のハンドラーを登録したイベントバス
+    let pool = common::create_test_pool().await;
+    let tenant_id = common::insert_tenant(&pool).await;
+    let member_service = Arc::new(MemberService::new());
+    let book_service = Arc::new(BookService::new());
+    let notifications = Arc::new(NotificationService::new());
+    let event_bus = Arc::new(InMemoryEventBus::synchronous());
+    let deps = ServiceDependencies {
+        event_bus: event_bus.clone(),
+        ..postgres_dependencies(
+            &pool,
+            tenant_id,
+            member_service.clone(),
+            book_service.clone(),
+            notifications.clone(),
+        )
+    };
+    event_bus.subscribe(Arc::new(NotificationSubscriber::new([deps.clone()])));
+    let member_id = MemberId::new();
+    member_service.add_member(member_id);
+
+    // Act: 配信ジョブを動かさずに貸出を進める
+    let (returned, extended) = circulate(&deps, &book_service, member_id).await;
+
+    // Assert: コミットの直後に通知され、配信記録には保存されたイベントのIDが残る
+    let keys: Vec<String> = notifications
+        .sent()
+        .into_iter()
+        .map(|n| n.dedup_key)
+        .collect();
+    assert_eq!(
+        keys,
+        vec![
+            notice_dedup_key(NoticeKind::Overdue, returned.value(), 2),
+            notice_dedup_key(NoticeKind::ReturnConfirmation, returned.value(), 3),
+            notice_dedup_key(NoticeKind::ExtensionConfirmation, extended.value(), 2),
+        ]
+    );
+    let from = Utc::now() - Duration::days(1);
+    let to = Utc::now() + Duration::minutes(1);
+    let mut event_ids = Vec::new();
+    for event_type in ["LoanBecameOverdue", "BookReturned", "LoanExtended"] {
+        let stored = deps
+            .event_store
+            .load_by_type(event_type, from, to)
+            .await
+            .unwrap();
+        event_ids.extend(stored.into_iter().map(|e| e.event_id));
+    }
+    let delivered = deps
+        .notification_log
+        .find_by_events(&event_ids)
+        .await
+        .unwrap();
+    assert_eq!(delivered.len(), 3);
+
+    // 配信ジョブは同じ通知を二度は送らない
+    let report = dispatch_notifications(&deps, from, to).await.unwrap();
+    assert_eq!(report.delivered, 0);
+    assert_eq!(report.already_delivered, 3);
+    assert_eq!(notifications.sent().len(), 3);
+}
+
+#[tokio::test]
+async fn test_undelivered_notices_are_retried() {
+    // Arrange: 通知の配信手段が止まっている
+    let pool = common::create_test_pool().await;
+    let tenant_id = common::insert_tenant(&pool).await;
+    let member_service = Arc::new(MemberService::new());
+    let book_service = Arc::new(BookService::new());
+    let notifications = Arc::new(NotificationService::new());
+    let deps = postgres_dependencies(
+        &pool,
+        tenant_id,
+        member_service.clone(),
+        book_service.clone(),
+        notifications.clone(),
+    );
+    let member_id = MemberId::new();
+    member_service.add_member(member_id);
+    circulate(&deps, &book_service, member_id).await;
+
+    let from = Utc::now() - Duration::days(1);
+    let to = Utc::now() + Duration::minutes(1);
+    notifications.set_unavailable(true);
+
+    // Act: 失敗したイベントは報告され、配信済みにならない
+    let report = dispatch_notifications(&deps, from, to).await.unwrap();
+    assert_eq!(report.delivered, 0);
+    assert_eq!(report.failed.len(), 3);
+    assert!(report.failed.iter().all(|f| f.retryable));
+    assert!(
+        report.failed[0]
+            .error
+            .contains("notification gateway is down")
+    );
+
+    // 復旧後の処理で配信される
+    notifications.set_unavailable(false);
+    let report = dispatch_notifications(&deps, from, to).await.unwrap();
+    assert_eq!(report.delivered, 3);
+    assert!(report.failed.is_empty());
+    assert_eq!(notifications.sent().len(), 3);
+}
+
+#[tokio::test]
+async fn test_due_soon_reminder_is_delivered_once_per_due_date() {
+    // Arrange: 2日後が返却期限の貸出
+    let pool = common::create_test_pool().await;
+    let tenant_id = common::insert_tenant(&pool).await;
+    let member_service = Arc::new(MemberService::new());
+    let book_service = Arc::new(BookService::new());
+    let notifications = Arc::new(NotificationService::new());
+    let deps = postgres_dependencies(
+        &pool,
+        tenant_id,
+        member_service.clone(),
+        book_service.clone(),
+        notifications.clone(),
+    );
+    let member_id = MemberId::new();
+    member_service.add_member(member_id);
+    let book_id = BookId::new();
+    book_service.add_available_book(book_id);
+    let loan_id = loan_book(
+        &deps,
+        LoanBook {
+            book_id,
+            member_id,
+            loaned_at: Utc::now() - Duration::days(12),
+            staff_id: StaffId::new(),
+        },
+    )
+    .await
+    .unwrap();
+
+    // Act: リマインダーのジョブが2回実行された後に通知を配信する
+    for _ in 0..2 {
+        remind_due_soon_loans(&deps, Utc::now(), Duration::days(3))
+            .await
+            .unwrap();
+    }
+    let from = Utc::now() - Duration::days(1);
+    let to = Utc::now() + Duration::minutes(1);
+    let report = dispatch_notifications(&deps, from, to).await.unwrap();
+
+    // Assert: リマインダーは1回だけ送られる
+    assert_eq!(report.delivered, 1);
+    assert!(report.failed.is_empty());
+    let sent = notifications.sent();
+    assert_eq!(sent.len(), 1);
+    assert_eq!(
+        sent[0].dedup_key,
+        notice_dedup_key(NoticeKind::DueSoonReminder, loan_id.value(), 2)
+    );
+    assert_eq!(sent[0].member_id, member_id);
+    assert!(sent[0].body.starts_with("due soon on "));
+}
+
+#[tokio::test]
+async fn test_overdue_notice_and_lost_declaration_are_delivered() {
+    // Arrange: 返却期限を61日過ぎた貸出
+    let pool = common::create_test_pool().await;
+    let tenant_id = common::insert_tenant(&pool).await;
+    let member_service = Arc::new(MemberService::new());
+    let book_service = Arc::new(BookService::new());
+    let notifications = Arc::new(NotificationService::new());
+    let deps = postgres_dependencies(
+        &pool,
+        tenant_id,
+        member_service.clone(),
+        book_service.clone(),
+        notifications.clone(),
+    );
+    let member_id = MemberId::new();
+    member_service.add_member(member_id);
+    let book_id = BookId::new();
+    book_service.add_available_book(book_id);
+    let loan_id = loan_book(
+        &deps,
+        LoanBook {
+            book_id,
+            member_id,
+            loaned_at: Utc::now() - Duration::days(75),
+            staff_id: StaffId::new(),
+        },
+    )
+    .await
+    .unwrap();
+
+    // Act: 延滞を検出し、督促のジョブが2回実行された後に通知を配信する
+    let now = Utc::now();
+    detect_overdue_loans(&deps, now).await.unwrap();
+    for _ in 0..2 {
+        advance_overdue_notices(&deps, now).await.unwrap();
+    }
+    let from = now - Duration::days(1);
+    let to = now + Duration::minutes(1);
+    let report = dispatch_notifications(&deps, from, to).await.unwrap();
+
+    // Assert: 延滞の通知、最終督促、紛失の通知が1回ずつ送られる
+    assert_eq!(report.delivered, 3);
+    assert!(report.failed.is_empty());
+    let sent = notifications.sent();
+    let notice = sent
+        .iter()
+        .find(|n| n.dedup_key == notice_dedup_key(NoticeKind::OverdueNotice, loan_id.value(), 3))
+        .expect("overdue notice");
+    assert!(notice.body.starts_with("overdue notice final due "));
+    let lost = sent
+        .iter()
+        .find(|n| n.dedup_key == notice_dedup_key(NoticeKind::LostDeclaration, loan_id.value(), 4))
+        .expect("lost declaration");
+    assert_eq!(lost.member_id, member_id);
+    assert!(lost.body.starts_with("declared lost "));
+}
+
+#[tokio::test]
+async fn test_first_overdue_notice_is_not_sent_after_the_overdue_notice() {
+    // Arrange: 返却期限を3日過ぎ、延滞の通知を配信済みの貸出
+    let pool = common::create_test_pool().await;
+    let tenant_id = common::insert_tenant(&pool).await;
+    let member_service = Arc::new(MemberService::new());
+    let book_service = Arc::new(BookService::new());
+    let notifications = Arc::new(NotificationService::new());
+    let deps = postgres_dependencies(
+        &pool,
+        tenant_id,
+        member_service.clone(),
+        book_service.clone(),
+        notifications.clone(),
+    );
+    let member_id = MemberId::new();
+    member_service.add_member(member_id);
+    let book_id = BookId::new();
+    book_service.add_available_book(book_id);
+    let loan_id = loan_book(
+        &deps,
+        LoanBook {
+            book_id,
+            member_id,
+            loaned_at: Utc::now() - Duration::days(17),
+            staff_id: StaffId::new(),
+        },
+    )
+    .await
+    .unwrap();
+    let now = Utc::now();
+    let from = now - Duration::days(1);
+    let to = now + Duration::minutes(1);
+    detect_overdue_loans(&deps, now).await.unwrap();
+    let report = dispatch_notifications(&deps, from, to).await.unwrap();
+    assert_eq!(report.delivered, 1);
+
+    // Act: 1回目の督促を記録して通知を配信する
+    let report = advance_overdue_notices(&deps, now).await.unwrap();
+    assert_eq!(report.notices.len(), 1);
+    let report = dispatch_notifications(&deps, from, to).await.unwrap();
+
+    // Assert: 1回目の督促は延滞の通知と重ならない
+    assert_eq!(report.delivered, 0);
+    assert_eq!(report.already_delivered, 2);
+    let keys: Vec<String> = notifications
+        .sent()
+        .into_iter()
+        .map(|n| n.dedup_key)
+        .collect();
+    assert_eq!(
+        keys,
+        vec![notice_dedup_key(NoticeKind::Overdue, loan_id.value(), 2)]
+    );
+}
+
+/// 会員の通知設定に従って通知を送るルーター（通知設定と保留中の通知はPostgreSQL）
+fn postgres_router(
+    pool: &PgPool,
+    tenant_id: TenantId,
+    gateway: Arc<NotificationGateway>,
+) -> Arc<NotificationRouter> {
+    Arc::new(NotificationRouter::new(
+        Arc::new(PostgresNotificationPreferenceStore::for_tenant(
+            pool.clone(),
+            tenant_id,
+        )),
+        Arc::new(PostgresDeferredNoticeQueue::for_tenant(
+            pool.clone(),
+            tenant_id,
+        )),
+        gateway,
+    ))
+}
+
+/// 日本標準時の2025-01-15の時刻
+fn jst(hour: u32, minute: u32) -> DateTime<Utc> {
+    chrono::FixedOffset::east_opt(9 * 3600)
+        .unwrap()
+        .with_ymd_and_hms(2025, 1, 15, hour, minute, 0)
+        .unwrap()
+        .with_timezone(&Utc)
+}
+
+fn overdue_notice(dedup_key: &str) -> Notice {
+    Notice {
+        dedup_key: dedup_key.to_string(),
+        book_title: "Mock Book Title".to_string(),
+        body: NoticeBody::Overdue {
+            due_date: jst(0, 0),
+        },
+    }
+}
+
+#[tokio::test]
+async fn test_overdue_notices_are_batched_into_a_daily_digest() {
+    // Arrange: 日次ダイジェストを選んだ会員が5冊を延滞している
+    let pool = common::create_test_pool().await;
+    let tenant_id = common::insert_tenant(&pool).await;
+    let member_service = Arc::new(MemberService::new());
+    let book_service = Arc::new(BookService::new());
+    let gateway = Arc::new(NotificationGateway::new());
+    let router = postgres_router(&pool, tenant_id, gateway.clone());
+    let deps = postgres_dependencies(
+        &pool,
+        tenant_id,
+        member_service.clone(),
+        book_service.clone(),
+        router.clone(),
+    );
+    let member_id = MemberId::new();
+    member_service.add_member(member_id);
+    let preferences = NotificationPreferences {
+        language: NotificationLanguage::En,
+        digest: DigestMode::Daily,
+        ..NotificationPreferences::default()
+    };
+    deps.notification_preferences
+        .save(member_id, &preferences, Utc::now())
+        .await
+        .unwrap();
+    for _ in 0..5 {
+        let book_id = BookId::new();
+        book_service.add_available_book(book_id);
+        loan_book(
+            &deps,
+            LoanBook {
+                book_id,
+                member_id,
+                loaned_at: Utc::now() - Duration::days(20),
+                staff_id: StaffId::new(),
+            },
+        )
+        .await
+        .unwrap();
+    }
+    detect_overdue_loans(&deps, Utc::now()).await.unwrap();
+
+    // Act: 延滞の通知は配信済みになるが、まだ送られない
+    let from = Utc::now() - Duration::days(1);
+    let to = Utc::now() + Duration::minutes(1);
+    let report = dispatch_notifications(&deps, from, to).await.unwrap();
+    assert_eq!(report.delivered, 5);
+    assert!(gateway.sent().is_empty());
+    let report = router.deliver_deferred(Utc::now()).await.unwrap();
+    assert_eq!(report.messages, 0);
+
+    // Assert: 翌朝8時以降に1通のダイジェストとして送られる
+    let report = router
+        .deliver_deferred(Utc::now() + Duration::days(1))
+        .await
+        .unwrap();
+    assert_eq!(report.messages, 1);
+    assert_eq!(report.notices, 5);
+    let sent = gateway.sent();
+    assert_eq!(sent.len(), 1);
+    assert!(sent[0].is_digest());
+    assert_eq!(sent[0].member_id, member_id);
+    assert_eq!(sent[0].channel, NotificationChannel::Email);
+    assert_eq!(sent[0].language, NotificationLanguage::En);
+    assert!(
+        sent[0]
+            .notices
+            .iter()
+            .all(|n| n.body.kind() == NoticeKind::Overdue)
+    );
+
+    // 送った通知は二度と送らない
+    let report = router
+        .deliver_deferred(Utc::now() + Duration::days(2))
+        .await
+        .unwrap();
+    assert_eq!(report.messages, 0);
+    assert_eq!(gateway.sent().len(), 1);
+}
+
+#[tokio::test]
+async fn test_notices_in_quiet_hours_are_deferred_until_they_end() {
+    // Arrange: 22:00〜07:00に通知を送らない会員
+    let pool = common::create_test_pool().await;
+    let tenant_id = common::insert_tenant(&pool).await;
+    let gateway = Arc::new(NotificationGateway::new());
+    let router = postgres_router(&pool, tenant_id, gateway.clone());
+    let store = PostgresNotificationPreferenceStore::for_tenant(pool.clone(), tenant_id);
+    let member_id = MemberId::new();
+    let quiet = NotificationPreferences {
+        quiet_hours: Some(QuietHours {
+            start: NaiveTime::from_hms_opt(22, 0, 0).unwrap(),
+            end: NaiveTime::from_hms_opt(7, 0, 0).unwrap(),
+        }),
+        ..NotificationPreferences::default()
+    };
+    store.save(member_id, &quiet, jst(12, 0)).await.unwrap();
+    assert_eq!(store.get(member_id).await.unwrap(), Some(quiet));
+
+    // Act: 23:00に発生した通知（同じ通知の再送を含む）
+    for _ in 0..2 {
+        router
+            .route(member_id, overdue_notice("quiet-1"), jst(23, 0))
+            .await
+            .unwrap();
+    }
+    // 通知設定のない会員にはすぐ送る
+    let other = MemberId::new();
+    router
+        .route(other, overdue_notice("other-1"), jst(23, 0))
+        .await
+        .unwrap();
+
+    // Assert
+    let sent = gateway.sent();
+    assert_eq!(sent.len(), 1);
+    assert_eq!(sent[0].member_id, other);
+    let report = router.deliver_deferred(jst(23, 30)).await.unwrap();
+    assert_eq!(report.messages, 0);
+
+    // 時間帯の終わりに送る。送れなかった場合は保留したまま、次回の配信で送る
+    let morning = jst(7, 0) + Duration::days(1);
+    gateway.set_unavailable(true);
+    let report = router.deliver_deferred(morning).await.unwrap();
+    assert_eq!(report.messages, 0);
+    assert_eq!(report.failed.len(), 1);
+    assert!(report.failed[0].retryable);
+
+    gateway.set_unavailable(false);
+    let report = router.deliver_deferred(morning).await.unwrap();
+    assert_eq!(report.messages, 1);
+    assert!(report.failed.is_empty());
+    let sent = gateway.sent();
+    assert_eq!(sent.len(), 2);
+    assert_eq!(sent[1].member_id, member_id);
+    assert!(!sent[1].is_digest());
+    assert_eq!(sent[1].notices[0].dedup_key, "quiet-1");
+}
+
+#[tokio::test]
+async fn test_deferred_notices_are_dropped_for_members_who_opt_out() {
+    // Arrange: 日次ダイジェストを選んだ2人の会員に通知が保留されている
+    let pool = common::create_test_pool().await;
+    let tenant_id = common::insert_tenant(&pool).await;
+    let gateway = Arc::new(NotificationGateway::new());
+    let router = postgres_router(&pool, tenant_id, gateway.clone());
+    let store = PostgresNotificationPreferenceStore::for_tenant(pool.clone(), tenant_id);
+    let daily = NotificationPreferences {
+        digest: DigestMode::Daily,
+        ..NotificationPreferences::default()
+    };
+    let (opted_out, erased) = (MemberId::new(), MemberId::new());
+    for member_id in [opted_out, erased] {
+        store.save(member_id, &daily, jst(12, 0)).await.unwrap();
+        router
+            .route(
+                member_id,
+                overdue_notice(&member_id.value().to_string()),
+                jst(12, 0),
+            )
+            .await
+            .unwrap();
+    }
+
+    // Act: 1人は通知を受け取らなくなり、もう1人は通知設定を削除する
+    let none = NotificationPreferences {
+        channel: NotificationChannel::None,
+        ..daily
+    };
+    store.save(opted_out, &none, jst(13, 0)).await.unwrap();
+    router
+        .route(opted_out, overdue_notice("after-opt-out"), jst(13, 0))
+        .await
+        .unwrap();
+    store.delete(erased).await.unwrap();
+
+    // Assert: どちらの会員にも送らない（削除した会員の保留中の通知は設定と一緒に消える）
+    let report = router
+        .deliver_deferred(jst(8, 0) + Duration::days(1))
+        .await
+        .unwrap();
+    assert_eq!(report.messages, 0);
+    assert_eq!(report.suppressed, 1);
+    assert!(gateway.sent().is_empty());
+
+    let remaining: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM deferred_notices WHERE tenant_id = $1")
+            .bind(tenant_id.value())
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(remaining, 0);
+}
@@ -260,6 +260,102 @@ async fn test_find_overdue_candidates() {
     cleanup_loan(&pool, active_loan_id).await;
 }
 
+#[tokio::test]
+async fn test_find_due_soon_candidates() {
+    let pool = common::create_test_pool().await;
+    let read_model = LoanReadModel::new(pool.clone());
+
+    let now = Utc::now();
+    let member_id = MemberId::new();
+
+    // Due in 2 days, 10 days and 1 day ago (only the first is due soon)
+    let mut loans = Vec::new();
+    for days in [2, 10, -1] {
+        let loan = LoanView {
+            due_date: now + chrono::Duration::days(days),
+            ..active_loan_view(member_id)
+        };
+        read_model.save(loan.clone()).await.unwrap();
+        loans.push(loan.loan_id);
+    }
+    // Returned loans are not reminded
+    let returned = LoanView {
+        due_date: now + chrono::Duration::days(1),
+        returned_at: Some(now),
+        status: LoanStatus::Returned,
+        ..active_loan_view(member_id)
+    };
+    read_model.save(returned.clone()).await.unwrap();
+    loans.push(returned.loan_id);
+
+    let candidates = read_model
+        .find_due_soon_candidates(now, now + chrono::Duration::days(3))
+        .await
+        .expect("Failed to find due-soon candidates");
+
+    let found: Vec<_> = candidates
+        .iter()
+        .map(|l| l.loan_id)
+        .filter(|id| loans.contains(id))
+        .collect();
+    assert_eq!(found, vec![loans[0]]);
+
+    // Cleanup
+    for loan_id in loans {
+        cleanup_loan(&pool, loan_id).await;
+    }
+}
+
+#[tokio::test]
+async fn test_find_overdue_loans() {
+    let pool = common::create_test_pool().await;
+    let read_model = LoanReadModel::new(pool.clone());
+
+    let now = Utc::now();
+    let member_id = MemberId::new();
+
+    // Overdue since 20 days and 1 day ago (only the first is due for a notice)
+    let mut loans = Vec::new();
+    for days in [20, 1] {
+        let loan = LoanView {
+            due_date: now - chrono::Duration::days(days),
+            status: LoanStatus::Overdue,
+            ..active_loan_view(member_id)
+        };
+        read_model.save(loan.clone()).await.unwrap();
+        loans.push(loan.loan_id);
+    }
+    // Lost loans are no longer escalated
+    let lost = LoanView {
+        due_date: now - chrono::Duration::days(70),
+        status: LoanStatus::Lost,
+        ..active_loan_view(member_id)
+    };
+    read_model.save(lost.clone()).await.unwrap();
+    loans.push(lost.loan_id);
+
+    let overdue = read_model
+        .find_overdue_loans(now - chrono::Duration::days(7))
+        .await
+        .expect("Failed to find overdue loans");
+
+    let found: Vec<_> = overdue
+        .iter()
+        .map(|l| l.loan_id)
+        .filter(|id| loans.contains(id))
+        .collect();
+    assert_eq!(found, vec![loans[0]]);
+
+    // Lost status round-trips through the read model
+    let view = read_model.get_by_id(lost.loan_id).await.unwrap().unwrap();
+    assert_eq!(view.status, LoanStatus::Lost);
+
+    // Cleanup
+    for loan_id in loans {
+        cleanup_loan(&pool, loan_id).await;
+    }
+}
+
 #[tokio::test]
 async fn test_find_by_member_id() {
     let pool = common::create_test_pool().await;
@@ -321,7 +417,7 @@ async fn test_find_by_member_id() {
 
     // Find by member ID
     let loans = read_model
-        .find_by_member_id(member_id)
+        .find_by_member_id(member_id, None)
         .await
         .expect("Failed to find loans by member id");
 
@@ -369,6 +465,7 @@ async fn test_projector_book_loaned() {
         loaned_at: now,
         due_date,
         loaned_by: staff_id,
+        override_token: None,
     })];
 
     // Project events
@@ -415,6 +512,7 @@ async fn test_projector_loan_extended() {
             loaned_at: now,
             due_date: old_due_date,
             loaned_by: staff_id,
+            override_token: None,
         }),
         DomainEvent::LoanExtended(LoanExtended {
             loan_id,
@@ -465,6 +563,7 @@ async fn test_projector_book_returned() {
             loaned_at: now,
             due_date: now + chrono::Duration::days(14),
             loaned_by: staff_id,
+            override_token: None,
         }),
         DomainEvent::BookReturned(BookReturned {
             loan_id,
@@ -515,6 +614,7 @@ async fn test_projector_loan_became_overdue() {
             loaned_at: now,
             due_date,
             loaned_by: staff_id,
+            override_token: None,
         }),
         DomainEvent::LoanBecameOverdue(LoanBecameOverdue {
             loan_id,
@@ -543,3 +643,247 @@ async fn test_projector_loan_became_overdue() {
     // Cleanup
     cleanup_loan(&pool, loan_id).await;
 }
+
+// ============================================================================
+// マルチテナント
+// ============================================================================
+
+fn active_loan_view(member_id: MemberId) -> LoanView {
+    let now = Utc::now();
+    LoanView {
+        loan_id: LoanId::new(),
+        book_id: BookId::new(),
+        member_id,
+        loaned_at: now,
+        due_date: now + chrono::Duration::days(14),
+        returned_at: None,
+        extension_count: 0,
+        status: LoanStatus::Active,
+        created_at: now,
+        updated_at: now,
+    }
+}
+
+#[tokio::test]
+async fn test_loan_read_model_is_scoped_to_tenant() {
+    let pool = common::create_test_pool().await;
+    let tenant_a = common::insert_tenant(&pool).await;
+    let tenant_b = common::insert_tenant(&pool).await;
+    let read_model_a = LoanReadModel::for_tenant(pool.clone(), tenant_a);
+    let read_model_b = LoanReadModel::for_tenant(pool.clone(), tenant_b);
+
+    let member_id = MemberId::new();
+    let loan_view = active_loan_view(member_id);
+    let loan_id = loan_view.loan_id;
+
+    read_model_a
+        .save(loan_view)
+        .await
+        .expect("Failed to save loan view");
+
+    // 同じテナントからは見える
+    assert!(read_model_a.get_by_id(loan_id).await.unwrap().is_some());
+    assert_eq!(
+        read_model_a
+            .find_by_member_id(member_id, None)
+            .await
+            .unwrap()
+            .len(),
+        1
+    );
+
+    // 別テナントからは見えない
+    assert!(read_model_b.get_by_id(loan_id).await.unwrap().is_none());
+    assert!(
+        read_model_b
+            .find_by_member_id(member_id, None)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+    assert!(
+        read_model_b
+            .get_active_loans_for_member(member_id)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+
+    // Cleanup
+    cleanup_loan(&pool, loan_id).await;
+}
+
+#[tokio::test]
+async fn test_row_level_security_isolates_tenants() {
+    let pool = common::create_test_pool().await;
+    let tenant_a = common::insert_tenant(&pool).await;
+    let tenant_b = common::insert_tenant(&pool).await;
+
+    let loan_view = active_loan_view(MemberId::new());
+    let loan_id = loan_view.loan_id;
+    LoanReadModel::for_tenant(pool.clone(), tenant_b)
+        .save(loan_view)
+        .await
+        .expect("Failed to save loan view");
+
+    // スーパーユーザーはRLSをバイパスするため、一般ロールで検証する
+    sqlx::query(
+        r#"
+        DO $$
+        BEGIN
+            IF NOT EXISTS (SELECT 1 FROM pg_roles WHERE rolname = 'rls_test_app') THEN
+                CREATE ROLE rls_test_app NOLOGIN;
+            END IF;
+        END
+        $$
+        "#,
+    )
+    .execute(&pool)
+    .await
+    .expect("Failed to create role");
+    sqlx::query("GRANT SELECT, INSERT ON loans_view TO rls_test_app")
+        .execute(&pool)
+        .await
+        .expect("Failed to grant privileges");
+
+    let mut tx = pool.begin().await.unwrap();
+    sqlx::query("SET LOCAL ROLE rls_test_app")
+        .execute(&mut *tx)
+        .await
+        .unwrap();
+    sqlx::query("SELECT set_config('app.tenant_id', $1, true)")
+        .bind(tenant_a.value().to_string())
+        .execute(&mut *tx)
+        .await
+        .unwrap();
+
+    // テナントAからはテナントBの行を読めない
+    let visible: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM loans_view WHERE loan_id = $1")
+        .bind(loan_id.value())
+        .fetch_one(&mut *tx)
+        .await
+        .unwrap();
+    assert_eq!(visible, 0);
+
+    // テナントAとしてテナントBの行を追加することもできない
+    let insert = sqlx::query(
+        r#"
+        INSERT INTO loans_view (tenant_id, loan_id, book_id, member_id, loaned_at, due_date, status)
+        VALUES ($1, $2, $3, $4, NOW(), NOW(), 'active')
+        "#,
+    )
+    .bind(tenant_b.value())
+    .bind(LoanId::new().value())
+    .bind(BookId::new().value())
+    .bind(MemberId::new().value())
+    .execute(&mut *tx)
+    .await;
+    assert!(insert.is_err());
+
+    tx.rollback().await.unwrap();
+
+    // Cleanup
+    cleanup_loan(&pool, loan_id).await;
+}
+
+/// 指定日数前に返却された貸出ビュー
+fn returned_loan_view(member_id: MemberId, days_ago: i64) -> LoanView {
+    let returned_at = Utc::now() - chrono::Duration::days(days_ago);
+    LoanView {
+        returned_at: Some(returned_at),
+        status: LoanStatus::Returned,
+        updated_at: returned_at,
+        ..active_loan_view(member_id)
+    }
+}
+
+#[tokio::test]
+async fn test_history_retention_and_opt_in() {
+    let pool = common::create_test_pool().await;
+    let tenant_id = common::insert_tenant(&pool).await;
+    let read_model = LoanReadModel::for_tenant(pool.clone(), tenant_id);
+    let cutoff = Utc::now() - chrono::Duration::days(30);
+
+    let member_id = MemberId::new();
+    let opted_in_member = MemberId::new();
+    let active = active_loan_view(member_id);
+    let recent = returned_loan_view(member_id, 10);
+    let expired = returned_loan_view(member_id, 60);
+    let kept = returned_loan_view(opted_in_member, 60);
+    for view in [&active, &recent, &expired, &kept] {
+        read_model.save(view.clone()).await.unwrap();
+    }
+    read_model
+        .save_history_opt_in(opted_in_member, Some(Utc::now()))
+        .await
+        .unwrap();
+    assert!(
+        read_model
+            .get_history_opt_in(opted_in_member)
+            .await
+            .unwrap()
+            .is_some()
+    );
+
+    // 匿名化前でも保持期間を過ぎた履歴は返さない
+    let loans = read_model
+        .find_by_member_id(member_id, Some(cutoff))
+        .await
+        .unwrap();
+    let mut loan_ids: Vec<_> = loans.iter().map(|l| l.loan_id.value()).collect();
+    loan_ids.sort();
+    let mut expected = vec![active.loan_id.value(), recent.loan_id.value()];
+    expected.sort();
+    assert_eq!(loan_ids, expected);
+    assert_eq!(
+        read_model
+            .find_by_member_id(opted_in_member, Some(cutoff))
+            .await
+            .unwrap()
+            .len(),
+        1
+    );
+
+    // 保持を選択していない会員の期限切れの貸出のみ匿名化される
+    assert_eq!(
+        read_model.anonymise_returned_loans(cutoff).await.unwrap(),
+        1
+    );
+    let anonymised = read_model
+        .get_by_id(expired.loan_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(anonymised.member_id.is_anonymised());
+    assert_eq!(anonymised.status, LoanStatus::Returned);
+    assert_eq!(
+        read_model
+            .find_by_member_id(member_id, None)
+            .await
+            .unwrap()
+            .len(),
+        2
+    );
+
+    // 保持を取り消すと次回の匿名化の対象になる
+    read_model
+        .save_history_opt_in(opted_in_member, None)
+        .await
+        .unwrap();
+    assert!(
+        read_model
+            .get_history_opt_in(opted_in_member)
+            .await
+            .unwrap()
+            .is_none()
+    );
+    assert_eq!(
+        read_model.anonymise_returned_loans(cutoff).await.unwrap(),
+        1
+    );
+
+    // Cleanup
+    for view in [&active, &recent, &expired, &kept] {
+        cleanup_loan(&pool, view.loan_id).await;
+    }
+}
@@ -0,0 +1,135 @@
+mod common;
+
+use chrono::Utc;
+use rusty_library_ddd::adapters::postgres::{
+    PostgresEventStore, PostgresLoanReadModel, PostgresUnitOfWork,
+};
+use rusty_library_ddd::domain::events::{BookLoaned, DomainEvent};
+use rusty_library_ddd::domain::value_objects::{BookId, LoanId, MemberId, StaffId};
+use rusty_library_ddd::ports::{EventStore, LoanReadModel, LoanStatus, LoanView, UnitOfWork};
+
+/// 貸出イベントとそのビュー
+fn loaned(extension_count: u8) -> (LoanId, DomainEvent, LoanView) {
+    let now = Utc::now();
+    let loan_id = LoanId::new();
+    let book_id = BookId::new();
+    let member_id = MemberId::new();
+    let event = DomainEvent::BookLoaned(BookLoaned {
+        loan_id,
+        book_id,
+        member_id,
+        loaned_at: now,
+        due_date: now + chrono::Duration::days(14),
+        loaned_by: StaffId::new(),
+        override_token: None,
+    });
+    let view = LoanView {
+        loan_id,
+        book_id,
+        member_id,
+        loaned_at: now,
+        due_date: now + chrono::Duration::days(14),
+        returned_at: None,
+        extension_count,
+        status: LoanStatus::Active,
+        created_at: now,
+        updated_at: now,
+    };
+    (loan_id, event, view)
+}
+
+#[tokio::test]
+async fn test_commit_appends_events_and_saves_view_together() {
+    let pool = common::create_test_pool().await;
+    let tenant_id = common::insert_tenant(&pool).await;
+    let unit_of_work = PostgresUnitOfWork::for_tenant(pool.clone(), tenant_id);
+    let event_store = PostgresEventStore::for_tenant(pool.clone(), tenant_id);
+    let read_model = PostgresLoanReadModel::for_tenant(pool.clone(), tenant_id);
+
+    let (loan_id, event, view) = loaned(0);
+    unit_of_work
+        .commit(loan_id.value(), "Loan", 0, vec![event.clone()], view)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        event_store.load(loan_id.value(), "Loan").await.unwrap(),
+        vec![event]
+    );
+    assert!(read_model.get_by_id(loan_id).await.unwrap().is_some());
+}
+
+#[tokio::test]
+async fn test_commit_returns_the_ids_of_the_stored_events() {
+    let pool = common::create_test_pool().await;
+    let tenant_id = common::insert_tenant(&pool).await;
+    let unit_of_work = PostgresUnitOfWork::for_tenant(pool.clone(), tenant_id);
+    let event_store = PostgresEventStore::for_tenant(pool.clone(), tenant_id);
+
+    let (loan_id, event, view) = loaned(0);
+    let appended = unit_of_work
+        .commit(loan_id.value(), "Loan", 0, vec![event.clone()], view)
+        .await
+        .unwrap();
+
+    // 返されたイベントIDとシーケンス番号は、保存されたイベントのもの
+    let occurred_at = event.occurred_at();
+    let stored = event_store
+        .load_by_type(
+            "BookLoaned",
+            occurred_at - chrono::Duration::seconds(1),
+            occurred_at + chrono::Duration::seconds(1),
+        )
+        .await
+        .unwrap()
+        .into_iter()
+        .find(|stored| stored.aggregate_id == loan_id.value())
+        .unwrap();
+    assert_eq!(appended.len(), 1);
+    assert_eq!(appended[0].event_id, stored.event_id);
+    assert_eq!(appended[0].sequence_number, stored.sequence_number);
+    assert_eq!(appended[0].recorded_at, stored.recorded_at);
+}
+
+#[tokio::test]
+async fn test_commit_rolls_back_events_when_view_cannot_be_saved() {
+    let pool = common::create_test_pool().await;
+    let tenant_id = common::insert_tenant(&pool).await;
+    let unit_of_work = PostgresUnitOfWork::for_tenant(pool.clone(), tenant_id);
+    let event_store = PostgresEventStore::for_tenant(pool.clone(), tenant_id);
+    let read_model = PostgresLoanReadModel::for_tenant(pool.clone(), tenant_id);
+
+    // loans_viewの制約（延長は1回まで）に違反するビュー
+    let (loan_id, event, view) = loaned(2);
+    assert!(
+        unit_of_work
+            .commit(loan_id.value(), "Loan", 0, vec![event], view)
+            .await
+            .is_err()
+    );
+
+    // イベントも保存されていない
+    assert!(
+        event_store
+            .load(loan_id.value(), "Loan")
+            .await
+            .unwrap()
+            .is_empty()
+    );
+    assert!(read_model.get_by_id(loan_id).await.unwrap().is_none());
+
+    // 失敗したコミットはハッシュチェーンにも残らず、次のコミットは成功する
+    let (loan_id, event, view) = loaned(0);
+    unit_of_work
+        .commit(loan_id.value(), "Loan", 0, vec![event], view)
+        .await
+        .unwrap();
+    assert_eq!(
+        event_store
+            .load(loan_id.value(), "Loan")
+            .await
+            .unwrap()
+            .len(),
+        1
+    );
+}